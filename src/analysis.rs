@@ -0,0 +1,3 @@
+pub mod cache_report;
+pub mod security_header_report;
+pub mod seo_report;
@@ -0,0 +1,214 @@
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::http::CacheHeaders;
+use crate::crawler::page_summary::PageSummary;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use url::Url;
+
+const HOUR: Duration = Duration::from_secs(3600);
+const DAY: Duration = Duration::from_secs(86_400);
+const MONTH: Duration = Duration::from_secs(30 * 86_400);
+
+/// HTML pages that caches may reuse for longer than this are reported, since updates to
+/// them can take that long to show.
+const MAX_HTML_LIFETIME: Duration = DAY;
+
+/// How caches may store a response, by its Cache-Control and Expires headers. Where several
+/// apply, the first listed wins, e.g. "private, max-age=60" is private.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CachePolicy {
+    /// Not stored by any cache.
+    NoStore,
+    /// Only stored by the browser, not by shared caches such as CDNs.
+    Private,
+    /// Stored, but checked with the server before every reuse.
+    NoCache,
+    /// Reused without checking for up to a max-age, or until Expires, in the given bucket.
+    MaxAge(MaxAgeBucket),
+    /// Neither max-age nor Expires, leaving caches to guess how long to keep it.
+    Missing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MaxAgeBucket {
+    Zero,
+    UnderAnHour,
+    UnderADay,
+    UnderAMonth,
+    AMonthOrMore,
+}
+
+impl CachePolicy {
+    pub fn of(cache_headers: &CacheHeaders) -> Self {
+        if cache_headers.directive("no-store").is_some() {
+            CachePolicy::NoStore
+        } else if cache_headers.directive("private").is_some() {
+            CachePolicy::Private
+        } else if cache_headers.directive("no-cache").is_some() {
+            CachePolicy::NoCache
+        } else {
+            let bucket = match cache_headers.freshness_lifetime() {
+                None => return CachePolicy::Missing,
+                Some(lifetime) if lifetime.is_zero() => MaxAgeBucket::Zero,
+                Some(lifetime) if lifetime < HOUR => MaxAgeBucket::UnderAnHour,
+                Some(lifetime) if lifetime < DAY => MaxAgeBucket::UnderADay,
+                Some(lifetime) if lifetime < MONTH => MaxAgeBucket::UnderAMonth,
+                Some(_) => MaxAgeBucket::AMonthOrMore,
+            };
+            CachePolicy::MaxAge(bucket)
+        }
+    }
+}
+
+impl fmt::Display for CachePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            CachePolicy::NoStore => "no-store",
+            CachePolicy::Private => "private",
+            CachePolicy::NoCache => "no-cache",
+            CachePolicy::MaxAge(MaxAgeBucket::Zero) => "max-age 0",
+            CachePolicy::MaxAge(MaxAgeBucket::UnderAnHour) => "max-age under an hour",
+            CachePolicy::MaxAge(MaxAgeBucket::UnderADay) => "max-age an hour to a day",
+            CachePolicy::MaxAge(MaxAgeBucket::UnderAMonth) => "max-age a day to a month",
+            CachePolicy::MaxAge(MaxAgeBucket::AMonthOrMore) => "max-age a month or more",
+            CachePolicy::Missing => "no max-age or Expires",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A caching policy likely to serve stale pages or refetch unchanged ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheIssue {
+    /// An HTML page caches may reuse for longer than a day without checking for updates.
+    LongLivedHtml { page: Url, lifetime: Duration },
+    /// A static asset such as a stylesheet, script or image that caches have no lifetime for.
+    UncachedAsset { page: Url, content_type: String },
+}
+
+/// How the successfully served pages of a crawl may be cached, run after the crawl.
+#[derive(Debug, Clone, Default)]
+pub struct CacheReport {
+    pages_by_policy: BTreeMap<CachePolicy, usize>,
+    issues: Vec<CacheIssue>,
+}
+
+impl CacheReport {
+    pub fn from_crawl_summaries(crawl_summaries: &[CrawlSummary]) -> Self {
+        let mut report = CacheReport::default();
+        for page_summary in crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .filter(|page_summary| (200..300).contains(&page_summary.status_code))
+        {
+            let Some(cache_headers) = &page_summary.cache_headers else {
+                continue;
+            };
+            let policy = CachePolicy::of(cache_headers);
+            *report.pages_by_policy.entry(policy).or_default() += 1;
+            if page_summary.content_type.contains("html") {
+                let lifetime = match policy {
+                    CachePolicy::NoStore | CachePolicy::NoCache => None,
+                    _ => cache_headers.freshness_lifetime(),
+                };
+                if let Some(lifetime) = lifetime.filter(|lifetime| *lifetime > MAX_HTML_LIFETIME) {
+                    report.issues.push(CacheIssue::LongLivedHtml {
+                        page: page_summary.url.clone(),
+                        lifetime,
+                    });
+                }
+            } else if policy == CachePolicy::Missing && is_static_asset(page_summary) {
+                report.issues.push(CacheIssue::UncachedAsset {
+                    page: page_summary.url.clone(),
+                    content_type: page_summary.content_type.clone(),
+                });
+            }
+        }
+        report
+    }
+
+    pub fn num_checked_pages(&self) -> usize {
+        self.pages_by_policy.values().sum()
+    }
+}
+
+/// Whether the page is a stylesheet, script, image, font or media file, which rarely change
+/// at the same URL and so should be cached.
+fn is_static_asset(page_summary: &PageSummary) -> bool {
+    let Ok(mime_type) = page_summary.content_type.parse::<mime::Mime>() else {
+        return false;
+    };
+    matches!(mime_type.type_().as_str(), "image" | "font" | "audio" | "video")
+        || matches!(mime_type.subtype().as_str(), "css" | "javascript" | "wasm")
+}
+
+impl fmt::Display for CacheReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Caching: {} pages checked, {} issues",
+            self.num_checked_pages(),
+            self.issues.len()
+        )?;
+        for (policy, num_pages) in &self.pages_by_policy {
+            writeln!(f, "   {}: {} pages", policy, num_pages)?;
+        }
+        for issue in &self.issues {
+            match issue {
+                CacheIssue::LongLivedHtml { page, lifetime } => writeln!(
+                    f,
+                    "   {}: HTML page cached for {}",
+                    page,
+                    humantime::format_duration(*lifetime)
+                )?,
+                CacheIssue::UncachedAsset { page, content_type } => writeln!(
+                    f,
+                    "   {}: {} asset without max-age or Expires",
+                    page, content_type
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, content_type: &str, cache_control: Option<&str>) -> PageSummary {
+        let mut page_summary = PageSummary::from_status_code(Url::parse(url).unwrap(), 0, 200);
+        page_summary.content_type = content_type.to_string();
+        page_summary.cache_headers = Some(CacheHeaders {
+            cache_control: cache_control.map(str::to_string),
+            ..CacheHeaders::default()
+        });
+        page_summary
+    }
+
+    #[test]
+    fn classifies_pages_and_flags_long_lived_html_and_uncached_assets() {
+        let crawl_summary = CrawlSummary::new(vec![
+            page("https://example.com/", "text/html", Some("max-age=604800")),
+            page("https://example.com/account", "text/html", Some("private, no-store")),
+            page("https://example.com/news", "text/html; charset=utf-8", Some("max-age=300")),
+            page("https://example.com/site.css", "text/css", Some("public")),
+            page("https://example.com/logo.png", "image/png", Some("max-age=31536000")),
+            page("https://example.com/feed.json", "application/json", None),
+            PageSummary::from_status_code(Url::parse("https://example.com/gone").unwrap(), 1, 404),
+        ]);
+        let report = CacheReport::from_crawl_summaries(&[crawl_summary]);
+        assert_eq!(
+            report.to_string(),
+            "Caching: 6 pages checked, 2 issues\n\
+             \x20  no-store: 1 pages\n\
+             \x20  max-age under an hour: 1 pages\n\
+             \x20  max-age a day to a month: 1 pages\n\
+             \x20  max-age a month or more: 1 pages\n\
+             \x20  no max-age or Expires: 2 pages\n\
+             \x20  https://example.com/: HTML page cached for 7days\n\
+             \x20  https://example.com/site.css: text/css asset without max-age or Expires\n"
+        );
+    }
+}
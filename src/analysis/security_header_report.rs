@@ -0,0 +1,87 @@
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::http::SecurityHeaders;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The security headers of each crawled host, as sent with the first page crawled on it.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityHeaderReport {
+    /// Keyed by origin, e.g. "https://example.com", since HSTS only applies over HTTPS.
+    headers_by_origin: BTreeMap<String, SecurityHeaders>,
+}
+
+impl SecurityHeaderReport {
+    pub fn from_crawl_summaries(crawl_summaries: &[CrawlSummary]) -> Self {
+        let mut report = SecurityHeaderReport::default();
+        for page_summary in crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+        {
+            if let Some(security_headers) = &page_summary.security_headers {
+                report
+                    .headers_by_origin
+                    .entry(page_summary.url.origin().ascii_serialization())
+                    .or_insert_with(|| security_headers.clone());
+            }
+        }
+        report
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers_by_origin.is_empty()
+    }
+}
+
+impl fmt::Display for SecurityHeaderReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Security headers:")?;
+        for (origin, security_headers) in &self.headers_by_origin {
+            let missing = security_headers.missing(origin.starts_with("https:"));
+            if missing.is_empty() {
+                writeln!(f, "   {}: all present", origin)?;
+            } else {
+                writeln!(f, "   {}: missing {}", origin, missing.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::page_summary::PageSummary;
+    use url::Url;
+
+    fn page(url: &str, security_headers: Option<SecurityHeaders>) -> PageSummary {
+        let mut page_summary = PageSummary::from_status_code(Url::parse(url).unwrap(), 0, 200);
+        page_summary.security_headers = security_headers;
+        page_summary
+    }
+
+    #[test]
+    fn reports_missing_headers_per_origin() {
+        let hardened = SecurityHeaders {
+            strict_transport_security: Some("max-age=31536000".to_string()),
+            content_security_policy: Some("default-src 'self'; frame-ancestors 'none'".to_string()),
+            x_frame_options: None,
+            x_content_type_options: Some("nosniff".to_string()),
+        };
+        let crawl_summary = CrawlSummary::new(vec![
+            page("https://example.com/", Some(hardened)),
+            page("https://example.com/a", None),
+            page("http://example.com/", Some(SecurityHeaders::default())),
+            page("https://cdn.example.com/", Some(SecurityHeaders::default())),
+        ]);
+        let report = SecurityHeaderReport::from_crawl_summaries(&[crawl_summary]);
+        assert_eq!(
+            report.to_string(),
+            "Security headers:\n\
+             \x20  http://example.com: missing Content-Security-Policy, X-Frame-Options, \
+             X-Content-Type-Options\n\
+             \x20  https://cdn.example.com: missing Strict-Transport-Security, \
+             Content-Security-Policy, X-Frame-Options, X-Content-Type-Options\n\
+             \x20  https://example.com: all present\n"
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use crate::crawler::crawl_response::NO_TITLE;
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::page_summary::PageSummary;
+use crate::crawler::link_anchor::LinkAnchor;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::time::SystemTime;
+use url::Url;
+
+/// Titles longer than this are usually truncated in search results.
+pub const DEFAULT_MAX_TITLE_LENGTH: usize = 60;
+
+/// An on-page SEO problem found after the crawl.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeoIssue {
+    MissingTitle { page: Url },
+    /// Several pages share one title, so search results cannot tell them apart.
+    DuplicateTitle { title: String, pages: Vec<Url> },
+    LongTitle { page: Url, length: usize },
+    MissingDescription { page: Url },
+    MultipleH1 { page: Url, count: usize },
+    /// Every internal link to the page uses anchor text like "click here" or none at all.
+    GenericAnchorsOnly { page: Url, anchor_texts: Vec<String> },
+    /// An https:// page that loads assets or links to pages over plain http://.
+    MixedContent { page: Url, insecure_urls: Vec<Url> },
+    /// The page's AMP version, from `<link rel="amphtml">`, was not served successfully.
+    BrokenAmpPage { page: Url, amp_url: Url, status_code: u16 },
+    /// The page's AMP version does not name the page as its canonical URL.
+    AmpMissingCanonical { page: Url, amp_url: Url, canonical: Option<Url> },
+    /// Linked internally, but robots.txt stops crawlers from following the link.
+    BlockedByRobotsTxt { page: Url, linked_from: Option<Url> },
+    /// Still served, though its `unavailable_after` robots directive has passed.
+    PastUnavailableAfter { page: Url, unavailable_after: String },
+    /// The date of the page's `unavailable_after` robots directive could not be read.
+    UnreadableUnavailableAfter { page: Url, unavailable_after: String },
+}
+
+/// Checks the titles, descriptions and headings of the crawled pages, run after the crawl.
+#[derive(Debug, Clone, Default)]
+pub struct SeoReport {
+    num_checked_pages: usize,
+    issues: Vec<SeoIssue>,
+}
+
+impl SeoReport {
+    pub fn from_crawl_summaries(crawl_summaries: &[CrawlSummary], max_title_length: usize) -> Self {
+        Self::from_crawl_summaries_at(crawl_summaries, max_title_length, SystemTime::now())
+    }
+
+    /// Checks the pages as of `now`, which decides whether `unavailable_after` dates passed.
+    fn from_crawl_summaries_at(
+        crawl_summaries: &[CrawlSummary],
+        max_title_length: usize,
+        now: SystemTime,
+    ) -> Self {
+        let mut report = SeoReport::default();
+        let page_summaries = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .collect::<Vec<_>>();
+
+        let pages_by_url = page_summaries
+            .iter()
+            .map(|page_summary| (&page_summary.url, *page_summary))
+            .collect::<HashMap<&Url, &PageSummary>>();
+
+        let mut anchors_by_target: HashMap<&Url, Vec<&LinkAnchor>> = HashMap::new();
+        for link_anchor in page_summaries
+            .iter()
+            .flat_map(|page_summary| &page_summary.internal_link_anchors)
+        {
+            anchors_by_target.entry(&link_anchor.url).or_default().push(link_anchor);
+        }
+
+        let mut pages_by_title: BTreeMap<&str, Vec<Url>> = BTreeMap::new();
+        let checked_pages = page_summaries
+            .iter()
+            .filter(|page_summary| Self::is_checked(page_summary));
+        for page_summary in checked_pages {
+            report.num_checked_pages += 1;
+            let page = &page_summary.url;
+            let title = page_summary.title.trim();
+            if title.is_empty() || title == NO_TITLE {
+                report.issues.push(SeoIssue::MissingTitle { page: page.clone() });
+            } else {
+                pages_by_title.entry(title).or_default().push(page.clone());
+                let length = title.chars().count();
+                if length > max_title_length {
+                    report.issues.push(SeoIssue::LongTitle { page: page.clone(), length });
+                }
+            }
+            if page_summary
+                .meta_description
+                .as_deref()
+                .is_none_or(|description| description.is_empty())
+            {
+                report.issues.push(SeoIssue::MissingDescription { page: page.clone() });
+            }
+            if page_summary.num_h1 > 1 {
+                report.issues.push(SeoIssue::MultipleH1 {
+                    page: page.clone(),
+                    count: page_summary.num_h1,
+                });
+            }
+            if !page_summary.mixed_content.is_empty() {
+                report.issues.push(SeoIssue::MixedContent {
+                    page: page.clone(),
+                    insecure_urls: page_summary.mixed_content.clone(),
+                });
+            }
+            if let Some(amp_url) = &page_summary.amp_url {
+                // AMP versions that were never crawled, e.g. on another host, can't be judged
+                match pages_by_url.get(amp_url) {
+                    Some(amp_page) if !(200..300).contains(&amp_page.status_code) => {
+                        report.issues.push(SeoIssue::BrokenAmpPage {
+                            page: page.clone(),
+                            amp_url: amp_url.clone(),
+                            status_code: amp_page.status_code,
+                        });
+                    }
+                    Some(amp_page) if amp_page.canonical.as_ref() != Some(page) => {
+                        report.issues.push(SeoIssue::AmpMissingCanonical {
+                            page: page.clone(),
+                            amp_url: amp_url.clone(),
+                            canonical: amp_page.canonical.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            // Pages nothing links to, like the seed, have no anchors to judge
+            if let Some(link_anchors) = anchors_by_target.get(page) {
+                if link_anchors.iter().all(|link_anchor| link_anchor.is_generic()) {
+                    let anchor_texts = link_anchors
+                        .iter()
+                        .map(|link_anchor| link_anchor.anchor_text.clone())
+                        .collect::<BTreeSet<_>>();
+                    report.issues.push(SeoIssue::GenericAnchorsOnly {
+                        page: page.clone(),
+                        anchor_texts: anchor_texts.into_iter().collect(),
+                    });
+                }
+            }
+        }
+        for (title, mut pages) in pages_by_title {
+            if pages.len() > 1 {
+                pages.sort();
+                report.issues.push(SeoIssue::DuplicateTitle {
+                    title: title.to_string(),
+                    pages,
+                });
+            }
+        }
+        let blocked_pages = page_summaries
+            .iter()
+            .filter(|page_summary| page_summary.blocked_by_robots_txt);
+        for page_summary in blocked_pages {
+            report.issues.push(SeoIssue::BlockedByRobotsTxt {
+                page: page_summary.url.clone(),
+                linked_from: page_summary.discovered_from.clone(),
+            });
+        }
+        // Any document can carry unavailable_after in an X-Robots-Tag header
+        let served_pages = page_summaries
+            .iter()
+            .filter(|page_summary| (200..300).contains(&page_summary.status_code));
+        for page_summary in served_pages {
+            let robots_directives = &page_summary.robots_directives;
+            let Some(unavailable_after) = &robots_directives.unavailable_after else {
+                continue;
+            };
+            let page = page_summary.url.clone();
+            let unavailable_after = unavailable_after.clone();
+            match robots_directives.unavailable_after_time() {
+                Some(time) if time <= now => {
+                    report.issues.push(SeoIssue::PastUnavailableAfter { page, unavailable_after })
+                }
+                Some(_) => {}
+                None => report
+                    .issues
+                    .push(SeoIssue::UnreadableUnavailableAfter { page, unavailable_after }),
+            }
+        }
+        report
+    }
+
+    /// Only HTML pages served successfully have titles and descriptions worth checking.
+    fn is_checked(page_summary: &PageSummary) -> bool {
+        (200..300).contains(&page_summary.status_code)
+            && !page_summary.soft_404
+            && page_summary.content_type.contains("html")
+    }
+}
+
+impl fmt::Display for SeoReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "SEO: {} pages checked, {} issues",
+            self.num_checked_pages,
+            self.issues.len()
+        )?;
+        for issue in &self.issues {
+            match issue {
+                SeoIssue::MissingTitle { page } => writeln!(f, "   {}: missing title", page)?,
+                SeoIssue::DuplicateTitle { title, pages } => {
+                    writeln!(f, "   {} pages share the title '{}'", pages.len(), title)?;
+                    for page in pages {
+                        writeln!(f, "      {}", page)?;
+                    }
+                }
+                SeoIssue::LongTitle { page, length } => {
+                    writeln!(f, "   {}: title is {} characters long", page, length)?
+                }
+                SeoIssue::MissingDescription { page } => {
+                    writeln!(f, "   {}: missing meta description", page)?
+                }
+                SeoIssue::MultipleH1 { page, count } => {
+                    writeln!(f, "   {}: {} h1 headings", page, count)?
+                }
+                SeoIssue::GenericAnchorsOnly { page, anchor_texts } => writeln!(
+                    f,
+                    "   {}: only linked with generic anchor text ({})",
+                    page,
+                    anchor_texts
+                        .iter()
+                        .map(|anchor_text| format!("'{}'", anchor_text))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )?,
+                SeoIssue::MixedContent { page, insecure_urls } => {
+                    writeln!(
+                        f,
+                        "   {}: mixed content, {} http:// references on an https:// page",
+                        page,
+                        insecure_urls.len()
+                    )?;
+                    for insecure_url in insecure_urls {
+                        writeln!(f, "      {}", insecure_url)?;
+                    }
+                }
+                SeoIssue::BrokenAmpPage { page, amp_url, status_code } => writeln!(
+                    f,
+                    "   {}: AMP version {} returned {}",
+                    page, amp_url, status_code
+                )?,
+                SeoIssue::AmpMissingCanonical { page, amp_url, canonical } => match canonical {
+                    Some(canonical) => writeln!(
+                        f,
+                        "   {}: AMP version {} names {} as canonical instead",
+                        page, amp_url, canonical
+                    )?,
+                    None => writeln!(
+                        f,
+                        "   {}: AMP version {} has no canonical link back",
+                        page, amp_url
+                    )?,
+                },
+                SeoIssue::BlockedByRobotsTxt { page, linked_from } => match linked_from {
+                    Some(linked_from) => writeln!(
+                        f,
+                        "   {}: blocked by robots.txt but linked from {}",
+                        page, linked_from
+                    )?,
+                    None => writeln!(f, "   {}: blocked by robots.txt", page)?,
+                },
+                SeoIssue::PastUnavailableAfter { page, unavailable_after } => writeln!(
+                    f,
+                    "   {}: still served after its unavailable_after date, {}",
+                    page, unavailable_after
+                )?,
+                SeoIssue::UnreadableUnavailableAfter { page, unavailable_after } => writeln!(
+                    f,
+                    "   {}: unreadable unavailable_after date '{}'",
+                    page, unavailable_after
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html_page(path: &str, title: &str, num_h1: usize, description: Option<&str>) -> PageSummary {
+        let url = Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        let mut page_summary =
+            PageSummary::new(url, 1, 200, "text/html".to_string(), title.to_string(), 0);
+        page_summary.num_h1 = num_h1;
+        page_summary.meta_description = description.map(str::to_string);
+        page_summary
+    }
+
+    #[test]
+    fn reports_title_description_heading_anchor_mixed_content_and_robots_issues() {
+        let mut blocked =
+            PageSummary::from_status_code(Url::parse("https://example.com/private").unwrap(), 1, 403);
+        blocked.blocked_by_robots_txt = true;
+        blocked.discovered_from = Some(Url::parse("https://example.com/").unwrap());
+        let link = |path: &str, anchor_text: &str| LinkAnchor {
+            url: Url::parse("https://example.com/").unwrap().join(path).unwrap(),
+            anchor_text: anchor_text.to_string(),
+        };
+        let mut home = html_page("/", "Home", 1, Some("Welcome"));
+        home.mixed_content = vec![Url::parse("http://cdn.example.com/logo.png").unwrap()];
+        home.internal_link_anchors = vec![
+            link("/a", "All products"),
+            link("/a", "Click here"),
+            link("/b", "Click here!"),
+            link("/b", ""),
+        ];
+        let crawl_summary = CrawlSummary::new(vec![
+            home,
+            html_page("/a", "Products", 1, Some("All products")),
+            html_page("/b", "Products", 2, Some("")),
+            html_page("/c", NO_TITLE, 0, Some("No title here")),
+            html_page("/d", &"Long ".repeat(20), 1, Some("Long")),
+            blocked,
+        ]);
+        let report = SeoReport::from_crawl_summaries(&[crawl_summary], DEFAULT_MAX_TITLE_LENGTH);
+        let page = |path: &str| Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![
+                SeoIssue::MixedContent {
+                    page: page("/"),
+                    insecure_urls: vec![Url::parse("http://cdn.example.com/logo.png").unwrap()],
+                },
+                SeoIssue::MissingDescription { page: page("/b") },
+                SeoIssue::MultipleH1 { page: page("/b"), count: 2 },
+                SeoIssue::GenericAnchorsOnly {
+                    page: page("/b"),
+                    anchor_texts: vec!["".to_string(), "Click here!".to_string()],
+                },
+                SeoIssue::MissingTitle { page: page("/c") },
+                SeoIssue::LongTitle { page: page("/d"), length: 99 },
+                SeoIssue::DuplicateTitle {
+                    title: "Products".to_string(),
+                    pages: vec![page("/a"), page("/b")],
+                },
+                SeoIssue::BlockedByRobotsTxt {
+                    page: page("/private"),
+                    linked_from: Some(page("/")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_broken_amp_pages_and_amp_pages_without_a_canonical_link_back() {
+        let page = |path: &str| Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        let mut a = html_page("/a", "A", 1, Some("A"));
+        a.amp_url = Some(page("/a/amp"));
+        let mut a_amp = html_page("/a/amp", "A (AMP)", 1, Some("A"));
+        a_amp.canonical = Some(page("/a"));
+        let mut b = html_page("/b", "B", 1, Some("B"));
+        b.amp_url = Some(page("/b/amp"));
+        let mut b_amp = html_page("/b/amp", "B (AMP)", 1, Some("B"));
+        b_amp.canonical = Some(page("/b/amp"));
+        let mut c = html_page("/c", "C", 1, Some("C"));
+        c.amp_url = Some(page("/c/amp"));
+        let crawl_summary = CrawlSummary::new(vec![
+            a,
+            a_amp,
+            b,
+            b_amp,
+            c,
+            PageSummary::from_status_code(page("/c/amp"), 2, 404),
+        ]);
+        let report = SeoReport::from_crawl_summaries(&[crawl_summary], DEFAULT_MAX_TITLE_LENGTH);
+        assert_eq!(
+            report.issues,
+            vec![
+                SeoIssue::AmpMissingCanonical {
+                    page: page("/b"),
+                    amp_url: page("/b/amp"),
+                    canonical: Some(page("/b/amp")),
+                },
+                SeoIssue::BrokenAmpPage {
+                    page: page("/c"),
+                    amp_url: page("/c/amp"),
+                    status_code: 404,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_pages_served_past_their_unavailable_after_date() {
+        let page = |path: &str, directives: &str| {
+            let mut page_summary = html_page(path, path, 1, Some(path));
+            page_summary.robots_directives.add_directives(directives);
+            page_summary
+        };
+        let crawl_summary = CrawlSummary::new(vec![
+            page("/expired", "noindex, unavailable_after: Fri, 01 Jan 2027 00:00:00 GMT"),
+            page("/expired-iso", "unavailable_after: 2027-01-01"),
+            page("/current", "unavailable_after: 2027-12-31T23:59:59Z, nofollow"),
+            page("/unreadable", "unavailable_after: next week"),
+        ]);
+        let now = httpdate::parse_http_date("Sat, 16 Oct 2027 12:00:00 GMT").unwrap();
+        let report =
+            SeoReport::from_crawl_summaries_at(&[crawl_summary], DEFAULT_MAX_TITLE_LENGTH, now);
+        let url = |path: &str| Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![
+                SeoIssue::PastUnavailableAfter {
+                    page: url("/expired"),
+                    unavailable_after: "Fri, 01 Jan 2027 00:00:00 GMT".to_string(),
+                },
+                SeoIssue::PastUnavailableAfter {
+                    page: url("/expired-iso"),
+                    unavailable_after: "2027-01-01".to_string(),
+                },
+                SeoIssue::UnreadableUnavailableAfter {
+                    page: url("/unreadable"),
+                    unavailable_after: "next week".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod check_outcome;
+pub mod expected_urls;
+pub mod fail_on_policy;
+pub mod status_assertion;
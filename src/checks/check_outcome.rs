@@ -0,0 +1,16 @@
+/// Result of evaluating a completed crawl against the configured checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckOutcome {
+    Passed,
+    Failed,
+}
+
+impl CheckOutcome {
+    pub fn from_passed(passed: bool) -> Self {
+        if passed {
+            CheckOutcome::Passed
+        } else {
+            CheckOutcome::Failed
+        }
+    }
+}
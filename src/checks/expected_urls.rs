@@ -0,0 +1,130 @@
+use crate::crawler::crawl_summary::CrawlSummary;
+use anyhow::Context;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use url::Url;
+
+/// The pages a site is expected to have, from an `--expected-urls` file.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedUrls {
+    urls: BTreeSet<Url>,
+}
+
+/// How the crawled pages differ from the expected ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedUrlsComparison {
+    num_expected: usize,
+    /// Expected URLs the crawl never reached.
+    pub missing: Vec<Url>,
+    /// Crawled URLs that are not expected.
+    pub unexpected: Vec<Url>,
+}
+
+impl ExpectedUrls {
+    /// Reads one URL per line. Blank lines and lines starting with "#" are ignored.
+    pub fn load_from_path(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open expected URLs file '{}'", path.display()))?;
+        let mut expected_urls = ExpectedUrls::default();
+        for (line_index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let url = Url::parse(line).with_context(|| {
+                format!("Invalid URL '{}' at {}:{}", line, path.display(), line_index + 1)
+            })?;
+            expected_urls.urls.insert(Self::strip_fragment(url));
+        }
+        Ok(expected_urls)
+    }
+
+    pub fn compare(&self, crawl_summaries: &[CrawlSummary]) -> ExpectedUrlsComparison {
+        // Pages blocked by robots.txt were found, but never fetched
+        let crawled_urls = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .filter(|page_summary| !page_summary.blocked_by_robots_txt)
+            .map(|page_summary| Self::strip_fragment(page_summary.url.clone()))
+            .collect::<BTreeSet<_>>();
+        ExpectedUrlsComparison {
+            num_expected: self.urls.len(),
+            missing: self.urls.difference(&crawled_urls).cloned().collect(),
+            unexpected: crawled_urls.difference(&self.urls).cloned().collect(),
+        }
+    }
+
+    fn strip_fragment(mut url: Url) -> Url {
+        url.set_fragment(None);
+        url
+    }
+}
+
+impl ExpectedUrlsComparison {
+    pub fn matches(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+impl fmt::Display for ExpectedUrlsComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Expected URLs: {} expected, {} missing, {} unexpected",
+            self.num_expected,
+            self.missing.len(),
+            self.unexpected.len()
+        )?;
+        for url in &self.missing {
+            writeln!(f, "   missing    {}", url)?;
+        }
+        for url in &self.unexpected {
+            writeln!(f, "   unexpected {}", url)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::page_summary::PageSummary;
+
+    #[test]
+    fn reports_missing_and_unexpected_urls() {
+        let path = std::env::temp_dir()
+            .join(format!("rusty-spider-expected-{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "# Release checklist\n\
+             https://example.com/\n\
+             \n\
+             https://example.com/about#team\n\
+             https://example.com/pricing\n",
+        )
+        .unwrap();
+        let expected_urls = ExpectedUrls::load_from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let page = |url: &str| PageSummary::from_status_code(Url::parse(url).unwrap(), 0, 200);
+        let crawl_summary = CrawlSummary::new(vec![
+            page("https://example.com/"),
+            page("https://example.com/about"),
+            page("https://example.com/old-page"),
+        ]);
+        let comparison = expected_urls.compare(&[crawl_summary]);
+        assert_eq!(
+            comparison.missing,
+            vec![Url::parse("https://example.com/pricing").unwrap()]
+        );
+        assert_eq!(
+            comparison.unexpected,
+            vec![Url::parse("https://example.com/old-page").unwrap()]
+        );
+        assert!(!comparison.matches());
+    }
+}
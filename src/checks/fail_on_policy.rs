@@ -0,0 +1,100 @@
+use crate::crawler::page_summary::PageSummary;
+use anyhow::anyhow;
+use std::fmt;
+use std::str::FromStr;
+
+/// A status code pattern given to `--fail-on`, e.g. "4xx", "404" or "soft404".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusPattern {
+    /// Matches every status code in a class, e.g. 4xx.
+    Class(u16),
+    /// Matches a single status code.
+    Exact(u16),
+    /// Matches pages served with a success status that look like "not found" pages.
+    Soft404,
+    /// Matches nothing; used to disable failing on status codes.
+    Nothing,
+}
+
+impl StatusPattern {
+    pub fn matches(&self, page_summary: &PageSummary) -> bool {
+        let status_code = page_summary.status_code;
+        match self {
+            StatusPattern::Class(class) => status_code / 100 == *class,
+            StatusPattern::Exact(expected) => status_code == *expected,
+            StatusPattern::Soft404 => page_summary.soft_404,
+            StatusPattern::Nothing => false,
+        }
+    }
+}
+
+impl FromStr for StatusPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if s == "none" {
+            return Ok(StatusPattern::Nothing);
+        }
+        if s == "soft404" {
+            return Ok(StatusPattern::Soft404);
+        }
+        if let Some(class) = s.strip_suffix("xx") {
+            return match class.parse::<u16>() {
+                Ok(class) if (1..=5).contains(&class) => Ok(StatusPattern::Class(class)),
+                _ => Err(anyhow!("Invalid status class '{}'", s)),
+            };
+        }
+        match s.parse::<u16>() {
+            Ok(status_code) if (100..=599).contains(&status_code) => Ok(StatusPattern::Exact(status_code)),
+            _ => Err(anyhow!("Invalid status pattern '{}' (expected e.g. 4xx, 404, soft404 or none)", s)),
+        }
+    }
+}
+
+impl fmt::Display for StatusPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusPattern::Class(class) => write!(f, "{}xx", class),
+            StatusPattern::Exact(status_code) => write!(f, "{}", status_code),
+            StatusPattern::Soft404 => write!(f, "soft404"),
+            StatusPattern::Nothing => write!(f, "none"),
+        }
+    }
+}
+
+/// Decides which crawled pages count as failures for the exit code.
+#[derive(Debug, Clone)]
+pub struct FailOnPolicy {
+    patterns: Vec<StatusPattern>,
+}
+
+impl FailOnPolicy {
+    pub fn new(patterns: Vec<StatusPattern>) -> Self {
+        Self { patterns }
+    }
+
+    pub fn is_failure(&self, page_summary: &PageSummary) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(page_summary))
+    }
+
+    pub fn failures<'a>(&self, page_summaries: &'a [PageSummary]) -> Vec<&'a PageSummary> {
+        page_summaries
+            .iter()
+            .filter(|page_summary| self.is_failure(page_summary))
+            .collect()
+    }
+}
+
+impl fmt::Display for FailOnPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", patterns.join(","))
+    }
+}
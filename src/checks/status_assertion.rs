@@ -0,0 +1,178 @@
+use crate::checks::fail_on_policy::StatusPattern;
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::scope::UrlPattern;
+use crate::crawler::page_summary::PageSummary;
+use anyhow::anyhow;
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+/// An invariant given to `--assert`, e.g. "/docs/ => 200" or "/admin/ => 401,403": every
+/// crawled page whose URL matches the pattern must have one of the expected statuses.
+#[derive(Debug, Clone)]
+pub struct StatusAssertion {
+    url_pattern: UrlPattern,
+    expected: Vec<StatusPattern>,
+}
+
+/// A crawled page that broke an assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionFailure {
+    pub assertion: String,
+    pub url: Url,
+    pub status_code: u16,
+}
+
+/// The outcome of checking every assertion against the crawled pages.
+#[derive(Debug, Clone, Default)]
+pub struct AssertionReport {
+    /// Each assertion with the number of pages it matched.
+    assertions: Vec<(String, usize)>,
+    pub failures: Vec<AssertionFailure>,
+}
+
+impl StatusAssertion {
+    pub fn matches_url(&self, url: &Url) -> bool {
+        self.url_pattern.matches(url)
+    }
+
+    pub fn is_satisfied_by(&self, page_summary: &PageSummary) -> bool {
+        self.expected
+            .iter()
+            .any(|pattern| pattern.matches(page_summary))
+    }
+}
+
+impl FromStr for StatusAssertion {
+    type Err = anyhow::Error;
+
+    /// Parses "PATTERN => STATUS[,STATUS...]", with statuses as accepted by `--fail-on`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (url_pattern, expected) = s
+            .split_once("=>")
+            .ok_or_else(|| anyhow!("Invalid assertion '{}' (expected e.g. \"/docs/ => 200\")", s))?;
+        if url_pattern.trim().is_empty() {
+            return Err(anyhow!("Invalid assertion '{}' (missing URL pattern)", s));
+        }
+        let url_pattern = url_pattern.parse::<UrlPattern>()?;
+        let expected = expected
+            .split(',')
+            .map(StatusPattern::from_str)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            url_pattern,
+            expected,
+        })
+    }
+}
+
+impl fmt::Display for StatusAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = self
+            .expected
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{} => {}", self.url_pattern, expected.join(","))
+    }
+}
+
+impl AssertionReport {
+    pub fn new(assertions: &[StatusAssertion], crawl_summaries: &[CrawlSummary]) -> Self {
+        let mut report = AssertionReport::default();
+        // Pages blocked by robots.txt were never fetched, so they have no real status
+        let page_summaries = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .filter(|page_summary| !page_summary.blocked_by_robots_txt)
+            .collect::<Vec<_>>();
+        for assertion in assertions {
+            let mut num_matched_pages = 0;
+            for page_summary in &page_summaries {
+                if !assertion.matches_url(&page_summary.url) {
+                    continue;
+                }
+                num_matched_pages += 1;
+                if !assertion.is_satisfied_by(page_summary) {
+                    report.failures.push(AssertionFailure {
+                        assertion: assertion.to_string(),
+                        url: page_summary.url.clone(),
+                        status_code: page_summary.status_code,
+                    });
+                }
+            }
+            report.assertions.push((assertion.to_string(), num_matched_pages));
+        }
+        report
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for AssertionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Assertions: {} checked, {} failing pages",
+            self.assertions.len(),
+            self.failures.len()
+        )?;
+        for (assertion, num_matched_pages) in &self.assertions {
+            let failures = self
+                .failures
+                .iter()
+                .filter(|failure| &failure.assertion == assertion)
+                .collect::<Vec<_>>();
+            if *num_matched_pages == 0 {
+                writeln!(f, "   {}: matched no pages", assertion)?;
+            } else if failures.is_empty() {
+                writeln!(f, "   {}: passed for {} pages", assertion, num_matched_pages)?;
+            } else {
+                writeln!(
+                    f,
+                    "   {}: failed for {} of {} pages",
+                    assertion,
+                    failures.len(),
+                    num_matched_pages
+                )?;
+                for failure in failures {
+                    writeln!(f, "      {} {}", failure.status_code, failure.url)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_pages_breaking_assertions() {
+        let page = |path: &str, status_code: u16| {
+            let url = Url::parse("https://example.com/").unwrap().join(path).unwrap();
+            PageSummary::from_status_code(url, 1, status_code)
+        };
+        let crawl_summary = CrawlSummary::new(vec![
+            page("/docs/a", 200),
+            page("/docs/b", 404),
+            page("/admin/", 401),
+        ]);
+        let assertions = ["/docs/ => 2xx", "/admin/ => 401,403", "/shop/ => 200"]
+            .map(|assertion| assertion.parse::<StatusAssertion>().unwrap());
+        let report = AssertionReport::new(&assertions, &[crawl_summary]);
+        assert_eq!(
+            report.failures,
+            vec![AssertionFailure {
+                assertion: "/docs/ => 2xx".to_string(),
+                url: Url::parse("https://example.com/docs/b").unwrap(),
+                status_code: 404,
+            }]
+        );
+        assert!("/docs/ 200".parse::<StatusAssertion>().is_err());
+        assert!("/docs/ => ok".parse::<StatusAssertion>().is_err());
+    }
+}
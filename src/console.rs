@@ -1,3 +1,5 @@
 pub mod console_progress_reporter;
 pub mod crawler_state;
-pub mod crawler_progress_event;
\ No newline at end of file
+pub mod crawler_progress_event;
+pub mod progress_mode;
+pub mod verbosity;
\ No newline at end of file
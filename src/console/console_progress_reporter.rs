@@ -1,11 +1,18 @@
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Stylize};
 use crossterm::{ExecutableCommand, QueueableCommand, queue};
-use std::collections::HashMap;
-use std::io::{Stdout, Write, stdout};
-use std::sync::Arc;
+use futures::StreamExt;
+use std::collections::{HashMap, VecDeque};
+use std::io::{Stderr, Write, stderr};
+use std::time::Duration;
 use tokio::select;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 use crate::console::crawler_progress_event::CrawlerProcessEvent;
 use crate::console::crawler_state::CrawlerState;
+use crate::console::progress_mode::ProgressMode;
+use crate::crawler::rate_limit::CircuitState;
 
 struct CrawlerInfo {
     index: usize,
@@ -13,47 +20,131 @@ struct CrawlerInfo {
     num_urls_to_crawl: usize,
     num_urls_crawled: usize,
     state: CrawlerState,
+    /// Current rate limit under adaptive throttling, in requests per second.
+    requests_per_second: Option<f64>,
     message: Option<String>,
+    /// Recent (elapsed, num_urls_crawled) samples used to estimate throughput.
+    throughput_samples: VecDeque<(Duration, usize)>,
+    /// When this crawler was last reported in plain progress mode.
+    last_plain_report: Option<Duration>,
+}
+
+impl CrawlerInfo {
+    /// Pages crawled per minute over the throughput window.
+    fn pages_per_minute(&self) -> Option<f64> {
+        let (first_elapsed, first_crawled) = self.throughput_samples.front()?;
+        let (last_elapsed, last_crawled) = self.throughput_samples.back()?;
+        let window = last_elapsed.saturating_sub(*first_elapsed).as_secs_f64();
+        if window <= 0.0 {
+            return None;
+        }
+        Some((last_crawled - first_crawled) as f64 * 60.0 / window)
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        let pages_per_minute = self.pages_per_minute()?;
+        if pages_per_minute <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(
+            self.num_urls_to_crawl as f64 * 60.0 / pages_per_minute,
+        ))
+    }
+}
+
+struct CompletedPageInfo {
+    crawler_index: usize,
+    url: Url,
+    status_code: u16,
+    depth: usize,
+    duration: Duration,
 }
 
 struct ConsoleState {
-    stdout: Stdout,
+    /// The progress display renders to stderr, leaving stdout free for results.
+    terminal: Stderr,
     crawlers: HashMap<usize, CrawlerInfo>,
+    completed_pages: VecDeque<CompletedPageInfo>,
+    /// Number of lines the results pane is scrolled up from the most recent page.
+    results_scroll: usize,
+    /// Whether the terminal is in raw mode, so key presses can be read for scrolling.
+    raw_mode: bool,
+    /// ID of the run, shown above the crawlers once it has started.
+    run_id: Option<String>,
+    /// The latest notice, shown below the run ID.
+    notice: Option<String>,
 }
 
-#[derive(Clone)]
+/// Maximum number of completed pages kept for the results pane.
+const MAX_COMPLETED_PAGES: usize = 1000;
+
+/// How far back progress samples are kept when estimating throughput.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Minimum time between progress lines for a crawler in plain progress mode.
+const PLAIN_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct ConsoleProcessReporter {
-    event_tx: Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::Sender<CrawlerProcessEvent>>>>,
+    mode: ProgressMode,
+    /// Events sent before the console starts are queued here, not lost.
+    event_rx: tokio::sync::mpsc::UnboundedReceiver<CrawlerProcessEvent>,
+}
+
+/// A reporter running in the background, which keeps the terminal until it is stopped.
+pub struct ConsoleReporterHandle {
+    stop_token: CancellationToken,
+    task: JoinHandle<anyhow::Result<()>>,
+}
+
+impl ConsoleReporterHandle {
+    /// Stops the reporter once it has shown the events already sent, and restores the terminal.
+    pub async fn stop(self) -> anyhow::Result<()> {
+        self.stop_token.cancel();
+        self.task.await?
+    }
 }
 
 impl ConsoleProcessReporter {
-    pub fn new() -> Self {
-        Self {
-            event_tx: Arc::new(tokio::sync::Mutex::new(None)),
-        }
+    /// A reporter for the events sent on the returned sender, which goes to the crawl.
+    pub fn new(
+        mode: ProgressMode,
+    ) -> (Self, tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>) {
+        let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel::<CrawlerProcessEvent>();
+        (Self { mode, event_rx }, event_tx)
     }
 
-    pub fn event_tx(&self) -> tokio::sync::mpsc::Sender<CrawlerProcessEvent> {
-        let event_tx = Arc::clone(&self.event_tx);
-        futures::executor::block_on(async move {
-            let mtx = event_tx.lock().await;
-            mtx.clone().unwrap()
-        })
+    /// Runs the reporter in the background, across any number of crawls, until the returned
+    /// handle stops it.
+    pub fn spawn(self, cancel_token: &CancellationToken) -> ConsoleReporterHandle {
+        let stop_token = cancel_token.child_token();
+        let task = tokio::task::spawn(self.run(cancel_token.clone(), stop_token.clone()));
+        ConsoleReporterHandle { stop_token, task }
     }
 
-    pub async fn run(&mut self, shutdown_notify: Arc<tokio::sync::Notify>) -> anyhow::Result<()> {
-        let (event_tx, mut event_rx) = tokio::sync::mpsc::channel::<CrawlerProcessEvent>(100);
-        {
-            let mut mtx = self.event_tx.lock().await;
-            mtx.replace(event_tx);
-        }
+    /// Shows progress until every sender is dropped or `stop_token` is cancelled, then
+    /// restores the terminal. Pressing Ctrl+C while the terminal is in raw mode cancels
+    /// `cancel_token`.
+    pub async fn run(
+        self,
+        cancel_token: CancellationToken,
+        stop_token: CancellationToken,
+    ) -> anyhow::Result<()> {
+        let Self { mode, mut event_rx } = self;
 
         let mut console_state = ConsoleState {
-            stdout: stdout(),
+            terminal: stderr(),
             crawlers: HashMap::new(),
+            completed_pages: VecDeque::new(),
+            results_scroll: 0,
+            raw_mode: false,
+            run_id: None,
+            notice: None,
         };
 
-        let _ = ConsoleProcessReporter::console_setup(&mut console_state).await;
+        if mode == ProgressMode::Tty {
+            let _ = ConsoleProcessReporter::console_setup(&mut console_state).await;
+        }
+        let mut terminal_events = console_state.raw_mode.then(EventStream::new);
 
         let mut shutdown_requested = false;
         while !shutdown_requested {
@@ -61,57 +152,129 @@ impl ConsoleProcessReporter {
                 progress_event = event_rx.recv() => {
                     match progress_event {
                         Some(progress_event) => {
-                            let _ = ConsoleProcessReporter::handle_event(progress_event, &mut console_state).await;
-                            let _ = ConsoleProcessReporter::console_redraw(&console_state).await;
-                        },
+                            let state = &mut console_state;
+                            ConsoleProcessReporter::show_event(mode, progress_event, state).await;
+                        }
                         None => {
                             shutdown_requested = true;
                         }
                     }
                 }
-                _ = shutdown_notify.notified() => {
+                terminal_event = async { terminal_events.as_mut().unwrap().next().await }, if terminal_events.is_some() => {
+                    if let Some(Ok(Event::Key(key_event))) = terminal_event {
+                        if ConsoleProcessReporter::is_interrupt_key(&key_event) {
+                            // Raw mode swallows Ctrl+C, so forward it as a shutdown request
+                            cancel_token.cancel();
+                            shutdown_requested = true;
+                        } else if ConsoleProcessReporter::handle_key(key_event, &mut console_state) {
+                            let _ = ConsoleProcessReporter::console_redraw(&console_state).await;
+                        }
+                    }
+                }
+                _ = stop_token.cancelled() => {
+                    // The crawl's last events are still shown
+                    while let Ok(progress_event) = event_rx.try_recv() {
+                        let state = &mut console_state;
+                        ConsoleProcessReporter::show_event(mode, progress_event, state).await;
+                    }
                     shutdown_requested = true;
                 }
             }
         }
 
-        let _ = ConsoleProcessReporter::console_teardown(&mut console_state).await;
-
-        {
-            let mut mtx = self.event_tx.lock().await;
-            mtx.take();
+        if mode == ProgressMode::Tty {
+            ConsoleProcessReporter::console_teardown(&mut console_state).await?;
+            // The alternate screen is gone, so the last notice is printed again
+            if let Some(notice) = &console_state.notice {
+                eprintln!("{}", notice);
+            }
         }
 
         Ok(())
     }
 
+    async fn show_event(
+        mode: ProgressMode,
+        progress_event: CrawlerProcessEvent,
+        state: &mut ConsoleState,
+    ) {
+        match mode {
+            ProgressMode::Tty => {
+                let _ = ConsoleProcessReporter::handle_event(progress_event, state).await;
+                let _ = ConsoleProcessReporter::console_redraw(state).await;
+            }
+            ProgressMode::Plain => {
+                ConsoleProcessReporter::plain_report(&progress_event, state);
+                let _ = ConsoleProcessReporter::handle_event(progress_event, state).await;
+            }
+            ProgressMode::None => {
+                // Nothing else owns the terminal
+                if let CrawlerProcessEvent::Notice { message } = progress_event {
+                    eprintln!("{}", message);
+                }
+            }
+        }
+    }
+
     async fn console_setup(state: &mut ConsoleState) -> anyhow::Result<()> {
-        let mut stdout = &state.stdout;
-        stdout.execute(crossterm::terminal::EnterAlternateScreen)?;
-        stdout.execute(crossterm::cursor::Hide)?;
-        stdout.execute(crossterm::terminal::Clear(
+        let mut terminal = &state.terminal;
+        terminal.execute(crossterm::terminal::EnterAlternateScreen)?;
+        state.raw_mode = crossterm::terminal::enable_raw_mode().is_ok();
+        terminal.execute(crossterm::cursor::Hide)?;
+        terminal.execute(crossterm::terminal::Clear(
             crossterm::terminal::ClearType::All,
         ))?;
         Ok(())
     }
 
     async fn console_teardown(state: &mut ConsoleState) -> anyhow::Result<()> {
-        let mut stdout = &state.stdout;
-        stdout.execute(crossterm::cursor::Show)?;
-        stdout.execute(crossterm::terminal::LeaveAlternateScreen)?;
+        let mut terminal = &state.terminal;
+        if state.raw_mode {
+            crossterm::terminal::disable_raw_mode()?;
+        }
+        terminal.execute(crossterm::cursor::Show)?;
+        terminal.execute(crossterm::terminal::LeaveAlternateScreen)?;
         Ok(())
     }
 
     async fn console_redraw(state: &ConsoleState) -> anyhow::Result<()> {
         let mut crawler_info = state.crawlers.values().collect::<Vec<&CrawlerInfo>>();
-        crawler_info.sort_by(|lhs, rhs| lhs.index.cmp(&rhs.index));
+        crawler_info.sort_by_key(|crawler_info| crawler_info.index);
 
-        let mut stdout = &state.stdout;
-        stdout.queue(crossterm::cursor::SavePosition)?;
+        let mut terminal = &state.terminal;
+        terminal.queue(crossterm::cursor::SavePosition)?;
+
+        let mut header_lines = 0;
+        if let Some(run_id) = &state.run_id {
+            queue!(
+                terminal,
+                crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+                crossterm::style::Print(format!("Run {}", run_id)),
+                crossterm::cursor::MoveToNextLine(1),
+            )?;
+            header_lines += 1;
+        }
+        if let Some(notice) = &state.notice {
+            queue!(
+                terminal,
+                crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+                crossterm::style::Print(notice),
+                crossterm::cursor::MoveToNextLine(1),
+            )?;
+            header_lines += 1;
+        }
+        if header_lines > 0 {
+            queue!(
+                terminal,
+                crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+                crossterm::cursor::MoveToNextLine(1),
+            )?;
+            header_lines += 1;
+        }
 
         for (index, crawler_info) in crawler_info.iter().enumerate() {
             if index > 0 {
-                queue!(stdout, crossterm::cursor::MoveToNextLine(2))?;
+                queue!(terminal, crossterm::cursor::MoveToNextLine(2))?;
             }
 
             let state_str = {
@@ -122,7 +285,7 @@ impl ConsoleProcessReporter {
             };
 
             queue!(
-                stdout,
+                terminal,
                 crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
                 crossterm::style::Print(format!(
                     "Crawling: {} ({})",
@@ -137,24 +300,230 @@ impl ConsoleProcessReporter {
                 )),
             )?;
 
+            if let Some(pages_per_minute) = crawler_info.pages_per_minute() {
+                queue!(
+                    terminal,
+                    crossterm::style::Print(format!(", {:.1} pages/min", pages_per_minute))
+                )?;
+            }
+            if let Some(requests_per_second) = crawler_info.requests_per_second {
+                queue!(
+                    terminal,
+                    crossterm::style::Print(format!(", rate: {:.1} req/s", requests_per_second))
+                )?;
+            }
+            if let Some(eta) = crawler_info.eta() {
+                queue!(
+                    terminal,
+                    crossterm::style::Print(format!(
+                        ", ETA: {}",
+                        ConsoleProcessReporter::format_duration(eta)
+                    ))
+                )?;
+            }
+
             if let Some(message) = &crawler_info.message {
                 queue!(
-                    stdout,
+                    terminal,
                     crossterm::style::Print(format!(", Message: {}", message))
                 )?;
             }
         }
-        stdout.queue(crossterm::cursor::RestorePosition)?;
-        stdout.flush()?;
+
+        if !crawler_info.is_empty() {
+            queue!(terminal, crossterm::cursor::MoveToNextLine(2))?;
+        }
+        let crawler_lines = (crawler_info.len() * 3).saturating_sub(1) as u16;
+        ConsoleProcessReporter::draw_results_pane(state, header_lines + crawler_lines)?;
+
+        queue!(
+            terminal,
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::FromCursorDown)
+        )?;
+        terminal.queue(crossterm::cursor::RestorePosition)?;
+        terminal.flush()?;
         Ok(())
     }
 
+    /// Draws the most recent completed pages, honoring the current scroll position.
+    fn draw_results_pane(state: &ConsoleState, lines_used: u16) -> anyhow::Result<()> {
+        let (width, height) = crossterm::terminal::size()?;
+        let visible_rows = height.saturating_sub(lines_used + 3) as usize;
+        let mut terminal = &state.terminal;
+
+        let num_pages = state.completed_pages.len();
+        let scroll = state.results_scroll.min(num_pages.saturating_sub(visible_rows));
+        let end = num_pages - scroll;
+        let start = end.saturating_sub(visible_rows);
+
+        queue!(
+            terminal,
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+            crossterm::style::Print(format!(
+                "Recent pages ({}-{} of {}, Up/Down/PgUp/PgDn to scroll):",
+                if num_pages > 0 { start + 1 } else { 0 },
+                end,
+                num_pages
+            )),
+        )?;
+        for page in state.completed_pages.range(start..end) {
+            let line = format!(
+                "   [{}] {} {:>6} ms  depth {}  {}",
+                page.crawler_index + 1,
+                page.status_code,
+                page.duration.as_millis(),
+                page.depth,
+                page.url
+            );
+            let line = line.chars().take(width as usize).collect::<String>();
+            queue!(
+                terminal,
+                crossterm::cursor::MoveToNextLine(1),
+                crossterm::terminal::Clear(crossterm::terminal::ClearType::CurrentLine),
+                crossterm::style::PrintStyledContent(
+                    line.with(ConsoleProcessReporter::status_color(page.status_code))
+                ),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Prints single-line progress to stderr. Progress updates are throttled per crawler.
+    fn plain_report(event: &CrawlerProcessEvent, state: &mut ConsoleState) {
+        match event {
+            CrawlerProcessEvent::RunStarted { run_id } => eprintln!("Run {}", run_id),
+            CrawlerProcessEvent::Notice { message } => eprintln!("{}", message),
+            CrawlerProcessEvent::Begin { crawler_index, url, .. } => {
+                eprintln!("[{}] Started {}", crawler_index + 1, url);
+            }
+            CrawlerProcessEvent::ProgressUpdate {
+                crawler_index,
+                elapsed,
+                ..
+            } => {
+                let Some(crawler_info) = state.crawlers.get_mut(crawler_index) else {
+                    return;
+                };
+                let report_due = crawler_info
+                    .last_plain_report
+                    .is_none_or(|last_report| *elapsed >= last_report + PLAIN_REPORT_INTERVAL);
+                if !report_due {
+                    return;
+                }
+                crawler_info.last_plain_report = Some(*elapsed);
+
+                let mut line = format!(
+                    "[{}] {}: {} crawled, {} remaining",
+                    crawler_index + 1,
+                    crawler_info.url,
+                    crawler_info.num_urls_crawled,
+                    crawler_info.num_urls_to_crawl
+                );
+                if let Some(pages_per_minute) = crawler_info.pages_per_minute() {
+                    line.push_str(&format!(", {:.1} pages/min", pages_per_minute));
+                }
+                if let Some(requests_per_second) = crawler_info.requests_per_second {
+                    line.push_str(&format!(", rate: {:.1} req/s", requests_per_second));
+                }
+                if let Some(eta) = crawler_info.eta() {
+                    line.push_str(&format!(
+                        ", ETA: {}",
+                        ConsoleProcessReporter::format_duration(eta)
+                    ));
+                }
+                eprintln!("{}", line);
+            }
+            CrawlerProcessEvent::CircuitStateChanged {
+                crawler_index,
+                host,
+                state: circuit_state,
+            } => {
+                eprintln!(
+                    "[{}] {}",
+                    crawler_index + 1,
+                    ConsoleProcessReporter::circuit_message(host, *circuit_state)
+                );
+            }
+            CrawlerProcessEvent::End { crawler_index } => {
+                if let Some(crawler_info) = state.crawlers.get(crawler_index) {
+                    eprintln!(
+                        "[{}] Finished {}: {} crawled",
+                        crawler_index + 1,
+                        crawler_info.url,
+                        crawler_info.num_urls_crawled
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn circuit_message(host: &str, circuit_state: CircuitState) -> String {
+        match circuit_state {
+            CircuitState::Open => format!("{} keeps failing; pausing it", host),
+            CircuitState::HalfOpen => format!("Retrying {} after its cooldown", host),
+            CircuitState::Closed => format!("{} has recovered", host),
+        }
+    }
+
+    fn format_duration(duration: Duration) -> String {
+        let total_seconds = duration.as_secs();
+        let (hours, minutes, seconds) = (
+            total_seconds / 3600,
+            (total_seconds / 60) % 60,
+            total_seconds % 60,
+        );
+        if hours > 0 {
+            format!("{}h {:02}m", hours, minutes)
+        } else if minutes > 0 {
+            format!("{}m {:02}s", minutes, seconds)
+        } else {
+            format!("{}s", seconds)
+        }
+    }
+
+    fn status_color(status_code: u16) -> Color {
+        match status_code {
+            200..=299 => Color::Green,
+            300..=399 => Color::Cyan,
+            400..=499 => Color::Yellow,
+            _ => Color::Red,
+        }
+    }
+
+    fn is_interrupt_key(key_event: &KeyEvent) -> bool {
+        key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL)
+    }
+
+    /// Scrolls the results pane. Returns true if the console needs to be redrawn.
+    fn handle_key(key_event: KeyEvent, state: &mut ConsoleState) -> bool {
+        if key_event.kind == KeyEventKind::Release {
+            return false;
+        }
+        let page_size = crossterm::terminal::size()
+            .map(|(_, height)| (height / 2) as usize)
+            .unwrap_or(10);
+        let max_scroll = state.completed_pages.len().saturating_sub(1);
+        let results_scroll = match key_event.code {
+            KeyCode::Up => state.results_scroll + 1,
+            KeyCode::Down => state.results_scroll.saturating_sub(1),
+            KeyCode::PageUp => state.results_scroll + page_size,
+            KeyCode::PageDown => state.results_scroll.saturating_sub(page_size),
+            KeyCode::Home => max_scroll,
+            KeyCode::End => 0,
+            _ => return false,
+        };
+        state.results_scroll = results_scroll.min(max_scroll);
+        true
+    }
+
     async fn handle_event(
         event: CrawlerProcessEvent,
         state: &mut ConsoleState,
     ) -> anyhow::Result<()> {
         match event {
-            CrawlerProcessEvent::Begin { crawler_index, url } => {
+            CrawlerProcessEvent::RunStarted { run_id } => state.run_id = Some(run_id),
+            CrawlerProcessEvent::Begin { crawler_index, url, .. } => {
                 state.crawlers.insert(
                     crawler_index,
                     CrawlerInfo {
@@ -163,7 +532,10 @@ impl ConsoleProcessReporter {
                         num_urls_to_crawl: 0,
                         num_urls_crawled: 0,
                         state: CrawlerState::Paused,
+                        requests_per_second: None,
                         message: None,
+                        throughput_samples: VecDeque::new(),
+                        last_plain_report: None,
                     },
                 );
             }
@@ -171,10 +543,20 @@ impl ConsoleProcessReporter {
                 crawler_index,
                 num_urls_crawled,
                 num_urls_to_crawl,
+                elapsed,
             } => {
                 if let Some(crawler_info) = state.crawlers.get_mut(&crawler_index) {
                     crawler_info.num_urls_crawled = num_urls_crawled;
                     crawler_info.num_urls_to_crawl = num_urls_to_crawl;
+
+                    let samples = &mut crawler_info.throughput_samples;
+                    samples.push_back((elapsed, num_urls_crawled));
+                    while samples
+                        .front()
+                        .is_some_and(|(sample_elapsed, _)| elapsed - *sample_elapsed > THROUGHPUT_WINDOW)
+                    {
+                        samples.pop_front();
+                    }
                 }
             }
             CrawlerProcessEvent::ProgressMessage {
@@ -185,6 +567,30 @@ impl ConsoleProcessReporter {
                     crawler_info.message = Some(message);
                 }
             }
+            CrawlerProcessEvent::PageCompleted {
+                crawler_index,
+                url,
+                status_code,
+                depth,
+                duration,
+            } => {
+                if state.completed_pages.len() == MAX_COMPLETED_PAGES {
+                    state.completed_pages.pop_front();
+                }
+                state
+                    .completed_pages
+                    .push_back(CompletedPageInfo {
+                        crawler_index,
+                        url,
+                        status_code,
+                        depth,
+                        duration,
+                    });
+                // Keep the same pages in view while the user is scrolled back
+                if state.results_scroll > 0 {
+                    state.results_scroll += 1;
+                }
+            }
             CrawlerProcessEvent::CrawlerStateChanged {
                 crawler_index,
                 state: crawler_state,
@@ -193,10 +599,75 @@ impl ConsoleProcessReporter {
                     crawler_info.state = crawler_state;
                 }
             }
+            CrawlerProcessEvent::RateChanged {
+                crawler_index,
+                requests_per_second,
+            } => {
+                if let Some(crawler_info) = state.crawlers.get_mut(&crawler_index) {
+                    crawler_info.requests_per_second = Some(requests_per_second);
+                }
+            }
+            CrawlerProcessEvent::CircuitStateChanged {
+                crawler_index,
+                host,
+                state: circuit_state,
+            } => {
+                if let Some(crawler_info) = state.crawlers.get_mut(&crawler_index) {
+                    crawler_info.message =
+                        Some(ConsoleProcessReporter::circuit_message(&host, circuit_state));
+                }
+            }
             CrawlerProcessEvent::End { crawler_index } => {
                 state.crawlers.remove(&crawler_index);
             }
+            CrawlerProcessEvent::Notice { message } => state.notice = Some(message),
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stops_once_the_crawl_drops_its_sender() {
+        let (console_reporter, event_tx) = ConsoleProcessReporter::new(ProgressMode::None);
+        let cancel_token = CancellationToken::new();
+        let run = tokio::task::spawn(console_reporter.run(cancel_token, CancellationToken::new()));
+        event_tx.send(CrawlerProcessEvent::End { crawler_index: 0 }).unwrap();
+        drop(event_tx);
+        let finished = tokio::time::timeout(Duration::from_secs(5), run).await;
+        assert!(finished.unwrap().unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn stops_when_told_to_even_with_senders_left() {
+        let (console_reporter, _event_tx) = ConsoleProcessReporter::new(ProgressMode::None);
+        let cancel_token = CancellationToken::new();
+        let stop_token = cancel_token.child_token();
+        let run = console_reporter.run(cancel_token.clone(), stop_token.clone());
+        let run = tokio::task::spawn(run);
+        stop_token.cancel();
+        let finished = tokio::time::timeout(Duration::from_secs(5), run).await;
+        assert!(finished.unwrap().unwrap().is_ok());
+        // Only the reporter stops, not the crawl
+        assert!(!cancel_token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn keeps_running_between_crawls_until_stopped() {
+        let (console_reporter, event_tx) = ConsoleProcessReporter::new(ProgressMode::None);
+        let cancel_token = CancellationToken::new();
+        let console_reporter = console_reporter.spawn(&cancel_token);
+        for _ in 0..2 {
+            // Each crawl holds a clone of the sender and drops it when it ends
+            let crawl_event_tx = event_tx.clone();
+            crawl_event_tx.send(CrawlerProcessEvent::End { crawler_index: 0 }).unwrap();
+            drop(crawl_event_tx);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert!(!console_reporter.task.is_finished());
+        }
+        let stopped = tokio::time::timeout(Duration::from_secs(5), console_reporter.stop()).await;
+        assert!(stopped.unwrap().is_ok());
+    }
+}
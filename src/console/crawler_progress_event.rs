@@ -1,26 +1,69 @@
+use std::time::Duration;
 use url::Url;
 use crate::console::crawler_state::CrawlerState;
+use crate::crawler::rate_limit::CircuitState;
 
-#[derive(Debug, Clone)]
+/// Serialized with a `type` tag, e.g. `{"type": "end", "crawler_index": 0}`.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum CrawlerProcessEvent {
+    /// Sent once before the crawlers begin.
+    RunStarted {
+        run_id: String,
+    },
     Begin {
         crawler_index: usize,
         url: Url,
+        /// ID of the seed's crawl, the same as in its results and published events.
+        seed_id: String,
     },
     ProgressUpdate {
         crawler_index: usize,
         num_urls_to_crawl: usize,
         num_urls_crawled: usize,
+        /// Time since the crawler began, serialized as fractional seconds.
+        #[serde(serialize_with = "serialize_secs")]
+        elapsed: Duration,
     },
     ProgressMessage {
         crawler_index: usize,
         message: String,
     },
+    PageCompleted {
+        crawler_index: usize,
+        url: Url,
+        status_code: u16,
+        /// Links from the seed to the page.
+        depth: usize,
+        /// Time taken to fetch and process the page, serialized as fractional seconds.
+        #[serde(serialize_with = "serialize_secs")]
+        duration: Duration,
+    },
+    /// The crawler's current request rate limit changed, under adaptive throttling.
+    RateChanged {
+        crawler_index: usize,
+        requests_per_second: f64,
+    },
     CrawlerStateChanged {
         crawler_index: usize,
         state: CrawlerState,
     },
+    /// The circuit breaker of a host the crawler is crawling opened, closed or let a trial
+    /// request through.
+    CircuitStateChanged {
+        crawler_index: usize,
+        host: String,
+        state: CircuitState,
+    },
     End {
         crawler_index: usize,
     },
+    /// A message about the whole run rather than one crawler, e.g. a monitor run's outcome.
+    Notice {
+        message: String,
+    },
+}
+
+fn serialize_secs<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CrawlerState {
     Crawling,
     Paused,
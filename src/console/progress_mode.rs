@@ -0,0 +1,24 @@
+use std::io::IsTerminal;
+
+/// How crawl progress is presented while crawling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProgressMode {
+    /// Full-screen terminal UI
+    Tty,
+    /// Periodic single-line updates on stderr, for CI logs and redirected output
+    Plain,
+    /// No progress output
+    None,
+}
+
+impl ProgressMode {
+    /// The full-screen UI when stderr is a terminal, and plain lines otherwise, e.g. when
+    /// running in a container or with redirected output.
+    pub fn for_stderr() -> Self {
+        if std::io::stderr().is_terminal() {
+            ProgressMode::Tty
+        } else {
+            ProgressMode::Plain
+        }
+    }
+}
@@ -0,0 +1,22 @@
+/// How much output is written besides progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only fatal errors
+    Quiet,
+    /// Per-page results
+    Normal,
+    /// Per-page results plus statistics and a summary of failing pages on stderr
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        if quiet {
+            Verbosity::Quiet
+        } else if verbose > 0 {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}
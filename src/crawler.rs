@@ -1,9 +1,37 @@
+pub mod accessibility;
+pub mod checkpoint;
+pub mod content;
+pub mod crawl_statistics;
 pub mod crawl_summary;
-mod crawl_response;
+pub mod crawl_response;
 mod crawl_error;
-mod page_summary;
+pub mod distributed;
+pub mod entry_point;
+pub mod fetch;
+pub mod page_summary;
 pub mod crawler_config;
+pub mod events;
+pub mod extract;
+pub mod feed;
+pub mod hreflang;
+pub mod http;
+pub mod link_anchor;
+pub mod memory_guard;
 pub mod multi;
-mod robots;
-mod seed;
-mod page;
+pub mod normalize;
+pub mod pagination;
+pub mod plugin;
+pub mod rate_limit;
+pub mod robots;
+pub mod schema;
+pub mod script;
+pub mod scope;
+pub mod search;
+pub mod seed;
+pub mod page;
+mod shared_crawl_state;
+mod sitemap;
+mod soft_404_detector;
+pub mod store;
+pub mod text;
+pub mod visited_filter;
@@ -0,0 +1,3 @@
+mod accessibility_issues;
+
+pub use accessibility_issues::AccessibilityIssues;
@@ -0,0 +1,147 @@
+use scraper::Selector;
+use std::fmt;
+use std::sync::LazyLock;
+
+static IMAGE_WITHOUT_ALT: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("img:not([alt])").unwrap());
+static LINK: LazyLock<Selector> = LazyLock::new(|| Selector::parse("a[href]").unwrap());
+static BUTTON: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("button, [role=button]").unwrap());
+static HEADING: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("h1, h2, h3, h4, h5, h6").unwrap());
+static HTML_LANG: LazyLock<Selector> = LazyLock::new(|| Selector::parse("html[lang]").unwrap());
+static IMAGE_WITH_ALT: LazyLock<Selector> = LazyLock::new(|| Selector::parse("img[alt]").unwrap());
+
+/// Counts of common accessibility problems in a page, or totals over several pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AccessibilityIssues {
+    /// `<img>` elements with no alt attribute. An empty alt marks a decorative image and is fine.
+    pub images_without_alt: usize,
+    /// Links with no text, image alt text or label for a screen reader to announce.
+    pub empty_links: usize,
+    /// Buttons with no text or label.
+    pub empty_buttons: usize,
+    /// Headings more than one level below the previous heading, e.g. an h4 after an h2.
+    pub skipped_heading_levels: usize,
+    /// Pages whose `<html>` element has no lang attribute.
+    pub missing_lang: usize,
+}
+
+impl AccessibilityIssues {
+    pub fn from_document(document: &scraper::Html) -> Self {
+        let images_without_alt = document.select(&IMAGE_WITHOUT_ALT).count();
+        let empty_links = document
+            .select(&LINK)
+            .filter(|link| !Self::has_accessible_name(*link))
+            .count();
+        let empty_buttons = document
+            .select(&BUTTON)
+            .filter(|button| !Self::has_accessible_name(*button))
+            .count();
+
+        let mut skipped_heading_levels = 0;
+        let mut previous_level = None;
+        for heading in document.select(&HEADING) {
+            let level = heading.value().name()[1..].parse::<usize>().unwrap_or(1);
+            if previous_level.is_some_and(|previous_level| level > previous_level + 1) {
+                skipped_heading_levels += 1;
+            }
+            previous_level = Some(level);
+        }
+
+        let has_lang = document
+            .select(&HTML_LANG)
+            .next()
+            .and_then(|html| html.value().attr("lang"))
+            .is_some_and(|lang| !lang.trim().is_empty());
+
+        Self {
+            images_without_alt,
+            empty_links,
+            empty_buttons,
+            skipped_heading_levels,
+            missing_lang: usize::from(!has_lang),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.images_without_alt
+            + self.empty_links
+            + self.empty_buttons
+            + self.skipped_heading_levels
+            + self.missing_lang
+    }
+
+    pub fn add(&mut self, other: &AccessibilityIssues) {
+        self.images_without_alt += other.images_without_alt;
+        self.empty_links += other.empty_links;
+        self.empty_buttons += other.empty_buttons;
+        self.skipped_heading_levels += other.skipped_heading_levels;
+        self.missing_lang += other.missing_lang;
+    }
+
+    /// Whether an element has text, an image with alt text, or a label for assistive
+    /// technology to announce.
+    fn has_accessible_name(element: scraper::ElementRef) -> bool {
+        let has_label = ["aria-label", "aria-labelledby", "title"].iter().any(|attribute| {
+            element.value().attr(attribute).is_some_and(|label| !label.trim().is_empty())
+        });
+        let has_text = element.text().any(|text| !text.trim().is_empty());
+        let has_img_alt = element
+            .select(&IMAGE_WITH_ALT)
+            .any(|img| img.value().attr("alt").is_some_and(|alt| !alt.trim().is_empty()));
+        has_label || has_text || has_img_alt
+    }
+}
+
+impl fmt::Display for AccessibilityIssues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = [
+            ("images without alt", self.images_without_alt),
+            ("empty links", self.empty_links),
+            ("empty buttons", self.empty_buttons),
+            ("skipped heading levels", self.skipped_heading_levels),
+            ("missing lang", self.missing_lang),
+        ];
+        let counts = counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(label, count)| format!("{}: {}", label, count))
+            .collect::<Vec<_>>();
+        if counts.is_empty() {
+            write!(f, "no issues")
+        } else {
+            write!(f, "{}", counts.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_missing_alt_text_empty_controls_heading_skips_and_lang() {
+        let document = scraper::Html::parse_document(
+            r#"<html><body>
+            <h1>Title</h1><h3>Skipped h2</h3><h4>Fine</h4><h2>Back up</h2><h5>Skipped</h5>
+            <img src="a.png"><img src="b.png" alt="">
+            <a href="/a">Text</a><a href="/b"><img src="c.png"></a>
+            <a href="/c"><img src="d.png" alt="Logo"></a><a href="/d" aria-label="Close"></a>
+            <button></button><button>OK</button>
+            </body></html>"#,
+        );
+        assert_eq!(
+            AccessibilityIssues::from_document(&document),
+            AccessibilityIssues {
+                images_without_alt: 2,
+                empty_links: 1,
+                empty_buttons: 1,
+                skipped_heading_levels: 2,
+                missing_lang: 1,
+            }
+        );
+        let document = scraper::Html::parse_document(r#"<html lang="en"><h1>Fine</h1></html>"#);
+        assert_eq!(AccessibilityIssues::from_document(&document).total(), 0);
+    }
+}
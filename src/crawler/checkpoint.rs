@@ -0,0 +1,9 @@
+mod checkpoint_file;
+mod checkpoint_interval;
+mod seed_checkpoint;
+mod versioned_state;
+
+pub use checkpoint_file::CheckpointFile;
+pub use checkpoint_interval::CheckpointInterval;
+pub use seed_checkpoint::SeedCheckpoint;
+pub use versioned_state::{Migration, VersionedState, fill_missing_fields};
@@ -0,0 +1,78 @@
+use anyhow::{Context, anyhow};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// First word of a checkpoint file's header line.
+const CHECKPOINT_MAGIC: &str = "rusty-spider-checkpoint";
+
+/// A state file that is never left half-written.
+///
+/// The file starts with a header line holding the SHA-256 of the payload that follows, so a
+/// truncated or corrupted file is detected on load. Saving writes a temporary file next to
+/// it, syncs it and renames it over the old one, so a crash mid-save leaves the previous
+/// snapshot intact.
+pub struct CheckpointFile {
+    path: PathBuf,
+}
+
+impl CheckpointFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn save(&self, payload: &[u8]) -> anyhow::Result<()> {
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        {
+            let mut file = File::create(&temp_path).with_context(|| {
+                format!("Unable to create checkpoint '{}'", temp_path.display())
+            })?;
+            writeln!(file, "{} {:x}", CHECKPOINT_MAGIC, Sha256::digest(payload))?;
+            file.write_all(payload)?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&temp_path, &self.path).with_context(|| {
+            format!("Unable to replace checkpoint '{}'", self.path.display())
+        })?;
+        Ok(())
+    }
+
+    /// Loads the payload of the last snapshot, or None if there is none.
+    pub fn load(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        let content = match std::fs::read(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Unable to read checkpoint '{}'", self.path.display())
+                });
+            }
+        };
+        let corrupt = || anyhow!("Checkpoint '{}' is corrupt", self.path.display());
+        let header_end = content.iter().position(|&b| b == b'\n').ok_or_else(corrupt)?;
+        let header = std::str::from_utf8(&content[..header_end]).map_err(|_| corrupt())?;
+        let checksum = header
+            .strip_prefix(CHECKPOINT_MAGIC)
+            .map(str::trim)
+            .ok_or_else(corrupt)?;
+        let payload = &content[header_end + 1..];
+        if format!("{:x}", Sha256::digest(payload)) != checksum {
+            return Err(corrupt());
+        }
+        Ok(Some(payload.to_vec()))
+    }
+
+    pub fn remove(&self) -> anyhow::Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
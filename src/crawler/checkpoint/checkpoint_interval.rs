@@ -0,0 +1,55 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How often a seed's crawl state is checkpointed: "100" for every 100 pages, or a
+/// duration such as "30s" or "5m".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointInterval {
+    Pages(usize),
+    Time(Duration),
+}
+
+impl CheckpointInterval {
+    /// Whether a checkpoint is due, `pages` pages and `elapsed` time after the last one.
+    pub fn is_due(&self, pages: usize, elapsed: Duration) -> bool {
+        match self {
+            CheckpointInterval::Pages(interval) => pages >= *interval,
+            CheckpointInterval::Time(interval) => elapsed >= *interval,
+        }
+    }
+}
+
+impl Default for CheckpointInterval {
+    fn default() -> Self {
+        CheckpointInterval::Pages(100)
+    }
+}
+
+impl FromStr for CheckpointInterval {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(pages) = s.trim().parse::<usize>() {
+            if pages == 0 {
+                return Err(anyhow::anyhow!("Checkpoint interval must be at least 1 page"));
+            }
+            return Ok(CheckpointInterval::Pages(pages));
+        }
+        let duration = humantime::parse_duration(s).map_err(|_| {
+            anyhow::anyhow!("Invalid checkpoint interval '{}': expected a page count or a duration", s)
+        })?;
+        Ok(CheckpointInterval::Time(duration))
+    }
+}
+
+impl Display for CheckpointInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointInterval::Pages(pages) => write!(f, "{}", pages),
+            CheckpointInterval::Time(duration) => {
+                write!(f, "{}", humantime::format_duration(*duration))
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::crawler::checkpoint::{Migration, VersionedState, fill_missing_fields};
+use crate::crawler::page_summary::PageSummary;
+use crate::crawler::seed::QueuedUrl;
+use serde_json::Value;
+use std::collections::HashMap;
+use url::Url;
+
+/// Everything needed to resume a seed's crawl: what is left to crawl, what has been
+/// crawled, and the results so far.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SeedCheckpoint {
+    pub seed: Url,
+    pub pending: Vec<QueuedUrl>,
+    /// URLs already crawled, including those excluded from the results.
+    pub crawled: Vec<Url>,
+    pub pages: Vec<PageSummary>,
+    /// Number of times each queued URL was found linked so far, empty when discoveries aren't
+    /// counted. Missing from older checkpoints.
+    #[serde(default)]
+    pub discovery_counts: HashMap<Url, usize>,
+}
+
+impl VersionedState for SeedCheckpoint {
+    const MIGRATIONS: &'static [Migration] = &[fill_in_page_fields];
+}
+
+/// Version 2: pages saved before checkpoints were versioned lack the fields added to page
+/// summaries since, which take the values of a page with no response.
+fn fill_in_page_fields(checkpoint: &mut Value) -> anyhow::Result<()> {
+    let Some(pages) = checkpoint.get_mut("pages").and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+    let placeholder_url = Url::parse("http://localhost/")?;
+    let template = serde_json::to_value(PageSummary::from_status_code(placeholder_url, 0, 0))?;
+    for page in pages {
+        fill_missing_fields(page, &template);
+    }
+    Ok(())
+}
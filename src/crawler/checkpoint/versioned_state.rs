@@ -0,0 +1,119 @@
+use anyhow::{Context, anyhow};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Upgrades a serialized state in place from one schema version to the next.
+pub type Migration = fn(&mut Value) -> anyhow::Result<()>;
+
+/// State kept in a `CheckpointFile` across runs, and so across upgrades of the crawler.
+///
+/// The payload records the schema version it was written with, as
+/// `{"schema_version": N, "state": ...}`. Loading an older payload runs the migrations from
+/// its version up to the current one before deserializing it; payloads written before
+/// states were versioned are the bare state, and count as version 1. Fields the current
+/// layout does not know, e.g. from a newer build that did not change the version, are
+/// ignored.
+pub trait VersionedState: Serialize + DeserializeOwned {
+    /// Steps that each upgrade the state by one version, oldest first: the first turns
+    /// version 1 into version 2. Add a step whenever a change to the layout would keep
+    /// older payloads from deserializing, e.g. a new field without a serde default.
+    const MIGRATIONS: &'static [Migration];
+
+    /// The schema version this build writes: one more than the number of migrations.
+    fn schema_version() -> u32 {
+        Self::MIGRATIONS.len() as u32 + 1
+    }
+
+    fn to_payload(&self) -> anyhow::Result<Vec<u8>> {
+        let payload = serde_json::json!({
+            "schema_version": Self::schema_version(),
+            "state": serde_json::to_value(self)?,
+        });
+        Ok(serde_json::to_vec(&payload)?)
+    }
+
+    fn from_payload(payload: &[u8]) -> anyhow::Result<Self> {
+        let mut payload: Value = serde_json::from_slice(payload)?;
+        let (version, mut state) = match payload.get("schema_version").and_then(Value::as_u64) {
+            Some(version) => {
+                let state = payload
+                    .get_mut("state")
+                    .map(Value::take)
+                    .ok_or_else(|| anyhow!("Missing state"))?;
+                (version, state)
+            }
+            None => (1, payload),
+        };
+        if version == 0 {
+            return Err(anyhow!("Invalid schema version 0"));
+        }
+        let current_version = u64::from(Self::schema_version());
+        if version > current_version {
+            return Err(anyhow!(
+                "Written by a newer version of the crawler, with schema version {}; this \
+                 version reads up to {}",
+                version,
+                current_version
+            ));
+        }
+        let migrations = &Self::MIGRATIONS[(version - 1) as usize..];
+        for (from_version, migration) in (version..).zip(migrations) {
+            migration(&mut state).with_context(|| {
+                format!("Unable to migrate from schema version {}", from_version)
+            })?;
+        }
+        Ok(serde_json::from_value(state)?)
+    }
+}
+
+/// Adds the fields of `template` that `target` lacks, recursing into objects both have, so
+/// that fields added to a layout take the values they have in `template`.
+pub fn fill_missing_fields(target: &mut Value, template: &Value) {
+    let (Value::Object(target), Value::Object(template)) = (target, template) else {
+        return;
+    };
+    for (name, template_value) in template {
+        match target.get_mut(name) {
+            Some(value) => fill_missing_fields(value, template_value),
+            None => {
+                target.insert(name.clone(), template_value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct State {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    impl VersionedState for State {
+        const MIGRATIONS: &'static [Migration] = &[|state| {
+            fill_missing_fields(state, &serde_json::json!({ "tags": [] }));
+            Ok(())
+        }];
+    }
+
+    #[test]
+    fn migrates_unversioned_payloads_and_rejects_newer_ones() {
+        let state = State {
+            name: "a".to_string(),
+            tags: vec!["b".to_string()],
+        };
+        assert_eq!(State::from_payload(&state.to_payload().unwrap()).unwrap(), state);
+
+        let unversioned = br#"{"name": "a", "dropped": 1}"#;
+        let migrated = State::from_payload(unversioned).unwrap();
+        assert_eq!(migrated.tags, Vec::<String>::new());
+
+        let newer = br#"{"schema_version": 3, "state": {"name": "a", "tags": []}}"#;
+        let error = State::from_payload(newer).unwrap_err();
+        assert!(error.to_string().contains("schema version 3"));
+    }
+}
@@ -0,0 +1,17 @@
+mod content_handler;
+mod content_handlers;
+mod content_kind;
+mod html_handler;
+mod pdf_document;
+mod pdf_handler;
+mod plain_text_handler;
+mod skip_handler;
+
+pub use content_handler::{ContentHandler, Document, ParseOptions};
+pub use content_handlers::ContentHandlers;
+pub use content_kind::ContentKind;
+pub use html_handler::HtmlHandler;
+pub use pdf_document::PdfDocument;
+pub use pdf_handler::PdfHandler;
+pub use plain_text_handler::PlainTextHandler;
+pub use skip_handler::SkipHandler;
@@ -0,0 +1,38 @@
+use crate::crawler::crawl_response::CrawlResponse;
+use crate::crawler::extract::ExtractRule;
+use crate::crawler::page::LinkExtractor;
+use std::sync::Arc;
+use url::Url;
+
+/// A fetched document, ready to be parsed by a content handler.
+pub struct Document<'a> {
+    pub url: &'a Url,
+    pub status_code: u16,
+    /// The Content-Type header as it was sent.
+    pub content_type: &'a str,
+    /// The parsed Content-Type, application/octet-stream if it was missing or malformed.
+    pub mime_type: &'a mime::Mime,
+    /// The body with its Content-Encoding undone.
+    pub body: &'a [u8],
+}
+
+/// Settings of the crawl that affect how documents are parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Root that root-relative links are resolved against, for local site builds.
+    pub site_root: Option<Url>,
+    pub extract_rules: Vec<ExtractRule>,
+    pub accessibility_checks: bool,
+    pub link_extractor: Arc<LinkExtractor>,
+    /// Parse HTML with `StreamingHtmlParser` instead of building a DOM.
+    pub streaming_html: bool,
+}
+
+/// Parses the documents of the content types it handles into crawl responses.
+pub trait ContentHandler: Send + Sync {
+    /// Whether this handler parses documents of `mime_type`.
+    fn handles(&self, mime_type: &mime::Mime) -> bool;
+
+    /// Parses a document into its title, links and text, or returns None to skip it.
+    fn parse(&self, document: &Document, options: &ParseOptions) -> Option<CrawlResponse>;
+}
@@ -0,0 +1,78 @@
+use crate::crawler::content::{
+    ContentHandler, ContentKind, HtmlHandler, PdfHandler, PlainTextHandler, SkipHandler,
+};
+
+/// The content handlers of a crawl, selected by the MIME type of each document.
+pub struct ContentHandlers {
+    handlers: Vec<Box<dyn ContentHandler>>,
+}
+
+impl ContentHandlers {
+    /// Handlers for the given kinds of content; documents of any other type are skipped.
+    pub fn new(content_kinds: &[ContentKind]) -> Self {
+        let handlers = content_kinds
+            .iter()
+            .map(|content_kind| -> Box<dyn ContentHandler> {
+                match content_kind {
+                    ContentKind::Html => Box::new(HtmlHandler),
+                    ContentKind::PlainText => Box::new(PlainTextHandler),
+                    ContentKind::Pdf => Box::new(PdfHandler),
+                }
+            })
+            .collect();
+        Self { handlers }
+    }
+
+    /// The first handler for `mime_type`, falling back to skipping the document.
+    pub fn handler_for(&self, mime_type: &mime::Mime) -> &dyn ContentHandler {
+        self.handlers
+            .iter()
+            .find(|handler| handler.handles(mime_type))
+            .map_or(&SkipHandler, |handler| handler.as_ref())
+    }
+}
+
+impl Default for ContentHandlers {
+    fn default() -> Self {
+        Self::new(&[ContentKind::Html])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::content::{Document, ParseOptions};
+    use url::Url;
+
+    fn parse(content_handlers: &ContentHandlers, content_type: &str, body: &str) -> Option<String> {
+        let url = Url::parse("https://example.com/doc").unwrap();
+        let mime_type = content_type.parse().unwrap();
+        let document = Document {
+            url: &url,
+            status_code: 200,
+            content_type,
+            mime_type: &mime_type,
+            body: body.as_bytes(),
+        };
+        content_handlers
+            .handler_for(&mime_type)
+            .parse(&document, &ParseOptions::default())
+            .map(|crawl_response| crawl_response.body_text)
+    }
+
+    #[test]
+    fn selects_handlers_by_mime_type_and_skips_the_rest() {
+        let content_handlers = ContentHandlers::new(&[ContentKind::Html, ContentKind::PlainText]);
+        let html = "<html><body><p>Hello  <b>world</b></p></body></html>";
+        assert_eq!(
+            parse(&content_handlers, "text/html; charset=utf-8", html),
+            Some("Hello world".to_string())
+        );
+        assert_eq!(
+            parse(&content_handlers, "text/plain", "Hello\n  world\n"),
+            Some("Hello world".to_string())
+        );
+        assert_eq!(parse(&content_handlers, "image/png", ""), None);
+        assert_eq!(parse(&ContentHandlers::default(), "text/plain", "Hello"), None);
+    }
+}
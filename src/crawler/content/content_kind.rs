@@ -0,0 +1,10 @@
+/// Kinds of content that can be parsed rather than skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContentKind {
+    /// HTML pages, parsed for their title, links and metadata
+    Html,
+    /// Plain text documents, kept for their text
+    PlainText,
+    /// PDF documents, parsed for their title, page count and links
+    Pdf,
+}
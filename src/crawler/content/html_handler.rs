@@ -0,0 +1,28 @@
+use crate::crawler::content::{ContentHandler, Document, ParseOptions};
+use crate::crawler::crawl_response::CrawlResponse;
+use crate::crawler::http::ContentDecoder;
+use crate::crawler::page::{PageCrawler, StreamingHtmlParser};
+
+/// Parses HTML pages for their title, links and metadata.
+pub struct HtmlHandler;
+
+impl ContentHandler for HtmlHandler {
+    fn handles(&self, mime_type: &mime::Mime) -> bool {
+        mime_type.type_() == mime::TEXT && mime_type.subtype() == mime::HTML
+    }
+
+    fn parse(&self, document: &Document, options: &ParseOptions) -> Option<CrawlResponse> {
+        let parse_html = if options.streaming_html {
+            StreamingHtmlParser::parse_html
+        } else {
+            PageCrawler::parse_html
+        };
+        Some(parse_html(
+            document.url,
+            document.status_code,
+            document.content_type.to_string(),
+            ContentDecoder::decode_text(document.body, document.mime_type),
+            options,
+        ))
+    }
+}
@@ -0,0 +1,227 @@
+use regex::bytes::Regex;
+use std::io::Read;
+use std::sync::LazyLock;
+
+/// A stream and the dictionary describing it.
+static STREAM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s-u)<<(.*?)>>\s*stream\r?\n").unwrap());
+
+/// A page object; `/Type /Pages` nodes of the page tree don't match.
+static PAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?-u)/Type\s*/Page\b").unwrap());
+
+/// The reference to the document information dictionary in the trailer.
+static INFO: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?-u)/Info\s+(\d+)\s+(\d+)\s+R").unwrap());
+
+static TITLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?-u)/Title\s*").unwrap());
+
+static URI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?-u)/URI\s*").unwrap());
+
+/// The title, page count and links of a PDF file.
+///
+/// PDFs are scanned for their objects rather than fully parsed: Flate-compressed streams,
+/// which hold the objects of PDF 1.5+ files, are inflated and searched along with the rest
+/// of the file. Encrypted files yield only their page count.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PdfDocument {
+    /// The Title entry of the document information dictionary.
+    pub title: Option<String>,
+    pub num_pages: usize,
+    /// Targets of the document's URI link actions, as written.
+    pub uris: Vec<String>,
+}
+
+impl PdfDocument {
+    pub fn parse(bytes: &[u8]) -> Self {
+        let mut searchable = bytes.to_vec();
+        for stream in STREAM.captures_iter(bytes) {
+            let (Some(dictionary), Some(start)) = (stream.get(1), stream.get(0)) else {
+                continue;
+            };
+            if !contains(dictionary.as_bytes(), b"/FlateDecode") {
+                continue;
+            }
+            // Inflating stops at the end of the compressed data; whatever a corrupt stream
+            // inflated to before the error is still searched
+            let mut inflated = Vec::new();
+            let _ = flate2::read::ZlibDecoder::new(&bytes[start.end()..]).read_to_end(&mut inflated);
+            searchable.push(b'\n');
+            searchable.extend_from_slice(&inflated);
+        }
+
+        let num_pages = PAGE.find_iter(&searchable).count();
+        if contains(&searchable, b"/Encrypt") {
+            return Self {
+                num_pages,
+                ..Self::default()
+            };
+        }
+        // Bookmarks have titles too, so the information dictionary's is preferred
+        let info = Self::info_dictionary(&searchable).unwrap_or(&searchable);
+        let title = TITLE
+            .find_iter(info)
+            .filter_map(|title| Self::string_at(&info[title.end()..]))
+            .find(|title| !title.trim().is_empty())
+            .map(|title| title.trim().to_string());
+        let mut uris = Vec::new();
+        for uri in URI.find_iter(&searchable) {
+            if let Some(uri) = Self::string_at(&searchable[uri.end()..]) {
+                let uri = uri.trim().to_string();
+                if !uri.is_empty() && !uris.contains(&uri) {
+                    uris.push(uri);
+                }
+            }
+        }
+        Self {
+            title,
+            num_pages,
+            uris,
+        }
+    }
+
+    /// The object holding the document information dictionary, if it is uncompressed.
+    fn info_dictionary(bytes: &[u8]) -> Option<&[u8]> {
+        let info = INFO.captures(bytes)?;
+        let object = Regex::new(&format!(
+            r"(?-u)(?:^|\s){}\s+{}\s+obj\b",
+            std::str::from_utf8(&info[1]).ok()?,
+            std::str::from_utf8(&info[2]).ok()?
+        ))
+        .ok()?
+        .find(bytes)?;
+        let object = &bytes[object.end()..];
+        let end = object
+            .windows(b"endobj".len())
+            .position(|window| window == b"endobj")
+            .unwrap_or(object.len());
+        Some(&object[..end])
+    }
+
+    /// Decodes the literal "(...)" or hex "<...>" string that `bytes` starts with.
+    fn string_at(bytes: &[u8]) -> Option<String> {
+        let raw = match bytes.first()? {
+            b'(' => Self::literal_string(&bytes[1..]),
+            b'<' if bytes.get(1) != Some(&b'<') => Self::hex_string(&bytes[1..])?,
+            _ => return None,
+        };
+        Some(Self::text_string(&raw))
+    }
+
+    fn literal_string(bytes: &[u8]) -> Vec<u8> {
+        let mut raw = Vec::new();
+        let mut nesting = 0;
+        let mut chars = bytes.iter().copied().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                b'(' => {
+                    nesting += 1;
+                    raw.push(c);
+                }
+                b')' if nesting == 0 => break,
+                b')' => {
+                    nesting -= 1;
+                    raw.push(c);
+                }
+                b'\\' => match chars.next() {
+                    Some(b'n') => raw.push(b'\n'),
+                    Some(b'r') => raw.push(b'\r'),
+                    Some(b't') => raw.push(b'\t'),
+                    Some(b'b') => raw.push(0x08),
+                    Some(b'f') => raw.push(0x0c),
+                    Some(digit @ b'0'..=b'7') => {
+                        let mut value = u32::from(digit - b'0');
+                        for _ in 0..2 {
+                            match chars.peek() {
+                                Some(&digit @ b'0'..=b'7') => {
+                                    value = value * 8 + u32::from(digit - b'0');
+                                    chars.next();
+                                }
+                                _ => break,
+                            }
+                        }
+                        raw.push(value as u8);
+                    }
+                    // A backslash at the end of a line continues the string on the next
+                    Some(b'\r') => {
+                        if chars.peek() == Some(&b'\n') {
+                            chars.next();
+                        }
+                    }
+                    Some(b'\n') => {}
+                    Some(escaped) => raw.push(escaped),
+                    None => break,
+                },
+                _ => raw.push(c),
+            }
+        }
+        raw
+    }
+
+    fn hex_string(bytes: &[u8]) -> Option<Vec<u8>> {
+        let end = bytes.iter().position(|&c| c == b'>')?;
+        let mut digits = bytes[..end]
+            .iter()
+            .filter_map(|&c| (c as char).to_digit(16))
+            .collect::<Vec<_>>();
+        // An odd final digit is followed by an implied 0
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+        Some(digits.chunks(2).map(|pair| (pair[0] * 16 + pair[1]) as u8).collect())
+    }
+
+    /// Text strings are UTF-16BE with a byte order mark, or otherwise PDFDocEncoding, which
+    /// matches Latin-1 for printable characters.
+    fn text_string(raw: &[u8]) -> String {
+        match raw.strip_prefix(&[0xfe, 0xff]) {
+            Some(utf16) => {
+                let units = utf16
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect::<Vec<_>>();
+                String::from_utf16_lossy(&units)
+            }
+            None => raw.iter().map(|&c| c as char).collect(),
+        }
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn finds_the_title_pages_and_links_including_compressed_objects() {
+        let mut compressed = flate2::write::ZlibEncoder::new(Vec::new(), Default::default());
+        compressed
+            .write_all(b"<< /Subtype /Link /A << /S /URI /URI (/docs/\\(2\\).html) >> >>")
+            .unwrap();
+        let mut pdf = b"%PDF-1.7\n\
+            1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+            2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >> endobj\n\
+            3 0 obj << /Type /Page /Annots [5 0 R] >> endobj\n\
+            4 0 obj << /Type/Page >> endobj\n\
+            5 0 obj << /A << /S /URI /URI (https://example.com/a) >> >> endobj\n\
+            6 0 obj << /Title (Chapter 1) /Parent 8 0 R >> endobj\n\
+            9 0 obj << /Title <FEFF005200650070006F00720074> /Author (A. Author) >> endobj\n\
+            7 0 obj << /Type /ObjStm /Filter /FlateDecode >> stream\n"
+            .to_vec();
+        pdf.extend_from_slice(&compressed.finish().unwrap());
+        pdf.extend_from_slice(b"\nendstream endobj\n");
+        pdf.extend_from_slice(b"trailer << /Root 1 0 R /Info 9 0 R >>\n%%EOF\n");
+
+        assert_eq!(
+            PdfDocument::parse(&pdf),
+            PdfDocument {
+                title: Some("Report".to_string()),
+                num_pages: 2,
+                uris: vec!["https://example.com/a".to_string(), "/docs/(2).html".to_string()],
+            }
+        );
+    }
+}
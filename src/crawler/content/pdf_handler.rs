@@ -0,0 +1,43 @@
+use crate::crawler::content::{ContentHandler, Document, ParseOptions, PdfDocument};
+use crate::crawler::crawl_response::CrawlResponse;
+use crate::crawler::page::PageCrawler;
+use std::collections::BTreeSet;
+
+/// Parses PDF documents for their title, page count and links.
+pub struct PdfHandler;
+
+impl ContentHandler for PdfHandler {
+    fn handles(&self, mime_type: &mime::Mime) -> bool {
+        mime_type.type_() == mime::APPLICATION && mime_type.subtype() == mime::PDF
+    }
+
+    fn parse(&self, document: &Document, _options: &ParseOptions) -> Option<CrawlResponse> {
+        let pdf_document = PdfDocument::parse(document.body);
+        let mut crawl_response = CrawlResponse::new(
+            document.url.clone(),
+            document.status_code,
+            document.content_type.to_string(),
+        );
+        crawl_response.title = pdf_document.title.unwrap_or_default();
+        crawl_response.num_pages = Some(pdf_document.num_pages);
+        // PDFs often link the same URL from every page
+        let links = pdf_document
+            .uris
+            .iter()
+            .filter_map(|uri| document.url.join(uri).ok())
+            .filter(|link| matches!(link.scheme(), "http" | "https" | "file"))
+            .map(|mut link| {
+                link.set_fragment(None);
+                link
+            })
+            .collect::<BTreeSet<_>>();
+        for link in links {
+            if PageCrawler::is_internal(&link, document.url) {
+                crawl_response.internal_links.push(link);
+            } else {
+                crawl_response.outgoing_links.push(link);
+            }
+        }
+        Some(crawl_response)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::crawler::content::{ContentHandler, Document, ParseOptions};
+use crate::crawler::crawl_response::CrawlResponse;
+use crate::crawler::http::ContentDecoder;
+
+/// Keeps the text of plain text documents, which have no title or links.
+pub struct PlainTextHandler;
+
+impl ContentHandler for PlainTextHandler {
+    fn handles(&self, mime_type: &mime::Mime) -> bool {
+        mime_type.type_() == mime::TEXT && mime_type.subtype() == mime::PLAIN
+    }
+
+    fn parse(&self, document: &Document, _options: &ParseOptions) -> Option<CrawlResponse> {
+        let text = ContentDecoder::decode_text(document.body, document.mime_type);
+        let mut crawl_response = CrawlResponse::new(
+            document.url.clone(),
+            document.status_code,
+            document.content_type.to_string(),
+        );
+        crawl_response.body_text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        crawl_response.body = text;
+        Some(crawl_response)
+    }
+}
@@ -0,0 +1,15 @@
+use crate::crawler::content::{ContentHandler, Document, ParseOptions};
+use crate::crawler::crawl_response::CrawlResponse;
+
+/// Skips documents of every content type; the fallback when no other handler applies.
+pub struct SkipHandler;
+
+impl ContentHandler for SkipHandler {
+    fn handles(&self, _mime_type: &mime::Mime) -> bool {
+        true
+    }
+
+    fn parse(&self, _document: &Document, _options: &ParseOptions) -> Option<CrawlResponse> {
+        None
+    }
+}
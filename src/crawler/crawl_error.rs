@@ -1,8 +1,40 @@
+use crate::crawler::http::CacheHeaders;
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, thiserror::Error)]
 pub enum CrawlError {
     #[error("HTTP Error Status Code = {0}")]
     HttpError(u16),
 
+    /// The body exceeded the maximum body size; holds the response status code.
+    #[error("Response body exceeds the maximum body size")]
+    TooLarge(u16),
+
+    /// No content handler parses the content type; holds the status code, content type and
+    /// caching headers.
+    #[error("Skipping unsupported content type: {1}")]
+    UnsupportedContentType(u16, String, CacheHeaders),
+
+    /// A redirect that was not followed; holds the status code and the redirect's target.
+    #[error("Not following the redirect to {1}")]
+    Redirect(u16, url::Url),
+
+    /// A response middleware kept the page from being parsed; holds the status code and why.
+    #[error("Skipped: {1}")]
+    Skipped(u16, String),
+
+    /// Fetching and parsing the page took longer than the page timeout.
+    #[error("Page timed out")]
+    Timeout,
+
+    /// The request timed out or could not connect on a distributed worker; holds why.
+    #[error("No response: {0}")]
+    NoResponse(String),
+
+    /// The crawl was cancelled while the page was being fetched.
+    #[error("Cancelled")]
+    Cancelled,
+
     #[error(transparent)]
     AnyError(#[from] anyhow::Error),
 
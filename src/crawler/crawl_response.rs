@@ -1,5 +1,16 @@
+use crate::crawler::accessibility::AccessibilityIssues;
+use crate::crawler::hreflang::HreflangAlternate;
+use crate::crawler::http::{CacheHeaders, SecurityHeaders};
+use crate::crawler::link_anchor::LinkAnchor;
+use crate::crawler::pagination::Pagination;
+use crate::crawler::robots::RobotsDirectives;
+use crate::crawler::schema::StructuredData;
+use std::collections::BTreeMap;
 use url::Url;
 
+/// Title given to HTML pages without a `<title>`.
+pub const NO_TITLE: &str = "No title";
+
 #[derive(Debug, Clone)]
 pub struct CrawlResponse {
     pub url: Url,
@@ -8,4 +19,100 @@ pub struct CrawlResponse {
     pub title: String,
     pub outgoing_links: Vec<Url>,
     pub internal_links: Vec<Url>,
+    /// Each distinct (URL, anchor text) pair among the internal links.
+    pub internal_link_anchors: Vec<LinkAnchor>,
+    pub body: String,
+    /// Visible text of the document body, with whitespace collapsed.
+    pub body_text: String,
+    /// Content of `<meta name="description">`, if present.
+    pub meta_description: Option<String>,
+    /// Where a `<meta http-equiv="refresh">` sends the reader, if anywhere.
+    pub meta_refresh: Option<Url>,
+    /// Number of `<h1>` headings.
+    pub num_h1: usize,
+    /// Number of pages of a PDF document.
+    pub num_pages: Option<usize>,
+    pub robots_directives: RobotsDirectives,
+    /// Next/previous pages if this page is part of a paginated series.
+    pub pagination: Pagination,
+    /// Translations of this page from `<link rel="alternate" hreflang>` annotations.
+    pub hreflang_alternates: Vec<HreflangAlternate>,
+    /// RSS and Atom feeds advertised with `<link rel="alternate">`.
+    pub feeds: Vec<Url>,
+    /// The preferred URL of this page, from `<link rel="canonical">`.
+    pub canonical: Option<Url>,
+    /// The AMP version of this page, from `<link rel="amphtml">`.
+    pub amp_url: Option<Url>,
+    /// Sources of the page's `<frame>` and `<iframe>` elements.
+    pub frames: Vec<Url>,
+    /// URLs found in onclick handlers and `data-href`/`data-url` attributes.
+    pub script_links: Vec<Url>,
+    /// Assets and links loaded over plain http:// from an https:// page.
+    pub mixed_content: Vec<Url>,
+    /// Values pulled out by extract rules, keyed by rule name.
+    pub extracted: BTreeMap<String, String>,
+    /// JSON-LD, microdata and RDFa embedded in the page.
+    pub structured_data: StructuredData,
+    /// Accessibility problems found, if accessibility checks were requested.
+    pub accessibility: Option<AccessibilityIssues>,
+    /// HTTP version of the response, e.g. "HTTP/2.0".
+    pub http_version: Option<String>,
+    /// Content-Encoding the body was served with, or None if it was sent uncompressed.
+    pub content_encoding: Option<String>,
+    /// Security-related response headers, empty for documents that were not fetched.
+    pub security_headers: SecurityHeaders,
+    /// Caching-related response headers, empty for documents that were not fetched.
+    pub cache_headers: CacheHeaders,
+    /// Response headers captured by name, keyed by lowercase name.
+    pub captured_headers: BTreeMap<String, String>,
+    /// Size of the body as transferred, before decompression.
+    pub transferred_bytes: usize,
+    /// Size of the body after decompression.
+    pub body_bytes: usize,
+    /// Hex SHA-256 of the decompressed body, for documents that were fetched.
+    pub content_hash: Option<String>,
+    /// Why a headless browser failed to render the document, if it was meant to.
+    pub render_error: Option<String>,
+}
+
+impl CrawlResponse {
+    /// A response with no title, links, text or metadata, for content handlers to fill in.
+    pub fn new(url: Url, status_code: u16, content_type: String) -> Self {
+        Self {
+            url,
+            status_code,
+            content_type,
+            title: String::new(),
+            outgoing_links: Vec::new(),
+            internal_links: Vec::new(),
+            internal_link_anchors: Vec::new(),
+            body: String::new(),
+            body_text: String::new(),
+            meta_description: None,
+            meta_refresh: None,
+            num_h1: 0,
+            num_pages: None,
+            robots_directives: RobotsDirectives::default(),
+            pagination: Pagination::default(),
+            hreflang_alternates: Vec::new(),
+            feeds: Vec::new(),
+            canonical: None,
+            amp_url: None,
+            frames: Vec::new(),
+            script_links: Vec::new(),
+            mixed_content: Vec::new(),
+            extracted: BTreeMap::new(),
+            structured_data: StructuredData::default(),
+            accessibility: None,
+            http_version: None,
+            content_encoding: None,
+            security_headers: SecurityHeaders::default(),
+            cache_headers: CacheHeaders::default(),
+            captured_headers: BTreeMap::new(),
+            transferred_bytes: 0,
+            body_bytes: 0,
+            content_hash: None,
+            render_error: None,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,567 @@
+use crate::crawler::accessibility::AccessibilityIssues;
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::seed::{RejectedUrlCounts, SuspectedTrap};
+use crate::crawler::text::THIN_CONTENT_WORDS;
+use crate::crawler::visited_filter::VisitedFilterStats;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use url::Url;
+
+/// Number of external hosts listed in the statistics.
+const TOP_EXTERNAL_HOSTS: usize = 10;
+
+/// Number of most linked pages listed in the statistics.
+const TOP_LINKED_PAGES: usize = 10;
+
+/// Aggregate statistics over the pages of one or more crawls.
+#[derive(Debug, Clone, Default)]
+pub struct CrawlStatistics {
+    num_pages: usize,
+    /// Number of pages per status class (2 for 2xx, 4 for 4xx, ...).
+    pages_by_status_class: BTreeMap<u16, usize>,
+    /// Number of pages found at each depth from the seed.
+    pages_by_depth: BTreeMap<usize, usize>,
+    /// Number of success responses that look like "not found" pages.
+    num_soft_404_pages: usize,
+    /// Number of pages abandoned for exceeding the maximum body size.
+    num_too_large_pages: usize,
+    num_skipped_pages: usize,
+    /// Number of pages abandoned for exceeding the page timeout.
+    num_timed_out_pages: usize,
+    /// Number of redirects that were reported rather than followed, and how many of those
+    /// led to another host.
+    num_unfollowed_redirects: usize,
+    num_off_host_redirects: usize,
+    /// Number of fetched pages per HTTP version.
+    pages_by_http_version: BTreeMap<String, usize>,
+    /// Number of fetched pages per Content-Encoding ("identity" when uncompressed).
+    pages_by_content_encoding: BTreeMap<String, usize>,
+    /// Bytes received over the wire, before decompression.
+    transferred_bytes: u64,
+    /// Bytes of page content after decompression.
+    body_bytes: u64,
+    /// RSS and Atom feeds advertised by each site's pages, keyed by site origin.
+    feeds_by_site: BTreeMap<String, BTreeSet<Url>>,
+    /// Paginated series found, each in rel=next order.
+    pagination_chains: Vec<Vec<Url>>,
+    /// URLs listed in a sitemap that no crawled page links to. None if no sitemap was
+    /// loaded.
+    orphan_urls: Option<Vec<Url>>,
+    /// Successfully crawled pages that no sitemap lists.
+    unlisted_urls: Option<Vec<Url>>,
+    /// Number of pages whose text was measured, and their total words and text-to-HTML ratio.
+    num_text_pages: usize,
+    total_words: usize,
+    total_text_to_html_ratio: f64,
+    /// Number of measured pages with too few words to be useful.
+    num_thin_pages: usize,
+    /// Readability scores of the pages that were scored.
+    reading_ease_scores: Vec<f64>,
+    /// Number of pages where the `--search` pattern matched, and the total number of matches.
+    num_search_matching_pages: usize,
+    num_search_matches: usize,
+    /// Number of pages checked for accessibility, how many had problems, and the problems.
+    num_accessibility_pages: usize,
+    num_inaccessible_pages: usize,
+    accessibility_totals: AccessibilityIssues,
+    /// Number of pages embedding any structured data.
+    num_structured_data_pages: usize,
+    /// Number of structured data entities per schema.org type.
+    structured_data_types: BTreeMap<String, usize>,
+    /// Discovered URLs not queued because they exceeded the URL limits, across all seeds.
+    rejected_url_counts: RejectedUrlCounts,
+    /// URL families skipped as suspected crawler traps, across all seeds.
+    suspected_traps: Vec<SuspectedTrap>,
+    /// Number of pages linking to each external host, and the distinct links to it summed
+    /// over those pages.
+    external_hosts: HashMap<String, (usize, usize)>,
+    /// Number of links found to pages of the crawl, and the distinct URLs they named.
+    num_link_discoveries: usize,
+    num_discovered_urls: usize,
+    /// The crawled pages linked to most often, with their in-degree.
+    most_linked_pages: Vec<(Url, usize)>,
+    /// Crawled pages, other than seeds, that no crawled page links to, e.g. pages only
+    /// listed in a sitemap.
+    unlinked_urls: Vec<Url>,
+    /// Number of times each host's circuit breaker opened, across all seeds.
+    circuit_breaker_trips: BTreeMap<String, usize>,
+    /// Combined accuracy of the seeds' bloom visited filters, if used.
+    visited_filter_stats: Option<VisitedFilterStats>,
+}
+
+impl CrawlStatistics {
+    pub fn from_crawl_summaries(crawl_summaries: &[CrawlSummary]) -> Self {
+        let mut statistics = CrawlStatistics::default();
+        for page_summary in crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+        {
+            statistics.num_pages += 1;
+            *statistics
+                .pages_by_status_class
+                .entry(page_summary.status_code / 100)
+                .or_default() += 1;
+            *statistics
+                .pages_by_depth
+                .entry(page_summary.depth)
+                .or_default() += 1;
+
+            if page_summary.too_large {
+                statistics.num_too_large_pages += 1;
+            }
+            if page_summary.timed_out {
+                statistics.num_timed_out_pages += 1;
+            }
+            if page_summary.skipped.is_some() {
+                statistics.num_skipped_pages += 1;
+            }
+            if page_summary.soft_404 {
+                statistics.num_soft_404_pages += 1;
+            }
+            if page_summary.redirect.is_some() {
+                statistics.num_unfollowed_redirects += 1;
+            }
+            if page_summary.off_host_redirect().is_some() {
+                statistics.num_off_host_redirects += 1;
+            }
+            if let Some(text_stats) = &page_summary.text_stats {
+                statistics.num_text_pages += 1;
+                statistics.total_words += text_stats.word_count;
+                statistics.total_text_to_html_ratio += text_stats.text_to_html_ratio;
+                if text_stats.is_thin() {
+                    statistics.num_thin_pages += 1;
+                }
+                statistics.reading_ease_scores.extend(text_stats.reading_ease);
+            }
+            if let Some(search_matches) = &page_summary.search_matches {
+                statistics.num_search_matching_pages += 1;
+                statistics.num_search_matches += search_matches.num_matches;
+            }
+            if let Some(accessibility) = &page_summary.accessibility {
+                statistics.num_accessibility_pages += 1;
+                if accessibility.total() > 0 {
+                    statistics.num_inaccessible_pages += 1;
+                }
+                statistics.accessibility_totals.add(accessibility);
+            }
+            if !page_summary.feeds.is_empty() {
+                statistics
+                    .feeds_by_site
+                    .entry(page_summary.url.origin().ascii_serialization())
+                    .or_default()
+                    .extend(page_summary.feeds.iter().cloned());
+            }
+            if !page_summary.structured_data.is_empty() {
+                statistics.num_structured_data_pages += 1;
+            }
+            for item_type in page_summary.structured_data.types() {
+                *statistics.structured_data_types.entry(item_type).or_default() += 1;
+            }
+            for (host, num_links) in &page_summary.outgoing_link_hosts {
+                let (num_linking_pages, total_links) =
+                    statistics.external_hosts.entry(host.clone()).or_default();
+                *num_linking_pages += 1;
+                *total_links += num_links;
+            }
+            if let Some(http_version) = &page_summary.http_version {
+                *statistics
+                    .pages_by_http_version
+                    .entry(http_version.clone())
+                    .or_default() += 1;
+            }
+
+            // Pages that were not fetched successfully have no body to account for
+            if page_summary.body_bytes > 0 {
+                let content_encoding = page_summary.content_encoding.as_deref().unwrap_or("identity");
+                *statistics
+                    .pages_by_content_encoding
+                    .entry(content_encoding.to_owned())
+                    .or_default() += 1;
+                statistics.transferred_bytes += page_summary.transferred_bytes as u64;
+                statistics.body_bytes += page_summary.body_bytes as u64;
+            }
+        }
+        statistics.add_in_degrees(crawl_summaries);
+        statistics.pagination_chains = Self::pagination_chains(crawl_summaries);
+        statistics.compare_with_sitemaps(crawl_summaries);
+        for crawl_summary in crawl_summaries {
+            statistics.rejected_url_counts.add(&crawl_summary.rejected_url_counts());
+        }
+        statistics.suspected_traps = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.suspected_traps().iter().cloned())
+            .collect();
+        for (host, num_trips) in crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.circuit_breaker_trips())
+        {
+            *statistics.circuit_breaker_trips.entry(host.clone()).or_default() += num_trips;
+        }
+        for visited_filter_stats in crawl_summaries
+            .iter()
+            .filter_map(|crawl_summary| crawl_summary.visited_filter_stats())
+        {
+            let combined = statistics.visited_filter_stats.get_or_insert_default();
+            combined.size_in_bytes += visited_filter_stats.size_in_bytes;
+            combined.num_urls += visited_filter_stats.num_urls;
+            combined.false_positive_rate = combined
+                .false_positive_rate
+                .max(visited_filter_stats.false_positive_rate);
+            combined.estimated_misses += visited_filter_stats.estimated_misses;
+        }
+        statistics
+    }
+
+    fn add_in_degrees(&mut self, crawl_summaries: &[CrawlSummary]) {
+        // Seeds whose discoveries weren't counted have no in-degrees to report
+        let counted_summaries = crawl_summaries
+            .iter()
+            .filter(|crawl_summary| crawl_summary.link_discoveries().is_some());
+        for crawl_summary in counted_summaries.clone() {
+            let (num_link_discoveries, num_discovered_urls) =
+                crawl_summary.link_discoveries().unwrap_or_default();
+            self.num_link_discoveries += num_link_discoveries;
+            self.num_discovered_urls += num_discovered_urls;
+        }
+        let page_summaries =
+            counted_summaries.flat_map(|crawl_summary| crawl_summary.page_summaries());
+        let mut linked_pages = page_summaries
+            .clone()
+            .filter(|page_summary| page_summary.in_degree > 0)
+            .map(|page_summary| (page_summary.url.clone(), page_summary.in_degree))
+            .collect::<Vec<_>>();
+        linked_pages.sort_by(|(url_a, in_degree_a), (url_b, in_degree_b)| {
+            in_degree_b.cmp(in_degree_a).then_with(|| url_a.cmp(url_b))
+        });
+        linked_pages.truncate(TOP_LINKED_PAGES);
+        self.most_linked_pages = linked_pages;
+        let mut unlinked_urls = page_summaries
+            .filter(|page_summary| page_summary.depth > 0 && page_summary.in_degree == 0)
+            .map(|page_summary| page_summary.url.clone())
+            .collect::<Vec<_>>();
+        unlinked_urls.sort();
+        self.unlinked_urls = unlinked_urls;
+    }
+
+    fn compare_with_sitemaps(&mut self, crawl_summaries: &[CrawlSummary]) {
+        let mut sitemap_urls = crawl_summaries
+            .iter()
+            .filter_map(|crawl_summary| crawl_summary.sitemap_urls())
+            .flatten()
+            .peekable();
+        if sitemap_urls.peek().is_none() {
+            return;
+        }
+        let sitemap_urls = sitemap_urls.collect::<HashSet<_>>();
+        let crawled_urls = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .map(|page_summary| &page_summary.url)
+            .collect::<HashSet<_>>();
+        // Linked pages the crawl's limits kept it from reaching aren't orphans
+        let linked_urls = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.linked_sitemap_urls())
+            .collect::<HashSet<_>>();
+
+        let mut orphan_urls = sitemap_urls
+            .iter()
+            .filter(|url| !crawled_urls.contains(*url) && !linked_urls.contains(*url))
+            .map(|url| (*url).clone())
+            .collect::<Vec<_>>();
+        orphan_urls.sort();
+        let mut unlisted_urls = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .filter(|page_summary| (200..300).contains(&page_summary.status_code))
+            .filter(|page_summary| !sitemap_urls.contains(&page_summary.url))
+            .map(|page_summary| page_summary.url.clone())
+            .collect::<Vec<_>>();
+        unlisted_urls.sort();
+        self.orphan_urls = Some(orphan_urls);
+        self.unlisted_urls = Some(unlisted_urls);
+    }
+
+    /// The external hosts linked from the most pages, then by the most links.
+    fn top_external_hosts(&self) -> Vec<(&String, (usize, usize))> {
+        let mut external_hosts = self
+            .external_hosts
+            .iter()
+            .map(|(host, counts)| (host, *counts))
+            .collect::<Vec<_>>();
+        external_hosts.sort_by(|(host_a, counts_a), (host_b, counts_b)| {
+            counts_b.cmp(counts_a).then_with(|| host_a.cmp(host_b))
+        });
+        external_hosts.truncate(TOP_EXTERNAL_HOSTS);
+        external_hosts
+    }
+
+    /// Follows rel=next links between crawled pages to reconstruct each paginated series.
+    fn pagination_chains(crawl_summaries: &[CrawlSummary]) -> Vec<Vec<Url>> {
+        let next_pages = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .filter_map(|page_summary| {
+                let next = page_summary.pagination.next.as_ref()?;
+                Some((&page_summary.url, next))
+            })
+            .collect::<HashMap<_, _>>();
+        let linked_from_previous = next_pages.values().copied().collect::<HashSet<_>>();
+
+        let mut chain_starts = next_pages
+            .keys()
+            .copied()
+            .filter(|url| !linked_from_previous.contains(url))
+            .collect::<Vec<_>>();
+        chain_starts.sort();
+
+        let mut chains = Vec::new();
+        for chain_start in chain_starts {
+            let mut chain = vec![chain_start.clone()];
+            let mut seen = HashSet::from([chain_start]);
+            let mut current = chain_start;
+            while let Some(next) = next_pages.get(current) {
+                // A next link pointing back into the chain would loop forever
+                if !seen.insert(*next) {
+                    break;
+                }
+                chain.push((*next).clone());
+                current = next;
+            }
+            chains.push(chain);
+        }
+        chains
+    }
+}
+
+impl fmt::Display for CrawlStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pages crawled: {}", self.num_pages)?;
+
+        writeln!(f, "Pages by status:")?;
+        for (status_class, num_pages) in &self.pages_by_status_class {
+            if *status_class == 0 {
+                writeln!(f, "   No response: {}", num_pages)?;
+            } else {
+                writeln!(f, "   {}xx: {}", status_class, num_pages)?;
+            }
+        }
+
+        writeln!(f, "Soft 404 pages: {}", self.num_soft_404_pages)?;
+        writeln!(f, "Pages too large to read: {}", self.num_too_large_pages)?;
+        if self.num_timed_out_pages > 0 {
+            writeln!(f, "Pages timed out: {}", self.num_timed_out_pages)?;
+        }
+        if self.num_skipped_pages > 0 {
+            writeln!(f, "Pages skipped by response middleware: {}", self.num_skipped_pages)?;
+        }
+        if self.num_unfollowed_redirects > 0 {
+            writeln!(
+                f,
+                "Redirects not followed: {} ({} off-host)",
+                self.num_unfollowed_redirects, self.num_off_host_redirects
+            )?;
+        }
+
+        writeln!(f, "Pages by depth:")?;
+        let max_count = self.pages_by_depth.values().copied().max().unwrap_or(0);
+        for (depth, num_pages) in &self.pages_by_depth {
+            // Scale the histogram bars to at most 40 columns
+            let bar_len = (num_pages * 40).div_ceil(max_count.max(1));
+            writeln!(f, "   {:>3}: {:>6} {}", depth, num_pages, "#".repeat(bar_len))?;
+        }
+
+        writeln!(f, "Pages by HTTP version:")?;
+        for (http_version, num_pages) in &self.pages_by_http_version {
+            writeln!(f, "   {}: {}", http_version, num_pages)?;
+        }
+
+        writeln!(f, "Pages by content encoding:")?;
+        for (content_encoding, num_pages) in &self.pages_by_content_encoding {
+            writeln!(f, "   {}: {}", content_encoding, num_pages)?;
+        }
+
+        if self.num_text_pages > 0 {
+            writeln!(
+                f,
+                "Text: {:.0} words per page on average, {:.1}% of the HTML, {} thin pages \
+                 (under {} words)",
+                self.total_words as f64 / self.num_text_pages as f64,
+                100.0 * self.total_text_to_html_ratio / self.num_text_pages as f64,
+                self.num_thin_pages,
+                THIN_CONTENT_WORDS
+            )?;
+        }
+        if !self.reading_ease_scores.is_empty() {
+            let min = self.reading_ease_scores.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = self.reading_ease_scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            writeln!(
+                f,
+                "Reading ease: {:.1} on average (hardest page {:.1}, easiest {:.1})",
+                self.reading_ease_scores.iter().sum::<f64>()
+                    / self.reading_ease_scores.len() as f64,
+                min,
+                max
+            )?;
+        }
+
+        if self.num_search_matching_pages > 0 {
+            writeln!(
+                f,
+                "Pages matching search: {} ({} matches)",
+                self.num_search_matching_pages, self.num_search_matches
+            )?;
+        }
+
+        if self.num_accessibility_pages > 0 {
+            writeln!(
+                f,
+                "Accessibility: {} of {} pages with issues ({})",
+                self.num_inaccessible_pages,
+                self.num_accessibility_pages,
+                self.accessibility_totals
+            )?;
+        }
+
+        if self.num_structured_data_pages > 0 {
+            writeln!(f, "Pages with structured data: {}", self.num_structured_data_pages)?;
+            for (item_type, count) in &self.structured_data_types {
+                writeln!(f, "   {}: {}", item_type, count)?;
+            }
+        }
+
+        if !self.feeds_by_site.is_empty() {
+            writeln!(f, "Feeds discovered:")?;
+            for (site, feeds) in &self.feeds_by_site {
+                writeln!(f, "   {}", site)?;
+                for feed in feeds {
+                    writeln!(f, "      {}", feed)?;
+                }
+            }
+        }
+
+        if !self.pagination_chains.is_empty() {
+            writeln!(f, "Pagination chains:")?;
+            for chain in &self.pagination_chains {
+                writeln!(f, "   {} pages starting at {}", chain.len(), chain[0])?;
+                for url in &chain[1..] {
+                    writeln!(f, "      -> {}", url)?;
+                }
+            }
+        }
+
+        if let Some(orphan_urls) = &self.orphan_urls {
+            writeln!(f, "Orphan pages (in sitemap, not linked): {}", orphan_urls.len())?;
+            for url in orphan_urls {
+                writeln!(f, "   {}", url)?;
+            }
+        }
+        if let Some(unlisted_urls) = &self.unlisted_urls {
+            writeln!(f, "Pages missing from sitemap: {}", unlisted_urls.len())?;
+            for url in unlisted_urls {
+                writeln!(f, "   {}", url)?;
+            }
+        }
+
+        if self.num_link_discoveries > 0 {
+            writeln!(
+                f,
+                "Links discovered: {} ({} distinct URLs, {} duplicates)",
+                self.num_link_discoveries,
+                self.num_discovered_urls,
+                self.num_link_discoveries - self.num_discovered_urls
+            )?;
+        }
+        if !self.most_linked_pages.is_empty() {
+            writeln!(f, "Most linked pages:")?;
+            for (url, in_degree) in &self.most_linked_pages {
+                writeln!(f, "   {}: linked {} times", url, in_degree)?;
+            }
+        }
+        if !self.unlinked_urls.is_empty() {
+            writeln!(f, "Pages no crawled page links to: {}", self.unlinked_urls.len())?;
+            for url in &self.unlinked_urls {
+                writeln!(f, "   {}", url)?;
+            }
+        }
+
+        if !self.external_hosts.is_empty() {
+            writeln!(f, "Top external domains ({} in total):", self.external_hosts.len())?;
+            for (host, (num_linking_pages, total_links)) in self.top_external_hosts() {
+                writeln!(f, "   {}: {} pages, {} links", host, num_linking_pages, total_links)?;
+            }
+        }
+
+        if self.rejected_url_counts.total() > 0 {
+            writeln!(
+                f,
+                "URLs rejected by URL limits: {} ({})",
+                self.rejected_url_counts.total(),
+                self.rejected_url_counts
+            )?;
+        }
+        if !self.suspected_traps.is_empty() {
+            writeln!(f, "Suspected crawler traps: {}", self.suspected_traps.len())?;
+            for trap in &self.suspected_traps {
+                writeln!(
+                    f,
+                    "   {} ({}): {} URLs skipped, e.g. {}",
+                    trap.family, trap.reason, trap.num_skipped, trap.example_url
+                )?;
+            }
+        }
+
+        if !self.circuit_breaker_trips.is_empty() {
+            writeln!(f, "Hosts paused after repeated failures:")?;
+            for (host, num_trips) in &self.circuit_breaker_trips {
+                writeln!(f, "   {}: circuit breaker opened {} times", host, num_trips)?;
+            }
+        }
+
+        if let Some(visited_filter_stats) = &self.visited_filter_stats {
+            writeln!(
+                f,
+                "Bloom visited filter: {} URLs in {} bytes, false-positive rate {:.4}%, ~{:.1} URLs possibly skipped",
+                visited_filter_stats.num_urls,
+                visited_filter_stats.size_in_bytes,
+                visited_filter_stats.false_positive_rate * 100.0,
+                visited_filter_stats.estimated_misses
+            )?;
+        }
+
+        let savings = if self.body_bytes > 0 {
+            100.0 * (1.0 - self.transferred_bytes as f64 / self.body_bytes as f64)
+        } else {
+            0.0
+        };
+        writeln!(
+            f,
+            "Bytes transferred: {} (decompressed: {}, {:.1}% saved by compression)",
+            self.transferred_bytes, self.body_bytes, savings
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::page_summary::PageSummary;
+
+    #[test]
+    fn reports_sitemap_urls_no_crawled_page_links_to_as_orphans() {
+        let url = |path: &str| Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        let mut crawl_summary = CrawlSummary::new(vec![
+            PageSummary::from_status_code(url("/"), 0, 200),
+            PageSummary::from_status_code(url("/a"), 1, 200),
+            PageSummary::from_status_code(url("/unlisted"), 1, 200),
+        ]);
+        crawl_summary.set_sitemap_urls(vec![url("/"), url("/a"), url("/b"), url("/c")]);
+        // "/b" was linked but lay beyond the crawl's limits
+        crawl_summary.set_linked_sitemap_urls(vec![url("/a"), url("/b")]);
+
+        let statistics = CrawlStatistics::from_crawl_summaries(&[crawl_summary]);
+        assert_eq!(statistics.orphan_urls, Some(vec![url("/c")]));
+        assert_eq!(statistics.unlisted_urls, Some(vec![url("/unlisted")]));
+    }
+}
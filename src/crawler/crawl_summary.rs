@@ -1,13 +1,55 @@
 use crate::crawler::page_summary::PageSummary;
+use crate::crawler::seed::{RejectedUrlCounts, SuspectedTrap};
+use crate::crawler::visited_filter::VisitedFilterStats;
+use std::collections::{BTreeMap, HashMap};
+use url::Url;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct CrawlSummary {
+    /// ID of the seed's crawl, see `RunInfo::id`; None for reprocessed pages.
+    seed_id: Option<String>,
+    #[serde(rename = "pages")]
     crawl_summaries: Vec<PageSummary>,
+    /// Whether the crawl stopped early because its time budget ran out.
+    truncated_by_time: bool,
+    /// Normalized page URLs listed in the seed's sitemaps, if they were loaded.
+    sitemap_urls: Option<Vec<Url>>,
+    /// Sitemap URLs linked from a crawled page, including those the crawl's page or depth
+    /// limits kept it from crawling.
+    linked_sitemap_urls: Vec<Url>,
+    visited_filter_stats: Option<VisitedFilterStats>,
+    /// URL families the crawl stopped following because they looked like crawler traps.
+    suspected_traps: Vec<SuspectedTrap>,
+    /// Discovered URLs not queued because they exceeded the URL limits.
+    rejected_url_counts: RejectedUrlCounts,
+    /// Number of times each host's circuit breaker opened on this seed's requests.
+    circuit_breaker_trips: BTreeMap<String, usize>,
+    /// Number of links to queued URLs found, and how many distinct URLs they named; the
+    /// difference are duplicate discoveries of URLs already seen. None when discoveries
+    /// weren't counted.
+    num_link_discoveries: Option<usize>,
+    num_discovered_urls: Option<usize>,
 }
 
 impl CrawlSummary {
     pub fn new(crawl_summaries: Vec<PageSummary>) -> Self {
-        Self { crawl_summaries }
+        Self {
+            seed_id: None,
+            crawl_summaries,
+            truncated_by_time: false,
+            sitemap_urls: None,
+            linked_sitemap_urls: Vec::new(),
+            visited_filter_stats: None,
+            suspected_traps: Vec::new(),
+            rejected_url_counts: RejectedUrlCounts::default(),
+            circuit_breaker_trips: BTreeMap::new(),
+            num_link_discoveries: None,
+            num_discovered_urls: None,
+        }
+    }
+
+    pub fn set_seed_id(&mut self, seed_id: String) {
+        self.seed_id = Some(seed_id);
     }
 
     pub fn page_summaries(&self) -> &[PageSummary] {
@@ -17,6 +59,79 @@ impl CrawlSummary {
     pub fn add_page_summary(&mut self, page_summary: PageSummary) {
         self.crawl_summaries.push(page_summary);
     }
+
+    pub fn truncated_by_time(&self) -> bool {
+        self.truncated_by_time
+    }
+
+    pub fn mark_truncated_by_time(&mut self) {
+        self.truncated_by_time = true;
+    }
+
+    pub fn sitemap_urls(&self) -> Option<&[Url]> {
+        self.sitemap_urls.as_deref()
+    }
+
+    pub fn visited_filter_stats(&self) -> Option<VisitedFilterStats> {
+        self.visited_filter_stats
+    }
+
+    pub fn set_visited_filter_stats(&mut self, visited_filter_stats: Option<VisitedFilterStats>) {
+        self.visited_filter_stats = visited_filter_stats;
+    }
+
+    pub fn suspected_traps(&self) -> &[SuspectedTrap] {
+        &self.suspected_traps
+    }
+
+    pub fn set_suspected_traps(&mut self, suspected_traps: Vec<SuspectedTrap>) {
+        self.suspected_traps = suspected_traps;
+    }
+
+    pub fn rejected_url_counts(&self) -> RejectedUrlCounts {
+        self.rejected_url_counts
+    }
+
+    pub fn set_rejected_url_counts(&mut self, rejected_url_counts: RejectedUrlCounts) {
+        self.rejected_url_counts = rejected_url_counts;
+    }
+
+    pub fn circuit_breaker_trips(&self) -> &BTreeMap<String, usize> {
+        &self.circuit_breaker_trips
+    }
+
+    pub fn add_circuit_breaker_trip(&mut self, host: &str) {
+        *self.circuit_breaker_trips.entry(host.to_string()).or_default() += 1;
+    }
+
+    /// Number of links found and the distinct URLs they named, if discoveries were counted.
+    pub fn link_discoveries(&self) -> Option<(usize, usize)> {
+        self.num_link_discoveries.zip(self.num_discovered_urls)
+    }
+
+    /// Sets the in-degree of each page from the number of times its URL was discovered.
+    pub fn set_discovery_counts(&mut self, discovery_counts: &HashMap<Url, usize>) {
+        for page_summary in &mut self.crawl_summaries {
+            page_summary.in_degree =
+                discovery_counts.get(&page_summary.url).copied().unwrap_or_default();
+        }
+        self.num_link_discoveries = Some(discovery_counts.values().sum());
+        // URLs queued without being linked to, such as the seed, have no discoveries
+        let linked_urls = discovery_counts.values().filter(|count| **count > 0).count();
+        self.num_discovered_urls = Some(linked_urls);
+    }
+
+    pub fn set_sitemap_urls(&mut self, sitemap_urls: Vec<Url>) {
+        self.sitemap_urls = Some(sitemap_urls);
+    }
+
+    pub fn linked_sitemap_urls(&self) -> &[Url] {
+        &self.linked_sitemap_urls
+    }
+
+    pub fn set_linked_sitemap_urls(&mut self, linked_sitemap_urls: Vec<Url>) {
+        self.linked_sitemap_urls = linked_sitemap_urls;
+    }
 }
 
 impl Default for CrawlSummary {
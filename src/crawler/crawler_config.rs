@@ -1,8 +1,97 @@
+use crate::crawler::checkpoint::CheckpointInterval;
+use crate::crawler::content::ContentKind;
+use crate::crawler::distributed::{DEFAULT_DISPATCH_WINDOW, DEFAULT_QUEUE_NAME};
+use crate::crawler::events::DEFAULT_NATS_SUBJECT;
+use crate::crawler::extract::ExtractRule;
+use crate::crawler::fetch::{RenderMode, RequestMiddleware};
+use crate::crawler::http::{
+    CrawlProfile, DEFAULT_MAX_REDIRECTS, HttpVersionPreference, ResolveOverride,
+};
+use crate::crawler::normalize::QueryPolicy;
+use crate::crawler::page::{DEFAULT_LINK_SELECTOR, ResponseMiddleware};
+use crate::crawler::scope::{FramePolicy, HostPattern, UrlPattern};
+use crate::crawler::search::TextSearch;
+use crate::crawler::seed::{CrawlStrategy, DEFAULT_TRAP_FAMILY_LIMIT, UrlLimits};
+use crate::crawler::soft_404_detector::DEFAULT_SOFT_404_PATTERNS;
+use crate::crawler::visited_filter::VisitedFilter;
+use reqwest::header::HeaderName;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
 #[derive(Clone)]
 pub struct CrawlerConfig {
     max_pages: usize,
     max_depth: usize,
+    strategy: CrawlStrategy,
+    priority_patterns: Vec<UrlPattern>,
+    trap_family_limit: Option<usize>,
+    url_limits: UrlLimits,
     requests_per_second: Option<f64>,
+    burst: u32,
+    adaptive_rate: bool,
+    circuit_breaker_threshold: Option<u32>,
+    circuit_breaker_cooldown: Duration,
+    save_bodies_dir: Option<PathBuf>,
+    global_dedupe: bool,
+    respect_robots_directives: bool,
+    follow_meta_refresh: bool,
+    follow_redirects: bool,
+    max_redirects: usize,
+    http_version: HttpVersionPreference,
+    resolve_overrides: Vec<ResolveOverride>,
+    doh_url: Option<Url>,
+    max_duration: Option<Duration>,
+    timeline_interval: Option<Duration>,
+    max_seed_duration: Option<Duration>,
+    query_policy: QueryPolicy,
+    soft_404_patterns: Vec<String>,
+    sitemap_report: bool,
+    follow_feeds: bool,
+    frame_policy: FramePolicy,
+    script_links: bool,
+    profile: CrawlProfile,
+    max_body_size: Option<u64>,
+    max_bandwidth: Option<u64>,
+    max_host_bandwidth: Option<u64>,
+    page_timeout: Option<Duration>,
+    frontier_memory_limit: Option<usize>,
+    frontier_spill_dir: Option<PathBuf>,
+    memory_limit: Option<u64>,
+    visited_filter: VisitedFilter,
+    expected_urls: usize,
+    render_mode: RenderMode,
+    spa_routes: bool,
+    webdriver_url: Url,
+    nats_url: Option<Url>,
+    nats_subject: String,
+    work_queue_url: Option<Url>,
+    queue_name: String,
+    dispatch_window: usize,
+    shared_frontier_url: Option<Url>,
+    jsonl_output: Option<PathBuf>,
+    render_timeout: Duration,
+    render_tabs: usize,
+    checkpoint_dir: Option<PathBuf>,
+    checkpoint_interval: CheckpointInterval,
+    extract_rules: Vec<ExtractRule>,
+    captured_headers: Vec<HeaderName>,
+    request_middleware: Vec<Arc<dyn RequestMiddleware>>,
+    response_middleware: Vec<Arc<dyn ResponseMiddleware>>,
+    script_file: Option<PathBuf>,
+    plugin_files: Vec<PathBuf>,
+    search: Option<TextSearch>,
+    readability: bool,
+    keep_body_text: bool,
+    accessibility_checks: bool,
+    content_kinds: Vec<ContentKind>,
+    allow_hosts: Vec<HostPattern>,
+    deny_hosts: Vec<HostPattern>,
+    allow_private_networks: bool,
+    deterministic: bool,
+    link_selector: String,
+    streaming_html: bool,
 }
 
 impl CrawlerConfig {
@@ -10,21 +99,836 @@ impl CrawlerConfig {
         Self {
             max_pages,
             max_depth,
+            strategy: CrawlStrategy::default(),
+            priority_patterns: Vec::new(),
+            trap_family_limit: Some(DEFAULT_TRAP_FAMILY_LIMIT),
+            url_limits: UrlLimits::default(),
             requests_per_second,
+            burst: 1,
+            adaptive_rate: false,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown: Duration::from_secs(60),
+            save_bodies_dir: None,
+            global_dedupe: false,
+            respect_robots_directives: true,
+            follow_meta_refresh: true,
+            follow_redirects: true,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            http_version: HttpVersionPreference::default(),
+            resolve_overrides: Vec::new(),
+            doh_url: None,
+            max_duration: None,
+            timeline_interval: None,
+            max_seed_duration: None,
+            query_policy: QueryPolicy::default(),
+            soft_404_patterns: DEFAULT_SOFT_404_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            sitemap_report: false,
+            follow_feeds: false,
+            frame_policy: FramePolicy::default(),
+            script_links: false,
+            profile: CrawlProfile::default(),
+            max_body_size: None,
+            max_bandwidth: None,
+            max_host_bandwidth: None,
+            page_timeout: None,
+            frontier_memory_limit: None,
+            frontier_spill_dir: None,
+            memory_limit: None,
+            visited_filter: VisitedFilter::default(),
+            expected_urls: 1_000_000,
+            render_mode: RenderMode::default(),
+            spa_routes: false,
+            webdriver_url: Url::parse("http://localhost:9515/").unwrap(),
+            nats_url: None,
+            nats_subject: DEFAULT_NATS_SUBJECT.to_string(),
+            work_queue_url: None,
+            queue_name: DEFAULT_QUEUE_NAME.to_string(),
+            dispatch_window: DEFAULT_DISPATCH_WINDOW,
+            shared_frontier_url: None,
+            jsonl_output: None,
+            render_timeout: Duration::from_secs(30),
+            render_tabs: 4,
+            checkpoint_dir: None,
+            checkpoint_interval: CheckpointInterval::default(),
+            extract_rules: Vec::new(),
+            captured_headers: Vec::new(),
+            request_middleware: Vec::new(),
+            response_middleware: Vec::new(),
+            script_file: None,
+            plugin_files: Vec::new(),
+            search: None,
+            readability: false,
+            keep_body_text: false,
+            accessibility_checks: false,
+            content_kinds: vec![ContentKind::Html],
+            allow_hosts: Vec::new(),
+            deny_hosts: Vec::new(),
+            allow_private_networks: false,
+            deterministic: false,
+            link_selector: DEFAULT_LINK_SELECTOR.to_string(),
+            streaming_html: false,
         }
     }
 
-    #[allow(dead_code)]
+    /// Crawls seeds one at a time and queues discovered links in sorted order, so that runs
+    /// over an unchanged site crawl the same pages in the same order.
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Follows the href of the elements matching the CSS selector `link_selector`.
+    pub fn with_link_selector(mut self, link_selector: &str) -> Self {
+        self.link_selector = link_selector.to_string();
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: CrawlStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn with_priority_patterns(mut self, priority_patterns: Vec<UrlPattern>) -> Self {
+        self.priority_patterns = priority_patterns;
+        self
+    }
+
+    /// Skips URLs that look like crawler traps, allowing up to `trap_family_limit` URLs that
+    /// differ only in numbers. None turns trap detection off.
+    pub fn with_trap_family_limit(mut self, trap_family_limit: Option<usize>) -> Self {
+        self.trap_family_limit = trap_family_limit;
+        self
+    }
+
+    /// Bounds on the length, path segments and query of the URLs queued from discovered links.
+    pub fn with_url_limits(mut self, url_limits: UrlLimits) -> Self {
+        self.url_limits = url_limits;
+        self
+    }
+
+    /// Lets up to `burst` requests to a host go out back to back after a lull, while
+    /// keeping to `requests_per_second` on average.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    pub fn with_adaptive_rate(mut self, adaptive_rate: bool) -> Self {
+        self.adaptive_rate = adaptive_rate;
+        self
+    }
+
+    /// Pauses crawling a host for `cooldown` once `threshold` requests to it in a row have
+    /// failed with a 5xx response, a timeout or a failed connection. None disables the breaker.
+    pub fn with_circuit_breaker(mut self, threshold: Option<u32>, cooldown: Duration) -> Self {
+        self.circuit_breaker_threshold = threshold;
+        self.circuit_breaker_cooldown = cooldown;
+        self
+    }
+
+    pub fn with_save_bodies_dir(mut self, save_bodies_dir: Option<PathBuf>) -> Self {
+        self.save_bodies_dir = save_bodies_dir;
+        self
+    }
+
+    pub fn with_global_dedupe(mut self, global_dedupe: bool) -> Self {
+        self.global_dedupe = global_dedupe;
+        self
+    }
+
+    pub fn with_respect_robots_directives(mut self, respect_robots_directives: bool) -> Self {
+        self.respect_robots_directives = respect_robots_directives;
+        self
+    }
+
+    pub fn with_follow_meta_refresh(mut self, follow_meta_refresh: bool) -> Self {
+        self.follow_meta_refresh = follow_meta_refresh;
+        self
+    }
+
+    /// Whether HTTP redirects are followed when fetching pages. Unfollowed redirects are
+    /// reported, and their targets queued like links if they are in scope.
+    pub fn with_follow_redirects(mut self, follow_redirects: bool) -> Self {
+        self.follow_redirects = follow_redirects;
+        self
+    }
+
+    /// Most redirects followed for one request before it fails.
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    pub fn with_http_version(mut self, http_version: HttpVersionPreference) -> Self {
+        self.http_version = http_version;
+        self
+    }
+
+    pub fn with_resolve_overrides(mut self, resolve_overrides: Vec<ResolveOverride>) -> Self {
+        self.resolve_overrides = resolve_overrides;
+        self
+    }
+
+    pub fn with_doh_url(mut self, doh_url: Option<Url>) -> Self {
+        self.doh_url = doh_url;
+        self
+    }
+
+    pub fn with_max_duration(mut self, max_duration: Option<Duration>) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+
+    /// Samples the run's progress every `timeline_interval` into its timeline.
+    pub fn with_timeline_interval(mut self, timeline_interval: Option<Duration>) -> Self {
+        self.timeline_interval = timeline_interval;
+        self
+    }
+
+    pub fn with_max_seed_duration(mut self, max_seed_duration: Option<Duration>) -> Self {
+        self.max_seed_duration = max_seed_duration;
+        self
+    }
+
+    pub fn with_query_policy(mut self, query_policy: QueryPolicy) -> Self {
+        self.query_policy = query_policy;
+        self
+    }
+
+    pub fn with_soft_404_patterns(mut self, soft_404_patterns: Vec<String>) -> Self {
+        self.soft_404_patterns = soft_404_patterns;
+        self
+    }
+
+    pub fn with_sitemap_report(mut self, sitemap_report: bool) -> Self {
+        self.sitemap_report = sitemap_report;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: CrawlProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn with_max_body_size(mut self, max_body_size: Option<u64>) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Caps the bytes per second received over the whole run and from each host; None leaves
+    /// that cap out.
+    pub fn with_bandwidth_limits(
+        mut self,
+        max_bandwidth: Option<u64>,
+        max_host_bandwidth: Option<u64>,
+    ) -> Self {
+        self.max_bandwidth = max_bandwidth;
+        self.max_host_bandwidth = max_host_bandwidth;
+        self
+    }
+
+    /// Longest a page may take to fetch and parse before it is recorded as timed out.
+    pub fn with_page_timeout(mut self, page_timeout: Option<Duration>) -> Self {
+        self.page_timeout = page_timeout;
+        self
+    }
+
+    pub fn with_frontier_memory_limit(mut self, frontier_memory_limit: Option<usize>) -> Self {
+        self.frontier_memory_limit = frontier_memory_limit;
+        self
+    }
+
+    pub fn with_frontier_spill_dir(mut self, frontier_spill_dir: Option<PathBuf>) -> Self {
+        self.frontier_spill_dir = frontier_spill_dir;
+        self
+    }
+
+    /// Sheds memory once the process's resident memory nears `memory_limit` bytes: frontiers
+    /// spill to disk and page text is no longer kept.
+    pub fn with_memory_limit(mut self, memory_limit: Option<u64>) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Uses `visited_filter` to remember crawled URLs, sized for `expected_urls` when a bloom
+    /// filter.
+    pub fn with_visited_filter(mut self, visited_filter: VisitedFilter, expected_urls: usize) -> Self {
+        self.visited_filter = visited_filter;
+        self.expected_urls = expected_urls;
+        self
+    }
+
+    pub fn with_render_mode(mut self, render_mode: RenderMode) -> Self {
+        self.render_mode = render_mode;
+        self
+    }
+
+    /// Crawls the "#/" and "#!/" routes of single-page apps as pages of their own. Only takes
+    /// effect when pages are rendered, since a route's content depends on the app running.
+    pub fn with_spa_routes(mut self, spa_routes: bool) -> Self {
+        self.spa_routes = spa_routes;
+        self
+    }
+
+    pub fn with_webdriver_url(mut self, webdriver_url: Url) -> Self {
+        self.webdriver_url = webdriver_url;
+        self
+    }
+
+    /// Publishes page results and the start and end of each seed's crawl to the NATS server
+    /// at `nats_url`, on subjects starting with `nats_subject`.
+    pub fn with_nats(mut self, nats_url: Option<Url>, nats_subject: &str) -> Self {
+        self.nats_url = nats_url;
+        self.nats_subject = nats_subject.to_string();
+        self
+    }
+
+    /// Coordinates a distributed crawl: pages are fetched by the workers taking requests from
+    /// the queue `queue_name` on the Redis server at `work_queue_url`, instead of locally.
+    pub fn with_work_queue(mut self, work_queue_url: Option<Url>, queue_name: &str) -> Self {
+        self.work_queue_url = work_queue_url;
+        self.queue_name = queue_name.to_string();
+        self
+    }
+
+    /// Number of pages each seed keeps handed to the workers of a distributed crawl at once,
+    /// so that several workers fetch one site together. At least 1.
+    pub fn with_dispatch_window(mut self, dispatch_window: usize) -> Self {
+        self.dispatch_window = dispatch_window.max(1);
+        self
+    }
+
+    /// Shares each seed's frontier and visited set with the other crawler processes using the
+    /// Redis server at `shared_frontier_url`, under keys starting with the queue name, so that
+    /// they crawl the seed together without fetching any page twice.
+    pub fn with_shared_frontier(mut self, shared_frontier_url: Option<Url>) -> Self {
+        self.shared_frontier_url = shared_frontier_url;
+        self
+    }
+
+    /// Streams each crawled page as a line of JSON to the file at `jsonl_output`, or to stdout
+    /// if it is "-".
+    pub fn with_jsonl_output(mut self, jsonl_output: Option<PathBuf>) -> Self {
+        self.jsonl_output = jsonl_output;
+        self
+    }
+
+    pub fn with_render_timeout(mut self, render_timeout: Duration) -> Self {
+        self.render_timeout = render_timeout;
+        self
+    }
+
+    pub fn with_render_tabs(mut self, render_tabs: usize) -> Self {
+        self.render_tabs = render_tabs;
+        self
+    }
+
+    pub fn with_checkpoint_dir(mut self, checkpoint_dir: Option<PathBuf>) -> Self {
+        self.checkpoint_dir = checkpoint_dir;
+        self
+    }
+
+    pub fn with_checkpoint_interval(mut self, checkpoint_interval: CheckpointInterval) -> Self {
+        self.checkpoint_interval = checkpoint_interval;
+        self
+    }
+
+    pub fn with_search(mut self, search: Option<TextSearch>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Hosts besides each seed's own that its crawl may follow links to.
+    pub fn with_allow_hosts(mut self, allow_hosts: Vec<HostPattern>) -> Self {
+        self.allow_hosts = allow_hosts;
+        self
+    }
+
+    /// Hosts never crawled, even if they are a seed's own host or an allowed host.
+    pub fn with_deny_hosts(mut self, deny_hosts: Vec<HostPattern>) -> Self {
+        self.deny_hosts = deny_hosts;
+        self
+    }
+
+    /// Private, link-local and loopback addresses are refused unless this is set.
+    pub fn with_allow_private_networks(mut self, allow_private_networks: bool) -> Self {
+        self.allow_private_networks = allow_private_networks;
+        self
+    }
+
+    /// Keeps the visible text of each page in its summary, for exports that index it.
+    pub fn with_keep_body_text(mut self, keep_body_text: bool) -> Self {
+        self.keep_body_text = keep_body_text;
+        self
+    }
+
+    pub fn with_readability(mut self, readability: bool) -> Self {
+        self.readability = readability;
+        self
+    }
+
+    pub fn with_accessibility_checks(mut self, accessibility_checks: bool) -> Self {
+        self.accessibility_checks = accessibility_checks;
+        self
+    }
+
+    /// Kinds of content that are parsed; documents of any other content type are skipped.
+    pub fn with_content_kinds(mut self, content_kinds: Vec<ContentKind>) -> Self {
+        self.content_kinds = content_kinds;
+        self
+    }
+
+    pub fn with_follow_feeds(mut self, follow_feeds: bool) -> Self {
+        self.follow_feeds = follow_feeds;
+        self
+    }
+
+    pub fn with_frame_policy(mut self, frame_policy: FramePolicy) -> Self {
+        self.frame_policy = frame_policy;
+        self
+    }
+
+    pub fn with_script_links(mut self, script_links: bool) -> Self {
+        self.script_links = script_links;
+        self
+    }
+
+    pub fn with_extract_rules(mut self, extract_rules: Vec<ExtractRule>) -> Self {
+        self.extract_rules = extract_rules;
+        self
+    }
+
+    /// Records the response headers named in `captured_headers` on each parsed page.
+    pub fn with_captured_headers(mut self, captured_headers: Vec<HeaderName>) -> Self {
+        self.captured_headers = captured_headers;
+        self
+    }
+
+    /// Runs `request_middleware` on every page request before it is sent, after any
+    /// middleware added before it.
+    pub fn with_request_middleware(
+        mut self,
+        request_middleware: Arc<dyn RequestMiddleware>,
+    ) -> Self {
+        self.request_middleware.push(request_middleware);
+        self
+    }
+
+    /// Runs `response_middleware` on every fetched page before it is parsed, after any
+    /// middleware added before it.
+    pub fn with_response_middleware(
+        mut self,
+        response_middleware: Arc<dyn ResponseMiddleware>,
+    ) -> Self {
+        self.response_middleware.push(response_middleware);
+        self
+    }
+
+    /// Runs the `should_follow` and `on_page` callbacks of the Rhai script in `script_file`
+    /// on discovered URLs and fetched pages.
+    pub fn with_script_file(mut self, script_file: Option<PathBuf>) -> Self {
+        self.script_file = script_file;
+        self
+    }
+
+    /// Runs the WebAssembly page processors in `plugin_files` on every parsed page, adding
+    /// their output to its extracted data.
+    pub fn with_plugin_files(mut self, plugin_files: Vec<PathBuf>) -> Self {
+        self.plugin_files = plugin_files;
+        self
+    }
+
+    /// Number of pages each seed's crawl stops at.
     pub fn max_pages(&self) -> usize {
         self.max_pages
     }
 
-    #[allow(dead_code)]
     pub fn max_depth(&self) -> usize {
         self.max_depth
     }
 
+    /// The order each seed's queued URLs are crawled in.
+    pub fn strategy(&self) -> CrawlStrategy {
+        self.strategy
+    }
+
+    /// URLs crawled before all others, whatever the strategy.
+    pub fn priority_patterns(&self) -> &[UrlPattern] {
+        &self.priority_patterns
+    }
+
+    pub fn trap_family_limit(&self) -> Option<usize> {
+        self.trap_family_limit
+    }
+
+    pub fn url_limits(&self) -> UrlLimits {
+        self.url_limits
+    }
+
+    /// Wall-clock budget for the whole run.
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration
+    }
+
+    /// How often the run's progress is sampled, if at all.
+    pub fn timeline_interval(&self) -> Option<Duration> {
+        self.timeline_interval
+    }
+
+    /// Wall-clock budget for each seed's crawl.
+    pub fn max_seed_duration(&self) -> Option<Duration> {
+        self.max_seed_duration
+    }
+
+    /// How query strings of discovered URLs are normalized before queueing.
+    pub fn query_policy(&self) -> &QueryPolicy {
+        &self.query_policy
+    }
+
+    /// Phrases in a page's title or short body that mark it as a soft 404.
+    pub fn soft_404_patterns(&self) -> &[String] {
+        &self.soft_404_patterns
+    }
+
+    /// Whether each seed's sitemaps are loaded to report orphan and unlisted pages.
+    pub fn sitemap_report(&self) -> bool {
+        self.sitemap_report
+    }
+
+    /// User agent and device headers sent with every request.
+    pub fn profile(&self) -> &CrawlProfile {
+        &self.profile
+    }
+
+    /// Largest response body, in bytes as transferred, that is read before giving up on a page.
+    pub fn max_body_size(&self) -> Option<u64> {
+        self.max_body_size
+    }
+
+    /// Bytes per second that may be received over the whole run.
+    pub fn max_bandwidth(&self) -> Option<u64> {
+        self.max_bandwidth
+    }
+
+    /// Bytes per second that may be received from each host.
+    pub fn max_host_bandwidth(&self) -> Option<u64> {
+        self.max_host_bandwidth
+    }
+
+    pub fn page_timeout(&self) -> Option<Duration> {
+        self.page_timeout
+    }
+
+    /// Number of queued URLs each seed keeps in memory before spilling to disk.
+    pub fn frontier_memory_limit(&self) -> Option<usize> {
+        self.frontier_memory_limit
+    }
+
+    /// Directory for frontier spill files, defaulting to the system temp directory.
+    pub fn frontier_spill_dir(&self) -> PathBuf {
+        self.frontier_spill_dir
+            .clone()
+            .unwrap_or_else(std::env::temp_dir)
+    }
+
+    /// Resident memory in bytes that the crawl tries to stay under.
+    pub fn memory_limit(&self) -> Option<u64> {
+        self.memory_limit
+    }
+
+    pub fn visited_filter(&self) -> VisitedFilter {
+        self.visited_filter
+    }
+
+    /// Number of URLs per seed the bloom visited filter is sized for.
+    pub fn expected_urls(&self) -> usize {
+        self.expected_urls
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    pub fn spa_routes(&self) -> bool {
+        self.spa_routes && self.render_mode == RenderMode::Js
+    }
+
+    /// WebDriver server (e.g. chromedriver) that drives the headless browser for `--render js`.
+    pub fn webdriver_url(&self) -> &Url {
+        &self.webdriver_url
+    }
+
+    pub fn nats_url(&self) -> Option<&Url> {
+        self.nats_url.as_ref()
+    }
+
+    pub fn nats_subject(&self) -> &str {
+        &self.nats_subject
+    }
+
+    pub fn work_queue_url(&self) -> Option<&Url> {
+        self.work_queue_url.as_ref()
+    }
+
+    pub fn queue_name(&self) -> &str {
+        &self.queue_name
+    }
+
+    pub fn dispatch_window(&self) -> usize {
+        self.dispatch_window
+    }
+
+    pub fn shared_frontier_url(&self) -> Option<&Url> {
+        self.shared_frontier_url.as_ref()
+    }
+
+    pub fn jsonl_output(&self) -> Option<&Path> {
+        self.jsonl_output.as_deref()
+    }
+
+    /// Longest a page may take to load and render before its unrendered HTML is used.
+    pub fn render_timeout(&self) -> Duration {
+        self.render_timeout
+    }
+
+    /// Number of browser sessions rendering pages at once.
+    pub fn render_tabs(&self) -> usize {
+        self.render_tabs
+    }
+
+    /// Directory each seed's crawl state is checkpointed to and resumed from.
+    pub fn checkpoint_dir(&self) -> Option<&Path> {
+        self.checkpoint_dir.as_deref()
+    }
+
+    pub fn checkpoint_interval(&self) -> CheckpointInterval {
+        self.checkpoint_interval
+    }
+
+    /// Pattern to search for in the visible text of every page.
+    pub fn search(&self) -> Option<&TextSearch> {
+        self.search.as_ref()
+    }
+
+    pub fn allow_hosts(&self) -> &[HostPattern] {
+        &self.allow_hosts
+    }
+
+    pub fn deny_hosts(&self) -> &[HostPattern] {
+        &self.deny_hosts
+    }
+
+    pub fn allow_private_networks(&self) -> bool {
+        self.allow_private_networks
+    }
+
+    /// Whether each page's text is scored for readability.
+    pub fn readability(&self) -> bool {
+        self.readability
+    }
+
+    pub fn keep_body_text(&self) -> bool {
+        self.keep_body_text
+    }
+
+    /// Whether each page is checked for missing alt text, empty links and buttons, skipped
+    /// heading levels and a missing lang attribute.
+    pub fn accessibility_checks(&self) -> bool {
+        self.accessibility_checks
+    }
+
+    pub fn content_kinds(&self) -> &[ContentKind] {
+        &self.content_kinds
+    }
+
+    /// Whether feeds advertised by crawled pages are fetched and their items crawled.
+    pub fn follow_feeds(&self) -> bool {
+        self.follow_feeds
+    }
+
+    /// Which frame and iframe sources are crawled.
+    pub fn frame_policy(&self) -> FramePolicy {
+        self.frame_policy
+    }
+
+    /// Whether URLs in onclick handlers and data-href/data-url attributes are crawled.
+    pub fn script_links(&self) -> bool {
+        self.script_links
+    }
+
+    /// Values to pull out of every crawled page.
+    pub fn extract_rules(&self) -> &[ExtractRule] {
+        &self.extract_rules
+    }
+
+    pub fn captured_headers(&self) -> &[HeaderName] {
+        &self.captured_headers
+    }
+
+    /// Middleware applied to every page request, in order.
+    pub fn request_middleware(&self) -> &[Arc<dyn RequestMiddleware>] {
+        &self.request_middleware
+    }
+
+    /// Middleware applied to every fetched page before parsing, in order.
+    pub fn response_middleware(&self) -> &[Arc<dyn ResponseMiddleware>] {
+        &self.response_middleware
+    }
+
+    pub fn script_file(&self) -> Option<&PathBuf> {
+        self.script_file.as_ref()
+    }
+
+    pub fn plugin_files(&self) -> &[PathBuf] {
+        &self.plugin_files
+    }
+
     pub fn requests_per_second(&self) -> Option<f64> {
         self.requests_per_second
     }
+
+    /// Requests per host that may be sent at once, within the rate limit.
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Whether each host's rate backs off on 429/503 responses and rising response times,
+    /// up to `requests_per_second`.
+    pub fn adaptive_rate(&self) -> bool {
+        self.adaptive_rate
+    }
+
+    /// Consecutive failed requests that open a host's circuit breaker, if it is enabled.
+    pub fn circuit_breaker_threshold(&self) -> Option<u32> {
+        self.circuit_breaker_threshold
+    }
+
+    /// How long a host is left alone once its circuit breaker opens.
+    pub fn circuit_breaker_cooldown(&self) -> Duration {
+        self.circuit_breaker_cooldown
+    }
+
+    pub fn save_bodies_dir(&self) -> Option<&Path> {
+        self.save_bodies_dir.as_deref()
+    }
+
+    /// Whether URLs crawled by one seed are skipped by all other seeds.
+    pub fn global_dedupe(&self) -> bool {
+        self.global_dedupe
+    }
+
+    /// Whether noindex/nofollow directives are obeyed. They are reported either way.
+    pub fn respect_robots_directives(&self) -> bool {
+        self.respect_robots_directives
+    }
+
+    /// Whether `<meta http-equiv="refresh">` targets are followed like redirects.
+    pub fn follow_meta_refresh(&self) -> bool {
+        self.follow_meta_refresh
+    }
+
+    pub fn follow_redirects(&self) -> bool {
+        self.follow_redirects
+    }
+
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects
+    }
+
+    pub fn http_version(&self) -> HttpVersionPreference {
+        self.http_version
+    }
+
+    /// Extracts links from HTML with a streaming tokenizer instead of a DOM, for crawls of
+    /// large pages. Only `<a href>` links are followed, and extract rules and accessibility
+    /// checks are skipped.
+    pub fn with_streaming_html(mut self, streaming_html: bool) -> Self {
+        self.streaming_html = streaming_html;
+        self
+    }
+
+    /// Host names pinned to fixed addresses, bypassing DNS.
+    pub fn resolve_overrides(&self) -> &[ResolveOverride] {
+        &self.resolve_overrides
+    }
+
+    /// DNS-over-HTTPS endpoint used instead of the system resolver, if any.
+    pub fn doh_url(&self) -> Option<&Url> {
+        self.doh_url.as_ref()
+    }
+
+    pub fn link_selector(&self) -> &str {
+        &self.link_selector
+    }
+
+    pub fn deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    pub fn streaming_html(&self) -> bool {
+        self.streaming_html
+    }
+
+    /// The settings that decide what a crawl fetches and how, recorded with its results.
+    pub fn snapshot(&self) -> serde_json::Value {
+        fn value_name<T: clap::ValueEnum>(value: &T) -> Option<String> {
+            value
+                .to_possible_value()
+                .map(|possible_value| possible_value.get_name().to_string())
+        }
+        fn display_all<T: std::fmt::Display>(values: &[T]) -> Vec<String> {
+            values.iter().map(ToString::to_string).collect()
+        }
+        serde_json::json!({
+            "max_pages": self.max_pages,
+            "max_depth": self.max_depth,
+            "strategy": value_name(&self.strategy),
+            "priority_patterns": display_all(&self.priority_patterns),
+            "trap_family_limit": self.trap_family_limit,
+            "max_url_length": self.url_limits.max_url_length,
+            "max_path_segments": self.url_limits.max_path_segments,
+            "max_query_length": self.url_limits.max_query_length,
+            "requests_per_second": self.requests_per_second,
+            "burst": self.burst,
+            "adaptive_rate": self.adaptive_rate,
+            "circuit_breaker_threshold": self.circuit_breaker_threshold,
+            "circuit_breaker_cooldown": humantime::format_duration(self.circuit_breaker_cooldown)
+                .to_string(),
+            "global_dedupe": self.global_dedupe,
+            "respect_robots_directives": self.respect_robots_directives,
+            "follow_meta_refresh": self.follow_meta_refresh,
+            "follow_redirects": self.follow_redirects,
+            "max_redirects": self.max_redirects,
+            "http_version": value_name(&self.http_version),
+            "max_duration": self.max_duration.map(|d| humantime::format_duration(d).to_string()),
+            "max_seed_duration": self
+                .max_seed_duration
+                .map(|d| humantime::format_duration(d).to_string()),
+            "timeline_interval": self
+                .timeline_interval
+                .map(|d| humantime::format_duration(d).to_string()),
+            "query_policy": self.query_policy.to_string(),
+            "follow_feeds": self.follow_feeds,
+            "frame_policy": value_name(&self.frame_policy),
+            "script_links": self.script_links,
+            "user_agent": self.profile.user_agent,
+            "accept_language": self.profile.accept_language,
+            "max_body_size": self.max_body_size,
+            "max_bandwidth": self.max_bandwidth,
+            "max_host_bandwidth": self.max_host_bandwidth,
+            "page_timeout": self.page_timeout.map(|d| humantime::format_duration(d).to_string()),
+            "visited_filter": value_name(&self.visited_filter),
+            "render_mode": value_name(&self.render_mode),
+            "content_types": self.content_kinds.iter().map(value_name).collect::<Vec<_>>(),
+            "allow_hosts": display_all(&self.allow_hosts),
+            "deny_hosts": display_all(&self.deny_hosts),
+            "allow_private_networks": self.allow_private_networks,
+            "deterministic": self.deterministic,
+            "link_selector": self.link_selector,
+            "streaming_html": self.streaming_html,
+        })
+    }
 }
@@ -0,0 +1,13 @@
+mod crawl_message;
+mod crawl_worker;
+mod frontier_service;
+mod redis_connection;
+mod redis_frontier_service;
+mod remote_fetcher;
+
+pub use crawl_message::{CrawlRequest, CrawlResponse, WorkerInfo};
+pub use crawl_worker::{CrawlWorker, HEARTBEAT_INTERVAL};
+pub use frontier_service::FrontierService;
+pub use redis_connection::{RedisConnection, RedisValue, redacted};
+pub use redis_frontier_service::{DEFAULT_QUEUE_NAME, RedisFrontierService};
+pub use remote_fetcher::{DEFAULT_DISPATCH_WINDOW, RemoteFetcher};
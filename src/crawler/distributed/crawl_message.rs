@@ -0,0 +1,201 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::fetch::FetchResult;
+use anyhow::{Context, anyhow};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use url::Url;
+
+/// A page for a worker to fetch, sent by the coordinator as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CrawlRequest {
+    /// Unique ID the response is sent back under.
+    pub id: String,
+    pub url: Url,
+}
+
+/// A worker's answer to a CrawlRequest.
+///
+/// On the wire it is its JSON, a newline, and then the page body as fetched, so that bodies
+/// need no escaping.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CrawlResponse {
+    pub request_id: String,
+    pub worker_id: String,
+    pub outcome: FetchOutcome,
+    #[serde(skip)]
+    pub body: Vec<u8>,
+}
+
+/// What became of a fetch on the worker.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum FetchOutcome {
+    /// The page was fetched; the response's body is the page body as transferred.
+    Fetched {
+        status_code: u16,
+        http_version: Option<String>,
+        headers: Vec<(String, String)>,
+        /// Why the worker's browser failed to render the page, if it did.
+        #[serde(default)]
+        render_error: Option<String>,
+    },
+    /// The body exceeded the worker's maximum body size.
+    TooLarge { status_code: u16 },
+    /// The request timed out or could not connect.
+    NoResponse { reason: String },
+    Failed { reason: String },
+}
+
+/// A worker as registered with the coordinator.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WorkerInfo {
+    pub id: String,
+    pub host_name: String,
+    pub pid: u32,
+}
+
+impl CrawlResponse {
+    /// The response to `request_id` for the result of fetching its page.
+    pub fn new(
+        request_id: String,
+        worker_id: String,
+        fetch_result: Result<FetchResult, CrawlError>,
+    ) -> Self {
+        let (outcome, body) = match fetch_result {
+            Ok(fetch_result) => (
+                FetchOutcome::Fetched {
+                    status_code: fetch_result.status_code,
+                    http_version: fetch_result.http_version,
+                    headers: fetch_result
+                        .headers
+                        .iter()
+                        .map(|(name, value)| {
+                            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                            (name.to_string(), value)
+                        })
+                        .collect(),
+                    render_error: fetch_result.render_error,
+                },
+                fetch_result.body,
+            ),
+            Err(CrawlError::TooLarge(status_code)) => {
+                (FetchOutcome::TooLarge { status_code }, Vec::new())
+            }
+            Err(CrawlError::ReqwestError(e)) if e.is_timeout() || e.is_connect() => {
+                let reason = format!("{:#}", anyhow::Error::from(e));
+                (FetchOutcome::NoResponse { reason }, Vec::new())
+            }
+            Err(CrawlError::NoResponse(reason)) => {
+                (FetchOutcome::NoResponse { reason }, Vec::new())
+            }
+            Err(e) => {
+                let reason = format!("{:#}", anyhow::Error::from(e));
+                (FetchOutcome::Failed { reason }, Vec::new())
+            }
+        };
+        Self {
+            request_id,
+            worker_id,
+            outcome,
+            body,
+        }
+    }
+
+    /// The fetch result as if the page had been fetched locally.
+    pub fn into_fetch_result(self) -> Result<FetchResult, CrawlError> {
+        match self.outcome {
+            FetchOutcome::Fetched {
+                status_code,
+                http_version,
+                headers,
+                render_error,
+            } => {
+                let mut header_map = HeaderMap::new();
+                for (name, value) in headers {
+                    if let (Ok(name), Ok(value)) =
+                        (HeaderName::try_from(name), HeaderValue::try_from(value))
+                    {
+                        header_map.append(name, value);
+                    }
+                }
+                Ok(FetchResult {
+                    status_code,
+                    http_version,
+                    headers: header_map,
+                    body: self.body,
+                    render_error,
+                })
+            }
+            FetchOutcome::TooLarge { status_code } => Err(CrawlError::TooLarge(status_code)),
+            FetchOutcome::NoResponse { reason } => Err(CrawlError::NoResponse(reason)),
+            // Like a page that didn't answer, a page the worker failed on only loses that page
+            FetchOutcome::Failed { reason } => Err(CrawlError::NoResponse(format!(
+                "worker {} failed: {}",
+                self.worker_id, reason
+            ))),
+        }
+    }
+
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        bytes.extend_from_slice(&self.body);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let newline = bytes
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .ok_or_else(|| anyhow!("Crawl response has no body separator"))?;
+        let mut response: Self =
+            serde_json::from_slice(&bytes[..newline]).context("Invalid crawl response")?;
+        response.body = bytes[newline + 1..].to_vec();
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::CONTENT_TYPE;
+
+    #[test]
+    fn crawl_responses_survive_the_wire() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        let body = b"<html>\n\x00binary\xff</html>".to_vec();
+        let fetch_result = FetchResult {
+            status_code: 200,
+            http_version: Some("HTTP/1.1".to_string()),
+            headers,
+            body: body.clone(),
+            render_error: Some("timed out".to_string()),
+        };
+        let response = CrawlResponse::new("r1".to_string(), "w1".to_string(), Ok(fetch_result));
+        let received = CrawlResponse::from_bytes(&response.to_bytes().unwrap()).unwrap();
+        assert_eq!(received, response);
+        let fetch_result = received.into_fetch_result().unwrap();
+        assert_eq!(fetch_result.status_code, 200);
+        assert_eq!(fetch_result.headers[CONTENT_TYPE], "text/html");
+        assert_eq!(fetch_result.body, body);
+        assert_eq!(fetch_result.render_error.as_deref(), Some("timed out"));
+
+        let too_large =
+            CrawlResponse::new("r2".to_string(), "w1".to_string(), Err(CrawlError::TooLarge(200)));
+        let received = CrawlResponse::from_bytes(&too_large.to_bytes().unwrap()).unwrap();
+        assert!(matches!(received.into_fetch_result(), Err(CrawlError::TooLarge(200))));
+    }
+
+    #[test]
+    fn worker_failures_only_lose_the_page() {
+        let error = anyhow!("invalid certificate").into();
+        let failed = CrawlResponse::new("r1".to_string(), "w1".to_string(), Err(error));
+        let received = CrawlResponse::from_bytes(&failed.to_bytes().unwrap()).unwrap();
+        match received.into_fetch_result() {
+            Err(CrawlError::NoResponse(reason)) => {
+                assert_eq!(reason, "worker w1 failed: invalid certificate")
+            }
+            result => panic!("Expected no response, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::crawler::crawler_config::CrawlerConfig;
+use crate::crawler::distributed::{CrawlResponse, FrontierService, RedisFrontierService, WorkerInfo};
+use crate::crawler::fetch::Fetcher;
+use crate::crawler::multi::{host_name, new_run_id};
+use crate::crawler::shared_crawl_state::SharedCrawlState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// How often workers renew their registration, and the coordinator checks on them.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a registration lasts without heartbeats: a worker is given up on after missing
+/// three in a row.
+const REGISTRATION_TTL: Duration = Duration::from_secs(15);
+
+/// Fetches the pages a coordinator hands out, one at a time, with its own fetcher and so
+/// its own fetch options, until cancelled. Run several, possibly on different machines, to
+/// fetch more pages at once.
+pub struct CrawlWorker {
+    service: Arc<dyn FrontierService>,
+    fetcher: Arc<dyn Fetcher>,
+    info: WorkerInfo,
+}
+
+impl CrawlWorker {
+    pub fn new(service: Arc<dyn FrontierService>, fetcher: Arc<dyn Fetcher>) -> Self {
+        Self {
+            service,
+            fetcher,
+            info: WorkerInfo {
+                id: new_run_id(),
+                host_name: host_name(),
+                pid: std::process::id(),
+            },
+        }
+    }
+
+    /// A worker taking requests from the queue `crawler_config.queue_name()` on the Redis
+    /// server at `queue_url`, and fetching them as a local crawl with `crawler_config` would.
+    /// Must be called within a runtime.
+    pub fn for_redis(queue_url: Url, crawler_config: &CrawlerConfig) -> anyhow::Result<Self> {
+        let service = RedisFrontierService::new(queue_url, crawler_config.queue_name());
+        // Workers fetch locally, whatever work queue the config names
+        let local_config =
+            crawler_config.clone().with_work_queue(None, crawler_config.queue_name());
+        let fetcher = SharedCrawlState::new(&local_config)?.fetcher;
+        Ok(Self::new(Arc::new(service), fetcher))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.info.id
+    }
+
+    /// Registers with the coordinator and fetches pages until `cancel_token` is cancelled,
+    /// then deregisters. Returns the number of pages fetched.
+    pub async fn run(&self, cancel_token: CancellationToken) -> anyhow::Result<usize> {
+        self.service.heartbeat(&self.info, REGISTRATION_TTL).await?;
+        let heartbeats = {
+            let service = Arc::clone(&self.service);
+            let info = self.info.clone();
+            tokio::task::spawn(async move {
+                loop {
+                    tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+                    if let Err(e) = service.heartbeat(&info, REGISTRATION_TTL).await {
+                        eprintln!("Warning: heartbeat failed: {:#}", e);
+                    }
+                }
+            })
+        };
+        let result = self.fetch_requests(&cancel_token).await;
+        heartbeats.abort();
+        // A page being fetched when cancelled goes back to the queue for another worker
+        self.service.deregister(&self.info.id).await?;
+        result
+    }
+
+    async fn fetch_requests(&self, cancel_token: &CancellationToken) -> anyhow::Result<usize> {
+        let mut num_pages = 0;
+        loop {
+            let request = tokio::select! {
+                request = self.service.take_request(&self.info.id, HEARTBEAT_INTERVAL) => request?,
+                _ = cancel_token.cancelled() => return Ok(num_pages),
+            };
+            let Some(request) = request else {
+                continue;
+            };
+            let fetch_result = tokio::select! {
+                fetch_result = self.fetcher.fetch(&request.url) => fetch_result,
+                _ = cancel_token.cancelled() => return Ok(num_pages),
+            };
+            let response =
+                CrawlResponse::new(request.id.clone(), self.info.id.clone(), fetch_result);
+            self.service.respond(&request, &response).await?;
+            num_pages += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::distributed::RemoteFetcher;
+    use crate::test_support::{MockRedisServer, MockSite, MockSiteServer};
+
+    #[tokio::test]
+    async fn fetches_the_pages_a_remote_fetcher_hands_out() {
+        let site = MockSite::new().with_html("/", &["/a"]);
+        let site_server = MockSiteServer::start(site).await.unwrap();
+        let redis_server = MockRedisServer::start().await.unwrap();
+        let config = CrawlerConfig::new(100, 4, None).with_allow_private_networks(true);
+        let crawl_worker = CrawlWorker::for_redis(redis_server.url().clone(), &config).unwrap();
+        let cancel_token = CancellationToken::new();
+        let worker = tokio::task::spawn({
+            let cancel_token = cancel_token.clone();
+            async move { crawl_worker.run(cancel_token).await }
+        });
+
+        let service = RedisFrontierService::new(redis_server.url().clone(), config.queue_name());
+        let remote_fetcher = RemoteFetcher::start(Arc::new(service), 2);
+        // A dispatched page is fetched while the coordinator waits on the one before it
+        remote_fetcher.dispatch(&site_server.url("/missing")).await;
+        let home = remote_fetcher.fetch(&site_server.url("/")).await.unwrap();
+        assert_eq!(home.status_code, 200);
+        assert!(String::from_utf8_lossy(&home.body).contains("href=\"/a\""));
+        let missing = remote_fetcher.fetch(&site_server.url("/missing")).await.unwrap();
+        assert_eq!(missing.status_code, 404);
+
+        cancel_token.cancel();
+        assert_eq!(worker.await.unwrap().unwrap(), 2);
+        // The worker deregistered, leaving nothing behind
+        assert!(redis_server.keys().is_empty(), "{:?}", redis_server.keys());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::crawler::distributed::{CrawlRequest, CrawlResponse, WorkerInfo};
+use futures::future::BoxFuture;
+use std::time::Duration;
+
+/// The channel between a coordinator, which manages the frontier, and the workers that fetch
+/// its pages, so that the transport can be swapped out.
+///
+/// Requests are handed out in the order they were submitted, each to one worker. Workers
+/// register and send heartbeats while they run; the requests held by a worker whose
+/// heartbeats stop are handed out again.
+pub trait FrontierService: Send + Sync {
+    /// Queues a page for the workers to fetch.
+    fn submit<'a>(&'a self, request: &'a CrawlRequest) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// Waits up to `timeout` for the response to the request `request_id`.
+    fn wait_for_response<'a>(
+        &'a self,
+        request_id: &'a str,
+        timeout: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<Option<CrawlResponse>>>;
+
+    /// Registers a worker, or renews its registration, for the next `ttl`.
+    fn heartbeat<'a>(
+        &'a self,
+        worker: &'a WorkerInfo,
+        ttl: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// Removes a worker that is shutting down, handing out its requests again.
+    fn deregister<'a>(&'a self, worker_id: &'a str) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// The workers whose registration is current.
+    fn live_workers(&self) -> BoxFuture<'_, anyhow::Result<Vec<WorkerInfo>>>;
+
+    /// Hands out again the requests held by workers whose registration lapsed, and returns
+    /// the IDs of those workers.
+    fn requeue_lost_requests(&self) -> BoxFuture<'_, anyhow::Result<Vec<String>>>;
+
+    /// Waits up to `timeout` for a request for the worker `worker_id` to fetch.
+    fn take_request<'a>(
+        &'a self,
+        worker_id: &'a str,
+        timeout: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<Option<CrawlRequest>>>;
+
+    /// Answers a request taken by the response's worker.
+    fn respond<'a>(
+        &'a self,
+        request: &'a CrawlRequest,
+        response: &'a CrawlResponse,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+}
@@ -0,0 +1,289 @@
+use crate::crawler::distributed::{
+    CrawlRequest, CrawlResponse, FrontierService, RedisConnection, RedisValue, WorkerInfo,
+};
+use futures::future::BoxFuture;
+use std::sync::Mutex;
+use std::time::Duration;
+use url::Url;
+
+/// Prefix of the Redis keys of the work queue when no `--queue-name` is given.
+pub const DEFAULT_QUEUE_NAME: &str = "rusty-spider";
+
+/// How long an uncollected response is kept, e.g. for a coordinator that gave up on it.
+const RESPONSE_TTL_SECS: u64 = 3600;
+
+/// A FrontierService on Redis lists, shared by the coordinator and workers that connect to
+/// the same server and queue name. Under the queue name `NAME`:
+///
+/// - `NAME:requests` lists the requests waiting for a worker
+/// - `NAME:claimed:<worker>` lists the requests a worker is fetching
+/// - `NAME:response:<request>` holds the response to a request until it is collected
+/// - `NAME:workers` is the set of registered workers, and `NAME:worker:<worker>` a worker's
+///   registration, which expires unless renewed by heartbeats
+pub struct RedisFrontierService {
+    redis_url: Url,
+    queue_name: String,
+    idle_connections: Mutex<Vec<RedisConnection>>,
+}
+
+impl RedisFrontierService {
+    /// Connects to the server lazily, on first use.
+    pub fn new(redis_url: Url, queue_name: &str) -> Self {
+        Self {
+            redis_url,
+            queue_name: queue_name.to_string(),
+            idle_connections: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn key(&self, suffix: &str) -> Vec<u8> {
+        format!("{}:{}", self.queue_name, suffix).into_bytes()
+    }
+
+    /// Runs a command on an idle connection, or a new one. Blocking commands hold their
+    /// connection until they return, so each waiting task gets its own.
+    async fn command(&self, args: &[&[u8]]) -> anyhow::Result<RedisValue> {
+        let idle_connection = self.idle_connections.lock().unwrap().pop();
+        let mut connection = match idle_connection {
+            Some(connection) => connection,
+            None => RedisConnection::connect(&self.redis_url).await?,
+        };
+        // A connection that failed, or whose command was cancelled, is not reused
+        let value = connection.command(args).await?;
+        self.idle_connections.lock().unwrap().push(connection);
+        Ok(value)
+    }
+
+    async fn worker_ids(&self) -> anyhow::Result<Vec<String>> {
+        self.command(&[b"SMEMBERS", &self.key("workers")])
+            .await?
+            .into_array()?
+            .into_iter()
+            .filter_map(|value| value.into_data().transpose())
+            .map(|data| Ok(String::from_utf8(data?)?))
+            .collect()
+    }
+
+    /// Moves the requests claimed by a worker back to the front of the queue.
+    async fn requeue_claimed(&self, worker_id: &str) -> anyhow::Result<()> {
+        let claimed_key = self.key(&format!("claimed:{}", worker_id));
+        let requests_key = self.key("requests");
+        loop {
+            let value = self
+                .command(&[b"LMOVE", &claimed_key, &requests_key, b"RIGHT", b"RIGHT"])
+                .await?;
+            if value == RedisValue::Nil {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// A timeout as the seconds argument of a blocking command, which 0 would make wait forever.
+fn timeout_arg(timeout: Duration) -> Vec<u8> {
+    format!("{:.3}", timeout.as_secs_f64().max(0.001)).into_bytes()
+}
+
+impl FrontierService for RedisFrontierService {
+    fn submit<'a>(&'a self, request: &'a CrawlRequest) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let request = serde_json::to_vec(request)?;
+            self.command(&[b"LPUSH", &self.key("requests"), &request]).await?;
+            Ok(())
+        })
+    }
+
+    fn wait_for_response<'a>(
+        &'a self,
+        request_id: &'a str,
+        timeout: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<Option<CrawlResponse>>> {
+        Box::pin(async move {
+            let response_key = self.key(&format!("response:{}", request_id));
+            let reply = self
+                .command(&[b"BLPOP", &response_key, &timeout_arg(timeout)])
+                .await?
+                .into_array()?;
+            // The reply names the key the value was popped from
+            match reply.into_iter().nth(1) {
+                Some(value) => match value.into_data()? {
+                    Some(data) => Ok(Some(CrawlResponse::from_bytes(&data)?)),
+                    None => Ok(None),
+                },
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn heartbeat<'a>(
+        &'a self,
+        worker: &'a WorkerInfo,
+        ttl: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let worker_info = serde_json::to_vec(worker)?;
+            let ttl_millis = ttl.as_millis().max(1).to_string();
+            self.command(&[
+                b"SET",
+                &self.key(&format!("worker:{}", worker.id)),
+                &worker_info,
+                b"PX",
+                ttl_millis.as_bytes(),
+            ])
+            .await?;
+            self.command(&[b"SADD", &self.key("workers"), worker.id.as_bytes()]).await?;
+            Ok(())
+        })
+    }
+
+    fn deregister<'a>(&'a self, worker_id: &'a str) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.requeue_claimed(worker_id).await?;
+            self.command(&[b"SREM", &self.key("workers"), worker_id.as_bytes()]).await?;
+            self.command(&[b"DEL", &self.key(&format!("worker:{}", worker_id))]).await?;
+            Ok(())
+        })
+    }
+
+    fn live_workers(&self) -> BoxFuture<'_, anyhow::Result<Vec<WorkerInfo>>> {
+        Box::pin(async move {
+            let mut workers = Vec::new();
+            for worker_id in self.worker_ids().await? {
+                let worker_key = self.key(&format!("worker:{}", worker_id));
+                let worker_info = self.command(&[b"GET", &worker_key]).await?.into_data()?;
+                if let Some(worker_info) = worker_info {
+                    workers.push(serde_json::from_slice(&worker_info)?);
+                }
+            }
+            Ok(workers)
+        })
+    }
+
+    fn requeue_lost_requests(&self) -> BoxFuture<'_, anyhow::Result<Vec<String>>> {
+        Box::pin(async move {
+            let mut lost_worker_ids = Vec::new();
+            for worker_id in self.worker_ids().await? {
+                let worker_key = self.key(&format!("worker:{}", worker_id));
+                if self.command(&[b"EXISTS", &worker_key]).await?.into_integer()? == 0 {
+                    self.requeue_claimed(&worker_id).await?;
+                    self.command(&[b"SREM", &self.key("workers"), worker_id.as_bytes()]).await?;
+                    lost_worker_ids.push(worker_id);
+                }
+            }
+            Ok(lost_worker_ids)
+        })
+    }
+
+    fn take_request<'a>(
+        &'a self,
+        worker_id: &'a str,
+        timeout: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<Option<CrawlRequest>>> {
+        Box::pin(async move {
+            let request = self
+                .command(&[
+                    b"BLMOVE",
+                    &self.key("requests"),
+                    &self.key(&format!("claimed:{}", worker_id)),
+                    b"RIGHT",
+                    b"LEFT",
+                    &timeout_arg(timeout),
+                ])
+                .await?
+                .into_data()?;
+            match request {
+                Some(request) => Ok(Some(serde_json::from_slice(&request)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn respond<'a>(
+        &'a self,
+        request: &'a CrawlRequest,
+        response: &'a CrawlResponse,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let response_key = self.key(&format!("response:{}", request.id));
+            self.command(&[b"LPUSH", &response_key, &response.to_bytes()?]).await?;
+            let ttl = RESPONSE_TTL_SECS.to_string();
+            self.command(&[b"EXPIRE", &response_key, ttl.as_bytes()]).await?;
+            // Requests serialize the same way every time, so this matches the claimed copy
+            let claimed_key = self.key(&format!("claimed:{}", response.worker_id));
+            let request = serde_json::to_vec(request)?;
+            self.command(&[b"LREM", &claimed_key, b"1", &request]).await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::crawl_error::CrawlError;
+    use crate::test_support::{MockRedisServer, MockReply};
+
+    fn request(id: &str) -> CrawlRequest {
+        CrawlRequest {
+            id: id.to_string(),
+            url: Url::parse(&format!("https://example.com/{}", id)).unwrap(),
+        }
+    }
+
+    fn worker(id: &str) -> WorkerInfo {
+        WorkerInfo {
+            id: id.to_string(),
+            host_name: "localhost".to_string(),
+            pid: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn hands_out_requests_in_order_and_returns_their_responses() {
+        let server = MockRedisServer::start().await.unwrap();
+        let service = RedisFrontierService::new(server.url().clone(), "test");
+        let timeout = Duration::from_millis(50);
+        service.submit(&request("r1")).await.unwrap();
+        service.submit(&request("r2")).await.unwrap();
+        assert_eq!(service.take_request("w1", timeout).await.unwrap(), Some(request("r1")));
+        assert_eq!(service.take_request("w2", timeout).await.unwrap(), Some(request("r2")));
+        assert_eq!(service.take_request("w1", timeout).await.unwrap(), None);
+
+        let response =
+            CrawlResponse::new("r1".to_string(), "w1".to_string(), Err(CrawlError::TooLarge(200)));
+        service.respond(&request("r1"), &response).await.unwrap();
+        let received = service.wait_for_response("r1", timeout).await.unwrap();
+        assert_eq!(received, Some(response));
+        // Responses are collected once, and only the unanswered request is still claimed
+        assert_eq!(service.wait_for_response("r1", timeout).await.unwrap(), None);
+        assert_eq!(server.keys(), vec!["test:claimed:w2"]);
+        let claimed = server.command(&[b"LRANGE", b"test:claimed:w2", b"0", b"-1"]);
+        let request_json = serde_json::to_vec(&request("r2")).unwrap();
+        assert_eq!(claimed, MockReply::Array(vec![MockReply::Data(request_json)]));
+    }
+
+    #[tokio::test]
+    async fn hands_out_the_requests_of_lost_workers_again() {
+        let server = MockRedisServer::start().await.unwrap();
+        let service = RedisFrontierService::new(server.url().clone(), "test");
+        let timeout = Duration::from_millis(50);
+        service.heartbeat(&worker("w1"), Duration::from_millis(100)).await.unwrap();
+        service.heartbeat(&worker("w2"), Duration::from_secs(60)).await.unwrap();
+        service.submit(&request("r1")).await.unwrap();
+        assert!(service.take_request("w1", timeout).await.unwrap().is_some());
+        assert_eq!(service.live_workers().await.unwrap(), vec![worker("w1"), worker("w2")]);
+        assert!(service.requeue_lost_requests().await.unwrap().is_empty());
+        assert_eq!(service.take_request("w2", timeout).await.unwrap(), None);
+
+        // w1 stops sending heartbeats
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(service.live_workers().await.unwrap(), vec![worker("w2")]);
+        assert_eq!(service.requeue_lost_requests().await.unwrap(), vec!["w1".to_string()]);
+        assert_eq!(service.take_request("w2", timeout).await.unwrap(), Some(request("r1")));
+
+        // A worker shutting down hands its requests back too
+        service.deregister("w2").await.unwrap();
+        assert!(service.live_workers().await.unwrap().is_empty());
+        assert_eq!(service.take_request("w3", timeout).await.unwrap(), Some(request("r1")));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::distributed::{CrawlRequest, FrontierService, HEARTBEAT_INTERVAL};
+use crate::crawler::fetch::{FetchResult, Fetcher};
+use crate::crawler::multi::new_run_id;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use url::Url;
+
+/// How long the coordinator waits for a worker to answer before giving up on a page.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Pages each seed keeps handed to the workers at once when no `--dispatch-window` is given.
+pub const DEFAULT_DISPATCH_WINDOW: usize = 8;
+
+/// The fetcher of a coordinator: hands each page to the workers through a FrontierService
+/// and waits for one of them to fetch it. Everything else, from the frontier and robots.txt
+/// to parsing, stays with the coordinator.
+///
+/// Pages are handed out as they are dispatched, up to `dispatch_window` per seed, so that
+/// several workers fetch one seed's pages at once while the coordinator waits on the first.
+pub struct RemoteFetcher {
+    service: Arc<dyn FrontierService>,
+    dispatch_window: usize,
+    /// The IDs of the requests for dispatched pages not yet fetched
+    dispatched: Mutex<HashMap<Url, String>>,
+}
+
+impl RemoteFetcher {
+    /// Also starts watching the workers in the background, handing out the pages of workers
+    /// that stopped sending heartbeats again, until the fetcher is dropped. Must be called
+    /// within a runtime.
+    pub fn start(service: Arc<dyn FrontierService>, dispatch_window: usize) -> Self {
+        let watched: Weak<dyn FrontierService> = Arc::downgrade(&service);
+        tokio::task::spawn(async move {
+            let mut had_workers = true;
+            loop {
+                tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+                let Some(service) = watched.upgrade() else {
+                    break;
+                };
+                match service.requeue_lost_requests().await {
+                    Ok(lost_worker_ids) => {
+                        for worker_id in lost_worker_ids {
+                            eprintln!(
+                                "Warning: worker {} stopped sending heartbeats; its pages are \
+                                 queued again",
+                                worker_id
+                            );
+                        }
+                    }
+                    Err(e) => eprintln!("Warning: unable to check on the workers: {:#}", e),
+                }
+                let has_workers = service
+                    .live_workers()
+                    .await
+                    .is_ok_and(|workers| !workers.is_empty());
+                if had_workers && !has_workers {
+                    eprintln!("Warning: no workers are running; pages wait until one starts");
+                }
+                had_workers = has_workers;
+            }
+        });
+        Self {
+            service,
+            dispatch_window: dispatch_window.max(1),
+            dispatched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Hands a page to the workers, returning the ID of its request.
+    async fn submit(&self, url: &Url) -> anyhow::Result<String> {
+        let request = CrawlRequest {
+            id: new_run_id(),
+            url: url.clone(),
+        };
+        self.service.submit(&request).await?;
+        Ok(request.id)
+    }
+
+    async fn fetch_remotely(&self, url: &Url) -> Result<FetchResult, CrawlError> {
+        // The work queue being unreachable loses the page, not the crawl
+        let unreachable =
+            |e: anyhow::Error| CrawlError::NoResponse(format!("work queue unreachable: {:#}", e));
+        let dispatched_id = self.dispatched.lock().unwrap().remove(url);
+        let request_id = match dispatched_id {
+            Some(request_id) => request_id,
+            None => self.submit(url).await.map_err(unreachable)?,
+        };
+        let response = self
+            .service
+            .wait_for_response(&request_id, RESPONSE_TIMEOUT)
+            .await
+            .map_err(unreachable)?;
+        match response {
+            Some(response) => response.into_fetch_result(),
+            None => Err(CrawlError::NoResponse(format!(
+                "no worker answered within {}",
+                humantime::format_duration(RESPONSE_TIMEOUT)
+            ))),
+        }
+    }
+}
+
+impl Fetcher for RemoteFetcher {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+        Box::pin(self.fetch_remotely(url))
+    }
+
+    fn dispatch_window(&self) -> usize {
+        self.dispatch_window
+    }
+
+    fn dispatch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            // A page that couldn't be handed out now is submitted again when fetched
+            if let Ok(request_id) = self.submit(url).await {
+                self.dispatched.lock().unwrap().insert(url.clone(), request_id);
+            }
+        })
+    }
+}
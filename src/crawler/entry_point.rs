@@ -0,0 +1,3 @@
+mod entry_point_prober;
+
+pub use entry_point_prober::EntryPointProber;
@@ -0,0 +1,142 @@
+use anyhow::{Context, anyhow};
+use futures::StreamExt;
+use std::time::Duration;
+use url::{Host, Url};
+
+/// How long each candidate URL may take to answer when no page timeout is given.
+const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Domains probed at once by `probe_all`.
+const MAX_CONCURRENT_PROBES: usize = 16;
+
+/// Finds the entry point of a site from its bare domain, for use as a seed: the page that
+/// the first of the domain's HTTPS, HTTP and www. variants to answer successfully ends up
+/// at, after redirects.
+pub struct EntryPointProber {
+    http_client: reqwest::Client,
+    timeout: Duration,
+}
+
+impl EntryPointProber {
+    /// Probes with `http_client`, which should follow redirects.
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self {
+            http_client,
+            timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The URLs tried for a domain such as "example.com", in order: HTTPS before HTTP, and
+    /// the domain as given before its www. variant. IP addresses have no www. variant.
+    pub fn candidate_urls(domain: &str) -> anyhow::Result<Vec<Url>> {
+        let domain = domain.trim().trim_end_matches('/').to_ascii_lowercase();
+        if domain.contains("://") {
+            return Err(anyhow!(
+                "'{}' is a URL rather than a bare domain; list URLs with --seed-file",
+                domain
+            ));
+        }
+        let url = Url::parse(&format!("https://{}/", domain))
+            .with_context(|| format!("Invalid domain '{}'", domain))?;
+        if url.path() != "/" || url.query().is_some() || !url.username().is_empty() {
+            return Err(anyhow!("Invalid domain '{}'", domain));
+        }
+        let mut hosts = vec![domain.clone()];
+        let is_ip_address = matches!(url.host(), Some(Host::Ipv4(_) | Host::Ipv6(_)));
+        if !is_ip_address && !domain.starts_with("www.") {
+            hosts.push(format!("www.{}", domain));
+        }
+        ["https", "http"]
+            .iter()
+            .flat_map(|scheme| hosts.iter().map(move |host| format!("{}://{}/", scheme, host)))
+            .map(|candidate| Ok(Url::parse(&candidate)?))
+            .collect()
+    }
+
+    /// The entry point of `domain`, or an error listing why each candidate URL failed.
+    pub async fn probe(&self, domain: &str) -> anyhow::Result<Url> {
+        let mut failures = Vec::new();
+        for candidate_url in Self::candidate_urls(domain)? {
+            let response = self
+                .http_client
+                .get(candidate_url.clone())
+                .timeout(self.timeout)
+                .send()
+                .await;
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    let mut entry_point = response.url().clone();
+                    entry_point.set_fragment(None);
+                    return Ok(entry_point);
+                }
+                Ok(response) => failures.push(format!(
+                    "{} answered {}",
+                    candidate_url,
+                    response.status().as_u16()
+                )),
+                Err(e) => {
+                    // The outer errors only repeat the URL
+                    let e = anyhow::Error::from(e);
+                    failures.push(format!("{}: {}", candidate_url, e.root_cause()))
+                }
+            }
+        }
+        Err(anyhow!("No working entry point for {} ({})", domain, failures.join("; ")))
+    }
+
+    /// The entry points of several domains, probed concurrently, in the order given.
+    pub async fn probe_all(&self, domains: &[String]) -> Vec<anyhow::Result<Url>> {
+        futures::stream::iter(domains)
+            .map(|domain| self.probe(domain))
+            .buffered(MAX_CONCURRENT_PROBES)
+            .collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::crawler_config::CrawlerConfig;
+    use crate::crawler::http::HttpClientBuilder;
+    use crate::test_support::{MockSite, MockSiteServer};
+
+    #[test]
+    fn candidates_prefer_https_and_the_bare_domain() {
+        let candidates = EntryPointProber::candidate_urls("Example.com").unwrap();
+        let candidates = candidates.iter().map(Url::as_str).collect::<Vec<_>>();
+        assert_eq!(
+            candidates,
+            [
+                "https://example.com/",
+                "https://www.example.com/",
+                "http://example.com/",
+                "http://www.example.com/",
+            ]
+        );
+        assert_eq!(EntryPointProber::candidate_urls("www.example.com").unwrap().len(), 2);
+        assert!(EntryPointProber::candidate_urls("https://example.com/").is_err());
+        assert!(EntryPointProber::candidate_urls("example.com/about").is_err());
+    }
+
+    #[tokio::test]
+    async fn probing_follows_the_initial_redirect() {
+        let site = MockSite::new()
+            .with_redirect("/", "/home")
+            .with_html("/home", &[]);
+        let server = MockSiteServer::start(site).await.unwrap();
+        let config = CrawlerConfig::new(100, 4, None).with_allow_private_networks(true);
+        let http_client = HttpClientBuilder::new(&config).build().unwrap();
+        let prober = EntryPointProber::new(http_client);
+
+        // The mock site only speaks HTTP, so the HTTPS candidate fails first
+        let domain = server.base_url().authority().to_string();
+        let entry_point = prober.probe(&domain).await.unwrap();
+        assert_eq!(entry_point, server.url("/home"));
+    }
+}
@@ -0,0 +1,9 @@
+mod crawl_event;
+mod event_publisher;
+mod nats_connection;
+mod result_stream;
+
+pub use crawl_event::CrawlEvent;
+pub use event_publisher::{DEFAULT_NATS_SUBJECT, EventPublisher};
+pub use nats_connection::NatsConnection;
+pub use result_stream::{ResultStream, STDOUT_PATH};
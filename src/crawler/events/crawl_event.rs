@@ -0,0 +1,47 @@
+use crate::crawler::multi::RunInfo;
+use crate::crawler::page_summary::PageSummary;
+use url::Url;
+
+/// Something that happened during a crawl, published as JSON while the crawl runs.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CrawlEvent<'a> {
+    RunStarted {
+        run: &'a RunInfo,
+    },
+    CrawlStarted {
+        run_id: &'a str,
+        seed_id: &'a str,
+        seed: &'a Url,
+    },
+    PageCrawled {
+        run_id: &'a str,
+        seed_id: &'a str,
+        seed: &'a Url,
+        page: &'a PageSummary,
+    },
+    CrawlFinished {
+        run_id: &'a str,
+        seed_id: &'a str,
+        seed: &'a Url,
+        num_pages: usize,
+        truncated_by_time: bool,
+    },
+    RunFinished {
+        run: &'a RunInfo,
+    },
+}
+
+impl CrawlEvent<'_> {
+    /// The last token of the subject the event is published on: page results go to "pages",
+    /// the start and end of the run and of each seed's crawl to "lifecycle".
+    pub fn subject_suffix(&self) -> &'static str {
+        match self {
+            CrawlEvent::PageCrawled { .. } => "pages",
+            CrawlEvent::RunStarted { .. }
+            | CrawlEvent::CrawlStarted { .. }
+            | CrawlEvent::CrawlFinished { .. }
+            | CrawlEvent::RunFinished { .. } => "lifecycle",
+        }
+    }
+}
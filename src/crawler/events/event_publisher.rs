@@ -0,0 +1,129 @@
+use crate::crawler::events::{CrawlEvent, NatsConnection};
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use url::Url;
+
+/// Subject prefix of published events when no `--nats-subject` is given.
+pub const DEFAULT_NATS_SUBJECT: &str = "rusty-spider";
+
+/// A subject and the JSON payload to publish to it.
+type Message = (String, Vec<u8>);
+
+/// Publishes crawl events to NATS from a background task, so crawling never waits on it.
+///
+/// Events are published to "<subject>.pages" and "<subject>.lifecycle". If the connection
+/// fails, later events are dropped and the error is returned by `close`.
+pub struct EventPublisher {
+    subject: String,
+    event_tx: Mutex<Option<mpsc::UnboundedSender<Message>>>,
+    publish_task: Mutex<Option<JoinHandle<anyhow::Result<()>>>>,
+}
+
+impl EventPublisher {
+    /// Starts publishing to the NATS server at `nats_url`. Must be called within a runtime.
+    pub fn spawn(nats_url: Url, subject: &str) -> Self {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let publish_task = tokio::task::spawn(Self::publish_events(nats_url, event_rx));
+        Self {
+            subject: subject.to_string(),
+            event_tx: Mutex::new(Some(event_tx)),
+            publish_task: Mutex::new(Some(publish_task)),
+        }
+    }
+
+    pub fn publish(&self, event: &CrawlEvent) {
+        let Ok(payload) = serde_json::to_vec(event) else {
+            return;
+        };
+        let subject = format!("{}.{}", self.subject, event.subject_suffix());
+        if let Some(event_tx) = self.event_tx.lock().unwrap().as_ref() {
+            let _ = event_tx.send((subject, payload));
+        }
+    }
+
+    /// Publishes the events still queued and closes the connection.
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.event_tx.lock().unwrap().take();
+        let publish_task = self.publish_task.lock().unwrap().take();
+        match publish_task {
+            Some(publish_task) => publish_task.await?,
+            None => Ok(()),
+        }
+    }
+
+    async fn publish_events(
+        nats_url: Url,
+        mut event_rx: mpsc::UnboundedReceiver<Message>,
+    ) -> anyhow::Result<()> {
+        let mut connection = NatsConnection::connect(&nats_url).await?;
+        loop {
+            tokio::select! {
+                event = event_rx.recv() => match event {
+                    Some((subject, payload)) => connection.publish(&subject, &payload).await?,
+                    None => break,
+                },
+                result = connection.handle_server_message() => result?,
+            }
+        }
+        connection.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::page_summary::PageSummary;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    #[tokio::test]
+    async fn publishes_events_as_json_to_nats() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_address = listener.local_addr().unwrap();
+        let server = tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            writer.write_all(b"INFO {}\r\n").await.unwrap();
+            let mut lines = BufReader::new(reader).lines();
+            let mut published = Vec::new();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if line == "PING" {
+                    writer.write_all(b"PONG\r\n").await.unwrap();
+                } else if let Some(subject) = line.strip_prefix("PUB ") {
+                    let payload = lines.next_line().await.unwrap().unwrap();
+                    published.push((subject.to_string(), payload));
+                }
+            }
+            published
+        });
+
+        let nats_url = Url::parse(&format!("nats://{}", server_address)).unwrap();
+        let event_publisher = EventPublisher::spawn(nats_url, "crawls");
+        let seed = Url::parse("https://example.com/").unwrap();
+        let page = PageSummary::from_status_code(seed.clone(), 0, 404);
+        let (run_id, seed_id) = ("r1", "r1-1");
+        event_publisher.publish(&CrawlEvent::CrawlStarted {
+            run_id,
+            seed_id,
+            seed: &seed,
+        });
+        event_publisher.publish(&CrawlEvent::PageCrawled {
+            run_id,
+            seed_id,
+            seed: &seed,
+            page: &page,
+        });
+        event_publisher.close().await.unwrap();
+
+        let published = server.await.unwrap();
+        assert_eq!(published.len(), 2);
+        assert!(published[0].0.starts_with("crawls.lifecycle "));
+        assert_eq!(
+            published[0].1,
+            r#"{"event":"crawl_started","run_id":"r1","seed_id":"r1-1","seed":"https://example.com/"}"#
+        );
+        assert!(published[1].0.starts_with("crawls.pages "));
+        let page_event = serde_json::from_str::<serde_json::Value>(&published[1].1).unwrap();
+        assert_eq!(page_event["page"]["status_code"], 404);
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{Context, anyhow};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use url::Url;
+
+/// Port of a NATS server whose URL doesn't give one.
+const DEFAULT_NATS_PORT: u16 = 4222;
+
+/// A client connection to a NATS server, speaking the plain-text core protocol.
+pub struct NatsConnection {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl NatsConnection {
+    /// Connects to a server at a URL like "nats://host:4222". A user and password in the URL
+    /// are sent as credentials, a user alone as an authentication token. Both are
+    /// percent-decoded first.
+    pub async fn connect(nats_url: &Url) -> anyhow::Result<Self> {
+        let host = nats_url
+            .host_str()
+            .ok_or_else(|| anyhow!("NATS URL {} has no host", redacted(nats_url)))?;
+        let port = nats_url.port().unwrap_or(DEFAULT_NATS_PORT);
+        let stream = TcpStream::connect((host, port))
+            .await
+            .with_context(|| format!("Unable to connect to NATS at {}", redacted(nats_url)))?;
+        let (reader, writer) = stream.into_split();
+        let mut connection = Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+        };
+
+        let info = connection.lines.next_line().await?.unwrap_or_default();
+        if !info.starts_with("INFO") {
+            return Err(anyhow!("{} is not a NATS server", redacted(nats_url)));
+        }
+        let mut options = serde_json::json!({
+            "verbose": false,
+            "pedantic": false,
+            "name": "rusty-spider",
+            "lang": "rust",
+            "version": env!("CARGO_PKG_VERSION"),
+            "protocol": 0,
+        });
+        match (nats_url.username(), nats_url.password()) {
+            ("", _) => {}
+            (user, Some(password)) => {
+                options["user"] = decoded(user).into();
+                options["pass"] = decoded(password).into();
+            }
+            (token, None) => options["auth_token"] = decoded(token).into(),
+        }
+        connection.write(format!("CONNECT {}\r\n", options).as_bytes()).await?;
+        // A round trip confirms the server accepted the connection
+        connection.flush().await?;
+        Ok(connection)
+    }
+
+    pub async fn publish(&mut self, subject: &str, payload: &[u8]) -> anyhow::Result<()> {
+        let mut message = format!("PUB {} {}\r\n", subject, payload.len()).into_bytes();
+        message.extend_from_slice(payload);
+        message.extend_from_slice(b"\r\n");
+        self.write(&message).await
+    }
+
+    /// Handles the next message from the server, answering its pings. Cancel safe, so it can
+    /// be polled alongside publishing.
+    pub async fn handle_server_message(&mut self) -> anyhow::Result<()> {
+        let line = self.next_line().await?;
+        self.handle_line(&line).await
+    }
+
+    /// Waits until the server has processed everything published so far.
+    pub async fn flush(&mut self) -> anyhow::Result<()> {
+        self.write(b"PING\r\n").await?;
+        loop {
+            let line = self.next_line().await?;
+            if line == "PONG" {
+                return Ok(());
+            }
+            self.handle_line(&line).await?;
+        }
+    }
+
+    async fn next_line(&mut self) -> anyhow::Result<String> {
+        self.lines
+            .next_line()
+            .await?
+            .ok_or_else(|| anyhow!("NATS server closed the connection"))
+    }
+
+    async fn handle_line(&mut self, line: &str) -> anyhow::Result<()> {
+        if line == "PING" {
+            self.write(b"PONG\r\n").await?;
+        } else if let Some(error) = line.strip_prefix("-ERR") {
+            return Err(anyhow!("NATS server error: {}", error.trim()));
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        self.writer.write_all(bytes).await?;
+        Ok(())
+    }
+}
+
+/// A user name, password or token from a URL, percent-decoded.
+fn decoded(credential: &str) -> String {
+    percent_encoding::percent_decode_str(credential).decode_utf8_lossy().into_owned()
+}
+
+/// The URL of a NATS server without its credentials, for messages. Unlike other servers', the
+/// user name of a NATS URL can be a token, so it is left out too.
+fn redacted(nats_url: &Url) -> Url {
+    let mut nats_url = nats_url.clone();
+    // Only fails for URLs that can't have credentials, which then have none to hide
+    let _ = nats_url.set_password(None);
+    let _ = nats_url.set_username("");
+    nats_url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sends_percent_decoded_credentials() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_address = listener.local_addr().unwrap();
+        let server = tokio::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            writer.write_all(b"INFO {}\r\n").await.unwrap();
+            let mut lines = BufReader::new(reader).lines();
+            let connect = lines.next_line().await.unwrap().unwrap();
+            // Answers the PING that confirms the connection
+            lines.next_line().await.unwrap();
+            writer.write_all(b"PONG\r\n").await.unwrap();
+            connect
+        });
+
+        let nats_url = format!("nats://spider:p%40ss%3Aword@{}", server_address);
+        NatsConnection::connect(&Url::parse(&nats_url).unwrap()).await.unwrap();
+
+        let connect = server.await.unwrap();
+        let options = connect.strip_prefix("CONNECT ").unwrap();
+        let options = serde_json::from_str::<serde_json::Value>(options).unwrap();
+        assert_eq!(options["user"], "spider");
+        assert_eq!(options["pass"], "p@ss:word");
+    }
+
+    #[tokio::test]
+    async fn leaves_credentials_out_of_errors() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_address = listener.local_addr().unwrap();
+        // Nothing listens on the port any more
+        drop(listener);
+
+        for nats_url in [
+            format!("nats://spider:secret@{}", server_address),
+            format!("nats://secret@{}", server_address),
+        ] {
+            let nats_url = Url::parse(&nats_url).unwrap();
+            let error = NatsConnection::connect(&nats_url).await.err().unwrap();
+            let message = format!("{:#}", error);
+            assert!(message.contains(&server_address.to_string()), "{}", message);
+            assert!(!message.contains("secret"), "{}", message);
+        }
+    }
+}
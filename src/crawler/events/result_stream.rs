@@ -0,0 +1,73 @@
+use crate::crawler::page_summary::PageSummary;
+use anyhow::Context;
+use std::path::Path;
+use std::sync::Mutex;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Path that streams results to stdout instead of a file.
+pub const STDOUT_PATH: &str = "-";
+
+/// Streams each crawled page as a line of JSON to stdout or a file while the crawl runs, from
+/// a background task so crawling never waits on the reader of a pipe.
+pub struct ResultStream {
+    line_tx: Mutex<Option<mpsc::UnboundedSender<Vec<u8>>>>,
+    write_task: Mutex<Option<JoinHandle<anyhow::Result<()>>>>,
+}
+
+impl ResultStream {
+    /// Starts streaming to the file at `path`, or to stdout if it is "-". Must be called
+    /// within a runtime.
+    pub fn spawn(path: &Path) -> anyhow::Result<Self> {
+        let writer: Box<dyn AsyncWrite + Send + Unpin> = if path == Path::new(STDOUT_PATH) {
+            Box::new(tokio::io::stdout())
+        } else {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Unable to create {}", path.display()))?;
+            Box::new(tokio::fs::File::from_std(file))
+        };
+        let (line_tx, line_rx) = mpsc::unbounded_channel();
+        let write_task = tokio::task::spawn(Self::write_lines(writer, line_rx));
+        Ok(Self {
+            line_tx: Mutex::new(Some(line_tx)),
+            write_task: Mutex::new(Some(write_task)),
+        })
+    }
+
+    pub fn write(&self, page_summary: &PageSummary) {
+        let Ok(mut line) = serde_json::to_vec(page_summary) else {
+            return;
+        };
+        line.push(b'\n');
+        if let Some(line_tx) = self.line_tx.lock().unwrap().as_ref() {
+            let _ = line_tx.send(line);
+        }
+    }
+
+    /// Writes the lines still queued and flushes the output.
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.line_tx.lock().unwrap().take();
+        let write_task = self.write_task.lock().unwrap().take();
+        match write_task {
+            Some(write_task) => write_task.await?,
+            None => Ok(()),
+        }
+    }
+
+    async fn write_lines(
+        writer: Box<dyn AsyncWrite + Send + Unpin>,
+        mut line_rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        while let Some(line) = line_rx.recv().await {
+            writer.write_all(&line).await?;
+            // Flush whenever the queue runs dry, so readers of a pipe see pages as they finish
+            if line_rx.is_empty() {
+                writer.flush().await?;
+            }
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+}
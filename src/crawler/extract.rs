@@ -0,0 +1,3 @@
+mod extract_rule;
+
+pub use extract_rule::ExtractRule;
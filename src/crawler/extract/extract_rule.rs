@@ -0,0 +1,90 @@
+use anyhow::anyhow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Pulls one value out of each page: `name:selector` takes the text of the first element
+/// matching the CSS selector, `name:selector@attr` takes its attribute instead, e.g.
+/// "price:.product-price" or "image:img.hero@src".
+#[derive(Debug, Clone)]
+pub struct ExtractRule {
+    pub name: String,
+    selector_text: String,
+    selector: scraper::Selector,
+    attribute: Option<String>,
+}
+
+impl ExtractRule {
+    /// The extracted value, or None if nothing on the page matches.
+    pub fn extract(&self, document: &scraper::Html) -> Option<String> {
+        let element = document.select(&self.selector).next()?;
+        match &self.attribute {
+            Some(attribute) => element.value().attr(attribute).map(|value| value.trim().to_string()),
+            None => Some(element.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")),
+        }
+    }
+}
+
+impl FromStr for ExtractRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, selector_text) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid extract rule '{}' (expected name:selector)", s))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Invalid extract rule '{}' (missing name)", s));
+        }
+        // CSS selectors never contain '@', so a trailing "@attr" names an attribute
+        let (selector_text, attribute) = match selector_text.rsplit_once('@') {
+            Some((selector_text, attribute)) => (selector_text, Some(attribute.trim().to_string())),
+            None => (selector_text, None),
+        };
+        let selector_text = selector_text.trim();
+        let selector = scraper::Selector::parse(selector_text)
+            .map_err(|e| anyhow!("Invalid selector '{}' in extract rule '{}': {}", selector_text, s, e))?;
+        Ok(Self {
+            name: name.to_string(),
+            selector_text: selector_text.to_string(),
+            selector,
+            attribute,
+        })
+    }
+}
+
+impl Display for ExtractRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.name, self.selector_text)?;
+        if let Some(attribute) = &self.attribute {
+            write!(f, "@{}", attribute)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_text_or_attribute_of_first_match() {
+        let document = scraper::Html::parse_document(
+            r#"<p class="price"> 12.50
+                 EUR </p><p class="price">9.99</p><img class="hero" src="/hero.png">"#,
+        );
+        let price: ExtractRule = "price:.price".parse().unwrap();
+        let image: ExtractRule = "image:img.hero@src".parse().unwrap();
+        let missing: ExtractRule = "sku:.sku".parse().unwrap();
+        assert_eq!(price.extract(&document).as_deref(), Some("12.50 EUR"));
+        assert_eq!(image.extract(&document).as_deref(), Some("/hero.png"));
+        assert_eq!(missing.extract(&document), None);
+        assert_eq!(image.to_string(), "image:img.hero@src");
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        assert!("no-selector".parse::<ExtractRule>().is_err());
+        assert!(":.price".parse::<ExtractRule>().is_err());
+        assert!("price:..price".parse::<ExtractRule>().is_err());
+    }
+}
@@ -0,0 +1,3 @@
+mod feed_loader;
+
+pub use feed_loader::FeedLoader;
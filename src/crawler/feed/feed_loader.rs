@@ -0,0 +1,135 @@
+use crate::crawler::http::ContentDecoder;
+use anyhow::Context;
+use url::Url;
+use xml::reader::{EventReader, XmlEvent};
+
+/// Content types of the feeds pages advertise with `<link rel="alternate">`.
+const FEED_CONTENT_TYPES: [&str; 2] = ["application/rss+xml", "application/atom+xml"];
+
+/// Fetches RSS and Atom feeds and collects the URLs of the items they list.
+pub struct FeedLoader {
+    http_client: reqwest::Client,
+}
+
+impl FeedLoader {
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self { http_client }
+    }
+
+    /// Whether a `<link rel="alternate">` of this type points to an RSS or Atom feed.
+    pub fn is_feed_type(link_type: &str) -> bool {
+        FEED_CONTENT_TYPES
+            .iter()
+            .any(|feed_type| link_type.trim().eq_ignore_ascii_case(feed_type))
+    }
+
+    pub async fn load(&self, feed_url: &Url) -> anyhow::Result<Vec<Url>> {
+        let response = self
+            .http_client
+            .get(feed_url.clone())
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Failed to fetch feed {}", feed_url))?;
+        let content_encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let body = response.bytes().await?;
+        let body = ContentDecoder::decode_content(&body, content_encoding.as_deref(), None)?;
+        Self::parse_feed(&body, feed_url).with_context(|| format!("Failed to parse feed {}", feed_url))
+    }
+
+    /// Item links of an RSS feed (`<item><link>`) or an Atom feed (`<entry><link href>`),
+    /// resolved against the feed URL.
+    fn parse_feed(body: &[u8], feed_url: &Url) -> anyhow::Result<Vec<Url>> {
+        let mut item_urls = Vec::new();
+        // Local names of the open elements, e.g. ["rss", "channel", "item", "link"]
+        let mut open_elements: Vec<String> = Vec::new();
+        let mut link_text = String::new();
+        for event in EventReader::new(body) {
+            match event? {
+                XmlEvent::StartElement { name, attributes, .. } => {
+                    let in_entry = open_elements.last().is_some_and(|element| element == "entry");
+                    if name.local_name == "link" && in_entry {
+                        let attribute = |local_name: &str| {
+                            attributes
+                                .iter()
+                                .find(|attribute| attribute.name.local_name == local_name)
+                                .map(|attribute| attribute.value.as_str())
+                        };
+                        // Atom entries may also link to comments, enclosures and the like
+                        if attribute("rel").is_none_or(|rel| rel == "alternate") {
+                            if let Some(Ok(url)) = attribute("href").map(|href| feed_url.join(href)) {
+                                item_urls.push(url);
+                            }
+                        }
+                    }
+                    if name.local_name == "link" {
+                        link_text.clear();
+                    }
+                    open_elements.push(name.local_name);
+                }
+                XmlEvent::Characters(text) | XmlEvent::CData(text)
+                    if open_elements.last().is_some_and(|element| element == "link") =>
+                {
+                    link_text.push_str(&text);
+                }
+                XmlEvent::EndElement { name } => {
+                    open_elements.pop();
+                    if name.local_name != "link"
+                        || open_elements.last().is_none_or(|element| element != "item")
+                    {
+                        continue;
+                    }
+                    if let Ok(url) = feed_url.join(link_text.trim()) {
+                        item_urls.push(url);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(item_urls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rss_and_atom_item_links() {
+        let feed_url = Url::parse("https://example.com/blog/feed.xml").unwrap();
+        let rss = br#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+                <title>Blog</title><link>https://example.com/blog/</link>
+                <item><title>One</title><link>https://example.com/blog/one</link></item>
+                <item><title>Two</title><link><![CDATA[/blog/two]]></link></item>
+            </channel></rss>"#;
+        assert_eq!(
+            FeedLoader::parse_feed(rss, &feed_url).unwrap(),
+            vec![
+                Url::parse("https://example.com/blog/one").unwrap(),
+                Url::parse("https://example.com/blog/two").unwrap(),
+            ]
+        );
+
+        let atom = br#"<?xml version="1.0"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <link href="https://example.com/blog/"/>
+                <entry>
+                    <link rel="alternate" href="https://example.com/blog/three"/>
+                    <link rel="replies" href="https://example.com/blog/three#comments"/>
+                </entry>
+                <entry><link href="four"/></entry>
+            </feed>"#;
+        assert_eq!(
+            FeedLoader::parse_feed(atom, &feed_url).unwrap(),
+            vec![
+                Url::parse("https://example.com/blog/three").unwrap(),
+                Url::parse("https://example.com/blog/four").unwrap(),
+            ]
+        );
+    }
+}
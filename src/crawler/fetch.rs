@@ -0,0 +1,14 @@
+mod fetcher;
+mod file_fetcher;
+mod js_render_fetcher;
+mod render_mode;
+mod request_middleware;
+mod reqwest_fetcher;
+mod webdriver_client;
+
+pub use fetcher::{FetchResult, Fetcher};
+pub use file_fetcher::FileFetcher;
+pub use js_render_fetcher::JsRenderFetcher;
+pub use render_mode::RenderMode;
+pub use request_middleware::{RequestMiddleware, StaticHeaders};
+pub use reqwest_fetcher::ReqwestFetcher;
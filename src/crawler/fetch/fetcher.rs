@@ -0,0 +1,38 @@
+use crate::crawler::crawl_error::CrawlError;
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use url::Url;
+
+/// A response as fetched, before any decoding or parsing.
+#[derive(Debug, Clone)]
+pub struct FetchResult {
+    pub status_code: u16,
+    pub http_version: Option<String>,
+    pub headers: HeaderMap,
+    /// The body as transferred, still content-encoded. Empty for unsuccessful responses.
+    pub body: Vec<u8>,
+    /// Why a headless browser failed to render the page, which then keeps its body as served.
+    pub render_error: Option<String>,
+}
+
+/// Retrieves pages for the PageCrawler, so that the backend can be swapped out.
+pub trait Fetcher: Send + Sync {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>>;
+
+    /// Number of pages a seed may `dispatch` ahead of fetching them, for backends that fetch
+    /// several pages at once elsewhere. 1 for backends that only fetch when asked.
+    fn dispatch_window(&self) -> usize {
+        1
+    }
+
+    /// Starts fetching a page that `fetch` will soon be called for, so that it is fetched
+    /// while the pages before it are processed.
+    fn dispatch<'a>(&'a self, _url: &'a Url) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+
+    /// Releases any resources held by the backend once the crawl is over.
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,96 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::fetch::{FetchResult, Fetcher};
+use futures::future::BoxFuture;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use std::io::ErrorKind;
+use std::path::Path;
+use url::Url;
+
+/// Serves `file://` URLs from the local file system, so a static site build can be
+/// checked without a web server. Other URLs are passed to the inner fetcher.
+///
+/// Like a static file server, a directory serves its `index.html`, missing files are
+/// reported as 404 and unreadable ones as 403.
+pub struct FileFetcher {
+    inner: Box<dyn Fetcher>,
+}
+
+impl FileFetcher {
+    pub fn new(inner: Box<dyn Fetcher>) -> Self {
+        Self { inner }
+    }
+
+    async fn fetch_file(url: &Url) -> Result<FetchResult, CrawlError> {
+        let Ok(mut path) = url.to_file_path() else {
+            return Ok(Self::status_only(400));
+        };
+        if tokio::fs::metadata(&path).await.is_ok_and(|metadata| metadata.is_dir()) {
+            path.push("index.html");
+        }
+        let body = match tokio::fs::read(&path).await {
+            Ok(body) => body,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::status_only(404)),
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                return Ok(Self::status_only(403));
+            }
+            Err(e) => return Err(CrawlError::AnyError(e.into())),
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(Self::content_type(&path)));
+        Ok(FetchResult {
+            status_code: 200,
+            http_version: None,
+            headers,
+            body,
+            render_error: None,
+        })
+    }
+
+    fn status_only(status_code: u16) -> FetchResult {
+        FetchResult {
+            status_code,
+            http_version: None,
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+            render_error: None,
+        }
+    }
+
+    /// Guesses the content type from the file extension, as a static file server would.
+    fn content_type(path: &Path) -> &'static str {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "html" | "htm" => "text/html",
+            "css" => "text/css",
+            "js" | "mjs" => "text/javascript",
+            "json" => "application/json",
+            "xml" => "application/xml",
+            "txt" => "text/plain",
+            "svg" => "image/svg+xml",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            "pdf" => "application/pdf",
+            _ => "application/octet-stream",
+        }
+    }
+}
+
+impl Fetcher for FileFetcher {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+        if url.scheme() == "file" {
+            Box::pin(Self::fetch_file(url))
+        } else {
+            self.inner.fetch(url)
+        }
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        self.inner.close()
+    }
+}
@@ -0,0 +1,319 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::fetch::webdriver_client::WebDriverClient;
+use crate::crawler::fetch::{FetchResult, Fetcher};
+use crate::crawler::http::{GuardedResolver, PrivateNetworkGuard};
+use crate::crawler::rate_limit::HostRateLimiter;
+use anyhow::anyhow;
+use futures::future::BoxFuture;
+use reqwest::dns::{Name, Resolve};
+use reqwest::header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, HeaderValue};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// Time WebDriver commands get beyond the render timeout, e.g. for a browser to start.
+const COMMAND_TIMEOUT_MARGIN: Duration = Duration::from_secs(30);
+
+/// Renders HTML pages in a headless browser so that links added by JavaScript are found.
+///
+/// Each page is first fetched by the inner fetcher, which supplies the status code and
+/// headers a browser does not expose. Successful HTML responses are then loaded in one of
+/// a pool of browser sessions and their body is replaced by the rendered DOM. The browser
+/// only goes to pages the inner fetcher has fetched, after the rate limiter allows another
+/// request and the private network guard has checked the host again. A page that fails to
+/// render keeps its body as served, with the failure recorded in `render_error`. If not even
+/// the first browser session can be started, the fetch fails.
+pub struct JsRenderFetcher {
+    inner: Box<dyn Fetcher>,
+    webdriver: WebDriverClient,
+    render_timeout: Duration,
+    /// Limits the number of pages rendering at once to the number of tabs.
+    tabs: Semaphore,
+    idle_sessions: Mutex<Vec<String>>,
+    /// Whether any session was started, so that a browser that fails to start at all ends
+    /// the crawl rather than every page quietly going unrendered.
+    session_started: AtomicBool,
+    rate_limiter: Option<Arc<HostRateLimiter>>,
+    allow_private_networks: bool,
+}
+
+impl JsRenderFetcher {
+    pub fn new(
+        inner: Box<dyn Fetcher>,
+        webdriver_url: Url,
+        render_timeout: Duration,
+        num_tabs: usize,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            inner,
+            webdriver: WebDriverClient::new(
+                webdriver_url,
+                render_timeout + COMMAND_TIMEOUT_MARGIN,
+            )?,
+            render_timeout,
+            tabs: Semaphore::new(num_tabs.max(1)),
+            idle_sessions: Mutex::new(Vec::new()),
+            session_started: AtomicBool::new(false),
+            rate_limiter: None,
+            allow_private_networks: false,
+        })
+    }
+
+    /// Counts the browser's visit to a page against the host's rate limit, like any request.
+    pub fn with_rate_limiter(mut self, rate_limiter: Option<Arc<HostRateLimiter>>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Lets the browser visit hosts on private networks.
+    pub fn with_allow_private_networks(mut self, allow_private_networks: bool) -> Self {
+        self.allow_private_networks = allow_private_networks;
+        self
+    }
+
+    async fn fetch_url(&self, url: &Url) -> Result<FetchResult, CrawlError> {
+        let mut fetch_result = self.inner.fetch(url).await?;
+        let is_html = fetch_result
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<mime::Mime>().ok())
+            .is_some_and(|mime| mime.type_() == mime::TEXT && mime.subtype() == mime::HTML);
+        if !(200..300).contains(&fetch_result.status_code) || !is_html {
+            return Ok(fetch_result);
+        }
+
+        if let Err(e) = self.check_host(url).await {
+            fetch_result.render_error = Some(format!("{:#}", e));
+            return Ok(fetch_result);
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            tokio::time::sleep(rate_limiter.reserve(url)).await;
+        }
+        match self.render(url).await {
+            Ok(rendered_html) => {
+                let headers = &mut fetch_result.headers;
+                headers.remove(CONTENT_ENCODING);
+                headers.remove(CONTENT_LENGTH);
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+                fetch_result.body = rendered_html.into_bytes();
+            }
+            Err(e) if !self.session_started.load(Ordering::Relaxed) => {
+                return Err(e.context("Unable to start a browser session for --render js").into());
+            }
+            Err(e) => fetch_result.render_error = Some(format!("{:#}", e)),
+        }
+        Ok(fetch_result)
+    }
+
+    /// Checks the page's host again before the browser resolves it itself, since the address
+    /// it resolved to for the inner fetch may have changed.
+    async fn check_host(&self, url: &Url) -> anyhow::Result<()> {
+        if self.allow_private_networks {
+            return Ok(());
+        }
+        if PrivateNetworkGuard::is_private_url(url) {
+            return Err(anyhow!("Not rendering {}: it is on a private network", url));
+        }
+        let host = url.host_str().ok_or_else(|| anyhow!("{} has no host", url))?;
+        // Literal addresses were checked above
+        let Ok(name) = Name::from_str(host) else {
+            return Ok(());
+        };
+        match GuardedResolver::new(None).resolve(name).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(anyhow!("Not rendering {}: {}", url, e)),
+        }
+    }
+
+    async fn render(&self, url: &Url) -> anyhow::Result<String> {
+        let _tab = self.tabs.acquire().await?;
+        let idle_session = self.idle_sessions.lock().unwrap().pop();
+        let session_id = match idle_session {
+            Some(session_id) => session_id,
+            None => {
+                let session_id = self.webdriver.new_session(self.render_timeout).await?;
+                self.session_started.store(true, Ordering::Relaxed);
+                session_id
+            }
+        };
+
+        let rendered = tokio::time::timeout(self.render_timeout, async {
+            // Going to a URL that differs from the session's last page only in its fragment,
+            // such as another route of the same app, wouldn't load the page afresh
+            if url.fragment().is_some() {
+                self.webdriver.navigate(&session_id, &Url::parse("about:blank")?).await?;
+            }
+            self.webdriver.navigate(&session_id, url).await?;
+            // A redirect in the browser leads to a page the inner fetcher never checked
+            let current_url = self.webdriver.current_url(&session_id).await?;
+            if !same_page(&current_url, url) {
+                return Err(anyhow!("The browser was redirected to {}", current_url));
+            }
+            self.webdriver.page_source(&session_id).await
+        })
+        .await;
+        match rendered {
+            Ok(Ok(rendered_html)) => {
+                self.idle_sessions.lock().unwrap().push(session_id);
+                Ok(rendered_html)
+            }
+            // The session may still be busy with the page, so it is not reused
+            Ok(Err(e)) => {
+                let _ = self.webdriver.delete_session(&session_id).await;
+                Err(e)
+            }
+            Err(e) => {
+                let _ = self.webdriver.delete_session(&session_id).await;
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn close_sessions(&self) {
+        let idle_sessions = std::mem::take(&mut *self.idle_sessions.lock().unwrap());
+        for session_id in idle_sessions {
+            let _ = self.webdriver.delete_session(&session_id).await;
+        }
+    }
+}
+
+/// Whether two URLs name the same page, whatever their fragments.
+fn same_page(a: &Url, b: &Url) -> bool {
+    a[..url::Position::AfterQuery] == b[..url::Position::AfterQuery]
+}
+
+impl Fetcher for JsRenderFetcher {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+        Box::pin(self.fetch_url(url))
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {
+            self.close_sessions().await;
+            self.inner.close().await;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::extract::State;
+    use axum::http::{Method, Request};
+    use reqwest::header::HeaderMap;
+
+    /// Serves every page as the same HTML, as fetched without a browser.
+    struct ServedPages;
+
+    impl Fetcher for ServedPages {
+        fn fetch<'a>(&'a self, _url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+            Box::pin(async move {
+                Ok(FetchResult {
+                    status_code: 200,
+                    http_version: None,
+                    headers,
+                    body: b"<html>served</html>".to_vec(),
+                    render_error: None,
+                })
+            })
+        }
+    }
+
+    /// A WebDriver server whose browser fails to load pages with "broken" in their URL and
+    /// is redirected away from pages with "moved" in theirs.
+    async fn start_webdriver() -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let current_url = Arc::new(Mutex::new(String::new()));
+        let router = axum::Router::new()
+            .fallback(serve_webdriver_command)
+            .with_state(current_url);
+        tokio::task::spawn(async move { axum::serve(listener, router).await });
+        endpoint
+    }
+
+    async fn serve_webdriver_command(
+        State(current_url): State<Arc<Mutex<String>>>,
+        request: Request<Body>,
+    ) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let body = axum::body::to_bytes(request.into_body(), usize::MAX).await.unwrap();
+        let value = match (method, path.as_str()) {
+            (Method::POST, "/session") => serde_json::json!({ "sessionId": "s1" }),
+            (Method::POST, "/session/s1/url") => {
+                let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+                let url = body["url"].as_str().unwrap();
+                if url.contains("broken") {
+                    let error = serde_json::json!({
+                        "value": { "error": "unknown error", "message": "net::ERR_FAILED" }
+                    });
+                    return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, axum::Json(error));
+                }
+                *current_url.lock().unwrap() = if url.contains("moved") {
+                    "http://elsewhere.example/".to_string()
+                } else {
+                    url.to_string()
+                };
+                serde_json::Value::Null
+            }
+            (Method::GET, "/session/s1/url") => current_url.lock().unwrap().clone().into(),
+            (Method::GET, "/session/s1/source") => "<html>rendered</html>".into(),
+            _ => serde_json::Value::Null,
+        };
+        (axum::http::StatusCode::OK, axum::Json(serde_json::json!({ "value": value })))
+    }
+
+    fn js_render_fetcher(webdriver_url: Url) -> JsRenderFetcher {
+        JsRenderFetcher::new(Box::new(ServedPages), webdriver_url, Duration::from_secs(5), 1)
+            .unwrap()
+            .with_allow_private_networks(true)
+    }
+
+    #[tokio::test]
+    async fn fails_if_no_browser_session_can_be_started() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let webdriver_url = format!("http://{}/", listener.local_addr().unwrap());
+        // Nothing listens on the port any more
+        drop(listener);
+        let fetcher = js_render_fetcher(Url::parse(&webdriver_url).unwrap());
+
+        let url = Url::parse("https://example.com/").unwrap();
+        assert!(fetcher.fetch(&url).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn records_pages_that_fail_to_render() {
+        let fetcher = js_render_fetcher(start_webdriver().await);
+
+        let rendered = fetcher.fetch(&Url::parse("https://example.com/").unwrap()).await.unwrap();
+        assert_eq!(rendered.body, b"<html>rendered</html>");
+        assert_eq!(rendered.render_error, None);
+
+        for path in ["broken", "moved"] {
+            let url = Url::parse("https://example.com/").unwrap().join(path).unwrap();
+            let served = fetcher.fetch(&url).await.unwrap();
+            assert_eq!(served.body, b"<html>served</html>");
+            assert!(served.render_error.is_some());
+        }
+        let redirected = Url::parse("https://example.com/moved").unwrap();
+        let served = fetcher.fetch(&redirected).await.unwrap();
+        assert!(served.render_error.unwrap().contains("elsewhere.example"));
+    }
+
+    #[tokio::test]
+    async fn keeps_the_browser_off_private_networks() {
+        let fetcher = js_render_fetcher(start_webdriver().await).with_allow_private_networks(false);
+
+        let served = fetcher.fetch(&Url::parse("http://127.0.0.1/").unwrap()).await.unwrap();
+        assert_eq!(served.body, b"<html>served</html>");
+        assert!(served.render_error.unwrap().contains("private network"));
+    }
+}
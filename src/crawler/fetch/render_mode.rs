@@ -0,0 +1,9 @@
+/// How fetched pages are turned into the HTML that links are extracted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum RenderMode {
+    /// Use the HTML as served
+    #[default]
+    None,
+    /// Run the page's JavaScript in a headless browser and use the rendered DOM
+    Js,
+}
@@ -0,0 +1,40 @@
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderName, HeaderValue};
+use url::Url;
+
+/// Changes each page request before the fetcher sends it, e.g. to sign it, add an HMAC or
+/// attach a freshly refreshed OAuth token, for endpoints with their own authentication
+/// schemes. Middleware runs in the order it was added to the crawler config; an error fails
+/// the page.
+pub trait RequestMiddleware: Send + Sync {
+    fn prepare<'a>(
+        &'a self,
+        url: &'a Url,
+        request: reqwest::RequestBuilder,
+    ) -> BoxFuture<'a, anyhow::Result<reqwest::RequestBuilder>>;
+}
+
+/// Adds the same headers to every page request, from `--request-header`.
+pub struct StaticHeaders {
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl StaticHeaders {
+    pub fn new(headers: Vec<(HeaderName, HeaderValue)>) -> Self {
+        Self { headers }
+    }
+}
+
+impl RequestMiddleware for StaticHeaders {
+    fn prepare<'a>(
+        &'a self,
+        _url: &'a Url,
+        request: reqwest::RequestBuilder,
+    ) -> BoxFuture<'a, anyhow::Result<reqwest::RequestBuilder>> {
+        let request = self
+            .headers
+            .iter()
+            .fold(request, |request, (name, value)| request.header(name, value));
+        Box::pin(async move { Ok(request) })
+    }
+}
@@ -0,0 +1,118 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::fetch::{FetchResult, Fetcher, RequestMiddleware};
+use crate::crawler::rate_limit::BandwidthLimiter;
+use futures::StreamExt;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use url::Url;
+
+/// Fetches pages over HTTP(S) with a shared reqwest client.
+pub struct ReqwestFetcher {
+    http_client: reqwest::Client,
+    max_body_size: Option<u64>,
+    bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
+    request_middleware: Vec<Arc<dyn RequestMiddleware>>,
+}
+
+impl ReqwestFetcher {
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self {
+            http_client,
+            max_body_size: None,
+            bandwidth_limiter: None,
+            request_middleware: Vec::new(),
+        }
+    }
+
+    /// Bodies larger than `max_body_size` bytes, as transferred, are abandoned. The page
+    /// crawler holds them to the same limit once decoded.
+    pub fn with_max_body_size(mut self, max_body_size: Option<u64>) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Paces body reads to keep within the bandwidth caps.
+    pub fn with_bandwidth_limiter(
+        mut self,
+        bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
+    ) -> Self {
+        self.bandwidth_limiter = bandwidth_limiter;
+        self
+    }
+
+    /// Passes every request through `request_middleware`, in order, before sending it.
+    pub fn with_request_middleware(
+        mut self,
+        request_middleware: Vec<Arc<dyn RequestMiddleware>>,
+    ) -> Self {
+        self.request_middleware = request_middleware;
+        self
+    }
+
+    async fn fetch_url(&self, url: &Url) -> Result<FetchResult, CrawlError> {
+        let mut request = self.http_client.get(url.clone());
+        for request_middleware in &self.request_middleware {
+            request = request_middleware.prepare(url, request).await?;
+        }
+        let response = request.send().await?;
+        let status_code = response.status().as_u16();
+        let http_version = format!("{:?}", response.version());
+        let headers = response.headers().clone();
+        // The body of an error page is of no interest
+        let body = if response.status().is_success() {
+            self.read_body(url, response).await?
+        } else {
+            Vec::new()
+        };
+        Ok(FetchResult {
+            status_code,
+            http_version: Some(http_version),
+            headers,
+            body,
+            render_error: None,
+        })
+    }
+
+    /// Streams the body of `url`, giving up as soon as it exceeds the maximum body size and
+    /// waiting between chunks to keep within the bandwidth caps.
+    async fn read_body(
+        &self,
+        url: &Url,
+        response: reqwest::Response,
+    ) -> Result<Vec<u8>, CrawlError> {
+        let status_code = response.status().as_u16();
+        if self.max_body_size.is_none() && self.bandwidth_limiter.is_none() {
+            return Ok(response.bytes().await?.to_vec());
+        }
+        let max_body_size = self.max_body_size.unwrap_or(u64::MAX);
+        if response
+            .content_length()
+            .is_some_and(|content_length| content_length > max_body_size)
+        {
+            return Err(CrawlError::TooLarge(status_code));
+        }
+
+        let mut body = Vec::new();
+        let mut body_stream = response.bytes_stream();
+        while let Some(chunk) = body_stream.next().await {
+            let chunk = chunk?;
+            if (body.len() + chunk.len()) as u64 > max_body_size {
+                return Err(CrawlError::TooLarge(status_code));
+            }
+            body.extend_from_slice(&chunk);
+            if let Some(bandwidth_limiter) = &self.bandwidth_limiter {
+                let wait = bandwidth_limiter.reserve(url, chunk.len());
+                if !wait.is_zero() {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+        Ok(body)
+    }
+}
+
+impl Fetcher for ReqwestFetcher {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+        Box::pin(self.fetch_url(url))
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::anyhow;
+use serde_json::json;
+use std::time::Duration;
+use url::Url;
+
+/// Time allowed to connect to the WebDriver server.
+const WEBDRIVER_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Minimal W3C WebDriver client, enough to drive a headless browser through
+/// chromedriver (or geckodriver) to render pages.
+#[derive(Clone)]
+pub struct WebDriverClient {
+    endpoint: Url,
+    http_client: reqwest::Client,
+}
+
+#[derive(serde::Deserialize)]
+struct WebDriverResponse {
+    value: serde_json::Value,
+}
+
+impl WebDriverClient {
+    /// A client of the WebDriver server at `endpoint`. Commands that get no answer within
+    /// `command_timeout` fail, so a hung browser can't stall the crawl.
+    pub fn new(endpoint: Url, command_timeout: Duration) -> anyhow::Result<Self> {
+        // The server is usually local, so neither proxies nor the crawl's private network
+        // guard apply to it
+        let http_client = reqwest::Client::builder()
+            .no_proxy()
+            .connect_timeout(WEBDRIVER_CONNECT_TIMEOUT)
+            .timeout(command_timeout)
+            .build()?;
+        Ok(Self {
+            endpoint,
+            http_client,
+        })
+    }
+
+    /// Starts a headless browser session and returns its id.
+    pub async fn new_session(&self, page_load_timeout: Duration) -> anyhow::Result<String> {
+        let capabilities = json!({
+            "capabilities": {
+                "alwaysMatch": {
+                    "goog:chromeOptions": { "args": ["--headless=new", "--disable-gpu"] },
+                    "moz:firefoxOptions": { "args": ["-headless"] },
+                    "timeouts": { "pageLoad": page_load_timeout.as_millis() as u64 },
+                }
+            }
+        });
+        let value = self.command(reqwest::Method::POST, "session", Some(capabilities)).await?;
+        value["sessionId"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("WebDriver did not return a session id"))
+    }
+
+    /// Loads `url` in the session, waiting for the page's load event.
+    pub async fn navigate(&self, session_id: &str, url: &Url) -> anyhow::Result<()> {
+        self.command(
+            reqwest::Method::POST,
+            &format!("session/{}/url", session_id),
+            Some(json!({ "url": url.as_str() })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// The URL of the session's page, which differs from the one navigated to if the browser
+    /// was redirected.
+    pub async fn current_url(&self, session_id: &str) -> anyhow::Result<Url> {
+        let value = self
+            .command(reqwest::Method::GET, &format!("session/{}/url", session_id), None)
+            .await?;
+        let current_url = value
+            .as_str()
+            .ok_or_else(|| anyhow!("WebDriver returned no current URL"))?;
+        Ok(Url::parse(current_url)?)
+    }
+
+    /// Serializes the current DOM of the session's page.
+    pub async fn page_source(&self, session_id: &str) -> anyhow::Result<String> {
+        let value = self
+            .command(reqwest::Method::GET, &format!("session/{}/source", session_id), None)
+            .await?;
+        value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("WebDriver returned no page source"))
+    }
+
+    pub async fn delete_session(&self, session_id: &str) -> anyhow::Result<()> {
+        self.command(reqwest::Method::DELETE, &format!("session/{}", session_id), None)
+            .await?;
+        Ok(())
+    }
+
+    async fn command(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let command_url = self.endpoint.join(path)?;
+        let mut request = self.http_client.request(method, command_url);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        let response: WebDriverResponse = response.json().await?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "WebDriver error: {} {}",
+                response.value["error"].as_str().unwrap_or("unknown error"),
+                response.value["message"].as_str().unwrap_or_default()
+            ));
+        }
+        Ok(response.value)
+    }
+}
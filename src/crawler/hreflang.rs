@@ -0,0 +1,5 @@
+mod hreflang_alternate;
+mod hreflang_report;
+
+pub use hreflang_alternate::HreflangAlternate;
+pub use hreflang_report::HreflangReport;
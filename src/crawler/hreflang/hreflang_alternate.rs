@@ -0,0 +1,28 @@
+use url::Url;
+
+/// A `<link rel="alternate" hreflang="...">` annotation naming a translation of a page.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HreflangAlternate {
+    /// Language (and optional region) code, e.g. "en", "de-AT" or "x-default".
+    pub hreflang: String,
+    pub url: Url,
+}
+
+impl HreflangAlternate {
+    /// Whether the code looks like an ISO 639-1 language with an optional ISO 3166-1 region,
+    /// or is "x-default".
+    pub fn has_valid_code(&self) -> bool {
+        if self.hreflang.eq_ignore_ascii_case("x-default") {
+            return true;
+        }
+        let mut parts = self.hreflang.split('-');
+        let language = parts.next().unwrap_or_default();
+        let region = parts.next();
+        if parts.next().is_some() {
+            return false;
+        }
+        let is_alpha = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_alphabetic());
+        // Scripts such as zh-Hant are also accepted in place of a region
+        is_alpha(language, 2) && region.is_none_or(|region| is_alpha(region, 2) || is_alpha(region, 4))
+    }
+}
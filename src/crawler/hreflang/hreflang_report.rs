@@ -0,0 +1,229 @@
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::page_summary::PageSummary;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use url::Url;
+
+/// A problem with one hreflang annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HreflangIssue {
+    /// The code is not a valid language/region code.
+    InvalidCode { page: Url, hreflang: String },
+    /// The alternate was crawled but did not return a success status.
+    BrokenAlternate { page: Url, alternate: Url, status_code: u16 },
+    /// The alternate is on a crawled host but was never crawled.
+    UncrawledAlternate { page: Url, alternate: Url },
+    /// The alternate does not link back to the page with its own hreflang annotations.
+    MissingReturnLink { page: Url, alternate: Url },
+}
+
+/// Cross-page validation of hreflang annotations, run after the crawl.
+#[derive(Debug, Clone, Default)]
+pub struct HreflangReport {
+    num_annotated_pages: usize,
+    issues: Vec<HreflangIssue>,
+    /// Alternates on hosts the crawl did not visit, as (page, alternate), which can't be
+    /// checked and are listed for information only.
+    external_alternates: Vec<(Url, Url)>,
+}
+
+impl HreflangReport {
+    pub fn from_crawl_summaries(crawl_summaries: &[CrawlSummary]) -> Self {
+        let pages = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .map(|page_summary| (&page_summary.url, page_summary))
+            .collect::<HashMap<&Url, &PageSummary>>();
+
+        let crawled_hosts = pages
+            .keys()
+            .filter_map(|url| url.host_str())
+            .collect::<HashSet<_>>();
+
+        let mut report = HreflangReport::default();
+        let mut annotated_pages = pages
+            .values()
+            .filter(|page_summary| !page_summary.hreflang_alternates.is_empty())
+            .collect::<Vec<_>>();
+        annotated_pages.sort_by_key(|page_summary| &page_summary.url);
+        for page_summary in annotated_pages {
+            report.num_annotated_pages += 1;
+            let page = &page_summary.url;
+            for alternate in &page_summary.hreflang_alternates {
+                if !alternate.has_valid_code() {
+                    report.issues.push(HreflangIssue::InvalidCode {
+                        page: page.clone(),
+                        hreflang: alternate.hreflang.clone(),
+                    });
+                }
+                // A page listing itself needs no return link
+                if &alternate.url == page {
+                    continue;
+                }
+                match pages.get(&alternate.url) {
+                    None if !alternate
+                        .url
+                        .host_str()
+                        .is_some_and(|host| crawled_hosts.contains(host)) =>
+                    {
+                        report
+                            .external_alternates
+                            .push((page.clone(), alternate.url.clone()))
+                    }
+                    None => report.issues.push(HreflangIssue::UncrawledAlternate {
+                        page: page.clone(),
+                        alternate: alternate.url.clone(),
+                    }),
+                    Some(alternate_page) if !(200..300).contains(&alternate_page.status_code) => {
+                        report.issues.push(HreflangIssue::BrokenAlternate {
+                            page: page.clone(),
+                            alternate: alternate.url.clone(),
+                            status_code: alternate_page.status_code,
+                        })
+                    }
+                    Some(alternate_page) => {
+                        let links_back = alternate_page
+                            .hreflang_alternates
+                            .iter()
+                            .any(|return_link| &return_link.url == page);
+                        if !links_back {
+                            report.issues.push(HreflangIssue::MissingReturnLink {
+                                page: page.clone(),
+                                alternate: alternate.url.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    /// Whether any crawled page had hreflang annotations.
+    pub fn has_annotations(&self) -> bool {
+        self.num_annotated_pages > 0
+    }
+}
+
+impl fmt::Display for HreflangReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Hreflang: {} annotated pages, {} issues, {} alternates on other hosts",
+            self.num_annotated_pages,
+            self.issues.len(),
+            self.external_alternates.len()
+        )?;
+        for issue in &self.issues {
+            match issue {
+                HreflangIssue::InvalidCode { page, hreflang } => {
+                    writeln!(f, "   {}: invalid hreflang code '{}'", page, hreflang)?
+                }
+                HreflangIssue::BrokenAlternate {
+                    page,
+                    alternate,
+                    status_code,
+                } => writeln!(
+                    f,
+                    "   {}: alternate {} returned {}",
+                    page, alternate, status_code
+                )?,
+                HreflangIssue::UncrawledAlternate { page, alternate } => {
+                    writeln!(f, "   {}: alternate {} was not crawled", page, alternate)?
+                }
+                HreflangIssue::MissingReturnLink { page, alternate } => {
+                    writeln!(f, "   {}: alternate {} does not link back", page, alternate)?
+                }
+            }
+        }
+        for (page, alternate) in &self.external_alternates {
+            writeln!(f, "   {}: alternate {} is on another host, not checked", page, alternate)?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::hreflang::HreflangAlternate;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    fn page(page_url: &str, status_code: u16, alternates: &[(&str, &str)]) -> PageSummary {
+        let mut page_summary = PageSummary::from_status_code(url(page_url), 0, status_code);
+        page_summary.hreflang_alternates = alternates
+            .iter()
+            .map(|(hreflang, alternate)| HreflangAlternate {
+                hreflang: hreflang.to_string(),
+                url: url(alternate),
+            })
+            .collect();
+        page_summary
+    }
+
+    #[test]
+    fn reports_issues_with_alternates_on_crawled_hosts() {
+        let crawl_summary = CrawlSummary::new(vec![
+            page(
+                "https://example.com/en",
+                200,
+                &[
+                    ("en", "https://example.com/en"),
+                    ("de", "https://example.com/de"),
+                    ("fr", "https://example.com/fr"),
+                    ("english", "https://example.com/es"),
+                    ("it", "https://example.com/it"),
+                ],
+            ),
+            page("https://example.com/de", 200, &[("en", "https://example.com/en")]),
+            page("https://example.com/fr", 200, &[]),
+            page("https://example.com/es", 404, &[]),
+        ]);
+
+        let report = HreflangReport::from_crawl_summaries(&[crawl_summary]);
+        let page = url("https://example.com/en");
+        assert_eq!(
+            report.issues,
+            vec![
+                HreflangIssue::MissingReturnLink {
+                    page: page.clone(),
+                    alternate: url("https://example.com/fr"),
+                },
+                HreflangIssue::InvalidCode {
+                    page: page.clone(),
+                    hreflang: "english".to_string(),
+                },
+                HreflangIssue::BrokenAlternate {
+                    page: page.clone(),
+                    alternate: url("https://example.com/es"),
+                    status_code: 404,
+                },
+                HreflangIssue::UncrawledAlternate {
+                    page,
+                    alternate: url("https://example.com/it"),
+                },
+            ]
+        );
+        assert!(report.external_alternates.is_empty());
+    }
+
+    #[test]
+    fn lists_alternates_on_other_hosts_apart_from_the_issues() {
+        let crawl_summary = CrawlSummary::new(vec![page(
+            "https://example.com/",
+            200,
+            &[("de", "https://example.de/")],
+        )]);
+
+        let report = HreflangReport::from_crawl_summaries(&[crawl_summary]);
+        assert!(report.issues.is_empty());
+        assert_eq!(
+            report.external_alternates,
+            vec![(url("https://example.com/"), url("https://example.de/"))]
+        );
+        assert!(report.to_string().contains("0 issues, 1 alternates on other hosts"));
+    }
+}
@@ -0,0 +1,23 @@
+#![allow(unused_imports)]
+
+mod cache_headers;
+mod content_decoder;
+mod crawl_profile;
+mod doh_resolver;
+mod guarded_resolver;
+mod http_client_builder;
+mod http_version_preference;
+mod private_network_guard;
+mod resolve_override;
+mod security_headers;
+
+pub use cache_headers::CacheHeaders;
+pub use content_decoder::{BodyTooLarge, ContentDecoder};
+pub use crawl_profile::{CrawlProfile, ProfileName};
+pub use doh_resolver::DohResolver;
+pub use guarded_resolver::GuardedResolver;
+pub use http_client_builder::{DEFAULT_MAX_REDIRECTS, HttpClientBuilder};
+pub use http_version_preference::HttpVersionPreference;
+pub use private_network_guard::PrivateNetworkGuard;
+pub use resolve_override::ResolveOverride;
+pub use security_headers::SecurityHeaders;
@@ -0,0 +1,87 @@
+use reqwest::header::HeaderMap;
+use std::time::{Duration, SystemTime};
+
+/// Response headers that decide whether and for how long caches may reuse a response.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CacheHeaders {
+    pub cache_control: Option<String>,
+    pub expires: Option<String>,
+    pub date: Option<String>,
+}
+
+impl CacheHeaders {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            let values = headers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(str::trim)
+                .collect::<Vec<_>>();
+            (!values.is_empty()).then(|| values.join(", "))
+        };
+        Self {
+            cache_control: header("cache-control"),
+            expires: header("expires"),
+            date: header("date"),
+        }
+    }
+
+    /// The value of a Cache-Control directive, e.g. "60" for `max-age`, or Some("") for a
+    /// directive without one such as `no-store`. None if the directive is absent.
+    pub fn directive(&self, name: &str) -> Option<&str> {
+        self.cache_control.as_deref()?.split(',').find_map(|directive| {
+            let (directive_name, value) = directive.split_once('=').unwrap_or((directive, ""));
+            directive_name
+                .trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().trim_matches('"'))
+        })
+    }
+
+    /// How long caches may reuse the response without checking back: its max-age, or else
+    /// the time from its Date, or from now without one, to its Expires. An Expires that
+    /// can't be read, such as "0", means the response is already stale. None if neither is
+    /// given, leaving caches to guess.
+    pub fn freshness_lifetime(&self) -> Option<Duration> {
+        if let Some(max_age) = self.directive("max-age") {
+            // An unreadable max-age also means stale
+            return Some(Duration::from_secs(max_age.parse().unwrap_or(0)));
+        }
+        let expires = self.expires.as_deref()?;
+        let Ok(expires) = httpdate::parse_http_date(expires) else {
+            return Some(Duration::ZERO);
+        };
+        let date = self
+            .date
+            .as_deref()
+            .and_then(|date| httpdate::parse_http_date(date).ok())
+            .unwrap_or_else(SystemTime::now);
+        Some(expires.duration_since(date).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_headers(cache_control: Option<&str>, expires: Option<&str>) -> CacheHeaders {
+        CacheHeaders {
+            cache_control: cache_control.map(str::to_string),
+            expires: expires.map(str::to_string),
+            date: Some("Sat, 16 Oct 2027 12:00:00 GMT".to_string()),
+        }
+    }
+
+    #[test]
+    fn max_age_takes_precedence_over_expires() {
+        let day = Duration::from_secs(86_400);
+        let expires = Some("Sun, 17 Oct 2027 12:00:00 GMT");
+        assert_eq!(cache_headers(None, expires).freshness_lifetime(), Some(day));
+        let headers = cache_headers(Some("public, MAX-AGE=\"60\""), expires);
+        assert_eq!(headers.directive("public"), Some(""));
+        assert_eq!(headers.freshness_lifetime(), Some(Duration::from_secs(60)));
+        assert_eq!(cache_headers(None, Some("0")).freshness_lifetime(), Some(Duration::ZERO));
+        assert_eq!(cache_headers(Some("no-cache"), None).freshness_lifetime(), None);
+    }
+}
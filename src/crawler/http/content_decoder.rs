@@ -0,0 +1,109 @@
+use anyhow::anyhow;
+use std::io::Read;
+
+/// Value of the Accept-Encoding header sent with every request.
+pub const ACCEPTED_ENCODINGS: &str = "gzip, deflate, br";
+
+/// A body decoded to more than the maximum body size; holds the maximum.
+#[derive(Debug, thiserror::Error)]
+#[error("Decoded body exceeds the maximum body size of {0} bytes")]
+pub struct BodyTooLarge(pub u64);
+
+/// Decodes response bodies according to their Content-Encoding and charset.
+pub struct ContentDecoder;
+
+impl ContentDecoder {
+    /// Undoes the Content-Encoding of a response body. `None` and "identity" leave it unchanged.
+    /// Decoding stops with `BodyTooLarge` once the decoded body outgrows `max_size` bytes.
+    pub fn decode_content(
+        body: &[u8],
+        content_encoding: Option<&str>,
+        max_size: Option<u64>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let max_size = max_size.unwrap_or(u64::MAX);
+        let mut decoded = Vec::new();
+        match content_encoding.map(|encoding| encoding.trim().to_ascii_lowercase()) {
+            None => return Ok(body.to_vec()),
+            Some(encoding) if encoding.is_empty() || encoding == "identity" => {
+                return Ok(body.to_vec());
+            }
+            Some(encoding) if encoding == "gzip" || encoding == "x-gzip" => {
+                let decoder = flate2::read::MultiGzDecoder::new(body);
+                Self::read_limited(decoder, max_size, &mut decoded)?;
+            }
+            Some(encoding) if encoding == "deflate" => {
+                // "deflate" should be zlib-wrapped, but some servers send raw deflate data
+                let decoder = flate2::read::ZlibDecoder::new(body);
+                match Self::read_limited(decoder, max_size, &mut decoded) {
+                    Err(e) if !e.is::<BodyTooLarge>() => {
+                        decoded.clear();
+                        let decoder = flate2::read::DeflateDecoder::new(body);
+                        Self::read_limited(decoder, max_size, &mut decoded)?;
+                    }
+                    result => result?,
+                }
+            }
+            Some(encoding) if encoding == "br" => {
+                let decoder = brotli_decompressor::Decompressor::new(body, 4096);
+                Self::read_limited(decoder, max_size, &mut decoded)?;
+            }
+            Some(encoding) => return Err(anyhow!("Unsupported content encoding '{}'", encoding)),
+        }
+        Ok(decoded)
+    }
+
+    /// Reads at most one byte past `max_size`, enough to tell that the body is too large.
+    fn read_limited(
+        decoder: impl Read,
+        max_size: u64,
+        decoded: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        decoder.take(max_size.saturating_add(1)).read_to_end(decoded)?;
+        if decoded.len() as u64 > max_size {
+            return Err(BodyTooLarge(max_size).into());
+        }
+        Ok(())
+    }
+
+    /// Decodes a body to text using the charset from its content type, defaulting to UTF-8.
+    pub fn decode_text(body: &[u8], content_type: &mime::Mime) -> String {
+        let encoding = content_type
+            .get_param(mime::CHARSET)
+            .and_then(|charset| encoding_rs::Encoding::for_label(charset.as_str().as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+        let (text, _, _) = encoding.decode(body);
+        text.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn stops_decoding_past_the_maximum_size() {
+        let body = gzip(&[b'a'; 1_000_000]);
+        assert!(body.len() < 10_000);
+        let result = ContentDecoder::decode_content(&body, Some("gzip"), Some(10_000));
+        assert!(result.unwrap_err().is::<BodyTooLarge>());
+
+        let decoded = ContentDecoder::decode_content(&body, Some("gzip"), Some(1_000_000));
+        assert_eq!(decoded.unwrap().len(), 1_000_000);
+    }
+
+    #[test]
+    fn does_not_retry_oversized_deflate_bodies_as_raw_deflate() {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&[b'a'; 100_000]).unwrap();
+        let body = encoder.finish().unwrap();
+        let result = ContentDecoder::decode_content(&body, Some("deflate"), Some(100));
+        assert!(result.unwrap_err().is::<BodyTooLarge>());
+    }
+}
@@ -0,0 +1,120 @@
+/// Built-in device profiles selectable with `--profile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProfileName {
+    /// A desktop browser on a wide screen
+    Desktop,
+    /// A phone browser on a narrow screen
+    Mobile,
+}
+
+/// Request headers that identify the kind of client crawling, so sites that vary by device
+/// or language can be audited as a particular visitor would see them, and who runs the crawl.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrawlProfile {
+    pub user_agent: Option<String>,
+    pub accept_language: Option<String>,
+    /// Sent as the Viewport-Width and Sec-CH-Viewport-Width client hints.
+    pub viewport_width: Option<u32>,
+    /// Email address or URL site owners can reach whoever runs the crawl at. Appended to the
+    /// User-Agent, and sent as the From header if it is an email address.
+    pub contact: Option<String>,
+}
+
+impl CrawlProfile {
+    pub fn from_name(profile_name: ProfileName) -> Self {
+        match profile_name {
+            ProfileName::Desktop => Self {
+                user_agent: Some(format!(
+                    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                     Chrome/124.0 Safari/537.36 rusty-spider/{}",
+                    env!("CARGO_PKG_VERSION")
+                )),
+                accept_language: Some("en-US,en;q=0.9".to_string()),
+                viewport_width: Some(1920),
+                contact: None,
+            },
+            ProfileName::Mobile => Self {
+                user_agent: Some(format!(
+                    "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+                     (KHTML, like Gecko) Chrome/124.0 Mobile Safari/537.36 rusty-spider/{}",
+                    env!("CARGO_PKG_VERSION")
+                )),
+                accept_language: Some("en-US,en;q=0.9".to_string()),
+                viewport_width: Some(412),
+                contact: None,
+            },
+        }
+    }
+
+    pub fn with_user_agent(mut self, user_agent: Option<String>) -> Self {
+        if user_agent.is_some() {
+            self.user_agent = user_agent;
+        }
+        self
+    }
+
+    pub fn with_accept_language(mut self, accept_language: Option<String>) -> Self {
+        if accept_language.is_some() {
+            self.accept_language = accept_language;
+        }
+        self
+    }
+
+    pub fn with_viewport_width(mut self, viewport_width: Option<u32>) -> Self {
+        if viewport_width.is_some() {
+            self.viewport_width = viewport_width;
+        }
+        self
+    }
+
+    pub fn with_contact(mut self, contact: Option<String>) -> Self {
+        self.contact = contact;
+        self
+    }
+
+    /// The User-Agent header to send, with the contact appended in the customary
+    /// "(+contact)" form. Without a User-Agent of its own, a contact is sent after the
+    /// crawler's name and version.
+    pub fn user_agent_header(&self) -> Option<String> {
+        let Some(contact) = &self.contact else {
+            return self.user_agent.clone();
+        };
+        let user_agent = self
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| format!("rusty-spider/{}", env!("CARGO_PKG_VERSION")));
+        Some(format!("{} (+{})", user_agent, contact))
+    }
+
+    /// The contact, if it is an email address, which is sent as the From header.
+    pub fn contact_email(&self) -> Option<&str> {
+        self.contact
+            .as_deref()
+            .filter(|contact| contact.contains('@') && !contact.contains("://"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contact_is_appended_to_the_user_agent_and_sent_as_from_if_an_email() {
+        let profile = CrawlProfile::default()
+            .with_user_agent(Some("AuditBot/2.0".to_string()))
+            .with_contact(Some("crawler@example.com".to_string()));
+        assert_eq!(
+            profile.user_agent_header().as_deref(),
+            Some("AuditBot/2.0 (+crawler@example.com)")
+        );
+        assert_eq!(profile.contact_email(), Some("crawler@example.com"));
+
+        let profile =
+            CrawlProfile::default().with_contact(Some("https://example.com/bot".to_string()));
+        let user_agent = profile.user_agent_header().unwrap();
+        assert!(user_agent.starts_with("rusty-spider/"));
+        assert!(user_agent.ends_with(" (+https://example.com/bot)"));
+        assert_eq!(profile.contact_email(), None);
+        assert_eq!(CrawlProfile::default().user_agent_header(), None);
+    }
+}
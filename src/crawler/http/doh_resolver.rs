@@ -0,0 +1,86 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, SocketAddr};
+use url::Url;
+
+/// DNS record types requested from the DNS-over-HTTPS server.
+const RECORD_TYPE_A: u16 = 1;
+const RECORD_TYPE_AAAA: u16 = 28;
+
+/// Resolves host names with a DNS-over-HTTPS server speaking the JSON API
+/// (`application/dns-json`), e.g. https://cloudflare-dns.com/dns-query.
+#[derive(Clone)]
+pub struct DohResolver {
+    endpoint: Url,
+    // The resolver needs its own client; the shared client resolves through this resolver.
+    http_client: reqwest::Client,
+}
+
+#[derive(serde::Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(serde::Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+impl DohResolver {
+    pub fn new(endpoint: Url) -> Self {
+        Self {
+            endpoint,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    async fn lookup(&self, name: &str, record_type: u16) -> anyhow::Result<Vec<IpAddr>> {
+        let mut query_url = self.endpoint.clone();
+        query_url
+            .query_pairs_mut()
+            .append_pair("name", name)
+            .append_pair("type", &record_type.to_string());
+        let response: DohResponse = self
+            .http_client
+            .get(query_url)
+            .header(reqwest::header::ACCEPT, "application/dns-json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response.status != 0 {
+            return Err(anyhow::anyhow!(
+                "DNS-over-HTTPS lookup of '{}' failed with DNS status {}",
+                name,
+                response.status
+            ));
+        }
+        // CNAME answers are followed by the server; only keep the address records
+        Ok(response
+            .answer
+            .iter()
+            .filter(|answer| answer.record_type == record_type)
+            .filter_map(|answer| answer.data.parse().ok())
+            .collect())
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let mut addrs = resolver.lookup(name.as_str(), RECORD_TYPE_A).await?;
+            addrs.extend(resolver.lookup(name.as_str(), RECORD_TYPE_AAAA).await?);
+            if addrs.is_empty() {
+                return Err(anyhow::anyhow!("No addresses found for '{}'", name.as_str()).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|addr| SocketAddr::new(addr, 0)));
+            Ok(addrs)
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use crate::crawler::http::PrivateNetworkGuard;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Resolves host names, with the system resolver or an inner resolver such as DNS over
+/// HTTPS, and refuses those that only resolve to private network addresses.
+#[derive(Clone)]
+pub struct GuardedResolver {
+    inner: Option<Arc<dyn Resolve>>,
+}
+
+impl GuardedResolver {
+    pub fn new(inner: Option<Arc<dyn Resolve>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = match inner {
+                Some(inner) => inner.resolve(name).await?.collect(),
+                None => tokio::net::lookup_host((host.as_str(), 0)).await?.collect(),
+            };
+            // Addresses are checked after resolution, so DNS rebinding cannot slip past
+            let (private_addrs, public_addrs): (Vec<_>, Vec<_>) = addrs
+                .into_iter()
+                .partition(|addr| PrivateNetworkGuard::is_private_address(&addr.ip()));
+            if public_addrs.is_empty() {
+                return Err(match private_addrs.first() {
+                    Some(addr) => anyhow::anyhow!(
+                        "Refusing to connect to '{}': it resolves to the private address {} \
+                         (use --allow-private-networks to allow this)",
+                        host,
+                        addr.ip()
+                    ),
+                    None => anyhow::anyhow!("No addresses found for '{}'", host),
+                }
+                .into());
+            }
+            let addrs: Addrs = Box::new(public_addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
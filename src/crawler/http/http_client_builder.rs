@@ -0,0 +1,136 @@
+use crate::crawler::crawler_config::CrawlerConfig;
+use crate::crawler::http::{
+    DohResolver, GuardedResolver, HttpVersionPreference, PrivateNetworkGuard,
+};
+use crate::crawler::http::content_decoder::ACCEPTED_ENCODINGS;
+use crate::crawler::scope::ScopePolicy;
+use reqwest::header::{ACCEPT_ENCODING, ACCEPT_LANGUAGE, FROM, HeaderMap, HeaderValue};
+use reqwest::dns::Resolve;
+use reqwest::redirect;
+use std::sync::Arc;
+
+/// Redirects followed per request when no `--max-redirects` is given, as with reqwest's
+/// default policy.
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// Builds the HTTP clients of a crawl.
+pub struct HttpClientBuilder<'a> {
+    config: &'a CrawlerConfig,
+    scoped_redirects: bool,
+}
+
+impl<'a> HttpClientBuilder<'a> {
+    pub fn new(config: &'a CrawlerConfig) -> Self {
+        Self {
+            config,
+            scoped_redirects: false,
+        }
+    }
+
+    /// Stops at redirects that leave the scope of the request's host, or at every redirect if
+    /// the crawl doesn't follow them, returning the redirect response instead. Used for page
+    /// fetches; robots.txt, sitemaps and feeds follow redirects wherever they go.
+    pub fn with_scoped_redirects(mut self, scoped_redirects: bool) -> Self {
+        self.scoped_redirects = scoped_redirects;
+        self
+    }
+
+    pub fn build(&self) -> anyhow::Result<reqwest::Client> {
+        // Compressed responses are decoded by ContentDecoder rather than by reqwest, so the
+        // Content-Encoding header and the transferred size stay visible to the crawler.
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(ACCEPTED_ENCODINGS));
+
+        let profile = self.config.profile();
+        if let Some(accept_language) = &profile.accept_language {
+            default_headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_str(accept_language)?);
+        }
+        if let Some(viewport_width) = profile.viewport_width {
+            let viewport_width = HeaderValue::from(viewport_width);
+            default_headers.insert("viewport-width", viewport_width.clone());
+            default_headers.insert("sec-ch-viewport-width", viewport_width);
+        }
+        if let Some(from) = profile.contact_email() {
+            default_headers.insert(FROM, HeaderValue::from_str(from)?);
+        }
+
+        let mut client_builder = reqwest::Client::builder().default_headers(default_headers);
+        if let Some(user_agent) = profile.user_agent_header() {
+            client_builder = client_builder.user_agent(user_agent);
+        }
+        client_builder = match self.config.http_version() {
+            HttpVersionPreference::Auto => client_builder,
+            HttpVersionPreference::Http1 => client_builder.http1_only(),
+            // Prior knowledge would break every server that only speaks HTTP/1.1
+            HttpVersionPreference::Http2 => client_builder.http2_adaptive_window(true),
+        };
+        let doh_resolver = self.config.doh_url().map(|doh_url| DohResolver::new(doh_url.clone()));
+        if self.config.allow_private_networks() {
+            if let Some(doh_resolver) = doh_resolver {
+                client_builder = client_builder.dns_resolver(Arc::new(doh_resolver));
+            }
+        } else {
+            let inner = doh_resolver.map(|doh_resolver| Arc::new(doh_resolver) as Arc<dyn Resolve>);
+            client_builder = client_builder.dns_resolver(Arc::new(GuardedResolver::new(inner)));
+        }
+        client_builder = client_builder.redirect(self.redirect_policy());
+        // Overrides take precedence over the resolver, as with curl's --resolve
+        for resolve_override in self.config.resolve_overrides() {
+            client_builder =
+                client_builder.resolve(&resolve_override.host, resolve_override.socket_addr());
+        }
+        let client = client_builder.build()?;
+        Ok(client)
+    }
+
+    fn redirect_policy(&self) -> redirect::Policy {
+        let allow_private_networks = self.config.allow_private_networks();
+        let max_redirects = self.config.max_redirects();
+        let follow_redirects = self.config.follow_redirects();
+        let redirect_scope = self
+            .scoped_redirects
+            .then(|| (self.config.allow_hosts().to_vec(), self.config.deny_hosts().to_vec()));
+        redirect::Policy::custom(move |attempt| {
+            // Redirects to a literal address skip the resolver, so they are checked here
+            if !allow_private_networks && PrivateNetworkGuard::is_private_url(attempt.url()) {
+                let error = anyhow::anyhow!(
+                    "Refusing to follow a redirect to the private address {}",
+                    attempt.url()
+                );
+                return attempt.error(error);
+            }
+            if let Some((allow_hosts, deny_hosts)) = &redirect_scope {
+                let scope_policy = ScopePolicy::new(attempt.previous()[0].clone())
+                    .with_allow_hosts(allow_hosts.clone())
+                    .with_deny_hosts(deny_hosts.clone())
+                    .with_allow_private_networks(allow_private_networks);
+                if !follow_redirects || !scope_policy.is_in_scope(attempt.url()) {
+                    return attempt.stop();
+                }
+            }
+            if attempt.previous().len() > max_redirects {
+                attempt.error(anyhow::anyhow!("Too many redirects"))
+            } else {
+                attempt.follow()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockSite, MockSiteServer};
+
+    #[tokio::test]
+    async fn preferring_http2_negotiates_rather_than_assumes_it() {
+        let server = MockSiteServer::start(MockSite::new().with_html("/", &[])).await.unwrap();
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_allow_private_networks(true)
+            .with_http_version(HttpVersionPreference::Http2);
+        let http_client = HttpClientBuilder::new(&config).build().unwrap();
+        // Plain http:// has no ALPN, so the client keeps to HTTP/1.1
+        let response = http_client.get(server.url("/")).send().await.unwrap();
+        assert_eq!(response.version(), reqwest::Version::HTTP_11);
+    }
+}
@@ -0,0 +1,12 @@
+/// Which HTTP protocol version the shared client negotiates with servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum HttpVersionPreference {
+    /// Negotiate via ALPN, using HTTP/2 when the server offers it
+    #[default]
+    Auto,
+    /// Only speak HTTP/1.1
+    Http1,
+    /// Prefer HTTP/2: negotiate it via ALPN with a flow-control window tuned for it, falling
+    /// back to HTTP/1.1 for servers that don't offer it
+    Http2,
+}
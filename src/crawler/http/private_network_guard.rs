@@ -0,0 +1,82 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// Recognizes addresses on private, link-local and loopback networks, so that an untrusted
+/// seed list cannot point the crawler at internal services such as cloud metadata endpoints.
+pub struct PrivateNetworkGuard;
+
+impl PrivateNetworkGuard {
+    pub fn is_private_address(ip: &IpAddr) -> bool {
+        match ip {
+            IpAddr::V4(ip) => Self::is_private_ipv4(ip),
+            IpAddr::V6(ip) => Self::is_private_ipv6(ip),
+        }
+    }
+
+    /// Whether the URL names a private address literally, e.g. http://169.254.169.254/.
+    /// Host names are checked when they are resolved, by `GuardedResolver`.
+    pub fn is_private_url(url: &Url) -> bool {
+        match url.host() {
+            Some(Host::Ipv4(ip)) => Self::is_private_ipv4(&ip),
+            Some(Host::Ipv6(ip)) => Self::is_private_ipv6(&ip),
+            _ => false,
+        }
+    }
+
+    fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+        let octets = ip.octets();
+        ip.is_private()
+            || ip.is_loopback()
+            || ip.is_link_local()
+            || ip.is_unspecified()
+            || ip.is_broadcast()
+            // "This network" 0.0.0.0/8 and carrier-grade NAT 100.64.0.0/10
+            || octets[0] == 0
+            || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+    }
+
+    fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+        if let Some(ipv4) = ip.to_ipv4_mapped() {
+            return Self::is_private_ipv4(&ipv4);
+        }
+        let first_segment = ip.segments()[0];
+        ip.is_loopback()
+            || ip.is_unspecified()
+            // Unique local fc00::/7 and link-local fe80::/10
+            || (first_segment & 0xfe00) == 0xfc00
+            || (first_segment & 0xffc0) == 0xfe80
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_private_link_local_and_loopback_addresses() {
+        let is_private = |ip: &str| PrivateNetworkGuard::is_private_address(&ip.parse().unwrap());
+        for ip in [
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(is_private(ip), "{}", ip);
+        }
+        for ip in ["93.184.216.34", "172.32.0.1", "100.128.0.1", "2606:4700::1111"] {
+            assert!(!is_private(ip), "{}", ip);
+        }
+        let is_private_url =
+            |url: &str| PrivateNetworkGuard::is_private_url(&Url::parse(url).unwrap());
+        assert!(is_private_url("http://169.254.169.254/latest/meta-data/"));
+        assert!(is_private_url("http://[::1]:8080/"));
+        assert!(!is_private_url("http://localhost/"));
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::anyhow;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+/// A `--resolve host:ip` override that pins a host name to an address, bypassing DNS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveOverride {
+    pub host: String,
+    pub addr: IpAddr,
+}
+
+impl ResolveOverride {
+    /// The address to hand to reqwest. Its port is ignored; the URL's port is used instead.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.addr, 0)
+    }
+}
+
+impl FromStr for ResolveOverride {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, addr) = s
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid resolve override '{}' (expected host:ip)", s))?;
+        if host.is_empty() {
+            return Err(anyhow!("Invalid resolve override '{}' (missing host)", s));
+        }
+        let addr = addr.trim_start_matches('[').trim_end_matches(']');
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|_| anyhow!("Invalid IP address '{}' in resolve override '{}'", addr, s))?;
+        Ok(Self {
+            host: host.to_ascii_lowercase(),
+            addr,
+        })
+    }
+}
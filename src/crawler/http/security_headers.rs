@@ -0,0 +1,55 @@
+use reqwest::header::HeaderMap;
+
+/// Response headers that harden a site against downgrade, injection and framing attacks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SecurityHeaders {
+    pub strict_transport_security: Option<String>,
+    pub content_security_policy: Option<String>,
+    pub x_frame_options: Option<String>,
+    pub x_content_type_options: Option<String>,
+}
+
+impl SecurityHeaders {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+        };
+        Self {
+            strict_transport_security: header("strict-transport-security"),
+            content_security_policy: header("content-security-policy"),
+            x_frame_options: header("x-frame-options"),
+            x_content_type_options: header("x-content-type-options"),
+        }
+    }
+
+    /// Names of the headers a response should have sent but didn't. Browsers ignore
+    /// Strict-Transport-Security over plain HTTP, so it is only expected over HTTPS, and a
+    /// Content-Security-Policy with frame-ancestors stands in for X-Frame-Options.
+    pub fn missing(&self, https: bool) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if https && self.strict_transport_security.is_none() {
+            missing.push("Strict-Transport-Security");
+        }
+        if self.content_security_policy.is_none() {
+            missing.push("Content-Security-Policy");
+        }
+        let frame_ancestors = self
+            .content_security_policy
+            .as_deref()
+            .is_some_and(|policy| policy.to_ascii_lowercase().contains("frame-ancestors"));
+        if self.x_frame_options.is_none() && !frame_ancestors {
+            missing.push("X-Frame-Options");
+        }
+        if !self
+            .x_content_type_options
+            .as_deref()
+            .is_some_and(|value| value.eq_ignore_ascii_case("nosniff"))
+        {
+            missing.push("X-Content-Type-Options");
+        }
+        missing
+    }
+}
@@ -0,0 +1,41 @@
+use url::Url;
+
+/// Anchor texts that say nothing about the page they link to.
+const GENERIC_ANCHOR_TEXTS: [&str; 16] = [
+    "click here",
+    "click",
+    "here",
+    "link",
+    "this",
+    "this page",
+    "more",
+    "read more",
+    "learn more",
+    "more info",
+    "more information",
+    "details",
+    "continue",
+    "continue reading",
+    "go",
+    "see more",
+];
+
+/// An internal link together with the text it is shown as.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct LinkAnchor {
+    pub url: Url,
+    /// Visible text of the link with whitespace collapsed, falling back to the alt text of an
+    /// image inside it or its aria-label. Empty if the link has none of these.
+    pub anchor_text: String,
+}
+
+impl LinkAnchor {
+    /// Whether the anchor text is empty or a phrase like "click here" that could link anywhere.
+    pub fn is_generic(&self) -> bool {
+        let anchor_text = self
+            .anchor_text
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        anchor_text.is_empty() || GENERIC_ANCHOR_TEXTS.contains(&anchor_text.as_str())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use bytesize::ByteSize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How often the resident memory is checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Share of the limit at which the crawl starts shedding memory.
+const DEGRADE_AT: f64 = 0.9;
+
+/// Watches the resident memory of the process and, once it nears the limit, has the seed
+/// crawlers shed memory rather than get the process killed: their frontiers spill to disk
+/// and page text is no longer kept. The crawl stays degraded for the rest of the run.
+#[derive(Clone)]
+pub struct MemoryGuard {
+    state: Arc<GuardState>,
+}
+
+struct GuardState {
+    limit: u64,
+    degraded: AtomicBool,
+    /// Where the warning goes once memory nears the limit. Held by the state, not the
+    /// watching task, so the channel closes as soon as the guard is dropped.
+    event_tx: OnceLock<UnboundedSender<CrawlerProcessEvent>>,
+}
+
+impl MemoryGuard {
+    /// Starts checking memory use against `limit` bytes in the background, until the guard
+    /// and all its clones are dropped. Must be called within a runtime.
+    pub fn start(limit: u64) -> Self {
+        let state = Arc::new(GuardState {
+            limit,
+            degraded: AtomicBool::new(false),
+            event_tx: OnceLock::new(),
+        });
+        let watched: Weak<GuardState> = Arc::downgrade(&state);
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                let Some(state) = watched.upgrade() else {
+                    break;
+                };
+                if let Some(resident) = resident_memory() {
+                    state.check(resident);
+                }
+            }
+        });
+        Self { state }
+    }
+
+    /// Sends the warning that memory use is near the limit to `event_tx`, for the console
+    /// to show. Without one, the warning is only reflected in `is_degraded`.
+    pub fn report_to(&self, event_tx: UnboundedSender<CrawlerProcessEvent>) {
+        let _ = self.state.event_tx.set(event_tx);
+    }
+
+    /// Whether memory use has come near the limit.
+    pub fn is_degraded(&self) -> bool {
+        self.state.degraded.load(Ordering::Relaxed)
+    }
+}
+
+impl GuardState {
+    fn check(&self, resident: u64) {
+        if resident as f64 >= self.limit as f64 * DEGRADE_AT
+            && !self.degraded.swap(true, Ordering::Relaxed)
+        {
+            let message = format!(
+                "Warning: memory use of {} is near the limit of {}; spilling frontiers to disk \
+                 and no longer keeping page text",
+                ByteSize::b(resident),
+                ByteSize::b(self.limit)
+            );
+            if let Some(event_tx) = self.event_tx.get() {
+                let _ = event_tx.send(CrawlerProcessEvent::Notice { message });
+            }
+        }
+    }
+}
+
+/// The resident set size of this process, read from /proc on Linux.
+pub fn resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let vm_rss = status.lines().find_map(|line| line.strip_prefix("VmRSS:"))?;
+    let kibibytes = vm_rss.trim().strip_suffix("kB")?.trim_end().parse::<u64>().ok()?;
+    Some(kibibytes * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_the_resident_memory() {
+        let resident = resident_memory().unwrap();
+        // Whatever the page size, a running test binary takes more than a megabyte
+        assert!(resident > 1024 * 1024, "{}", resident);
+    }
+
+    #[tokio::test]
+    async fn warns_once_through_the_event_channel() {
+        let memory_guard = MemoryGuard::start(1000);
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        memory_guard.report_to(event_tx);
+
+        memory_guard.state.check(800);
+        assert!(!memory_guard.is_degraded());
+        memory_guard.state.check(950);
+        memory_guard.state.check(990);
+        assert!(memory_guard.is_degraded());
+
+        drop(memory_guard);
+        let mut notices = Vec::new();
+        while let Some(event) = event_rx.recv().await {
+            if let CrawlerProcessEvent::Notice { message } = event {
+                notices.push(message);
+            }
+        }
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].contains("near the limit"));
+    }
+}
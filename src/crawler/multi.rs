@@ -1,3 +1,11 @@
+mod crawl_run;
 mod multi_crawler;
+mod run_info;
+mod shared_visited_set;
+mod timeline;
 
-pub use multi_crawler::MultiCrawler;
\ No newline at end of file
+pub use crawl_run::CrawlRun;
+pub use multi_crawler::MultiCrawler;
+pub use run_info::{RunInfo, host_name, new_run_id};
+pub use shared_visited_set::SharedVisitedSet;
+pub use timeline::{TimelineRecorder, TimelineSample};
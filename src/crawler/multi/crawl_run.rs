@@ -0,0 +1,10 @@
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::multi::RunInfo;
+
+/// The results of a crawl of all seeds, with the details of the run that produced them.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CrawlRun {
+    pub run: RunInfo,
+    #[serde(rename = "results")]
+    pub crawl_summaries: Vec<CrawlSummary>,
+}
@@ -1,70 +1,212 @@
-use std::sync::Arc;
 use url::Url;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use futures::future::join_all;
-use crate::console::console_progress_reporter::ConsoleProcessReporter;
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
 use crate::crawler::crawl_summary::CrawlSummary;
 use crate::crawler::crawler_config::CrawlerConfig;
-use crate::crawler::seed::ConsoleProgressReporter;
+use crate::crawler::events::CrawlEvent;
+use crate::crawler::multi::{CrawlRun, RunInfo, TimelineRecorder};
+use crate::crawler::seed::ChannelProgressReporter;
+use crate::crawler::seed::CrawlControl;
 use crate::crawler::seed::SeedCrawler;
+use crate::crawler::shared_crawl_state::SharedCrawlState;
 
 #[derive(Clone)]
 pub struct MultiCrawler {
-    shutdown_notify: Arc<tokio::sync::Notify>,
+    cancel_token: CancellationToken,
     crawler_config: CrawlerConfig,
-    console_process_reporter: ConsoleProcessReporter,
-    seeds: Vec<Url>,
+    event_tx: tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
+    run_info: RunInfo,
+    crawl_control: CrawlControl,
 }
 
 impl MultiCrawler {
     pub fn new(
-        shutdown_notify: Arc<tokio::sync::Notify>,
+        cancel_token: CancellationToken,
         crawler_config: CrawlerConfig,
-        console_process_reporter: ConsoleProcessReporter,
+        event_tx: tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
     ) -> Self {
         Self {
-            shutdown_notify,
+            cancel_token,
+            run_info: RunInfo::new(&crawler_config),
             crawler_config,
-            console_process_reporter,
-            seeds: Vec::new(),
+            event_tx,
+            crawl_control: CrawlControl::new(),
         }
     }
 
+    /// The ID of the run, attached to its events and results.
+    pub fn run_id(&self) -> &str {
+        &self.run_info.id
+    }
+
     pub fn add_seed(&mut self, seed: Url) {
-        self.seeds.push(seed);
+        self.run_info.seeds.push(seed);
+    }
+
+    /// A handle that pauses and resumes the crawl while it runs, without keeping the crawler
+    /// (and so its event channel) alive.
+    pub fn crawl_control(&self) -> CrawlControl {
+        self.crawl_control.clone()
     }
 
-    pub async fn run(self) -> anyhow::Result<Vec<CrawlSummary>> {
-        let shutdown_notify = Arc::clone(&self.shutdown_notify);
-        let console_process_reporter = self.console_process_reporter.clone();
+    pub async fn run(mut self) -> anyhow::Result<CrawlRun> {
+        self.run_info.start();
+        let cancel_token = self.cancel_token.clone();
+        let (timeline_recorder, event_tx) = match self.crawler_config.timeline_interval() {
+            Some(interval) => {
+                let (timeline_recorder, event_tx) =
+                    TimelineRecorder::start(interval, self.event_tx.clone());
+                (Some(timeline_recorder), event_tx)
+            }
+            None => (None, self.event_tx.clone()),
+        };
         let crawler_config = self.crawler_config.clone();
-        let handles = self
-            .seeds
-            .iter()
-            .cloned()
-            .enumerate()
-            .map(|(crawler_index, seed)| {
-                let shutdown_notify = Arc::clone(&shutdown_notify);
-                let console_reporter = console_process_reporter.clone();
-                let crawler_config = crawler_config.clone();
-                tokio::task::spawn(async move {
-                    let progress_reporter = ConsoleProgressReporter::new(
-                        crawler_index,
-                        seed.clone(),
-                        console_reporter.event_tx(),
-                    );
-                    let seed_crawler =
-                        SeedCrawler::new(shutdown_notify, seed.clone(), progress_reporter);
-                    let crawl_summary = seed_crawler.crawl(crawler_config).await?;
-                    Ok::<CrawlSummary, anyhow::Error>(crawl_summary)
-                })
-            })
-            .collect::<Vec<JoinHandle<anyhow::Result<CrawlSummary>>>>();
-        let all_tasks = join_all(handles).await;
-        let results: Vec<CrawlSummary> = all_tasks
+        let mut shared_state = SharedCrawlState::new(&crawler_config)?;
+        shared_state.crawl_control = self.crawl_control.clone();
+        if let Some(memory_guard) = &shared_state.memory_guard {
+            memory_guard.report_to(event_tx.clone());
+        }
+        if let Some(event_publisher) = &shared_state.event_publisher {
+            event_publisher.publish(&CrawlEvent::RunStarted { run: &self.run_info });
+        }
+        let _ = event_tx.send(CrawlerProcessEvent::RunStarted {
+            run_id: self.run_info.id.clone(),
+        });
+        let run_info = &self.run_info;
+        let crawl_seed = |(crawler_index, seed): (usize, Url)| {
+            let run_id = run_info.id.clone();
+            let seed_id = run_info.seed_id(crawler_index);
+            let cancel_token = cancel_token.clone();
+            let event_tx = event_tx.clone();
+            let crawler_config = crawler_config.clone();
+            let shared_state = shared_state.clone();
+            let handle: JoinHandle<anyhow::Result<CrawlSummary>> = tokio::task::spawn(async move {
+                let progress_reporter = ChannelProgressReporter::new(
+                    crawler_index,
+                    seed.clone(),
+                    seed_id.clone(),
+                    event_tx,
+                );
+                let seed_crawler =
+                    SeedCrawler::new(cancel_token, seed.clone(), progress_reporter, shared_state)
+                        .with_crawl_ids(run_id, seed_id);
+                let crawl_summary = seed_crawler.crawl(crawler_config).await?;
+                Ok(crawl_summary)
+            });
+            handle
+        };
+        let seeds = self.run_info.seeds.iter().cloned().enumerate();
+        let all_tasks = if crawler_config.deterministic() {
+            // One seed at a time, so seeds sharing pages or hosts always split them the same way
+            let mut all_tasks = Vec::new();
+            for seed in seeds {
+                all_tasks.push(crawl_seed(seed).await);
+            }
+            all_tasks
+        } else {
+            join_all(seeds.map(crawl_seed)).await
+        };
+        shared_state.fetcher.close().await;
+        let crawl_summaries: Vec<CrawlSummary> = all_tasks
             .into_iter()
-            .filter_map(|task_result| task_result.ok().and_then(|res| res.ok()))
+            .zip(&self.run_info.seeds)
+            .filter_map(|(task_result, seed)| match task_result {
+                Ok(Ok(crawl_summary)) => Some(crawl_summary),
+                Ok(Err(e)) => {
+                    eprintln!("Crawl of {} failed: {:#}", seed, e);
+                    None
+                }
+                Err(e) => {
+                    eprintln!("Crawl of {} failed: {}", seed, e);
+                    None
+                }
+            })
             .collect();
-        Ok(results)
+        self.run_info.finish(&crawl_summaries);
+        if let Some(timeline_recorder) = timeline_recorder {
+            self.run_info.timeline = timeline_recorder.finish();
+        }
+        if let Some(event_publisher) = &shared_state.event_publisher {
+            event_publisher.publish(&CrawlEvent::RunFinished { run: &self.run_info });
+            if let Err(e) = event_publisher.close().await {
+                eprintln!("Publishing crawl events failed: {:#}", e);
+            }
+        }
+        if let Some(result_stream) = &shared_state.result_stream {
+            if let Err(e) = result_stream.close().await {
+                eprintln!("Streaming results failed: {:#}", e);
+            }
+        }
+        Ok(CrawlRun {
+            run: self.run_info,
+            crawl_summaries,
+        })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockSite, MockSiteServer};
+
+    #[tokio::test]
+    async fn global_dedupe_crawls_shared_pages_once() {
+        let site = MockSite::new()
+            .with_html("/", &["/shared", "/other"])
+            .with_html("/other", &["/shared"])
+            .with_html("/shared", &[]);
+        let server = MockSiteServer::start(site).await.unwrap();
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::task::spawn(async move { while event_rx.recv().await.is_some() {} });
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_global_dedupe(true)
+            .with_allow_private_networks(true);
+        let mut multi_crawler =
+            MultiCrawler::new(CancellationToken::new(), config, event_tx);
+        multi_crawler.add_seed(server.url("/"));
+        multi_crawler.add_seed(server.url("/other"));
+
+        let crawl_run = multi_crawler.run().await.unwrap();
+        let crawl_summaries = crawl_run.crawl_summaries;
+        assert_eq!(crawl_summaries.len(), 2);
+        assert_eq!(crawl_run.run.seeds.len(), 2);
+        assert!(crawl_run.run.finished_at.is_some());
+        let shared_crawls = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .filter(|page_summary| page_summary.url == server.url("/shared"))
+            .count();
+        assert_eq!(shared_crawls, 1);
+    }
+
+    #[tokio::test]
+    async fn paused_crawls_wait_until_resumed() {
+        let server = MockSiteServer::start(MockSite::chain(3)).await.unwrap();
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let config = CrawlerConfig::new(100, 4, None).with_allow_private_networks(true);
+        let mut multi_crawler =
+            MultiCrawler::new(CancellationToken::new(), config, event_tx);
+        multi_crawler.add_seed(server.url("/"));
+        let crawl_control = multi_crawler.crawl_control();
+        crawl_control.pause();
+
+        let run = tokio::task::spawn(multi_crawler.run());
+        let mut num_pages_completed = 0;
+        let paused = tokio::time::timeout(std::time::Duration::from_millis(300), async {
+            while let Some(event) = event_rx.recv().await {
+                if matches!(event, CrawlerProcessEvent::PageCompleted { .. }) {
+                    num_pages_completed += 1;
+                }
+            }
+        })
+        .await;
+        assert!(paused.is_err());
+        assert_eq!(num_pages_completed, 0);
+
+        crawl_control.resume();
+        let crawl_run = run.await.unwrap().unwrap();
+        assert_eq!(crawl_run.crawl_summaries[0].page_summaries().len(), 3);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::crawler_config::CrawlerConfig;
+use crate::crawler::multi::TimelineSample;
+use sha2::{Digest, Sha256};
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use url::Url;
+
+/// When, where and how a crawl ran, recorded with its results so a run can be audited and
+/// repeated with the same settings.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RunInfo {
+    /// Unique ID of the run, e.g. "3f9a0c1d27b4e856". Each seed's crawl is identified by
+    /// the run ID and the seed's number, e.g. "3f9a0c1d27b4e856-2".
+    pub id: String,
+    pub version: &'static str,
+    pub host: String,
+    /// Email address or URL of whoever ran the crawl, from `--contact`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    /// RFC 3339 timestamps in UTC.
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub seeds: Vec<Url>,
+    pub config: serde_json::Value,
+    /// Linked URLs that were not crawled because robots.txt disallows them.
+    pub robots_disallowed: Vec<Url>,
+    /// Samples of the run's progress, from `--timeline-interval`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<TimelineSample>,
+}
+
+impl RunInfo {
+    pub fn new(crawler_config: &CrawlerConfig) -> Self {
+        Self {
+            id: new_run_id(),
+            version: env!("CARGO_PKG_VERSION"),
+            host: host_name(),
+            contact: crawler_config.profile().contact.clone(),
+            started_at: String::new(),
+            finished_at: None,
+            seeds: Vec::new(),
+            config: crawler_config.snapshot(),
+            robots_disallowed: Vec::new(),
+            timeline: Vec::new(),
+        }
+    }
+
+    /// The ID of the crawl of the seed at `seed_index` in `seeds`.
+    pub fn seed_id(&self, seed_index: usize) -> String {
+        format!("{}-{}", self.id, seed_index + 1)
+    }
+
+    pub fn start(&mut self) {
+        self.started_at = humantime::format_rfc3339_seconds(SystemTime::now()).to_string();
+    }
+
+    /// Records the end of the run and the robots.txt decisions made during it.
+    pub fn finish(&mut self, crawl_summaries: &[CrawlSummary]) {
+        self.finished_at = Some(humantime::format_rfc3339_seconds(SystemTime::now()).to_string());
+        self.robots_disallowed = crawl_summaries
+            .iter()
+            .flat_map(CrawlSummary::page_summaries)
+            .filter(|page_summary| page_summary.blocked_by_robots_txt)
+            .map(|page_summary| page_summary.url.clone())
+            .collect();
+    }
+}
+
+impl Display for RunInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Run {}: rusty-spider {} on {}, started {}, finished {}",
+            self.id,
+            self.version,
+            self.host,
+            self.started_at,
+            self.finished_at.as_deref().unwrap_or("-")
+        )?;
+        writeln!(
+            f,
+            "   {} seeds, {} linked URLs disallowed by robots.txt",
+            self.seeds.len(),
+            self.robots_disallowed.len()
+        )?;
+        if !self.timeline.is_empty() {
+            writeln!(f, "   Timeline:")?;
+            for sample in &self.timeline {
+                writeln!(f, "      {}", sample)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A run ID unique across machines and processes: a hash of the host, process, time and a
+/// count of the runs started by this process.
+pub fn new_run_id() -> String {
+    static NUM_RUNS: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let seed = format!(
+        "{} {} {} {}",
+        host_name(),
+        std::process::id(),
+        nanos,
+        NUM_RUNS.fetch_add(1, Ordering::Relaxed)
+    );
+    let digest = format!("{:x}", Sha256::digest(seed.as_bytes()));
+    digest[..16].to_string()
+}
+
+/// The name of the machine running the crawl, or "unknown" if it can't be found.
+pub fn host_name() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
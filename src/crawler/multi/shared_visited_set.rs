@@ -0,0 +1,20 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// Set of URLs crawled by any seed crawler, so seeds sharing a host don't crawl the same pages twice.
+#[derive(Clone, Default)]
+pub struct SharedVisitedSet {
+    urls: Arc<Mutex<HashSet<Url>>>,
+}
+
+impl SharedVisitedSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claims a URL for crawling. Returns false if another crawler has already claimed it.
+    pub fn try_claim(&self, url: &Url) -> bool {
+        self.urls.lock().unwrap().insert(url.clone())
+    }
+}
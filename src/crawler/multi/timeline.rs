@@ -0,0 +1,151 @@
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use crate::crawler::memory_guard::resident_memory;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Shortest interval between samples.
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The state of a run at one point in time, to spot slowdowns in long crawls.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TimelineSample {
+    /// Seconds since the run started.
+    pub elapsed_secs: f64,
+    /// Pages completed per second since the previous sample.
+    pub pages_per_second: f64,
+    /// Share of the pages completed since the previous sample that got no response or a
+    /// 4xx or 5xx status.
+    pub error_rate: f64,
+    /// URLs waiting to be crawled, over all seeds.
+    pub num_urls_to_crawl: usize,
+    /// Resident memory of the process in bytes, where the platform reports it.
+    pub memory_bytes: Option<u64>,
+}
+
+/// Samples a run's progress events at a fixed interval.
+pub struct TimelineRecorder {
+    state: Arc<Mutex<RecorderState>>,
+    sampler: JoinHandle<()>,
+}
+
+struct RecorderState {
+    started: Instant,
+    num_pages: usize,
+    num_errors: usize,
+    /// Queued URLs of each crawler, by crawler index.
+    urls_to_crawl: HashMap<usize, usize>,
+    /// When the previous sample was taken, and the page and error counts at the time.
+    previous: (Instant, usize, usize),
+    samples: Vec<TimelineSample>,
+}
+
+impl TimelineRecorder {
+    /// Starts sampling every `interval`, and returns the sender to give the crawlers in place of
+    /// `event_tx`; their events are passed on to it.
+    pub fn start(
+        interval: Duration,
+        event_tx: tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
+    ) -> (Self, tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>) {
+        // Tokio intervals must not be zero, and sampling much more often only slows the crawl
+        let interval = interval.max(MIN_INTERVAL);
+        let now = Instant::now();
+        let state = Arc::new(Mutex::new(RecorderState {
+            started: now,
+            num_pages: 0,
+            num_errors: 0,
+            urls_to_crawl: HashMap::new(),
+            previous: (now, 0, 0),
+            samples: Vec::new(),
+        }));
+
+        let (forward_tx, mut forward_rx) = tokio::sync::mpsc::unbounded_channel();
+        {
+            let state = Arc::clone(&state);
+            tokio::task::spawn(async move {
+                while let Some(event) = forward_rx.recv().await {
+                    state.lock().unwrap().handle_event(&event);
+                    let _ = event_tx.send(event);
+                }
+            });
+        }
+        let sampler = {
+            let state = Arc::clone(&state);
+            tokio::task::spawn(async move {
+                let mut ticks = tokio::time::interval_at(now + interval, interval);
+                loop {
+                    ticks.tick().await;
+                    state.lock().unwrap().sample(Instant::now());
+                }
+            })
+        };
+        (Self { state, sampler }, forward_tx)
+    }
+
+    /// Stops sampling and returns the samples, ending with one taken now.
+    pub fn finish(self) -> Vec<TimelineSample> {
+        self.sampler.abort();
+        let mut state = self.state.lock().unwrap();
+        state.sample(Instant::now());
+        std::mem::take(&mut state.samples)
+    }
+}
+
+impl RecorderState {
+    fn handle_event(&mut self, event: &CrawlerProcessEvent) {
+        match event {
+            CrawlerProcessEvent::ProgressUpdate {
+                crawler_index,
+                num_urls_to_crawl,
+                ..
+            } => {
+                self.urls_to_crawl.insert(*crawler_index, *num_urls_to_crawl);
+            }
+            CrawlerProcessEvent::PageCompleted { status_code, .. } => {
+                self.num_pages += 1;
+                if *status_code == 0 || *status_code >= 400 {
+                    self.num_errors += 1;
+                }
+            }
+            CrawlerProcessEvent::End { crawler_index } => {
+                self.urls_to_crawl.remove(crawler_index);
+            }
+            _ => {}
+        }
+    }
+
+    fn sample(&mut self, now: Instant) {
+        let (previous_time, previous_pages, previous_errors) = self.previous;
+        let window = now.saturating_duration_since(previous_time).as_secs_f64();
+        let num_pages = self.num_pages - previous_pages;
+        let num_errors = self.num_errors - previous_errors;
+        self.samples.push(TimelineSample {
+            elapsed_secs: now.saturating_duration_since(self.started).as_secs_f64(),
+            pages_per_second: if window > 0.0 { num_pages as f64 / window } else { 0.0 },
+            error_rate: if num_pages > 0 { num_errors as f64 / num_pages as f64 } else { 0.0 },
+            num_urls_to_crawl: self.urls_to_crawl.values().sum(),
+            memory_bytes: resident_memory(),
+        });
+        self.previous = (now, self.num_pages, self.num_errors);
+    }
+}
+
+impl Display for TimelineSample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0}s: {:.1} pages/s, {:.1}% errors, {} URLs to crawl",
+            self.elapsed_secs,
+            self.pages_per_second,
+            self.error_rate * 100.0,
+            self.num_urls_to_crawl
+        )?;
+        if let Some(memory_bytes) = self.memory_bytes {
+            write!(f, ", {} memory", bytesize::ByteSize::b(memory_bytes))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod query_policy;
+mod url_normalizer;
+
+pub use query_policy::QueryPolicy;
+pub use url_normalizer::UrlNormalizer;
@@ -0,0 +1,103 @@
+use anyhow::anyhow;
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+/// How query strings are treated when normalizing discovered URLs.
+///
+/// Parameter patterns match a parameter name exactly, or by prefix when they end in "*"
+/// (e.g. "utm_*").
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum QueryPolicy {
+    /// Keep every query parameter.
+    KeepAll,
+    /// Remove the query string entirely.
+    #[default]
+    StripAll,
+    /// Keep only the parameters matching these patterns.
+    KeepOnly(Vec<String>),
+    /// Remove the parameters matching these patterns and keep the rest.
+    StripOnly(Vec<String>),
+}
+
+impl QueryPolicy {
+    pub fn apply(&self, url: &mut Url) {
+        match url.query() {
+            None => return,
+            // "/page?" is the same page as "/page"
+            Some("") => {
+                url.set_query(None);
+                return;
+            }
+            Some(_) => {}
+        }
+        let keep_param: &dyn Fn(&str) -> bool = match self {
+            QueryPolicy::KeepAll => return,
+            QueryPolicy::StripAll => {
+                url.set_query(None);
+                return;
+            }
+            QueryPolicy::KeepOnly(patterns) => &|name| Self::matches_any(patterns, name),
+            QueryPolicy::StripOnly(patterns) => &|name| !Self::matches_any(patterns, name),
+        };
+
+        let kept_pairs = url
+            .query_pairs()
+            .filter(|(name, _)| keep_param(name))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect::<Vec<_>>();
+        if kept_pairs.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(kept_pairs);
+        }
+    }
+
+    fn matches_any(patterns: &[String], name: &str) -> bool {
+        patterns.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        })
+    }
+}
+
+impl FromStr for QueryPolicy {
+    type Err = anyhow::Error;
+
+    /// Parses "keep", "strip", "keep:page,id" or "strip:utm_*,fbclid".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mode, patterns) = match s.trim().split_once(':') {
+            Some((mode, patterns)) => (mode, Some(patterns)),
+            None => (s.trim(), None),
+        };
+        let patterns = patterns.map(|patterns| {
+            patterns
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        });
+        match (mode.to_ascii_lowercase().as_str(), patterns) {
+            ("keep", None) => Ok(QueryPolicy::KeepAll),
+            ("strip", None) => Ok(QueryPolicy::StripAll),
+            ("keep", Some(patterns)) => Ok(QueryPolicy::KeepOnly(patterns)),
+            ("strip", Some(patterns)) => Ok(QueryPolicy::StripOnly(patterns)),
+            _ => Err(anyhow!(
+                "Invalid query policy '{}' (expected keep, strip, keep:NAME,... or strip:NAME,...)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for QueryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryPolicy::KeepAll => write!(f, "keep"),
+            QueryPolicy::StripAll => write!(f, "strip"),
+            QueryPolicy::KeepOnly(patterns) => write!(f, "keep:{}", patterns.join(",")),
+            QueryPolicy::StripOnly(patterns) => write!(f, "strip:{}", patterns.join(",")),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::crawler::normalize::QueryPolicy;
+use url::Url;
+
+/// Reduces discovered URLs to the form used to queue and deduplicate them.
+#[derive(Debug, Clone, Default)]
+pub struct UrlNormalizer {
+    query_policy: QueryPolicy,
+    route_fragments: bool,
+}
+
+impl UrlNormalizer {
+    pub fn new(query_policy: QueryPolicy) -> Self {
+        Self {
+            query_policy,
+            route_fragments: false,
+        }
+    }
+
+    /// Keeps fragments that are single-page app routes, such as "#/about" or "#!/about",
+    /// so that each route is crawled as a page of its own.
+    pub fn with_route_fragments(mut self, route_fragments: bool) -> Self {
+        self.route_fragments = route_fragments;
+        self
+    }
+
+    /// Whether a fragment, without its "#", is a route of a single-page app.
+    pub fn is_route_fragment(fragment: &str) -> bool {
+        fragment.starts_with('/') || fragment.starts_with("!/")
+    }
+
+    /// Removes the fragment, unless it is a route that is kept, and applies the query policy.
+    pub fn normalize(&self, url: &Url) -> Url {
+        let mut normalized_url = url.clone();
+        let keeps_fragment = self.route_fragments
+            && normalized_url.fragment().is_some_and(Self::is_route_fragment);
+        if !keeps_fragment {
+            normalized_url.set_fragment(None);
+        }
+        self.query_policy.apply(&mut normalized_url);
+        normalized_url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeping_queries_dedupes_on_the_full_url() {
+        let url_normalizer = UrlNormalizer::new(QueryPolicy::KeepAll);
+        let normalize =
+            |url: &str| url_normalizer.normalize(&Url::parse(url).unwrap()).to_string();
+        assert_eq!(normalize("https://example.com/?p=123#comments"), "https://example.com/?p=123");
+        assert_ne!(
+            normalize("https://example.com/?p=123"),
+            normalize("https://example.com/?p=124")
+        );
+        assert_eq!(normalize("https://example.com/page?"), "https://example.com/page");
+    }
+
+    #[test]
+    fn route_fragments_are_kept_when_enabled() {
+        let url_normalizer = UrlNormalizer::default().with_route_fragments(true);
+        let normalize =
+            |url: &str| url_normalizer.normalize(&Url::parse(url).unwrap()).to_string();
+        assert_eq!(normalize("https://example.com/#/users/1"), "https://example.com/#/users/1");
+        assert_eq!(normalize("https://example.com/#!/about"), "https://example.com/#!/about");
+        assert_eq!(normalize("https://example.com/#top"), "https://example.com/");
+        let url_normalizer = UrlNormalizer::default();
+        let url = Url::parse("https://example.com/#/users/1").unwrap();
+        assert_eq!(url_normalizer.normalize(&url).as_str(), "https://example.com/");
+    }
+}
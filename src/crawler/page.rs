@@ -1,3 +1,13 @@
+mod fetched_page;
+mod link_extractor;
+mod meta_refresh;
 mod page_crawler;
+mod response_middleware;
+mod script_links;
+mod streaming_html_parser;
 
+pub use fetched_page::FetchedPage;
+pub use link_extractor::{DEFAULT_LINK_SELECTOR, LinkExtractor};
 pub use page_crawler::PageCrawler;
+pub use response_middleware::{ResponseAction, ResponseMiddleware, SkipIfContains, StripElements};
+pub use streaming_html_parser::StreamingHtmlParser;
@@ -0,0 +1,18 @@
+use reqwest::header::HeaderMap;
+use url::Url;
+
+/// A successfully fetched page, between the fetch and parse phases of the PageCrawler.
+pub struct FetchedPage {
+    pub url: Url,
+    pub status_code: u16,
+    pub http_version: Option<String>,
+    pub headers: HeaderMap,
+    /// The Content-Encoding the body was sent with, if any.
+    pub content_encoding: Option<String>,
+    /// Size of the body as transferred, before decoding.
+    pub transferred_bytes: usize,
+    /// The body with its Content-Encoding undone.
+    pub body: Vec<u8>,
+    /// Why a headless browser failed to render the page, if it was meant to.
+    pub render_error: Option<String>,
+}
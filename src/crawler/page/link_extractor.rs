@@ -0,0 +1,279 @@
+use crate::crawler::feed::FeedLoader;
+use crate::crawler::hreflang::HreflangAlternate;
+use crate::crawler::link_anchor::LinkAnchor;
+use crate::crawler::normalize::UrlNormalizer;
+use crate::crawler::page::PageCrawler;
+use crate::crawler::page::script_links::ScriptLinks;
+use crate::crawler::pagination::Pagination;
+use anyhow::anyhow;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{BTreeSet, HashSet};
+use url::Url;
+
+/// Elements whose href is followed as a link when no `--link-selector` is given.
+pub const DEFAULT_LINK_SELECTOR: &str = "a[href]";
+
+/// The links found in an HTML document.
+#[derive(Debug, Default)]
+pub struct PageLinks {
+    pub internal_links: Vec<Url>,
+    pub outgoing_links: Vec<Url>,
+    pub internal_link_anchors: Vec<LinkAnchor>,
+    pub canonical: Option<Url>,
+    pub amp_url: Option<Url>,
+    pub hreflang_alternates: Vec<HreflangAlternate>,
+    pub feeds: Vec<Url>,
+    pub pagination: Pagination,
+    pub frames: Vec<Url>,
+    pub script_links: Vec<Url>,
+}
+
+/// Finds the links in HTML documents. Its selectors are compiled once, so one extractor
+/// should be shared by every page of a crawl.
+#[derive(Debug)]
+pub struct LinkExtractor {
+    link_selector: Selector,
+    anchor_image_selector: Selector,
+    link_rel_selector: Selector,
+    frame_selector: Selector,
+    script_link_selector: Selector,
+    /// Follows links to single-page app routes such as "#/about" rather than skipping them
+    /// like other fragment links.
+    route_fragments: bool,
+}
+
+impl LinkExtractor {
+    pub fn new() -> Self {
+        Self {
+            link_selector: Selector::parse(DEFAULT_LINK_SELECTOR).unwrap(),
+            anchor_image_selector: Selector::parse("img[alt]").unwrap(),
+            link_rel_selector: Selector::parse("link[rel][href]").unwrap(),
+            frame_selector: Selector::parse("frame[src], iframe[src]").unwrap(),
+            script_link_selector: Selector::parse("[onclick], [data-href], [data-url]").unwrap(),
+            route_fragments: false,
+        }
+    }
+
+    /// Follows the href of the elements matching the CSS selector `link_selector` instead of
+    /// "a[href]", e.g. "a[href], area[href]" to follow image map links too.
+    pub fn with_link_selector(mut self, link_selector: &str) -> anyhow::Result<Self> {
+        self.link_selector = Selector::parse(link_selector)
+            .map_err(|e| anyhow!("Invalid link selector '{}': {}", link_selector, e))?;
+        Ok(self)
+    }
+
+    /// Follows links to single-page app routes, which only lead to other pages when the app
+    /// runs, i.e. when pages are rendered.
+    pub fn with_route_fragments(mut self, route_fragments: bool) -> Self {
+        self.route_fragments = route_fragments;
+        self
+    }
+
+    /// Extracts the links of a document fetched from `url`. Root-relative links are resolved
+    /// against `site_root` if given.
+    pub fn extract(&self, document: &Html, url: &Url, site_root: Option<&Url>) -> PageLinks {
+        let mut page_links = PageLinks::default();
+        self.add_anchor_links(document, url, site_root, &mut page_links);
+        self.add_link_rels(document, url, site_root, &mut page_links);
+        page_links.frames = document
+            .select(&self.frame_selector)
+            .filter_map(|element| element.value().attr("src"))
+            .filter(|src| !src.trim().is_empty())
+            .filter_map(|src| Self::resolve_link(url, src.trim(), site_root).ok())
+            .filter(|frame_url| matches!(frame_url.scheme(), "http" | "https" | "file"))
+            .collect();
+        page_links.script_links = self.script_links(document, url, site_root);
+        page_links
+    }
+
+    /// Resolves absolute, root-relative and relative links alike.
+    pub fn resolve_link(
+        url: &Url,
+        link: &str,
+        site_root: Option<&Url>,
+    ) -> Result<Url, url::ParseError> {
+        match site_root {
+            Some(site_root) if link.starts_with('/') && !link.starts_with("//") => {
+                site_root.join(&link[1..])
+            }
+            _ => url.join(link),
+        }
+    }
+
+    /// Resolves the href of a link to follow, or returns None for fragment, mailto,
+    /// javascript and tel links. Fragment links to app routes are followed if enabled.
+    pub fn resolve_href(&self, url: &Url, href: &str, site_root: Option<&Url>) -> Option<Url> {
+        if let Some(fragment) = href.strip_prefix('#') {
+            if !(self.route_fragments && UrlNormalizer::is_route_fragment(fragment)) {
+                return None;
+            }
+        }
+        if ["mailto:", "javascript:", "tel:"].iter().any(|prefix| href.starts_with(prefix)) {
+            return None;
+        }
+        Self::resolve_link(url, href, site_root).ok()
+    }
+
+    fn add_anchor_links(
+        &self,
+        document: &Html,
+        url_to_crawl: &Url,
+        site_root: Option<&Url>,
+        page_links: &mut PageLinks,
+    ) {
+        let mut discovered_urls: HashSet<Url> = HashSet::new();
+        let mut link_anchors: BTreeSet<LinkAnchor> = BTreeSet::new();
+        for element in document.select(&self.link_selector) {
+            if let Some(link) = element.value().attr("href") {
+                let Some(url) = self.resolve_href(url_to_crawl, link, site_root) else {
+                    continue;
+                };
+                if PageCrawler::is_internal(&url, url_to_crawl) {
+                    link_anchors.insert(LinkAnchor {
+                        url: url.clone(),
+                        anchor_text: self.anchor_text(element),
+                    });
+                }
+                discovered_urls.insert(url);
+            }
+        }
+
+        for discovered_url in discovered_urls {
+            if PageCrawler::is_internal(&discovered_url, url_to_crawl) {
+                page_links.internal_links.push(discovered_url);
+            } else {
+                page_links.outgoing_links.push(discovered_url);
+            }
+        }
+        page_links.internal_link_anchors = link_anchors.into_iter().collect();
+    }
+
+    /// Canonical, AMP, hreflang, feed and pagination links from `<link rel>` elements.
+    fn add_link_rels(
+        &self,
+        document: &Html,
+        url_to_crawl: &Url,
+        site_root: Option<&Url>,
+        page_links: &mut PageLinks,
+    ) {
+        for element in document.select(&self.link_rel_selector) {
+            let attr = |name: &str| element.value().attr(name);
+            Self::add_link_rel(attr, url_to_crawl, site_root, page_links);
+        }
+    }
+
+    /// Adds a `<link rel>` element, given a lookup of its attributes, to the links it names.
+    pub fn add_link_rel<'a>(
+        attr: impl Fn(&str) -> Option<&'a str>,
+        url_to_crawl: &Url,
+        site_root: Option<&Url>,
+        page_links: &mut PageLinks,
+    ) {
+        let rels = attr("rel").unwrap_or_default();
+        let href = attr("href").unwrap_or_default();
+        let Ok(link_url) = Self::resolve_link(url_to_crawl, href, site_root) else {
+            return;
+        };
+        let has_rel = |name: &str| {
+            rels.split_ascii_whitespace().any(|rel| rel.eq_ignore_ascii_case(name))
+        };
+        if has_rel("canonical") && page_links.canonical.is_none() {
+            page_links.canonical = Some(link_url.clone());
+        }
+        if has_rel("amphtml") && page_links.amp_url.is_none() {
+            page_links.amp_url = Some(link_url.clone());
+        }
+        let alternate = rels.eq_ignore_ascii_case("alternate");
+        match attr("hreflang") {
+            Some(hreflang) if alternate => {
+                page_links.hreflang_alternates.push(HreflangAlternate {
+                    hreflang: hreflang.trim().to_string(),
+                    url: link_url,
+                });
+            }
+            _ if alternate && attr("type").is_some_and(FeedLoader::is_feed_type) => {
+                page_links.feeds.push(link_url);
+            }
+            _ => page_links.pagination.add_link(rels, link_url),
+        }
+    }
+
+    /// Navigation targets of onclick handlers and data-href/data-url attributes.
+    fn script_links(
+        &self,
+        document: &Html,
+        url_to_crawl: &Url,
+        site_root: Option<&Url>,
+    ) -> Vec<Url> {
+        let mut script_links: BTreeSet<Url> = BTreeSet::new();
+        for element in document.select(&self.script_link_selector) {
+            let onclick_target = element
+                .value()
+                .attr("onclick")
+                .and_then(ScriptLinks::onclick_target);
+            let attribute_targets = ScriptLinks::URL_ATTRIBUTES
+                .iter()
+                .filter_map(|attribute| element.value().attr(attribute))
+                .map(str::trim);
+            for target in onclick_target.into_iter().chain(attribute_targets) {
+                if target.is_empty() || target.starts_with('#') {
+                    continue;
+                }
+                if let Ok(link_url) = Self::resolve_link(url_to_crawl, target, site_root) {
+                    if matches!(link_url.scheme(), "http" | "https" | "file") {
+                        script_links.insert(link_url);
+                    }
+                }
+            }
+        }
+        script_links.into_iter().collect()
+    }
+
+    /// The text a link is shown as: its visible text, or failing that the alt text of an image
+    /// inside it or its aria-label.
+    fn anchor_text(&self, link: ElementRef) -> String {
+        let text = link.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            return text;
+        }
+        link.select(&self.anchor_image_selector)
+            .filter_map(|img| img.value().attr("alt"))
+            .chain(link.value().attr("aria-label"))
+            .map(str::trim)
+            .find(|alt| !alt.is_empty())
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+impl Default for LinkExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_elements_matching_the_link_selector() {
+        let document = Html::parse_document(
+            r#"<a href="/a"><img alt="Logo"></a>
+            <map><area href="/b"></map>
+            <a href="mailto:x@example.com">Mail</a>"#,
+        );
+        let url = Url::parse("https://example.com/").unwrap();
+
+        let page_links = LinkExtractor::new().extract(&document, &url, None);
+        assert_eq!(page_links.internal_links, vec![url.join("/a").unwrap()]);
+        assert_eq!(page_links.internal_link_anchors[0].anchor_text, "Logo");
+
+        let link_extractor =
+            LinkExtractor::new().with_link_selector("a[href], area[href]").unwrap();
+        let mut internal_links = link_extractor.extract(&document, &url, None).internal_links;
+        internal_links.sort();
+        assert_eq!(internal_links, vec![url.join("/a").unwrap(), url.join("/b").unwrap()]);
+        assert!(LinkExtractor::new().with_link_selector("a[").is_err());
+    }
+}
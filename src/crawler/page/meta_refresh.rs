@@ -0,0 +1,60 @@
+/// Parses the `content` of a `<meta http-equiv="refresh">` tag.
+pub struct MetaRefresh;
+
+impl MetaRefresh {
+    /// The URL a refresh sends the reader to, as written, e.g. "/new" for `0; url='/new'`.
+    /// None for a refresh that only reloads the page.
+    pub fn target(content: &str) -> Option<&str> {
+        let content = content.trim();
+        let delay_end = content
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(content.len());
+        if delay_end == 0 {
+            return None;
+        }
+        let rest = content[delay_end..].trim_start();
+        let rest = rest
+            .strip_prefix(';')
+            .or_else(|| rest.strip_prefix(','))
+            .unwrap_or(rest)
+            .trim_start();
+        let target = match rest.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+                let after_url = rest[3..].trim_start();
+                match after_url.strip_prefix('=') {
+                    Some(after_equals) => after_equals.trim_start(),
+                    None => rest,
+                }
+            }
+            _ => rest,
+        };
+        let target = match target.chars().next() {
+            Some(quote @ ('\'' | '"')) => {
+                let unquoted = &target[1..];
+                unquoted.find(quote).map_or(unquoted, |end| &unquoted[..end])
+            }
+            _ => target,
+        };
+        let target = target.trim();
+        (!target.is_empty()).then_some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_target_of_a_refresh() {
+        assert_eq!(MetaRefresh::target("0;url=/new"), Some("/new"));
+        assert_eq!(
+            MetaRefresh::target(" 5 ; URL = 'https://example.com/a b' "),
+            Some("https://example.com/a b")
+        );
+        assert_eq!(MetaRefresh::target("0, url=\"next.html\""), Some("next.html"));
+        assert_eq!(MetaRefresh::target("0; https://example.com/"), Some("https://example.com/"));
+        assert_eq!(MetaRefresh::target("30"), None);
+        assert_eq!(MetaRefresh::target("0; url="), None);
+        assert_eq!(MetaRefresh::target("url=/new"), None);
+    }
+}
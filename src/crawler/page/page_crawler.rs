@@ -1,49 +1,351 @@
+use crate::crawler::accessibility::AccessibilityIssues;
+use crate::crawler::content::{ContentHandlers, Document, ParseOptions};
 use crate::crawler::crawl_error::CrawlError;
-use crate::crawler::crawl_response::CrawlResponse;
-use anyhow::anyhow;
-use std::collections::HashSet;
+use crate::crawler::crawl_response::{CrawlResponse, NO_TITLE};
+use crate::crawler::extract::ExtractRule;
+use crate::crawler::fetch::Fetcher;
+use crate::crawler::http::{BodyTooLarge, CacheHeaders, ContentDecoder, SecurityHeaders};
+use crate::crawler::page::meta_refresh::MetaRefresh;
+use crate::crawler::page::{FetchedPage, LinkExtractor, ResponseAction, ResponseMiddleware};
+use crate::crawler::pagination::Pagination;
+use crate::crawler::plugin::WasmPlugin;
+use crate::crawler::robots::{ROBOTS_AGENT, RobotsDirectives};
+use crate::crawler::schema::StructuredData;
+use crate::crawler::script::CrawlScript;
+use reqwest::header::{HeaderMap, HeaderName};
+use scraper::Selector;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
-pub struct PageCrawler {}
+// Selectors are compiled once rather than for every parsed page
+static TITLE: LazyLock<Selector> = LazyLock::new(|| Selector::parse("title").unwrap());
+static META: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("meta[name][content]").unwrap());
+static META_HTTP_EQUIV: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("meta[http-equiv][content]").unwrap());
+static H1: LazyLock<Selector> = LazyLock::new(|| Selector::parse("h1").unwrap());
+static BODY: LazyLock<Selector> = LazyLock::new(|| Selector::parse("body").unwrap());
+
+/// Elements loading an asset, and the attribute holding its URL.
+static ASSET_ATTRIBUTES: LazyLock<[(Selector, &str); 3]> = LazyLock::new(|| {
+    let selector = |selector: &str| Selector::parse(selector).unwrap();
+    [
+        (
+            selector(
+                "img[src], script[src], iframe[src], frame[src], embed[src], source[src], \
+                 video[src], audio[src], track[src], input[type=image][src]",
+            ),
+            "src",
+        ),
+        (selector("video[poster]"), "poster"),
+        (selector("object[data]"), "data"),
+    ]
+});
+static SRCSET: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("img[srcset], source[srcset]").unwrap());
+static LINK_REL: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("link[rel][href]").unwrap());
+
+#[derive(Clone)]
+pub struct PageCrawler {
+    fetcher: Arc<dyn Fetcher>,
+    content_handlers: Arc<ContentHandlers>,
+    parse_options: ParseOptions,
+    cancel_token: CancellationToken,
+    page_timeout: Option<Duration>,
+    max_body_size: Option<u64>,
+    response_middleware: Vec<Arc<dyn ResponseMiddleware>>,
+    crawl_script: Option<Arc<CrawlScript>>,
+    plugins: Vec<Arc<WasmPlugin>>,
+    captured_headers: Vec<HeaderName>,
+}
 
 impl PageCrawler {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(fetcher: Arc<dyn Fetcher>) -> Self {
+        Self {
+            fetcher,
+            content_handlers: Arc::new(ContentHandlers::default()),
+            parse_options: ParseOptions::default(),
+            cancel_token: CancellationToken::new(),
+            page_timeout: None,
+            max_body_size: None,
+            response_middleware: Vec::new(),
+            crawl_script: None,
+            plugins: Vec::new(),
+            captured_headers: Vec::new(),
+        }
+    }
+
+    /// Abandons the request in flight with `CrawlError::Cancelled` once `cancel_token` is
+    /// cancelled.
+    pub fn with_cancel_token(mut self, cancel_token: CancellationToken) -> Self {
+        self.cancel_token = cancel_token;
+        self
+    }
+
+    /// Resolves root-relative links ("/about/") against `site_root` rather than the URL's
+    /// own root. Used for local site builds, whose root is a directory, not "file:///".
+    pub fn with_site_root(mut self, site_root: Option<Url>) -> Self {
+        self.parse_options.site_root = site_root;
+        self
+    }
+
+    pub fn with_extract_rules(mut self, extract_rules: Vec<ExtractRule>) -> Self {
+        self.parse_options.extract_rules = extract_rules;
+        self
+    }
+
+    pub fn with_accessibility_checks(mut self, accessibility_checks: bool) -> Self {
+        self.parse_options.accessibility_checks = accessibility_checks;
+        self
+    }
+
+    /// Finds the links of HTML pages with `link_extractor` instead of the default one.
+    pub fn with_link_extractor(mut self, link_extractor: Arc<LinkExtractor>) -> Self {
+        self.parse_options.link_extractor = link_extractor;
+        self
     }
-    
+
+    /// Parses HTML pages with `StreamingHtmlParser`, which skips building a DOM.
+    pub fn with_streaming_html(mut self, streaming_html: bool) -> Self {
+        self.parse_options.streaming_html = streaming_html;
+        self
+    }
+
+    /// Parses documents with `content_handlers`, chosen by their MIME type. Only HTML pages
+    /// are parsed by default.
+    pub fn with_content_handlers(mut self, content_handlers: ContentHandlers) -> Self {
+        self.content_handlers = Arc::new(content_handlers);
+        self
+    }
+
+    /// Pages that take longer than `page_timeout` to fetch and parse are abandoned.
+    pub fn with_page_timeout(mut self, page_timeout: Option<Duration>) -> Self {
+        self.page_timeout = page_timeout;
+        self
+    }
+
+    /// Passes every fetched page through `response_middleware`, in order, before parsing it.
+    pub fn with_response_middleware(
+        mut self,
+        response_middleware: Vec<Arc<dyn ResponseMiddleware>>,
+    ) -> Self {
+        self.response_middleware = response_middleware;
+        self
+    }
+
+    /// Adds the fields returned by the `on_page` callback of `crawl_script` to each parsed
+    /// page's extracted data. A failing callback skips the page.
+    pub fn with_crawl_script(mut self, crawl_script: Option<Arc<CrawlScript>>) -> Self {
+        self.crawl_script = crawl_script;
+        self
+    }
+
+    /// Adds the output of each of `plugins`, in order, to each parsed page's extracted data. A
+    /// failing plugin skips the page.
+    pub fn with_plugins(mut self, plugins: Vec<Arc<WasmPlugin>>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Bodies that decode to more than `max_body_size` bytes are abandoned as too large.
+    pub fn with_max_body_size(mut self, max_body_size: Option<u64>) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Records the response headers named in `captured_headers` for each parsed page.
+    pub fn with_captured_headers(mut self, captured_headers: Vec<HeaderName>) -> Self {
+        self.captured_headers = captured_headers;
+        self
+    }
+
+    /// Fetches a page and parses it with the content handler for its type. Parsing runs on
+    /// the blocking thread pool, so large documents don't hold up the runtime's other tasks.
+    /// Gives up on pages that take longer than `page_timeout` to fetch and parse, with
+    /// `CrawlError::Timeout`. A parse already running finishes in the background.
     pub async fn crawl(&self, url: &Url) -> Result<CrawlResponse, CrawlError> {
-        let url_to_crawl = url;
+        match self.page_timeout {
+            Some(page_timeout) => tokio::time::timeout(page_timeout, self.fetch_and_parse(url))
+                .await
+                .unwrap_or(Err(CrawlError::Timeout)),
+            None => self.fetch_and_parse(url).await,
+        }
+    }
 
-        let crawl_response = reqwest::get(url_to_crawl.clone()).await?;
-        if !crawl_response.status().is_success() {
-            return Err(CrawlError::HttpError(crawl_response.status().as_u16()));
+    async fn fetch_and_parse(&self, url: &Url) -> Result<CrawlResponse, CrawlError> {
+        let fetched_page = tokio::select! {
+            fetched_page = self.fetch(url) => fetched_page?,
+            _ = self.cancel_token.cancelled() => return Err(CrawlError::Cancelled),
+        };
+        let page_crawler = self.clone();
+        tokio::task::spawn_blocking(move || page_crawler.parse(&fetched_page))
+            .await
+            .map_err(anyhow::Error::from)?
+    }
+
+    /// The fetch phase: requests a page, undoes its Content-Encoding and runs the response
+    /// middleware over it. Unsuccessful responses and skipped pages are errors.
+    pub async fn fetch(&self, url: &Url) -> Result<FetchedPage, CrawlError> {
+        let fetch_result = self.fetcher.fetch(url).await?;
+        let status_code = fetch_result.status_code;
+        // The HTTP client only hands back redirects it was told not to follow
+        let location = fetch_result
+            .headers
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| url.join(location).ok());
+        if let Some(location) = location.filter(|_| (300..400).contains(&status_code)) {
+            return Err(CrawlError::Redirect(status_code, location));
         }
-        let status_code = crawl_response.status().as_u16();
+        if !(200..300).contains(&status_code) {
+            return Err(CrawlError::HttpError(status_code));
+        }
+        let content_encoding = fetch_result
+            .headers
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| v != "identity");
+        // A body that can't be decoded only loses this page, not the crawl
+        let body = ContentDecoder::decode_content(
+            &fetch_result.body,
+            content_encoding.as_deref(),
+            self.max_body_size,
+        )
+        .map_err(|e| {
+            if e.is::<BodyTooLarge>() {
+                CrawlError::TooLarge(status_code)
+            } else {
+                CrawlError::Skipped(status_code, format!("{:#}", e))
+            }
+        })?;
+        let mut fetched_page = FetchedPage {
+            url: url.clone(),
+            status_code,
+            http_version: fetch_result.http_version,
+            headers: fetch_result.headers,
+            content_encoding,
+            transferred_bytes: fetch_result.body.len(),
+            body,
+            render_error: fetch_result.render_error,
+        };
+        for response_middleware in &self.response_middleware {
+            let action = response_middleware.process(&mut fetched_page).await?;
+            if let ResponseAction::Skip(reason) = action {
+                return Err(CrawlError::Skipped(status_code, reason));
+            }
+        }
+        Ok(fetched_page)
+    }
 
-        let content_type_str = crawl_response
-            .headers()
+    /// The parse phase: parses a fetched page with the content handler for its type, adding
+    /// what its headers say about it.
+    pub fn parse(&self, fetched_page: &FetchedPage) -> Result<CrawlResponse, CrawlError> {
+        let url_to_crawl = &fetched_page.url;
+        let status_code = fetched_page.status_code;
+        let headers = &fetched_page.headers;
+
+        let content_type_str = headers
             .get(reqwest::header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
             .unwrap_or("unknown")
             .to_string();
-        let content_type: mime::Mime = content_type_str.clone().parse()?;
-        match (content_type.type_(), content_type.subtype()) {
-            (mime::TEXT, mime::HTML) => {}
-            _ => {
-                println!("Skipping non-HTML content type: {}", content_type);
-                return Err(CrawlError::AnyError(anyhow!(
-                    "Skipping non-HTML content type: {}",
-                    content_type
-                )));
+        let content_type: mime::Mime = content_type_str
+            .parse()
+            .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+        let content_handler = self.content_handlers.handler_for(&content_type);
+
+        let mut header_pagination = Pagination::default();
+        for header_value in headers.get_all(reqwest::header::LINK) {
+            if let Ok(header_value) = header_value.to_str() {
+                header_pagination.add_link_header(header_value, url_to_crawl);
+            }
+        }
+
+        let mut header_directives = RobotsDirectives::default();
+        for header_value in headers.get_all("x-robots-tag") {
+            if let Ok(header_value) = header_value.to_str() {
+                header_directives.add_header_value(header_value, ROBOTS_AGENT);
+            }
+        }
+
+        let document = Document {
+            url: url_to_crawl,
+            status_code,
+            content_type: &content_type_str,
+            mime_type: &content_type,
+            body: &fetched_page.body,
+        };
+        let Some(mut result) = content_handler.parse(&document, &self.parse_options) else {
+            return Err(CrawlError::UnsupportedContentType(
+                status_code,
+                content_type_str,
+                CacheHeaders::from_headers(headers),
+            ));
+        };
+        result.http_version = fetched_page.http_version.clone();
+        result.content_encoding = fetched_page.content_encoding.clone();
+        result.security_headers = SecurityHeaders::from_headers(headers);
+        result.cache_headers = CacheHeaders::from_headers(headers);
+        result.captured_headers = self.capture_headers(headers);
+        result.transferred_bytes = fetched_page.transferred_bytes;
+        result.body_bytes = fetched_page.body.len();
+        result.content_hash = Some(format!("{:x}", Sha256::digest(&fetched_page.body)));
+        result.render_error = fetched_page.render_error.clone();
+        result.robots_directives.noindex |= header_directives.noindex;
+        result.robots_directives.nofollow |= header_directives.nofollow;
+        if result.robots_directives.unavailable_after.is_none() {
+            result.robots_directives.unavailable_after = header_directives.unavailable_after;
+        }
+        // Links in the document take precedence over the Link header
+        if result.pagination.next.is_none() {
+            result.pagination.next = header_pagination.next;
+        }
+        if result.pagination.prev.is_none() {
+            result.pagination.prev = header_pagination.prev;
+        }
+        if let Some(crawl_script) = &self.crawl_script {
+            let html = String::from_utf8_lossy(&fetched_page.body);
+            match crawl_script.on_page(url_to_crawl, &html) {
+                Ok(fields) => result.extracted.extend(fields),
+                Err(e) => {
+                    let reason = format!("on_page failed: {:#}", e);
+                    return Err(CrawlError::Skipped(status_code, reason));
+                }
+            }
+        }
+        for plugin in &self.plugins {
+            match plugin.process(url_to_crawl, &fetched_page.body) {
+                Ok(fields) => result.extracted.extend(fields),
+                Err(e) => {
+                    let reason = format!("plugin {} failed: {:#}", plugin.path().display(), e);
+                    return Err(CrawlError::Skipped(status_code, reason));
+                }
             }
         }
+        Ok(result)
+    }
 
-        let html_text = crawl_response.text().await?;
-        let document = scraper::Html::parse_document(html_text.as_str());
+    /// Extracts the title and links from an HTML document fetched from `url`, and checks it
+    /// for accessibility problems if `options.accessibility_checks` is set.
+    pub fn parse_html(
+        url: &Url,
+        status_code: u16,
+        content_type: String,
+        html_text: String,
+        options: &ParseOptions,
+    ) -> CrawlResponse {
+        let url_to_crawl = url;
+        let site_root = options.site_root.as_ref();
+        let document = scraper::Html::parse_document(&html_text);
 
         let title = {
-            let title_selector = scraper::Selector::parse("title").unwrap();
-            if let Some(title_element) = document.select(&title_selector).next() {
+            if let Some(title_element) = document.select(&TITLE).next() {
                 let title = title_element.inner_html();
                 Some(title)
             } else {
@@ -51,55 +353,217 @@ impl PageCrawler {
             }
         };
 
-        let mut discovered_urls: HashSet<Url> = HashSet::new();
-        let link_selector = scraper::Selector::parse("a[href]").unwrap();
-        for element in document.select(&link_selector) {
-            if let Some(link) = element.value().attr("href") {
-                let url = {
-                    if link.starts_with("/") {
-                        let mut new_url = url_to_crawl.clone();
-                        new_url.set_path(link);
-                        new_url
-                    } else if link.starts_with("#") {
-                        continue; // Ignore fragment links
-                    } else if link.starts_with("mailto:") {
-                        continue; // Ignore mailto links
-                    } else if link.starts_with("javascript:") {
-                        continue; // Ignore javascript links
-                    } else if link.starts_with("tel:") {
-                        continue; // Ignore tel links
-                    } else {
-                        if let Ok(link_url) = Url::parse(link) {
-                            link_url
-                        } else {
-                            continue;
-                        }
-                    }
-                };
-                discovered_urls.insert(url);
+        let mut robots_directives = RobotsDirectives::default();
+        let mut meta_description = None;
+        {
+            for element in document.select(&META) {
+                let name = element.value().attr("name").unwrap_or_default();
+                let content = element.value().attr("content").unwrap_or_default();
+                if name.eq_ignore_ascii_case("robots") || name.eq_ignore_ascii_case(ROBOTS_AGENT) {
+                    robots_directives.add_directives(content);
+                } else if name.eq_ignore_ascii_case("description") && meta_description.is_none() {
+                    meta_description = Some(content.trim().to_string());
+                }
             }
         }
 
-        let mut external_urls: Vec<Url> = Vec::new();
-        let mut internal_urls: Vec<Url> = Vec::new();
-        for discovered_url in discovered_urls {
-            if discovered_url.has_host()
-                && discovered_url.host().unwrap() == url_to_crawl.host().unwrap()
-            {
-                internal_urls.push(discovered_url);
-            } else {
-                external_urls.push(discovered_url);
-            }
-        }
+        let meta_refresh = {
+            document
+                .select(&META_HTTP_EQUIV)
+                .filter(|element| {
+                    let http_equiv = element.value().attr("http-equiv").unwrap_or_default();
+                    http_equiv.trim().eq_ignore_ascii_case("refresh")
+                })
+                .filter_map(|element| MetaRefresh::target(element.value().attr("content")?))
+                .find_map(|target| {
+                    LinkExtractor::resolve_link(url_to_crawl, target, site_root).ok()
+                })
+        };
+
+        let num_h1 = document.select(&H1).count();
+
+        let assets = Self::asset_urls(&document, url_to_crawl, site_root);
+
+        let body_text = Self::visible_text(&document);
 
-        let result = CrawlResponse {
+        let extracted = options
+            .extract_rules
+            .iter()
+            .filter_map(|rule| Some((rule.name.clone(), rule.extract(&document)?)))
+            .collect::<BTreeMap<_, _>>();
+        let structured_data = StructuredData::from_document(&document);
+        let accessibility =
+            options.accessibility_checks.then(|| AccessibilityIssues::from_document(&document));
+
+        let page_links = options.link_extractor.extract(&document, url_to_crawl, site_root);
+
+        // Browsers block or warn about http:// resources on an https:// page
+        let mixed_content = if url_to_crawl.scheme() == "https" {
+            assets
+                .iter()
+                .chain(&page_links.internal_links)
+                .chain(&page_links.outgoing_links)
+                .filter(|reference| reference.scheme() == "http")
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let body_bytes = html_text.len();
+        CrawlResponse {
             url: url_to_crawl.clone(),
             status_code,
-            content_type: content_type_str,
-            title: title.unwrap_or_else(|| "No title".to_string()),
-            outgoing_links: external_urls,
-            internal_links: internal_urls,
+            content_type,
+            title: title.unwrap_or_else(|| NO_TITLE.to_string()),
+            outgoing_links: page_links.outgoing_links,
+            internal_links: page_links.internal_links,
+            internal_link_anchors: page_links.internal_link_anchors,
+            body: html_text,
+            body_text,
+            meta_description,
+            meta_refresh,
+            num_h1,
+            num_pages: None,
+            robots_directives,
+            pagination: page_links.pagination,
+            hreflang_alternates: page_links.hreflang_alternates,
+            feeds: page_links.feeds,
+            canonical: page_links.canonical,
+            amp_url: page_links.amp_url,
+            frames: page_links.frames,
+            script_links: page_links.script_links,
+            mixed_content,
+            extracted,
+            structured_data,
+            accessibility,
+            http_version: None,
+            content_encoding: None,
+            security_headers: SecurityHeaders::default(),
+            cache_headers: CacheHeaders::default(),
+            captured_headers: BTreeMap::new(),
+            transferred_bytes: body_bytes,
+            body_bytes,
+            content_hash: None,
+            render_error: None,
+        }
+    }
+
+    /// The values of the captured headers present in `headers`, joined with ", " if repeated.
+    fn capture_headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        let mut captured_headers = BTreeMap::new();
+        for name in &self.captured_headers {
+            let values = headers
+                .get_all(name)
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect::<Vec<_>>();
+            if !values.is_empty() {
+                captured_headers.insert(name.to_string(), values.join(", "));
+            }
+        }
+        captured_headers
+    }
+
+    /// Whether a link from the page at `page_url` stays on the same site.
+    pub fn is_internal(link_url: &Url, page_url: &Url) -> bool {
+        // file:// URLs have no host, so any other local file is internal
+        link_url.host() == page_url.host()
+            && (link_url.scheme() == "file") == (page_url.scheme() == "file")
+    }
+
+    /// URLs of the images, scripts, stylesheets, media and embedded objects a page loads.
+    fn asset_urls(document: &scraper::Html, url: &Url, site_root: Option<&Url>) -> Vec<Url> {
+        let mut assets: BTreeSet<Url> = BTreeSet::new();
+        let mut add_asset = |reference: &str| {
+            let reference = reference.trim();
+            if reference.is_empty() || reference.starts_with("data:") {
+                return;
+            }
+            if let Ok(asset_url) = LinkExtractor::resolve_link(url, reference, site_root) {
+                assets.insert(asset_url);
+            }
         };
-        Ok(result)
+        for (selector, attribute) in ASSET_ATTRIBUTES.iter() {
+            for element in document.select(selector) {
+                add_asset(element.value().attr(attribute).unwrap_or_default());
+            }
+        }
+        // Each srcset candidate is a URL followed by an optional width or density
+        for element in document.select(&SRCSET) {
+            let srcset = element.value().attr("srcset").unwrap_or_default();
+            for candidate in srcset.split(',') {
+                add_asset(candidate.split_whitespace().next().unwrap_or_default());
+            }
+        }
+        for element in document.select(&LINK_REL) {
+            let rels = element.value().attr("rel").unwrap_or_default();
+            let loads_resource = rels.split_whitespace().any(|rel| {
+                ["stylesheet", "icon", "preload", "modulepreload", "manifest", "apple-touch-icon"]
+                    .iter()
+                    .any(|resource_rel| rel.eq_ignore_ascii_case(resource_rel))
+            });
+            if loads_resource {
+                add_asset(element.value().attr("href").unwrap_or_default());
+            }
+        }
+        assets.into_iter().collect()
+    }
+
+    /// Collects the text a reader would see in the body, skipping scripts and styles.
+    fn visible_text(document: &scraper::Html) -> String {
+        let Some(body) = document.select(&BODY).next() else {
+            return String::new();
+        };
+        let mut words: Vec<&str> = Vec::new();
+        for node in body.descendants() {
+            let Some(text) = node.value().as_text() else {
+                continue;
+            };
+            let hidden = node.ancestors().any(|ancestor| {
+                ancestor.value().as_element().is_some_and(|element| {
+                    matches!(element.name(), "script" | "style" | "noscript" | "template")
+                })
+            });
+            if !hidden {
+                words.extend(text.split_whitespace());
+            }
+        }
+        words.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_http_assets_and_links_on_https_pages_as_mixed_content() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="http://cdn.example.com/site.css">
+            <script src="https://cdn.example.com/app.js"></script>
+            </head><body>
+            <img src="/logo.png" srcset="http://cdn.example.com/logo-2x.png 2x">
+            <a href="http://example.com/old">Old</a>
+            <a href="/new">New</a>
+            </body></html>"#;
+        let parse = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            let html = html.to_string();
+            let options = ParseOptions::default();
+            PageCrawler::parse_html(&url, 200, "text/html".to_string(), html, &options)
+        };
+        let mixed_content = parse("https://example.com/").mixed_content;
+        assert_eq!(
+            mixed_content.iter().map(Url::as_str).collect::<Vec<_>>(),
+            vec![
+                "http://cdn.example.com/logo-2x.png",
+                "http://cdn.example.com/site.css",
+                "http://example.com/old",
+            ]
+        );
+        assert!(parse("http://example.com/").mixed_content.is_empty());
     }
-}
\ No newline at end of file
+}
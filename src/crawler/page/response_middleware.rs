@@ -0,0 +1,144 @@
+use crate::crawler::page::FetchedPage;
+use futures::future::BoxFuture;
+use scraper::{Html, Selector};
+
+/// What becomes of a page once a response middleware has seen it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseAction {
+    /// Go on to the next middleware, then parse the page.
+    Continue,
+    /// Record the page without parsing it, for the given reason.
+    Skip(String),
+}
+
+/// Observes or changes each successfully fetched page before it is parsed, e.g. to strip
+/// markup, decrypt the body or note custom headers, and may keep the page from being parsed.
+/// Middleware runs in the order it was added to the crawler config, on the body with its
+/// Content-Encoding undone; an error fails the page.
+pub trait ResponseMiddleware: Send + Sync {
+    fn process<'a>(
+        &'a self,
+        fetched_page: &'a mut FetchedPage,
+    ) -> BoxFuture<'a, anyhow::Result<ResponseAction>>;
+}
+
+/// Removes the elements matching any of its selectors from HTML pages, e.g. cookie banners,
+/// so that their text and links are left out of the results. From `--strip-selector`.
+pub struct StripElements {
+    selectors: Vec<Selector>,
+}
+
+impl StripElements {
+    pub fn new(selectors: Vec<Selector>) -> Self {
+        Self { selectors }
+    }
+
+    fn strip(&self, fetched_page: &mut FetchedPage) {
+        let is_html = fetched_page
+            .headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.contains("html"));
+        if !is_html {
+            return;
+        }
+        let mut document = Html::parse_document(&String::from_utf8_lossy(&fetched_page.body));
+        let node_ids = self
+            .selectors
+            .iter()
+            .flat_map(|selector| document.select(selector).map(|element| element.id()))
+            .collect::<Vec<_>>();
+        // Pages without a match are left byte for byte as they were
+        if node_ids.is_empty() {
+            return;
+        }
+        for node_id in node_ids {
+            if let Some(mut node) = document.tree.get_mut(node_id) {
+                node.detach();
+            }
+        }
+        fetched_page.body = document.html().into_bytes();
+    }
+}
+
+impl ResponseMiddleware for StripElements {
+    fn process<'a>(
+        &'a self,
+        fetched_page: &'a mut FetchedPage,
+    ) -> BoxFuture<'a, anyhow::Result<ResponseAction>> {
+        self.strip(fetched_page);
+        Box::pin(async { Ok(ResponseAction::Continue) })
+    }
+}
+
+/// Skips pages whose body contains any of its texts, e.g. a CAPTCHA or login wall served
+/// in place of the content. From `--skip-if-contains`.
+pub struct SkipIfContains {
+    texts: Vec<String>,
+}
+
+impl SkipIfContains {
+    pub fn new(texts: Vec<String>) -> Self {
+        Self { texts }
+    }
+}
+
+impl ResponseMiddleware for SkipIfContains {
+    fn process<'a>(
+        &'a self,
+        fetched_page: &'a mut FetchedPage,
+    ) -> BoxFuture<'a, anyhow::Result<ResponseAction>> {
+        let body = String::from_utf8_lossy(&fetched_page.body);
+        let action = match self.texts.iter().find(|text| body.contains(text.as_str())) {
+            Some(text) => ResponseAction::Skip(format!("contains {:?}", text)),
+            None => ResponseAction::Continue,
+        };
+        Box::pin(async { Ok(action) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+    use url::Url;
+
+    fn html_page(body: &str) -> FetchedPage {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+        FetchedPage {
+            url: Url::parse("https://example.com/").unwrap(),
+            status_code: 200,
+            http_version: None,
+            headers,
+            content_encoding: None,
+            transferred_bytes: body.len(),
+            body: body.as_bytes().to_vec(),
+            render_error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn strips_matching_elements_and_skips_matching_pages() {
+        let strip_elements = StripElements::new(vec![Selector::parse("#consent").unwrap()]);
+        let mut page = html_page(
+            "<html><body><div id=\"consent\">Accept cookies</div><p>Content</p></body></html>",
+        );
+        let action = strip_elements.process(&mut page).await.unwrap();
+        assert_eq!(action, ResponseAction::Continue);
+        let body = String::from_utf8(page.body.clone()).unwrap();
+        assert!(!body.contains("Accept cookies"));
+        assert!(body.contains("<p>Content</p>"));
+
+        let skip_if_contains = SkipIfContains::new(vec!["captcha".to_string()]);
+        assert_eq!(
+            skip_if_contains.process(&mut page).await.unwrap(),
+            ResponseAction::Continue
+        );
+        let mut challenge = html_page("<html><body>Solve the captcha</body></html>");
+        assert_eq!(
+            skip_if_contains.process(&mut challenge).await.unwrap(),
+            ResponseAction::Skip("contains \"captcha\"".to_string())
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Navigation in an onclick handler: assigning to `location` or `location.href`, or calling
+/// `location.assign()`, `location.replace()` or `window.open()` with a string literal.
+static ONCLICK_NAVIGATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:location(?:\.href)?\s*=|location\.(?:assign|replace)\s*\(|window\.open\s*\()\s*(?:'([^']*)'|"([^"]*)")"#,
+    )
+    .unwrap()
+});
+
+/// Finds the URLs that navigation hidden behind JavaScript goes to, without running it.
+pub struct ScriptLinks;
+
+impl ScriptLinks {
+    /// Attributes that scripts commonly read a link's URL from.
+    pub const URL_ATTRIBUTES: [&str; 2] = ["data-href", "data-url"];
+
+    /// The URL an onclick handler navigates to, e.g. "/next" for `location.href='/next'`.
+    pub fn onclick_target(onclick: &str) -> Option<&str> {
+        let captures = ONCLICK_NAVIGATION.captures(onclick)?;
+        let target = captures.get(1).or_else(|| captures.get(2))?.as_str().trim();
+        (!target.is_empty()).then_some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_where_onclick_handlers_navigate() {
+        assert_eq!(ScriptLinks::onclick_target("location.href='/next'"), Some("/next"));
+        assert_eq!(
+            ScriptLinks::onclick_target("track(); window.location = \"/a?b=1\"; return false;"),
+            Some("/a?b=1")
+        );
+        assert_eq!(ScriptLinks::onclick_target("location.assign( '/c' )"), Some("/c"));
+        assert_eq!(
+            ScriptLinks::onclick_target("window.open('https://example.com/')"),
+            Some("https://example.com/")
+        );
+        assert_eq!(ScriptLinks::onclick_target("toggleMenu()"), None);
+        assert_eq!(ScriptLinks::onclick_target("location.href = url"), None);
+    }
+}
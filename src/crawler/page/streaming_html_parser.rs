@@ -0,0 +1,294 @@
+use crate::crawler::content::ParseOptions;
+use crate::crawler::crawl_response::{CrawlResponse, NO_TITLE};
+use crate::crawler::link_anchor::LinkAnchor;
+use crate::crawler::page::meta_refresh::MetaRefresh;
+use crate::crawler::page::link_extractor::PageLinks;
+use crate::crawler::page::{LinkExtractor, PageCrawler};
+use crate::crawler::robots::ROBOTS_AGENT;
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use url::Url;
+
+/// Extracts the title, links, robots directives and text of HTML pages from a single pass of
+/// the HTML tokenizer, without building a DOM. Much cheaper than `PageCrawler::parse_html` on
+/// large pages, but only `<a href>` links are followed, and CSS selectors, structured data and
+/// the asset, accessibility and mixed content checks are not available.
+pub struct StreamingHtmlParser;
+
+impl StreamingHtmlParser {
+    pub fn parse_html(
+        url: &Url,
+        status_code: u16,
+        content_type: String,
+        html_text: String,
+        options: &ParseOptions,
+    ) -> CrawlResponse {
+        let site_root = options.site_root.as_ref();
+        let input = BufferQueue::default();
+        input.push_back(StrTendril::from_slice(&html_text));
+        let tokenizer = Tokenizer::new(PageSink::default(), TokenizerOpts::default());
+        let _ = tokenizer.feed(&input);
+        tokenizer.end();
+        let page = tokenizer.sink.page.into_inner();
+
+        let mut page_links = PageLinks::default();
+        for link_rel in &page.link_rels {
+            let attr = |name: &str| {
+                link_rel.iter().find(|(attribute, _)| attribute == name).map(|(_, value)| &**value)
+            };
+            LinkExtractor::add_link_rel(attr, url, site_root, &mut page_links);
+        }
+        let mut internal_links = BTreeSet::new();
+        let mut outgoing_links = BTreeSet::new();
+        let mut link_anchors = BTreeSet::new();
+        let link_extractor = &options.link_extractor;
+        for link in page.links {
+            let Some(link_url) = link_extractor.resolve_href(url, link.href.trim(), site_root)
+            else {
+                continue;
+            };
+            if PageCrawler::is_internal(&link_url, url) {
+                let anchor_text = Some(link.text.join(" "))
+                    .filter(|text| !text.is_empty())
+                    .or(link.image_alt)
+                    .or(link.aria_label)
+                    .unwrap_or_default();
+                link_anchors.insert(LinkAnchor {
+                    url: link_url.clone(),
+                    anchor_text,
+                });
+                internal_links.insert(link_url);
+            } else {
+                outgoing_links.insert(link_url);
+            }
+        }
+
+        let mut crawl_response = CrawlResponse::new(url.clone(), status_code, content_type);
+        crawl_response.internal_links = internal_links.into_iter().collect();
+        crawl_response.outgoing_links = outgoing_links.into_iter().collect();
+        crawl_response.internal_link_anchors = link_anchors.into_iter().collect();
+        crawl_response.canonical = page_links.canonical;
+        crawl_response.amp_url = page_links.amp_url;
+        crawl_response.hreflang_alternates = page_links.hreflang_alternates;
+        crawl_response.feeds = page_links.feeds;
+        crawl_response.pagination = page_links.pagination;
+
+        crawl_response.title = page.title.unwrap_or_else(|| NO_TITLE.to_string());
+        crawl_response.meta_description = page.meta_description;
+        crawl_response.meta_refresh = page.meta_refresh.and_then(|target| {
+            LinkExtractor::resolve_link(url, &target, site_root).ok()
+        });
+        for content in &page.robots_directives {
+            crawl_response.robots_directives.add_directives(content);
+        }
+        crawl_response.num_h1 = page.num_h1;
+        crawl_response.body_text = page.words.join(" ");
+        crawl_response.body_bytes = html_text.len();
+        crawl_response.transferred_bytes = html_text.len();
+        crawl_response.body = html_text;
+        crawl_response
+    }
+}
+
+/// An `<a href>` as written, and the words of its text.
+#[derive(Default)]
+struct StreamedLink {
+    href: String,
+    text: Vec<String>,
+    /// Alt text of the first image inside the link that has one.
+    image_alt: Option<String>,
+    aria_label: Option<String>,
+}
+
+#[derive(Default)]
+struct StreamedPage {
+    title: Option<String>,
+    links: Vec<StreamedLink>,
+    meta_description: Option<String>,
+    meta_refresh: Option<String>,
+    /// Attributes of each `<link rel href>` element.
+    link_rels: Vec<Vec<(String, String)>>,
+    robots_directives: Vec<String>,
+    num_h1: usize,
+    words: Vec<String>,
+    in_title: bool,
+    /// Inside a script, style, noscript or repeated title element, whose text is not shown.
+    in_raw_text: bool,
+    template_depth: usize,
+    /// Index in `links` of the link whose text is being read.
+    open_link: Option<usize>,
+}
+
+impl StreamedPage {
+    fn start_tag(&mut self, tag: &Tag) -> TokenSinkResult<()> {
+        let attr = |name: &str| {
+            tag.attrs
+                .iter()
+                .find(|attribute| &*attribute.name.local == name)
+                .map(|attribute| attribute.value.to_string())
+        };
+        match &*tag.name {
+            "title" if self.title.is_none() => {
+                self.in_title = true;
+                self.title = Some(String::new());
+                return TokenSinkResult::RawData(RawKind::Rcdata);
+            }
+            "title" => {
+                self.in_raw_text = true;
+                return TokenSinkResult::RawData(RawKind::Rcdata);
+            }
+            "script" => {
+                self.in_raw_text = true;
+                return TokenSinkResult::RawData(RawKind::ScriptData);
+            }
+            "style" | "noscript" => {
+                self.in_raw_text = true;
+                return TokenSinkResult::RawData(RawKind::Rawtext);
+            }
+            "template" => self.template_depth += 1,
+            "a" => {
+                self.open_link = attr("href").map(|href| {
+                    self.links.push(StreamedLink {
+                        href,
+                        aria_label: attr("aria-label")
+                            .map(|label| label.trim().to_string())
+                            .filter(|label| !label.is_empty()),
+                        ..StreamedLink::default()
+                    });
+                    self.links.len() - 1
+                });
+            }
+            "img" => {
+                let alt = attr("alt").filter(|alt| !alt.trim().is_empty());
+                if let (Some(alt), Some(open_link)) = (alt, self.open_link) {
+                    self.links[open_link].image_alt.get_or_insert(alt.trim().to_string());
+                }
+            }
+            "meta" => {
+                let content = attr("content").unwrap_or_default();
+                let name = attr("name").unwrap_or_default();
+                let http_equiv = attr("http-equiv").unwrap_or_default();
+                if name.eq_ignore_ascii_case("robots") || name.eq_ignore_ascii_case(ROBOTS_AGENT) {
+                    self.robots_directives.push(content);
+                } else if name.eq_ignore_ascii_case("description") {
+                    self.meta_description.get_or_insert(content.trim().to_string());
+                } else if http_equiv.trim().eq_ignore_ascii_case("refresh")
+                    && self.meta_refresh.is_none()
+                {
+                    self.meta_refresh = MetaRefresh::target(&content).map(str::to_string);
+                }
+            }
+            "link" if attr("rel").is_some() && attr("href").is_some() => {
+                let attributes = tag.attrs.iter().map(|attribute| {
+                    (attribute.name.local.to_string(), attribute.value.to_string())
+                });
+                self.link_rels.push(attributes.collect());
+            }
+            "h1" => self.num_h1 += 1,
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+
+    fn end_tag(&mut self, tag: &Tag) {
+        match &*tag.name {
+            "title" => {
+                self.in_title = false;
+                self.in_raw_text = false;
+            }
+            "script" | "style" | "noscript" => self.in_raw_text = false,
+            "template" => self.template_depth = self.template_depth.saturating_sub(1),
+            "a" => self.open_link = None,
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.in_title {
+            if let Some(title) = self.title.as_mut() {
+                title.push_str(text);
+            }
+        } else if !self.in_raw_text && self.template_depth == 0 {
+            let words = text.split_whitespace().map(str::to_string);
+            if let Some(open_link) = self.open_link {
+                self.links[open_link].text.extend(words.clone());
+            }
+            self.words.extend(words);
+        }
+    }
+}
+
+#[derive(Default)]
+struct PageSink {
+    page: RefCell<StreamedPage>,
+}
+
+impl TokenSink for PageSink {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        let mut page = self.page.borrow_mut();
+        match token {
+            Token::TagToken(tag) if tag.kind == TagKind::StartTag => return page.start_tag(&tag),
+            Token::TagToken(tag) => page.end_tag(&tag),
+            Token::CharacterTokens(text) => page.text(&text),
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_title_links_and_text_without_a_dom() {
+        let html = r#"<html><head><title>Home &amp; away</title>
+            <meta name="robots" content="nofollow">
+            <link rel="canonical" href="/home">
+            <script>var link = "<a href='/not-a-link'>";</script>
+            </head><body><h1>Welcome</h1>
+            <a href="/about">About <b>us</b></a>
+            <a href="/logo"><img alt="Logo"></a>
+            <a href="mailto:x@example.com">Mail</a>
+            <a href="https://other.example.com/">Elsewhere</a>
+            </body></html>"#;
+        let url = Url::parse("https://example.com/").unwrap();
+        let crawl_response = StreamingHtmlParser::parse_html(
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+            &ParseOptions::default(),
+        );
+
+        assert_eq!(crawl_response.title, "Home & away");
+        assert_eq!(
+            crawl_response.internal_link_anchors,
+            vec![
+                LinkAnchor {
+                    url: url.join("/about").unwrap(),
+                    anchor_text: "About us".to_string(),
+                },
+                LinkAnchor {
+                    url: url.join("/logo").unwrap(),
+                    anchor_text: "Logo".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            crawl_response.outgoing_links,
+            vec![Url::parse("https://other.example.com/").unwrap()]
+        );
+        assert_eq!(crawl_response.canonical, Some(url.join("/home").unwrap()));
+        assert!(crawl_response.robots_directives.nofollow);
+        assert_eq!(crawl_response.num_h1, 1);
+        assert_eq!(crawl_response.body_text, "Welcome About us Mail Elsewhere");
+    }
+}
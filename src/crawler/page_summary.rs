@@ -1,17 +1,110 @@
+use crate::crawler::accessibility::AccessibilityIssues;
+use crate::crawler::hreflang::HreflangAlternate;
+use crate::crawler::http::{CacheHeaders, SecurityHeaders};
+use crate::crawler::link_anchor::LinkAnchor;
+use crate::crawler::pagination::Pagination;
+use crate::crawler::robots::RobotsDirectives;
+use crate::crawler::schema::StructuredData;
+use crate::crawler::search::SearchMatches;
+use crate::crawler::text::TextStats;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use url::Url;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PageSummary {
     pub url: Url,
+    /// Number of links followed from the seed to reach this page.
+    pub depth: usize,
+    /// The page this URL was first discovered on, or None for the seed.
+    pub discovered_from: Option<Url>,
+    /// Number of times crawled pages linked to this URL over the whole crawl. Only known
+    /// once the seed's crawl ends, so always 0 in streamed results and events, and in
+    /// crawls that bound their memory with a bloom filter or a spilled frontier.
+    pub in_degree: usize,
+    /// IDs of the run and the seed crawl that crawled the page, None for reprocessed pages.
+    pub run_id: Option<String>,
+    pub seed_id: Option<String>,
+    /// 0 if no response was received, because the request timed out or failed to connect.
     pub status_code: u16,
     pub content_type: String,
     pub title: String,
     pub num_outgoing_links: usize,
+    /// Distinct targets of the outgoing links per host, ignoring fragments.
+    pub outgoing_link_hosts: BTreeMap<String, usize>,
+    /// Internal links from this page with their anchor text, normalized like queued URLs.
+    pub internal_link_anchors: Vec<LinkAnchor>,
+    pub body_path: Option<PathBuf>,
+    /// Served with a success status, but looks like a "not found" page.
+    pub soft_404: bool,
+    /// The body was abandoned for exceeding the maximum body size.
+    pub too_large: bool,
+    /// Abandoned for taking longer than the page timeout to fetch and parse.
+    pub timed_out: bool,
+    /// Why a response middleware kept the page from being parsed, if one did.
+    pub skipped: Option<String>,
+    /// Content of `<meta name="description">`, if present.
+    pub meta_description: Option<String>,
+    /// Where a `<meta http-equiv="refresh">` redirects this page to, if anywhere.
+    pub meta_refresh: Option<Url>,
+    /// Where an HTTP redirect that was not followed points: a redirect out of scope, or any
+    /// redirect when the crawl doesn't follow them.
+    pub redirect: Option<Url>,
+    /// Number of `<h1>` headings.
+    pub num_h1: usize,
+    /// Number of pages of a PDF document.
+    pub num_pages: Option<usize>,
+    /// Assets and links loaded over plain http:// from an https:// page.
+    pub mixed_content: Vec<Url>,
+    /// Not crawled because robots.txt disallows it, though it is linked from a crawled page.
+    pub blocked_by_robots_txt: bool,
+    pub robots_directives: RobotsDirectives,
+    /// Next/previous pages if this page is part of a paginated series.
+    pub pagination: Pagination,
+    /// Translations of this page from `<link rel="alternate" hreflang>` annotations.
+    pub hreflang_alternates: Vec<HreflangAlternate>,
+    /// RSS and Atom feeds advertised with `<link rel="alternate">`.
+    pub feeds: Vec<Url>,
+    /// The preferred URL of this page from `<link rel="canonical">`, normalized like queued URLs.
+    pub canonical: Option<Url>,
+    /// The AMP version of this page from `<link rel="amphtml">`, normalized like queued URLs.
+    pub amp_url: Option<Url>,
+    /// Values pulled out by `--extract` rules, keyed by rule name.
+    pub extracted: BTreeMap<String, String>,
+    /// JSON-LD, microdata and RDFa embedded in the page.
+    pub structured_data: StructuredData,
+    /// Accessibility problems found, if `--accessibility` checks were requested.
+    pub accessibility: Option<AccessibilityIssues>,
+    /// Word count and other measures of the visible text, for pages that were parsed.
+    pub text_stats: Option<TextStats>,
+    /// Where the `--search` pattern matched the page's visible text, if it did.
+    pub search_matches: Option<SearchMatches>,
+    /// Visible text of the page, kept only when the crawl is configured to keep it.
+    pub body_text: Option<String>,
+    /// HTTP version of the response, e.g. "HTTP/2.0".
+    pub http_version: Option<String>,
+    /// Content-Encoding the body was served with, or None if it was sent uncompressed.
+    pub content_encoding: Option<String>,
+    /// Security-related response headers, kept only for the first page crawled on each host.
+    pub security_headers: Option<SecurityHeaders>,
+    /// Cache-Control, Expires and Date response headers, for pages served successfully,
+    /// whether or not they were parsed.
+    pub cache_headers: Option<CacheHeaders>,
+    /// Response headers named by `--capture-headers` that the page was served with, keyed by
+    /// lowercase name. Repeated headers have their values joined with ", ".
+    pub captured_headers: BTreeMap<String, String>,
+    pub transferred_bytes: usize,
+    pub body_bytes: usize,
+    /// Hex SHA-256 of the decompressed body, for pages that were fetched.
+    pub content_hash: Option<String>,
+    /// Why `--render js` failed to render the page, which was then parsed as served.
+    pub render_error: Option<String>,
 }
 
 impl PageSummary {
     pub fn new(
         url: Url,
+        depth: usize,
         status_code: u16,
         content_type: String,
         title: String,
@@ -19,20 +112,147 @@ impl PageSummary {
     ) -> Self {
         Self {
             url,
+            depth,
+            discovered_from: None,
+            in_degree: 0,
+            run_id: None,
+            seed_id: None,
             status_code,
             content_type,
             title,
             num_outgoing_links,
+            outgoing_link_hosts: BTreeMap::new(),
+            internal_link_anchors: Vec::new(),
+            body_path: None,
+            soft_404: false,
+            too_large: false,
+            skipped: None,
+            timed_out: false,
+            meta_description: None,
+            meta_refresh: None,
+            redirect: None,
+            num_h1: 0,
+            num_pages: None,
+            mixed_content: Vec::new(),
+            blocked_by_robots_txt: false,
+            robots_directives: RobotsDirectives::default(),
+            pagination: Pagination::default(),
+            hreflang_alternates: Vec::new(),
+            feeds: Vec::new(),
+            canonical: None,
+            amp_url: None,
+            extracted: BTreeMap::new(),
+            structured_data: StructuredData::default(),
+            accessibility: None,
+            text_stats: None,
+            search_matches: None,
+            body_text: None,
+            http_version: None,
+            content_encoding: None,
+            security_headers: None,
+            cache_headers: None,
+            captured_headers: BTreeMap::new(),
+            transferred_bytes: 0,
+            body_bytes: 0,
+            content_hash: None,
+            render_error: None,
         }
     }
 
-    pub fn from_status_code(url: Url, status_code: u16) -> Self {
+    /// Counts the distinct links to each host, ignoring fragments. Links without a host are
+    /// left out.
+    pub fn count_by_host(links: &[Url]) -> BTreeMap<String, usize> {
+        let distinct_links = links
+            .iter()
+            .map(|link| {
+                let mut link = link.clone();
+                link.set_fragment(None);
+                link
+            })
+            .collect::<BTreeSet<_>>();
+        let mut hosts = BTreeMap::new();
+        for host in distinct_links.iter().filter_map(Url::host_str) {
+            *hosts.entry(host.to_string()).or_default() += 1;
+        }
+        hosts
+    }
+
+    /// The target of an unfollowed redirect to another host.
+    pub fn off_host_redirect(&self) -> Option<&Url> {
+        self.redirect.as_ref().filter(|redirect| redirect.host() != self.url.host())
+    }
+
+    pub fn from_status_code(url: Url, depth: usize, status_code: u16) -> Self {
         Self {
             url,
+            depth,
+            discovered_from: None,
+            in_degree: 0,
+            run_id: None,
+            seed_id: None,
             status_code,
             content_type: String::new(),
             title: String::new(),
             num_outgoing_links: 0,
+            outgoing_link_hosts: BTreeMap::new(),
+            internal_link_anchors: Vec::new(),
+            body_path: None,
+            soft_404: false,
+            too_large: false,
+            skipped: None,
+            timed_out: false,
+            meta_description: None,
+            meta_refresh: None,
+            redirect: None,
+            num_h1: 0,
+            num_pages: None,
+            mixed_content: Vec::new(),
+            blocked_by_robots_txt: false,
+            robots_directives: RobotsDirectives::default(),
+            pagination: Pagination::default(),
+            hreflang_alternates: Vec::new(),
+            feeds: Vec::new(),
+            canonical: None,
+            amp_url: None,
+            extracted: BTreeMap::new(),
+            structured_data: StructuredData::default(),
+            accessibility: None,
+            text_stats: None,
+            search_matches: None,
+            body_text: None,
+            http_version: None,
+            content_encoding: None,
+            security_headers: None,
+            cache_headers: None,
+            captured_headers: BTreeMap::new(),
+            transferred_bytes: 0,
+            body_bytes: 0,
+            content_hash: None,
+            render_error: None,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_distinct_links_by_host() {
+        let links = [
+            "https://cdn.example.com/a.js",
+            "https://cdn.example.com/a.js#top",
+            "https://cdn.example.com/b.js",
+            "https://partner.example.org/",
+            "mailto:someone@example.com",
+        ]
+        .map(|link| Url::parse(link).unwrap());
+        assert_eq!(
+            PageSummary::count_by_host(&links),
+            BTreeMap::from([
+                ("cdn.example.com".to_string(), 2),
+                ("partner.example.org".to_string(), 1),
+            ])
+        );
+    }
+}
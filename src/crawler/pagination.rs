@@ -0,0 +1,50 @@
+use url::Url;
+
+/// Neighbouring pages of a paginated series, from `<link rel="next|prev">` tags and
+/// `Link` response headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Pagination {
+    pub next: Option<Url>,
+    pub prev: Option<Url>,
+}
+
+impl Pagination {
+    /// Records `url` if `rels` (a space-separated rel attribute) names it as next or previous.
+    /// The first link found for each direction wins.
+    pub fn add_link(&mut self, rels: &str, url: Url) {
+        for rel in rels.split_ascii_whitespace() {
+            match rel.to_ascii_lowercase().as_str() {
+                "next" if self.next.is_none() => self.next = Some(url.clone()),
+                "prev" | "previous" if self.prev.is_none() => self.prev = Some(url.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Adds the links from a `Link` header value such as
+    /// `<https://example.com/?page=3>; rel="next", <https://example.com/?page=1>; rel="prev"`.
+    /// Relative targets are resolved against `base`.
+    pub fn add_link_header(&mut self, header_value: &str, base: &Url) {
+        let mut rest = header_value;
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+                break;
+            };
+            let target = &rest[start + 1..end];
+            // Parameters run until the next link, which starts at the next '<'
+            let params_end = rest[end..].find('<').map_or(rest.len(), |next| end + next);
+            let params = &rest[end + 1..params_end];
+            rest = &rest[params_end..];
+
+            let rels = params.split(';').find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("rel")
+                    .then(|| value.trim().trim_end_matches(',').trim().trim_matches('"'))
+            });
+            if let (Some(rels), Ok(url)) = (rels, base.join(target)) {
+                self.add_link(rels, url);
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod wasm_plugin;
+
+pub use wasm_plugin::WasmPlugin;
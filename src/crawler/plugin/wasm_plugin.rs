@@ -0,0 +1,150 @@
+use anyhow::{Context, anyhow};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use url::Url;
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Instructions a plugin may execute per page, so that a runaway loop fails the page rather
+/// than hanging the crawl.
+const FUEL_PER_PAGE: u64 = 1_000_000_000;
+
+/// Linear memory a plugin may grow to.
+const MAX_MEMORY: usize = 256 * 1024 * 1024;
+
+/// A custom page extractor compiled to WebAssembly, from `--plugin`. It runs sandboxed: the
+/// module gets no imports, so it can only compute on the page it is given.
+///
+/// The module implements the PageProcessor interface by exporting
+///
+/// - `memory`, its linear memory
+/// - `alloc(len: i32) -> i32`, returning the address of `len` free bytes
+/// - `process(url_ptr: i32, url_len: i32, body_ptr: i32, body_len: i32) -> i64`, called with
+///   the page's URL and body written to memory from `alloc`. It returns the address of its
+///   output in the upper 32 bits and the output's length in the lower 32, or 0 for no output.
+///   The output is a JSON object whose fields are added to the page's extracted data.
+///
+/// Each page gets a fresh instance of the module, so plugins keep no state between pages.
+pub struct WasmPlugin {
+    path: PathBuf,
+    engine: Engine,
+    module: Module,
+}
+
+struct PluginState {
+    limits: StoreLimits,
+}
+
+impl WasmPlugin {
+    /// Compiles the module in `path`, in the binary or the text format.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::from_file(&engine, path)
+            .with_context(|| format!("Unable to load plugin {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            engine,
+            module,
+        })
+    }
+
+    /// The fields the plugin extracts from a page fetched from `url`, with values that aren't
+    /// strings turned into JSON text.
+    pub fn process(&self, url: &Url, body: &[u8]) -> anyhow::Result<BTreeMap<String, String>> {
+        let mut store = Store::new(
+            &self.engine,
+            PluginState {
+                limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_PAGE)?;
+        let instance = Instance::new(&mut store, &self.module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow!("Exports no memory"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let process = instance.get_typed_func::<(i32, i32, i32, i32), i64>(&mut store, "process")?;
+
+        let mut write = |bytes: &[u8]| -> anyhow::Result<(i32, i32)> {
+            let len = i32::try_from(bytes.len()).context("Input too large")?;
+            let ptr = alloc.call(&mut store, len)?;
+            memory.write(&mut store, ptr as u32 as usize, bytes)?;
+            Ok((ptr, len))
+        };
+        let (url_ptr, url_len) = write(url.as_str().as_bytes())?;
+        let (body_ptr, body_len) = write(body)?;
+        let output = process.call(&mut store, (url_ptr, url_len, body_ptr, body_len))? as u64;
+        if output == 0 {
+            return Ok(BTreeMap::new());
+        }
+
+        let output_ptr = (output >> 32) as usize;
+        let output_len = (output & 0xffff_ffff) as usize;
+        let output = memory
+            .data(&store)
+            .get(output_ptr..output_ptr + output_len)
+            .ok_or_else(|| anyhow!("Returned output outside its memory"))?;
+        let fields: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(output).context("Returned output that is not a JSON object")?;
+        Ok(fields
+            .into_iter()
+            .map(|(name, value)| match value {
+                serde_json::Value::String(value) => (name, value),
+                value => (name, value.to_string()),
+            })
+            .collect())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmPlugin").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a fixed JSON object for pages with a body, and nothing for empty ones.
+    const PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "{\"answer\":42,\"kind\":\"test\"}")
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "process")
+                (param $url_ptr i32) (param $url_len i32)
+                (param $body_ptr i32) (param $body_len i32)
+                (result i64)
+            (if (result i64) (i32.eqz (local.get $body_len))
+              (then (i64.const 0))
+              (else (i64.const 27)))))
+    "#;
+
+    #[test]
+    fn merges_the_plugin_output() {
+        let path = std::env::temp_dir()
+            .join(format!("rusty-spider-plugin-test-{}.wat", std::process::id()));
+        std::fs::write(&path, PLUGIN).unwrap();
+        let plugin = WasmPlugin::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let url = Url::parse("https://example.com/").unwrap();
+        let fields = plugin.process(&url, b"<html></html>").unwrap();
+        assert_eq!(fields["answer"], "42");
+        assert_eq!(fields["kind"], "test");
+        assert!(plugin.process(&url, b"").unwrap().is_empty());
+    }
+}
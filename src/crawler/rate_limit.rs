@@ -0,0 +1,13 @@
+mod adaptive_rate;
+mod bandwidth_limiter;
+mod host_circuit_breaker;
+mod host_rate_limiter;
+mod throttle_feedback_fetcher;
+mod token_bucket;
+
+pub use adaptive_rate::AdaptiveRate;
+pub use bandwidth_limiter::BandwidthLimiter;
+pub use host_circuit_breaker::{CircuitState, HostCircuitBreaker};
+pub use host_rate_limiter::HostRateLimiter;
+pub use throttle_feedback_fetcher::ThrottleFeedbackFetcher;
+pub use token_bucket::TokenBucket;
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+/// Slowest rate adaptive throttling backs off to, in requests per second.
+const MIN_RATE: f64 = 0.1;
+
+/// Weight of the newest response time in the smoothed latency.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Smoothed latency, relative to the fastest seen, above which the server counts as strained.
+const STRAINED_LATENCY_RATIO: f64 = 2.0;
+
+/// Rate multiplier applied on a 429 or 503 response.
+const OVERLOAD_BACKOFF: f64 = 0.5;
+
+/// Rate multiplier applied on each response while latency is strained.
+const LATENCY_BACKOFF: f64 = 0.8;
+
+/// Fraction of the maximum rate regained on each healthy response.
+const RECOVERY_STEP: f64 = 0.05;
+
+/// Tracks how fast a host may be crawled: halves the rate when the server says it is
+/// overloaded, eases off while its response times rise, and creeps back up to the maximum
+/// while it responds normally.
+#[derive(Debug, Clone)]
+pub struct AdaptiveRate {
+    max_rate: f64,
+    rate: f64,
+    /// Smoothed response time, in seconds.
+    latency: Option<f64>,
+    /// Lowest smoothed response time seen, taken as the server's unloaded latency.
+    baseline_latency: Option<f64>,
+}
+
+impl AdaptiveRate {
+    pub fn new(max_rate: f64) -> Self {
+        Self {
+            max_rate,
+            rate: max_rate,
+            latency: None,
+            baseline_latency: None,
+        }
+    }
+
+    /// Updates the rate from one response and returns the new rate.
+    pub fn record_response(&mut self, status_code: u16, latency: Duration) -> f64 {
+        if status_code == 429 || status_code == 503 {
+            self.rate = (self.rate * OVERLOAD_BACKOFF).max(MIN_RATE);
+            return self.rate;
+        }
+
+        let latency = latency.as_secs_f64();
+        let smoothed = match self.latency {
+            Some(smoothed) => smoothed + LATENCY_SMOOTHING * (latency - smoothed),
+            None => latency,
+        };
+        self.latency = Some(smoothed);
+        let baseline = self.baseline_latency.map_or(smoothed, |baseline| baseline.min(smoothed));
+        self.baseline_latency = Some(baseline);
+
+        if smoothed > baseline * STRAINED_LATENCY_RATIO {
+            self.rate = (self.rate * LATENCY_BACKOFF).max(MIN_RATE);
+        } else {
+            self.rate = (self.rate + self.max_rate * RECOVERY_STEP).min(self.max_rate);
+        }
+        self.rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halves_on_overload_and_recovers_gradually() {
+        let mut adaptive_rate = AdaptiveRate::new(10.0);
+        let latency = Duration::from_millis(50);
+        assert_eq!(adaptive_rate.record_response(200, latency), 10.0);
+        assert_eq!(adaptive_rate.record_response(429, latency), 5.0);
+        assert_eq!(adaptive_rate.record_response(503, latency), 2.5);
+        assert_eq!(adaptive_rate.record_response(200, latency), 3.0);
+        for _ in 0..20 {
+            adaptive_rate.record_response(200, latency);
+        }
+        assert_eq!(adaptive_rate.record_response(200, latency), 10.0);
+    }
+
+    #[test]
+    fn backs_off_while_latency_is_strained() {
+        let mut adaptive_rate = AdaptiveRate::new(10.0);
+        adaptive_rate.record_response(200, Duration::from_millis(50));
+        let mut rate = 10.0;
+        for _ in 0..10 {
+            rate = adaptive_rate.record_response(200, Duration::from_millis(1000));
+        }
+        assert!(rate < 5.0);
+    }
+}
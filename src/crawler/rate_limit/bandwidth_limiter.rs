@@ -0,0 +1,59 @@
+use crate::crawler::rate_limit::TokenBucket;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use url::Url;
+
+/// Caps the bytes per second received over the whole run and from each host, shared by all
+/// seed crawlers. Bodies are paced as they are streamed, so a cap slows the transfer itself
+/// rather than only the start of the next request.
+pub struct BandwidthLimiter {
+    total: Option<Mutex<TokenBucket>>,
+    max_host_bandwidth: Option<u64>,
+    hosts: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+    /// Limits the run to `max_bandwidth` and each host to `max_host_bandwidth` bytes per
+    /// second; None leaves that limit out.
+    pub fn new(max_bandwidth: Option<u64>, max_host_bandwidth: Option<u64>) -> Self {
+        Self {
+            total: max_bandwidth.map(|max_bandwidth| Mutex::new(Self::bucket(max_bandwidth))),
+            max_host_bandwidth,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Accounts for `num_bytes` received from the host of `url`, returning how long to wait
+    /// before reading more.
+    pub fn reserve(&self, url: &Url, num_bytes: usize) -> Duration {
+        let now = Instant::now();
+        let num_bytes = num_bytes as f64;
+        let total_wait = match &self.total {
+            Some(total) => total.lock().unwrap().reserve_many(now, num_bytes),
+            None => Duration::ZERO,
+        };
+        let host_wait = match self.max_host_bandwidth {
+            Some(max_host_bandwidth) => {
+                let host = url.host_str().unwrap_or_default();
+                let mut hosts = self.hosts.lock().unwrap();
+                let bucket = match hosts.get_mut(host) {
+                    Some(bucket) => bucket,
+                    None => hosts
+                        .entry(host.to_string())
+                        .or_insert_with(|| Self::bucket(max_host_bandwidth)),
+                };
+                bucket.reserve_many(now, num_bytes)
+            }
+            None => Duration::ZERO,
+        };
+        total_wait.max(host_wait)
+    }
+
+    /// A bucket that lets a second's worth of bytes through at once.
+    fn bucket(bytes_per_second: u64) -> TokenBucket {
+        let burst = u32::try_from(bytes_per_second).unwrap_or(u32::MAX);
+        TokenBucket::new(bytes_per_second as f64, burst)
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use url::Url;
+
+/// The state of a host's circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// The host is healthy and crawled normally.
+    Closed,
+    /// The host failed repeatedly and is left alone until its cooldown ends.
+    Open,
+    /// The cooldown has ended; the next response decides whether the host is healthy again.
+    HalfOpen,
+}
+
+/// Stops crawling a host for a cooldown after it fails a number of requests in a row, with
+/// 5xx responses, timeouts or refused connections, so a struggling server doesn't use up
+/// the page budget on errors. Shared by all seed crawlers, like the rate limiter.
+pub struct HostCircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    hosts: Mutex<HashMap<String, HostHealth>>,
+}
+
+#[derive(Debug, Default)]
+struct HostHealth {
+    consecutive_failures: u32,
+    /// When the open breaker's cooldown ends.
+    open_until: Option<Instant>,
+}
+
+impl HostHealth {
+    fn state(&self, now: Instant) -> CircuitState {
+        match self.open_until {
+            None => CircuitState::Closed,
+            Some(open_until) if now < open_until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Records a response, returning the new state if it changed.
+    fn record_outcome(
+        &mut self,
+        healthy: bool,
+        failure_threshold: u32,
+        cooldown: Duration,
+        now: Instant,
+    ) -> Option<CircuitState> {
+        let state = self.state(now);
+        if healthy {
+            self.consecutive_failures = 0;
+            self.open_until = None;
+            return (state != CircuitState::Closed).then_some(CircuitState::Closed);
+        }
+        self.consecutive_failures += 1;
+        // A failed trial request reopens the breaker at once
+        if state == CircuitState::HalfOpen || self.consecutive_failures >= failure_threshold {
+            if state == CircuitState::Open {
+                return None;
+            }
+            self.open_until = Some(now + cooldown);
+            return Some(CircuitState::Open);
+        }
+        None
+    }
+}
+
+impl HostCircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long requests to the host of `url` must wait for its breaker's cooldown to end;
+    /// zero unless the breaker is open.
+    pub fn cooldown_remaining(&self, url: &Url) -> Duration {
+        let hosts = self.hosts.lock().unwrap();
+        hosts
+            .get(url.host_str().unwrap_or_default())
+            .and_then(|host_health| host_health.open_until)
+            .map_or(Duration::ZERO, |open_until| {
+                open_until.saturating_duration_since(Instant::now())
+            })
+    }
+
+    /// Records whether a request to the host of `url` succeeded, returning the breaker's new
+    /// state if this changed it.
+    pub fn record_outcome(&self, url: &Url, healthy: bool) -> Option<CircuitState> {
+        let host = url.host_str().unwrap_or_default();
+        let mut hosts = self.hosts.lock().unwrap();
+        hosts.entry(host.to_string()).or_default().record_outcome(
+            healthy,
+            self.failure_threshold,
+            self.cooldown,
+            Instant::now(),
+        )
+    }
+
+    /// Whether a page with `status_code` counts against its host's health: a 5xx response,
+    /// or 0 for no response at all.
+    pub fn is_failure_status(status_code: u16) -> bool {
+        status_code == 0 || status_code >= 500
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_consecutive_failures_and_closes_after_a_successful_trial() {
+        let cooldown = Duration::from_secs(30);
+        let mut host_health = HostHealth::default();
+        let start = Instant::now();
+        assert_eq!(host_health.record_outcome(false, 3, cooldown, start), None);
+        assert_eq!(host_health.record_outcome(true, 3, cooldown, start), None);
+        assert_eq!(host_health.record_outcome(false, 3, cooldown, start), None);
+        assert_eq!(host_health.record_outcome(false, 3, cooldown, start), None);
+        assert_eq!(
+            host_health.record_outcome(false, 3, cooldown, start),
+            Some(CircuitState::Open)
+        );
+        assert_eq!(host_health.state(start + Duration::from_secs(10)), CircuitState::Open);
+
+        // A failed trial reopens the breaker for another cooldown
+        let trial = start + cooldown;
+        assert_eq!(host_health.state(trial), CircuitState::HalfOpen);
+        assert_eq!(
+            host_health.record_outcome(false, 3, cooldown, trial),
+            Some(CircuitState::Open)
+        );
+        assert_eq!(host_health.state(trial + Duration::from_secs(10)), CircuitState::Open);
+
+        let trial = trial + cooldown;
+        assert_eq!(
+            host_health.record_outcome(true, 3, cooldown, trial),
+            Some(CircuitState::Closed)
+        );
+        assert_eq!(host_health.state(trial), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::crawler::rate_limit::{AdaptiveRate, TokenBucket};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use url::Url;
+
+/// Longest Retry-After that is honored, so a misconfigured server cannot stall the crawl.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// A token bucket per host, shared by all seed crawlers, so that seeds on the same site
+/// share its rate limit. In adaptive mode each host's rate follows its server's health.
+pub struct HostRateLimiter {
+    requests_per_second: f64,
+    burst: u32,
+    adaptive: bool,
+    hosts: Mutex<HashMap<String, HostLimit>>,
+}
+
+struct HostLimit {
+    bucket: TokenBucket,
+    adaptive_rate: AdaptiveRate,
+}
+
+impl HostRateLimiter {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+            adaptive: false,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adapts each host's rate to its server, with `requests_per_second` as the maximum.
+    pub fn with_adaptive(mut self, adaptive: bool) -> Self {
+        self.adaptive = adaptive;
+        self
+    }
+
+    /// Reserves a request to the host of `url`, returning how long to wait before sending it.
+    pub fn reserve(&self, url: &Url) -> Duration {
+        self.with_host(url, |host_limit| host_limit.bucket.reserve(Instant::now()))
+    }
+
+    /// Feeds a response from the host of `url` back into its rate, in adaptive mode.
+    pub fn record_response(
+        &self,
+        url: &Url,
+        status_code: u16,
+        latency: Duration,
+        retry_after: Option<Duration>,
+    ) {
+        if !self.adaptive {
+            return;
+        }
+        self.with_host(url, |host_limit| {
+            let now = Instant::now();
+            let rate = host_limit.adaptive_rate.record_response(status_code, latency);
+            host_limit.bucket.set_rate(rate, now);
+            if let Some(retry_after) = retry_after {
+                host_limit.bucket.pause_until(now, now + retry_after.min(MAX_RETRY_AFTER));
+            }
+        });
+    }
+
+    /// The rate requests to the host of `url` are currently limited to.
+    pub fn current_rate(&self, url: &Url) -> f64 {
+        self.with_host(url, |host_limit| host_limit.bucket.rate())
+    }
+
+    fn with_host<T>(&self, url: &Url, f: impl FnOnce(&mut HostLimit) -> T) -> T {
+        let host = url.host_str().unwrap_or_default();
+        let mut hosts = self.hosts.lock().unwrap();
+        let host_limit = match hosts.get_mut(host) {
+            Some(host_limit) => host_limit,
+            None => hosts.entry(host.to_string()).or_insert_with(|| HostLimit {
+                bucket: TokenBucket::new(self.requests_per_second, self.burst),
+                adaptive_rate: AdaptiveRate::new(self.requests_per_second),
+            }),
+        };
+        f(host_limit)
+    }
+}
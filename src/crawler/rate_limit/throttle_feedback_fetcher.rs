@@ -0,0 +1,61 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::fetch::{FetchResult, Fetcher};
+use crate::crawler::rate_limit::HostRateLimiter;
+use futures::future::BoxFuture;
+use reqwest::header::RETRY_AFTER;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+/// Reports the response time, status and Retry-After of every fetch to the rate limiter,
+/// so that it can adapt each host's rate.
+pub struct ThrottleFeedbackFetcher {
+    inner: Box<dyn Fetcher>,
+    rate_limiter: Arc<HostRateLimiter>,
+}
+
+impl ThrottleFeedbackFetcher {
+    pub fn new(inner: Box<dyn Fetcher>, rate_limiter: Arc<HostRateLimiter>) -> Self {
+        Self {
+            inner,
+            rate_limiter,
+        }
+    }
+
+    async fn fetch_url(&self, url: &Url) -> Result<FetchResult, CrawlError> {
+        let start = tokio::time::Instant::now();
+        let fetch_result = self.inner.fetch(url).await?;
+        let retry_after = fetch_result
+            .headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Self::parse_retry_after);
+        self.rate_limiter
+            .record_response(url, fetch_result.status_code, start.elapsed(), retry_after);
+        Ok(fetch_result)
+    }
+
+    /// Parses a Retry-After value, either a number of seconds or an HTTP date.
+    fn parse_retry_after(value: &str) -> Option<Duration> {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let retry_at = httpdate::parse_http_date(value).ok()?;
+        Some(
+            retry_at
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO),
+        )
+    }
+}
+
+impl Fetcher for ThrottleFeedbackFetcher {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+        Box::pin(self.fetch_url(url))
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        self.inner.close()
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Slowest refill rate, one token every ~17 minutes, used in place of rates that are zero,
+/// negative or not a number, which would never refill the bucket.
+const MIN_RATE: f64 = 0.001;
+
+/// Allows `rate` requests per second on average, and up to `burst` at once after a lull.
+///
+/// Tokens are reserved rather than taken: a caller that finds the bucket empty still gets
+/// its token, along with how long to wait before using it. Concurrent callers therefore
+/// queue up fairly instead of polling.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    /// May go negative while reservations are outstanding.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket refilling at `rate` tokens per second and holding at most `burst`.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: usable_rate(rate),
+            burst,
+            tokens: burst,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Reserves a token, returning how long to wait before it may be used.
+    pub fn reserve(&mut self, now: Instant) -> Duration {
+        self.reserve_many(now, 1.0)
+    }
+
+    /// Reserves `tokens` tokens at once, e.g. one per byte received.
+    pub fn reserve_many(&mut self, now: Instant, tokens: f64) -> Duration {
+        self.refill(now);
+        self.tokens -= tokens;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(-self.tokens / self.rate).unwrap_or(Duration::MAX)
+        }
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Changes the refill rate from `now` on.
+    pub fn set_rate(&mut self, rate: f64, now: Instant) {
+        self.refill(now);
+        self.rate = usable_rate(rate);
+    }
+
+    /// Holds back the next token until `until`, e.g. for a Retry-After.
+    pub fn pause_until(&mut self, now: Instant, until: Instant) {
+        self.refill(now);
+        let pause = until.saturating_duration_since(now).as_secs_f64();
+        self.tokens = self.tokens.min(1.0 - pause * self.rate);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last_refill = now;
+    }
+}
+
+fn usable_rate(rate: f64) -> f64 {
+    if rate.is_finite() && rate > 0.0 {
+        rate.max(MIN_RATE)
+    } else {
+        MIN_RATE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_a_burst_then_spaces_requests_at_the_rate() {
+        let mut bucket = TokenBucket::new(2.0, 3);
+        let now = bucket.last_refill;
+        let waits = (0..5).map(|_| bucket.reserve(now)).collect::<Vec<_>>();
+        assert_eq!(
+            waits,
+            vec![
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_millis(1000),
+            ]
+        );
+    }
+
+    #[test]
+    fn unusable_rates_wait_instead_of_panicking() {
+        for rate in [0.0, -1.0, f64::NAN] {
+            let mut bucket = TokenBucket::new(rate, 1);
+            let now = bucket.last_refill;
+            bucket.reserve(now);
+            assert_eq!(bucket.reserve(now), Duration::from_secs(1000));
+        }
+    }
+
+    #[test]
+    fn refills_up_to_the_burst_size() {
+        let mut bucket = TokenBucket::new(1.0, 2);
+        let start = bucket.last_refill;
+        bucket.reserve(start);
+        bucket.reserve(start);
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_secs(1));
+    }
+}
@@ -1,9 +1,18 @@
 #![allow(unused_imports)]
 
+mod robots_directives;
+mod robots_txt_cache;
+mod robots_txt_match;
 mod robots_txt_matcher;
 mod robots_txt_source;
 mod robots_txt_view;
 
+pub use robots_directives::RobotsDirectives;
+pub use robots_txt_cache::RobotsTxtCache;
+pub use robots_txt_match::RobotsTxtMatch;
 pub use robots_txt_matcher::RobotsTxtMatcher;
 pub use robots_txt_source::RobotsTxtSource;
 pub use robots_txt_view::RobotsTxtView;
+
+/// User agent the crawler identifies as when evaluating robots.txt and robots directives.
+pub const ROBOTS_AGENT: &str = "rusty-spider";
@@ -0,0 +1,93 @@
+use std::fmt;
+use std::time::SystemTime;
+
+/// Indexing directives from `<meta name="robots">` tags and `X-Robots-Tag` headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RobotsDirectives {
+    pub noindex: bool,
+    pub nofollow: bool,
+    /// The date after which the page should drop out of search results, as written.
+    pub unavailable_after: Option<String>,
+}
+
+impl RobotsDirectives {
+    /// Adds the directives from a comma-separated list such as "noindex, nofollow".
+    pub fn add_directives(&mut self, directives: &str) {
+        let mut directives = directives.split(',').peekable();
+        while let Some(directive) = directives.next() {
+            if let Some((name, value)) = directive.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("unavailable_after") {
+                    // Dates like "Fri, 31 Dec 2027 23:59:59 GMT" contain commas
+                    let mut date = value.to_string();
+                    let continues_date =
+                        |rest: &&str| rest.trim_start().starts_with(|c: char| c.is_ascii_digit());
+                    while let Some(rest) = directives.next_if(continues_date) {
+                        date.push(',');
+                        date.push_str(rest);
+                    }
+                    self.unavailable_after.get_or_insert_with(|| date.trim().to_string());
+                }
+                continue;
+            }
+            match directive.trim().to_ascii_lowercase().as_str() {
+                "noindex" => self.noindex = true,
+                "nofollow" => self.nofollow = true,
+                "none" => {
+                    self.noindex = true;
+                    self.nofollow = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Adds the directives from an `X-Robots-Tag` header value. Values prefixed with a user
+    /// agent (e.g. "googlebot: noindex") only apply if the prefix matches `agent`.
+    pub fn add_header_value(&mut self, header_value: &str, agent: &str) {
+        if let Some((prefix, directives)) = header_value.split_once(':') {
+            let prefix = prefix.trim();
+            if !RobotsDirectives::is_directive_name(prefix) {
+                if prefix.eq_ignore_ascii_case(agent) {
+                    self.add_directives(directives);
+                }
+                return;
+            }
+        }
+        self.add_directives(header_value);
+    }
+
+    /// When `unavailable_after` takes effect, or None if it is missing or unreadable. Reads
+    /// ISO 8601 dates, assumed to be UTC without a zone, and RFC 1123 and RFC 850 GMT dates.
+    pub fn unavailable_after_time(&self) -> Option<SystemTime> {
+        let date = self.unavailable_after.as_deref()?;
+        if date.len() == "2027-12-31".len() {
+            return humantime::parse_rfc3339_weak(&format!("{} 00:00:00", date)).ok();
+        }
+        humantime::parse_rfc3339_weak(date)
+            .ok()
+            .or_else(|| httpdate::parse_http_date(date).ok())
+    }
+
+    /// Whether a header prefix is a directive taking a value rather than a user agent.
+    fn is_directive_name(prefix: &str) -> bool {
+        ["unavailable_after", "max-snippet", "max-image-preview", "max-video-preview"]
+            .iter()
+            .any(|name| prefix.eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for RobotsDirectives {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut directives = Vec::new();
+        if self.noindex {
+            directives.push("noindex".to_string());
+        }
+        if self.nofollow {
+            directives.push("nofollow".to_string());
+        }
+        if let Some(unavailable_after) = &self.unavailable_after {
+            directives.push(format!("unavailable_after: {}", unavailable_after));
+        }
+        write!(f, "{}", directives.join(" "))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::crawler::robots::RobotsTxtSource;
+use std::collections::HashMap;
+use url::Url;
+
+/// robots.txt files of the hosts a crawl reaches, loaded on first use.
+pub struct RobotsTxtCache {
+    http_client: reqwest::Client,
+    agent: String,
+    /// Keyed by origin; None if robots.txt could not be loaded.
+    sources: HashMap<String, Option<RobotsTxtSource>>,
+}
+
+impl RobotsTxtCache {
+    pub fn new(http_client: reqwest::Client, agent: &str) -> Self {
+        Self {
+            http_client,
+            agent: agent.to_owned(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Adds a robots.txt that has already been loaded for the host of `url`.
+    pub fn insert(&mut self, url: &Url, source: RobotsTxtSource) {
+        self.sources.insert(url.origin().ascii_serialization(), Some(source));
+    }
+
+    /// Whether robots.txt allows crawling `url`. A host whose robots.txt fails to load is
+    /// treated as disallowing everything, as its owner's wishes are unknown.
+    pub async fn is_allowed(&mut self, url: &Url) -> bool {
+        let origin = url.origin().ascii_serialization();
+        if !self.sources.contains_key(&origin) {
+            let source = RobotsTxtSource::load_from_url(&self.http_client, url, &self.agent)
+                .await
+                .ok();
+            self.sources.insert(origin.clone(), source);
+        }
+        match &self.sources[&origin] {
+            Some(source) => source.view().matcher().check_path(url.path()),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+/// The outcome of checking a path against robots.txt, including the rule that decided it.
+#[derive(Debug, Clone)]
+pub struct RobotsTxtMatch {
+    pub allowed: bool,
+    /// The rule that decided the outcome, or None if no rule matched and the path is allowed by default.
+    pub matched_rule: Option<String>,
+}
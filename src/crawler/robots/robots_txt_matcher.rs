@@ -1,14 +1,56 @@
+use crate::crawler::robots::robots_txt_match::RobotsTxtMatch;
+use robots_txt::matcher::SimpleMatcher;
+use unicase::UniCase;
+
 #[derive(Clone)]
 pub struct RobotsTxtMatcher<'a> {
-    matcher: robots_txt::matcher::SimpleMatcher<'a>,
+    matcher: SimpleMatcher<'a>,
 }
 
 impl<'a> RobotsTxtMatcher<'a> {
-    pub fn new(matcher: robots_txt::matcher::SimpleMatcher<'a>) -> Self {
+    pub fn new(matcher: SimpleMatcher<'a>) -> Self {
         Self { matcher }
     }
 
     pub fn check_path(&self, path: &str) -> bool {
         self.matcher.check_path(path)
     }
-}
\ No newline at end of file
+
+    /// Checks a path the same way as `check_path`, also reporting which rule matched.
+    pub fn explain_path(&self, path: &str) -> RobotsTxtMatch {
+        match &self.matcher {
+            SimpleMatcher::GlobalRule(allowed) => RobotsTxtMatch {
+                allowed: *allowed,
+                matched_rule: Some(if *allowed {
+                    "Allow: / (applies to every path)".to_owned()
+                } else {
+                    "Disallow: / (applies to every path)".to_owned()
+                }),
+            },
+            SimpleMatcher::Rules(rules) => {
+                for rule in rules.iter() {
+                    let rule_description = rule.to_string().trim_end().to_owned();
+                    if rule.path.is_empty() {
+                        return RobotsTxtMatch {
+                            allowed: true,
+                            matched_rule: Some(rule_description),
+                        };
+                    }
+                    if rule.path.len() > path.len() || !path.is_char_boundary(rule.path.len()) {
+                        continue;
+                    }
+                    if UniCase::new(&path[..rule.path.len()]) == UniCase::new(rule.path.as_ref()) {
+                        return RobotsTxtMatch {
+                            allowed: rule.allow,
+                            matched_rule: Some(rule_description),
+                        };
+                    }
+                }
+                RobotsTxtMatch {
+                    allowed: true,
+                    matched_rule: None,
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use url::Url;
+use crate::crawler::http::ContentDecoder;
 use reqwest::StatusCode;
 use robots_txt::Robots;
 use crate::crawler::robots::robots_txt_view::RobotsTxtView;
@@ -10,10 +11,21 @@ pub struct RobotsTxtSource {
 }
 
 impl RobotsTxtSource {
-    pub async fn load_from_url(url: &Url, agent: &str) -> anyhow::Result<Self> {
+    pub async fn load_from_url(
+        http_client: &reqwest::Client,
+        url: &Url,
+        agent: &str,
+    ) -> anyhow::Result<Self> {
+        // Local files have no robots.txt and nobody to object to crawling them
+        if url.scheme() == "file" {
+            return Ok(Self {
+                content: String::new(),
+                agent: agent.to_owned(),
+            });
+        }
         let mut robots_txt_url = url.clone();
         robots_txt_url.set_path("/robots.txt");
-        let robots_response = reqwest::get(robots_txt_url).await?;
+        let robots_response = http_client.get(robots_txt_url).send().await?;
         if !robots_response.status().is_success() {
             if robots_response.status() == StatusCode::NOT_FOUND {
                 return Ok(Self {
@@ -23,7 +35,14 @@ impl RobotsTxtSource {
             }
             return Err(anyhow::anyhow!("An error occurred fetching robots.txt"));
         }
-        let content = robots_response.text().await?;
+        let content_encoding = robots_response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let body = robots_response.bytes().await?;
+        let body = ContentDecoder::decode_content(&body, content_encoding.as_deref(), None)?;
+        let content = String::from_utf8_lossy(&body).into_owned();
         Ok(Self {
             content,
             agent: agent.to_owned(),
@@ -24,4 +24,24 @@ impl<'a> RobotsTxtView<'a> {
         );
         RobotsTxtMatcher::new(matcher)
     }
+
+    /// Sitemaps listed in robots.txt. Sitemap lines apply to every agent, wherever they appear.
+    pub fn sitemaps(&self) -> Vec<url::Url> {
+        self.robot
+            .sections
+            .iter()
+            .chain(std::iter::once(&self.robot.default_section))
+            .flat_map(|section| section.sitemaps.iter().cloned())
+            .collect()
+    }
+
+    /// User agents listed in the robots.txt section that applies to this view's agent.
+    pub fn section_user_agents(&self) -> Vec<String> {
+        self.robot
+            .choose_section(self.agent.as_str())
+            .useragents
+            .iter()
+            .map(|user_agent| user_agent.to_string())
+            .collect()
+    }
 }
@@ -0,0 +1,5 @@
+mod microdata_item;
+mod structured_data;
+
+pub use microdata_item::MicrodataItem;
+pub use structured_data::StructuredData;
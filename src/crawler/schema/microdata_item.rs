@@ -0,0 +1,79 @@
+use scraper::ElementRef;
+use std::collections::BTreeMap;
+
+/// An item marked up in the HTML itself, with microdata (`itemscope`/`itemprop`) or RDFa
+/// (`typeof`/`property`) attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MicrodataItem {
+    /// The `itemtype` or `typeof` attribute, e.g. "https://schema.org/Product".
+    pub item_type: Option<String>,
+    /// Property values by name. Nested items are represented by their type.
+    pub properties: BTreeMap<String, Vec<String>>,
+    /// Marked up with RDFa rather than microdata.
+    pub rdfa: bool,
+}
+
+impl MicrodataItem {
+    /// Collects the properties that belong to `element` rather than to an item nested in it.
+    pub fn from_microdata(element: ElementRef) -> Self {
+        Self::from_element(element, "itemscope", "itemtype", "itemprop", false)
+    }
+
+    pub fn from_rdfa(element: ElementRef) -> Self {
+        Self::from_element(element, "typeof", "typeof", "property", true)
+    }
+
+    fn from_element(
+        element: ElementRef,
+        scope_attribute: &str,
+        type_attribute: &str,
+        property_attribute: &str,
+        rdfa: bool,
+    ) -> Self {
+        let mut item = MicrodataItem {
+            item_type: element.value().attr(type_attribute).map(|item_type| item_type.trim().to_string()),
+            properties: BTreeMap::new(),
+            rdfa,
+        };
+        for property in element.descendants().skip(1).filter_map(ElementRef::wrap) {
+            let Some(names) = property.value().attr(property_attribute) else {
+                continue;
+            };
+            let owner = property
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|ancestor| ancestor.value().attr(scope_attribute).is_some());
+            if owner.is_none_or(|owner| owner.id() != element.id()) {
+                continue;
+            }
+            let value = if property.value().attr(scope_attribute).is_some() {
+                property.value().attr(type_attribute).unwrap_or_default().trim().to_string()
+            } else {
+                Self::property_value(property)
+            };
+            // A property attribute may name several properties sharing one value
+            for name in names.split_whitespace() {
+                item.properties.entry(name.to_string()).or_default().push(value.clone());
+            }
+        }
+        item
+    }
+
+    /// The value of a property element, which depends on the element it is on.
+    fn property_value(property: ElementRef) -> String {
+        let element = property.value();
+        let attribute = match element.name() {
+            _ if element.attr("content").is_some() => "content",
+            "a" | "area" | "link" => "href",
+            "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => "src",
+            "object" => "data",
+            "data" | "meter" => "value",
+            "time" if element.attr("datetime").is_some() => "datetime",
+            _ => "",
+        };
+        match element.attr(attribute) {
+            Some(value) => value.trim().to_string(),
+            None => property.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" "),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::crawler::schema::MicrodataItem;
+use scraper::Selector;
+use std::sync::LazyLock;
+
+static SCRIPT: LazyLock<Selector> = LazyLock::new(|| Selector::parse("script[type]").unwrap());
+static ITEMSCOPE: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[itemscope]").unwrap());
+static TYPEOF: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[typeof]").unwrap());
+
+/// Machine-readable data embedded in a page, as used by search engines for rich results.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StructuredData {
+    /// Parsed `<script type="application/ld+json">` blocks.
+    pub json_ld: Vec<serde_json::Value>,
+    /// Number of JSON-LD blocks that are not valid JSON.
+    pub num_invalid_json_ld: usize,
+    /// Items marked up with microdata or RDFa attributes.
+    pub items: Vec<MicrodataItem>,
+}
+
+impl StructuredData {
+    pub fn from_document(document: &scraper::Html) -> Self {
+        let mut structured_data = StructuredData::default();
+
+        for element in document.select(&SCRIPT) {
+            let script_type = element.value().attr("type").unwrap_or_default();
+            if !script_type.trim().eq_ignore_ascii_case("application/ld+json") {
+                continue;
+            }
+            match serde_json::from_str(&element.text().collect::<String>()) {
+                Ok(value) => structured_data.json_ld.push(value),
+                Err(_) => structured_data.num_invalid_json_ld += 1,
+            }
+        }
+
+        structured_data
+            .items
+            .extend(document.select(&ITEMSCOPE).map(MicrodataItem::from_microdata));
+        structured_data.items.extend(document.select(&TYPEOF).map(MicrodataItem::from_rdfa));
+
+        structured_data
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.json_ld.is_empty() && self.num_invalid_json_ld == 0 && self.items.is_empty()
+    }
+
+    /// Short names of the types of every entity on the page, including nested ones, e.g.
+    /// "Product" for both "https://schema.org/Product" and "schema:Product".
+    pub fn types(&self) -> Vec<String> {
+        let mut types = Vec::new();
+        for value in &self.json_ld {
+            Self::collect_json_ld_types(value, &mut types);
+        }
+        for item in &self.items {
+            types.extend(
+                item.item_type
+                    .iter()
+                    .flat_map(|item_type| item_type.split_whitespace())
+                    .map(Self::short_type_name),
+            );
+        }
+        types
+    }
+
+    fn collect_json_ld_types(value: &serde_json::Value, types: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(object) => {
+                match object.get("@type") {
+                    Some(serde_json::Value::String(item_type)) => types.push(Self::short_type_name(item_type)),
+                    Some(serde_json::Value::Array(item_types)) => types.extend(
+                        item_types
+                            .iter()
+                            .filter_map(serde_json::Value::as_str)
+                            .map(Self::short_type_name),
+                    ),
+                    _ => {}
+                }
+                for (key, nested) in object {
+                    if key != "@type" {
+                        Self::collect_json_ld_types(nested, types);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for nested in values {
+                    Self::collect_json_ld_types(nested, types);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn short_type_name(item_type: &str) -> String {
+        let item_type = item_type.trim().trim_end_matches('/');
+        item_type
+            .rsplit(['/', '#', ':'])
+            .next()
+            .unwrap_or(item_type)
+            .to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_json_ld_microdata_and_rdfa() {
+        let document = scraper::Html::parse_document(
+            r#"<html><head>
+                <script type="application/ld+json">
+                    {"@context": "https://schema.org", "@type": "Product", "name": "Lamp",
+                     "offers": {"@type": "Offer", "price": "12.50"}}
+                </script>
+                <script type="application/ld+json">{ not json</script>
+            </head><body>
+                <div itemscope itemtype="https://schema.org/Person">
+                    <span itemprop="name">Ada  Lovelace</span>
+                    <a itemprop="url" href="/ada">home</a>
+                    <div itemprop="address" itemscope itemtype="https://schema.org/PostalAddress">
+                        <span itemprop="addressLocality">London</span>
+                    </div>
+                </div>
+                <div vocab="https://schema.org/" typeof="Event">
+                    <span property="name">Launch</span>
+                </div>
+            </body></html>"#,
+        );
+        let structured_data = StructuredData::from_document(&document);
+        assert_eq!(structured_data.json_ld.len(), 1);
+        assert_eq!(structured_data.num_invalid_json_ld, 1);
+        assert_eq!(structured_data.items.len(), 3);
+
+        let person = &structured_data.items[0];
+        assert_eq!(person.properties["name"], vec!["Ada Lovelace"]);
+        assert_eq!(person.properties["url"], vec!["/ada"]);
+        assert_eq!(person.properties["address"], vec!["https://schema.org/PostalAddress"]);
+        assert!(!person.properties.contains_key("addressLocality"));
+        assert_eq!(structured_data.items[1].properties["addressLocality"], vec!["London"]);
+        assert!(structured_data.items[2].rdfa);
+
+        assert_eq!(
+            structured_data.types(),
+            vec!["Product", "Offer", "Person", "PostalAddress", "Event"]
+        );
+    }
+}
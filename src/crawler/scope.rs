@@ -0,0 +1,9 @@
+mod frame_policy;
+mod host_pattern;
+mod scope_policy;
+mod url_pattern;
+
+pub use frame_policy::FramePolicy;
+pub use host_pattern::HostPattern;
+pub use scope_policy::ScopePolicy;
+pub use url_pattern::UrlPattern;
@@ -0,0 +1,27 @@
+use crate::crawler::scope::ScopePolicy;
+use url::Url;
+
+/// Which `<frame>` and `<iframe>` sources are crawled along with the pages embedding them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum FramePolicy {
+    /// Frames are not crawled
+    #[default]
+    None,
+    /// Frames served from the same host as the page embedding them
+    SameHost,
+    /// Frames on any host the crawl is scoped to, including --allow-host hosts
+    InScope,
+}
+
+impl FramePolicy {
+    /// Whether the frame at `frame_url`, embedded in the page at `page_url`, is crawled.
+    pub fn follows(&self, frame_url: &Url, page_url: &Url, scope_policy: &ScopePolicy) -> bool {
+        match self {
+            FramePolicy::None => false,
+            FramePolicy::SameHost => {
+                frame_url.host() == page_url.host() && scope_policy.is_in_scope(frame_url)
+            }
+            FramePolicy::InScope => scope_policy.is_in_scope(frame_url),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::anyhow;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A host name pattern given to `--allow-host` or `--deny-host`: a glob such as
+/// "*.example.com", where "*" matches any run of characters, or a regular expression
+/// prefixed with "re:", such as "re:^docs[0-9]+\.example\.com$". Globs match the whole
+/// host name and ignore case.
+#[derive(Debug, Clone)]
+pub enum HostPattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl HostPattern {
+    pub fn matches(&self, host: &str) -> bool {
+        match self {
+            HostPattern::Glob(glob) => Self::matches_glob(glob, &host.to_ascii_lowercase()),
+            HostPattern::Regex(regex) => regex.is_match(host),
+        }
+    }
+
+    fn matches_glob(glob: &str, host: &str) -> bool {
+        let mut parts = glob.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = host.strip_prefix(first) else {
+            return false;
+        };
+        let parts = parts.collect::<Vec<_>>();
+        for (index, part) in parts.iter().enumerate() {
+            if index == parts.len() - 1 {
+                return rest.ends_with(part);
+            }
+            match rest.find(part) {
+                Some(position) => rest = &rest[position + part.len()..],
+                None => return false,
+            }
+        }
+        rest.is_empty()
+    }
+}
+
+impl FromStr for HostPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(regex) = s.strip_prefix("re:") {
+            let regex = Regex::new(regex)
+                .map_err(|e| anyhow!("Invalid host regex '{}': {}", regex, e))?;
+            return Ok(HostPattern::Regex(regex));
+        }
+        if s.is_empty() || s.contains(['/', ':']) {
+            return Err(anyhow!(
+                "Invalid host pattern '{}' (expected e.g. cdn.example.com or *.example.com)",
+                s
+            ));
+        }
+        Ok(HostPattern::Glob(s.to_ascii_lowercase()))
+    }
+}
+
+impl Display for HostPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostPattern::Glob(glob) => write!(f, "{}", glob),
+            HostPattern::Regex(regex) => write!(f, "re:{}", regex),
+        }
+    }
+}
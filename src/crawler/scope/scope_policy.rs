@@ -0,0 +1,109 @@
+use crate::crawler::http::PrivateNetworkGuard;
+use crate::crawler::scope::HostPattern;
+use crate::crawler::script::CrawlScript;
+use std::sync::Arc;
+use url::Url;
+
+/// Decides which discovered URLs a seed crawl follows: those on the seed's own host and on
+/// any `--allow-host`, except hosts matching a `--deny-host`, unless private networks are
+/// allowed literal private addresses, and URLs the `--script` says not to follow.
+#[derive(Debug, Clone)]
+pub struct ScopePolicy {
+    seed: Url,
+    allow_hosts: Vec<HostPattern>,
+    deny_hosts: Vec<HostPattern>,
+    allow_private_networks: bool,
+    crawl_script: Option<Arc<CrawlScript>>,
+}
+
+impl ScopePolicy {
+    pub fn new(seed: Url) -> Self {
+        Self {
+            seed,
+            allow_hosts: Vec::new(),
+            deny_hosts: Vec::new(),
+            allow_private_networks: false,
+            crawl_script: None,
+        }
+    }
+
+    pub fn with_allow_hosts(mut self, allow_hosts: Vec<HostPattern>) -> Self {
+        self.allow_hosts = allow_hosts;
+        self
+    }
+
+    pub fn with_deny_hosts(mut self, deny_hosts: Vec<HostPattern>) -> Self {
+        self.deny_hosts = deny_hosts;
+        self
+    }
+
+    pub fn with_allow_private_networks(mut self, allow_private_networks: bool) -> Self {
+        self.allow_private_networks = allow_private_networks;
+        self
+    }
+
+    pub fn with_crawl_script(mut self, crawl_script: Option<Arc<CrawlScript>>) -> Self {
+        self.crawl_script = crawl_script;
+        self
+    }
+
+    pub fn is_in_scope(&self, url: &Url) -> bool {
+        self.is_in_host_scope(url)
+            && self.crawl_script.as_ref().is_none_or(|crawl_script| crawl_script.should_follow(url))
+    }
+
+    fn is_in_host_scope(&self, url: &Url) -> bool {
+        // file:// URLs have no host, so a local seed's scope is every other local file
+        if (url.scheme() == "file") != (self.seed.scheme() == "file") {
+            return false;
+        }
+        if url.scheme() == "file" || self.is_seed_host(url) {
+            return !self.is_denied(url);
+        }
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        !self.is_denied(url) && self.allow_hosts.iter().any(|pattern| pattern.matches(host))
+    }
+
+    /// Whether a URL is on the seed's own host, whose robots.txt the crawl loads up front.
+    pub fn is_seed_host(&self, url: &Url) -> bool {
+        url.host() == self.seed.host()
+    }
+
+    fn is_denied(&self, url: &Url) -> bool {
+        if !self.allow_private_networks && PrivateNetworkGuard::is_private_url(url) {
+            return true;
+        }
+        url.host_str()
+            .is_some_and(|host| self.deny_hosts.iter().any(|pattern| pattern.matches(host)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_seed_host_and_allowed_hosts_unless_denied() {
+        let patterns = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|pattern| pattern.parse::<HostPattern>().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let scope_policy = ScopePolicy::new(Url::parse("https://www.example.com/").unwrap())
+            .with_allow_hosts(patterns(&["*.example.com", "re:^cdn[0-9]\\.example\\.net$"]))
+            .with_deny_hosts(patterns(&["ads.example.com"]));
+        let in_scope = |url: &str| scope_policy.is_in_scope(&Url::parse(url).unwrap());
+        assert!(in_scope("https://www.example.com/about"));
+        assert!(in_scope("https://DOCS.example.com/guide"));
+        assert!(in_scope("https://cdn1.example.net/app.js"));
+        assert!(!in_scope("https://ads.example.com/banner"));
+        assert!(!in_scope("https://example.com/"));
+        assert!(!in_scope("https://cdn10.example.net/app.js"));
+        assert!(!in_scope("https://elsewhere.org/"));
+        assert!(!in_scope("https://10.0.0.1/"));
+        assert!("https://example.com".parse::<HostPattern>().is_err());
+    }
+}
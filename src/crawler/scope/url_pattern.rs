@@ -0,0 +1,93 @@
+use anyhow::anyhow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use url::Url;
+
+/// A URL pattern such as "/docs/*" or "/*.pdf$", used by `--assert` and `--prioritize`.
+///
+/// Patterns starting with "/" match the path and query, others the whole URL. As in
+/// robots.txt rules, a pattern matches any URL it is a prefix of, "*" matches any run of
+/// characters and a trailing "$" anchors the pattern to the end of the URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlPattern {
+    pattern: String,
+}
+
+impl UrlPattern {
+    pub fn matches(&self, url: &Url) -> bool {
+        let target = if self.pattern.starts_with('/') {
+            &url[url::Position::BeforePath..url::Position::AfterQuery]
+        } else {
+            url.as_str()
+        };
+        let (pattern, anchored) = match self.pattern.strip_suffix('$') {
+            Some(pattern) => (pattern, true),
+            None => (self.pattern.as_str(), false),
+        };
+        Self::matches_glob(pattern, target, anchored)
+    }
+
+    /// Whether `pattern`, with "*" wildcards, matches the start of `target` (or all of it, if
+    /// `anchored`).
+    fn matches_glob(pattern: &str, target: &str, anchored: bool) -> bool {
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = target.strip_prefix(first) else {
+            return false;
+        };
+        let parts = parts.collect::<Vec<_>>();
+        for (index, part) in parts.iter().enumerate() {
+            // The last part of an anchored pattern must end the target
+            if anchored && index == parts.len() - 1 {
+                return rest.ends_with(part);
+            }
+            match rest.find(part) {
+                Some(position) => rest = &rest[position + part.len()..],
+                None => return false,
+            }
+        }
+        !anchored || rest.is_empty()
+    }
+}
+
+impl FromStr for UrlPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pattern = s.trim();
+        if pattern.is_empty() {
+            return Err(anyhow!("Invalid URL pattern '{}' (expected e.g. /docs/*)", s));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+impl Display for UrlPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, url: &str) -> bool {
+        let pattern: UrlPattern = pattern.parse().unwrap();
+        pattern.matches(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn matches_urls_like_robots_txt_rules() {
+        assert!(matches("/docs/", "https://example.com/docs/intro"));
+        assert!(matches("/docs/*", "https://example.com/docs/intro"));
+        assert!(!matches("/docs/", "https://example.com/blog/docs/"));
+        assert!(matches("/*.pdf$", "https://example.com/files/report.pdf"));
+        assert!(!matches("/*.pdf$", "https://example.com/files/report.pdf.html"));
+        assert!(matches("/search?q=", "https://example.com/search?q=rust"));
+        assert!(matches("https://example.com/admin", "https://example.com/admin/users"));
+        assert!(!matches("https://example.com/admin", "https://other.example/admin"));
+    }
+}
@@ -0,0 +1,3 @@
+mod crawl_script;
+
+pub use crawl_script::CrawlScript;
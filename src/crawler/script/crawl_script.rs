@@ -0,0 +1,141 @@
+use anyhow::{Context, anyhow};
+use rhai::{AST, CallFnOptions, Dynamic, Engine, Scope};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use url::Url;
+
+/// Operations a single callback may run, so that a runaway loop fails the call rather than
+/// hanging the crawl.
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// Custom crawl logic from a Rhai script, from `--script`. The script may define either of
+///
+/// - `should_follow(url)`, returning whether a discovered URL in scope is followed
+/// - `on_page(url, html)`, returning a map of extra fields added to the page's extracted data
+///
+/// Both are optional; without `should_follow` every URL in scope is followed.
+pub struct CrawlScript {
+    engine: Engine,
+    ast: AST,
+    has_should_follow: bool,
+    has_on_page: bool,
+    /// Whether a failing `should_follow` has been reported already.
+    should_follow_failed: AtomicBool,
+}
+
+impl CrawlScript {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read script {}", path.display()))?;
+        Self::compile(&source).with_context(|| format!("Unable to load script {}", path.display()))
+    }
+
+    pub fn compile(source: &str) -> anyhow::Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine.compile(source)?;
+        let defines = |name: &str, num_params: usize| {
+            ast.iter_functions()
+                .any(|function| function.name == name && function.params.len() == num_params)
+        };
+        let has_should_follow = defines("should_follow", 1);
+        let has_on_page = defines("on_page", 2);
+        if !has_should_follow && !has_on_page {
+            return Err(anyhow!("Defines neither should_follow(url) nor on_page(url, html)"));
+        }
+        Ok(Self {
+            engine,
+            ast,
+            has_should_follow,
+            has_on_page,
+            should_follow_failed: AtomicBool::new(false),
+        })
+    }
+
+    /// Whether the crawl follows `url`. A failing call doesn't follow it, with a warning the
+    /// first time.
+    pub fn should_follow(&self, url: &Url) -> bool {
+        if !self.has_should_follow {
+            return true;
+        }
+        let follow = self.call("should_follow", (url.to_string(),)).and_then(|follow| {
+            follow
+                .as_bool()
+                .map_err(|type_name| anyhow!("returned a {} rather than a bool", type_name))
+        });
+        match follow {
+            Ok(follow) => follow,
+            Err(e) => {
+                if !self.should_follow_failed.swap(true, Ordering::Relaxed) {
+                    eprintln!("Warning: should_follow failed for {}, not following: {:#}", url, e);
+                }
+                false
+            }
+        }
+    }
+
+    /// The extra fields for a page fetched from `url`, with values turned into text.
+    pub fn on_page(&self, url: &Url, html: &str) -> anyhow::Result<BTreeMap<String, String>> {
+        if !self.has_on_page {
+            return Ok(BTreeMap::new());
+        }
+        let result = self.call("on_page", (url.to_string(), html.to_string()))?;
+        if result.is_unit() {
+            return Ok(BTreeMap::new());
+        }
+        let type_name = result.type_name();
+        let fields = result
+            .try_cast::<rhai::Map>()
+            .ok_or_else(|| anyhow!("on_page returned a {} rather than a map", type_name))?;
+        Ok(fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect())
+    }
+
+    fn call(&self, name: &str, args: impl rhai::FuncArgs) -> anyhow::Result<Dynamic> {
+        // Only the script's functions are used; its top level never runs
+        let options = CallFnOptions::new().eval_ast(false);
+        Ok(self
+            .engine
+            .call_fn_with_options(options, &mut Scope::new(), &self.ast, name, args)?)
+    }
+}
+
+impl fmt::Debug for CrawlScript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrawlScript")
+            .field("has_should_follow", &self.has_should_follow)
+            .field("has_on_page", &self.has_on_page)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calls_the_script_callbacks() {
+        let script = CrawlScript::compile(
+            r#"
+            fn should_follow(url) { !url.contains("/private/") }
+            fn on_page(url, html) {
+                if html.contains("<h1>") { #{ has_heading: true, length: html.len() } }
+            }
+            "#,
+        )
+        .unwrap();
+        let url = Url::parse("https://example.com/").unwrap();
+        assert!(script.should_follow(&url));
+        assert!(!script.should_follow(&url.join("/private/notes").unwrap()));
+        let fields = script.on_page(&url, "<h1>Hi</h1>").unwrap();
+        assert_eq!(fields["has_heading"], "true");
+        assert_eq!(fields["length"], "11");
+        assert!(script.on_page(&url, "<p>Hi</p>").unwrap().is_empty());
+
+        assert!(CrawlScript::compile("let x = 1;").is_err());
+    }
+}
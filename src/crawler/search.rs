@@ -0,0 +1,5 @@
+mod search_matches;
+mod text_search;
+
+pub use search_matches::SearchMatches;
+pub use text_search::TextSearch;
@@ -0,0 +1,7 @@
+/// Where a `--search` pattern matched the visible text of a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SearchMatches {
+    pub num_matches: usize,
+    /// The first few matches with some surrounding text.
+    pub snippets: Vec<String>,
+}
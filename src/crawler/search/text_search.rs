@@ -0,0 +1,94 @@
+use crate::crawler::search::SearchMatches;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Snippets recorded per page; further matches are only counted.
+const MAX_SNIPPETS: usize = 3;
+/// Bytes of text kept on each side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 40;
+
+/// A regular expression searched for in the visible text of every page, like a site-wide grep.
+#[derive(Debug, Clone)]
+pub struct TextSearch {
+    regex: Regex,
+}
+
+impl TextSearch {
+    /// The matches in `text`, or None if there are none.
+    pub fn search(&self, text: &str) -> Option<SearchMatches> {
+        let mut search_matches = SearchMatches::default();
+        for found in self.regex.find_iter(text) {
+            search_matches.num_matches += 1;
+            if search_matches.snippets.len() < MAX_SNIPPETS {
+                search_matches
+                    .snippets
+                    .push(Self::snippet(text, found.start(), found.end()));
+            }
+        }
+        (search_matches.num_matches > 0).then_some(search_matches)
+    }
+
+    fn snippet(text: &str, start: usize, end: usize) -> String {
+        let mut snippet_start = start.saturating_sub(SNIPPET_CONTEXT);
+        while !text.is_char_boundary(snippet_start) {
+            snippet_start -= 1;
+        }
+        let mut snippet_end = (end + SNIPPET_CONTEXT).min(text.len());
+        while !text.is_char_boundary(snippet_end) {
+            snippet_end += 1;
+        }
+        let mut snippet = String::new();
+        if snippet_start > 0 {
+            snippet.push('…');
+        }
+        snippet.push_str(&text[snippet_start..snippet_end]);
+        if snippet_end < text.len() {
+            snippet.push('…');
+        }
+        snippet
+    }
+}
+
+impl FromStr for TextSearch {
+    type Err = regex::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self { regex: Regex::new(s)? })
+    }
+}
+
+impl Display for TextSearch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.regex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_matches_and_keeps_snippets_of_the_first() {
+        let search: TextSearch = "(?i)widget ?2000".parse().unwrap();
+        let text = format!(
+            "{} Widget 2000 is gone, try the widget2000 or WIDGET 2000. {}",
+            "x".repeat(50),
+            "y".repeat(50)
+        );
+        let search_matches = search.search(&text).unwrap();
+        assert_eq!(search_matches.num_matches, 3);
+        assert_eq!(search_matches.snippets.len(), 3);
+        assert!(search_matches.snippets[0].starts_with('…'));
+        assert!(search_matches.snippets[0].contains("Widget 2000 is gone"));
+        assert_eq!(search.search("nothing here"), None);
+    }
+
+    #[test]
+    fn snippets_respect_char_boundaries() {
+        let search: TextSearch = "needle".parse().unwrap();
+        let text = format!("{}needle{}", "é".repeat(30), "ü".repeat(30));
+        let snippet = &search.search(&text).unwrap().snippets[0];
+        assert!(snippet.contains("needle"));
+    }
+}
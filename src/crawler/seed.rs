@@ -1,10 +1,28 @@
 #![allow(unused_imports)]
 
+mod bloom_filter;
+mod crawl_control;
 mod crawl_context;
+mod frontier;
+mod frontier_policy;
+mod queued_url;
+mod redis_frontier;
 mod seed_crawler;
+mod spill_file;
+mod trap_detector;
+mod url_limits;
+mod visited_urls;
 mod progress_reporter;
-mod console_progress_reporter;
+mod channel_progress_reporter;
 
+pub use crawl_control::CrawlControl;
+pub use frontier_policy::{CrawlStrategy, FrontierPolicy, FrontierPriority};
+pub use queued_url::QueuedUrl;
+pub use url_limits::{
+    DEFAULT_MAX_PATH_SEGMENTS, DEFAULT_MAX_QUERY_LENGTH, DEFAULT_MAX_URL_LENGTH, RejectedUrlCounts,
+    UrlLimits,
+};
+pub use trap_detector::{DEFAULT_TRAP_FAMILY_LIMIT, SuspectedTrap, TrapReason};
 pub use seed_crawler::SeedCrawler;
 pub use progress_reporter::ProgressReporter;
-pub use console_progress_reporter::ConsoleProgressReporter;
+pub use channel_progress_reporter::ChannelProgressReporter;
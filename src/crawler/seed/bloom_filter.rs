@@ -0,0 +1,95 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// A fixed-size probabilistic set. `contains` never misses an inserted item, but may report
+/// items that were never inserted at roughly the configured false-positive rate.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    num_items: usize,
+}
+
+impl BloomFilter {
+    /// Sizes the filter to hold `expected_items` at `false_positive_rate`.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let expected_items = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-expected_items * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / expected_items) * ln2).round().max(1.0) as u32;
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+            num_items: 0,
+        }
+    }
+
+    pub fn insert<T: Hash>(&mut self, item: &T) {
+        for bit in self.bit_indexes(item) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.num_items += 1;
+    }
+
+    pub fn contains<T: Hash>(&self, item: &T) -> bool {
+        self.bit_indexes(item)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Number of insertions so far.
+    pub fn len(&self) -> usize {
+        self.num_items
+    }
+
+    /// Memory used by the bit array, in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// False-positive rate expected at the current number of insertions.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let exponent = -(self.num_hashes as f64) * self.num_items as f64 / self.num_bits as f64;
+        (1.0 - exponent.exp()).powi(self.num_hashes as i32)
+    }
+
+    /// Derives the item's bit positions from two hashes (Kirsch-Mitzenmacher double hashing).
+    fn bit_indexes<T: Hash>(&self, item: &T) -> impl Iterator<Item = u64> + use<T> {
+        let hash_with_seed = |seed: u8| {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            item.hash(&mut hasher);
+            hasher.finish()
+        };
+        let (h1, h2) = (hash_with_seed(0), hash_with_seed(1) | 1);
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_misses_an_inserted_item() {
+        let mut filter = BloomFilter::new(1000, 0.01);
+        for item in 0..1000 {
+            filter.insert(&item);
+        }
+        assert!((0..1000).all(|item| filter.contains(&item)));
+        assert_eq!(filter.len(), 1000);
+    }
+
+    #[test]
+    fn keeps_near_the_configured_false_positive_rate() {
+        let mut filter = BloomFilter::new(1000, 0.01);
+        for item in 0..1000 {
+            filter.insert(&item);
+        }
+        let false_positives = (1000..11_000).filter(|item| filter.contains(item)).count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+        let estimated_rate = filter.estimated_false_positive_rate();
+        assert!((0.005..0.02).contains(&estimated_rate), "{}", estimated_rate);
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+use url::Url;
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use crate::console::crawler_state::CrawlerState;
+use crate::crawler::rate_limit::CircuitState;
+use crate::crawler::seed::progress_reporter::ProgressReporter;
+
+/// Forwards a seed crawler's progress over a channel, to the console reporter or the API
+/// server. The channel is unbounded, so reporting never blocks the crawl; events sent after
+/// the receiver has gone are dropped.
+#[derive(Clone)]
+pub struct ChannelProgressReporter {
+    index: usize,
+    url: Url,
+    seed_id: String,
+    event_tx: tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
+}
+
+impl ChannelProgressReporter {
+    pub fn new(
+        index: usize,
+        url: Url,
+        seed_id: String,
+        event_tx: tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
+    ) -> Self {
+        Self { index, url, seed_id, event_tx }
+    }
+
+    fn send(&self, event: CrawlerProcessEvent) {
+        let _ = self.event_tx.send(event);
+    }
+}
+
+impl ProgressReporter for ChannelProgressReporter {
+    fn begin(&self) {
+        self.send(CrawlerProcessEvent::Begin {
+            crawler_index: self.index,
+            url: self.url.clone(),
+            seed_id: self.seed_id.clone(),
+        });
+    }
+
+    fn progress_update(&self, num_urls_to_crawl: usize, num_urls_crawled: usize, elapsed: Duration) {
+        self.send(CrawlerProcessEvent::ProgressUpdate {
+            crawler_index: self.index,
+            num_urls_to_crawl,
+            num_urls_crawled,
+            elapsed,
+        });
+    }
+
+    fn progress_message(&self, message: &str) {
+        self.send(CrawlerProcessEvent::ProgressMessage {
+            crawler_index: self.index,
+            message: message.to_owned(),
+        });
+    }
+
+    fn page_completed(&self, url: &Url, status_code: u16, depth: usize, duration: Duration) {
+        self.send(CrawlerProcessEvent::PageCompleted {
+            crawler_index: self.index,
+            url: url.clone(),
+            status_code,
+            depth,
+            duration,
+        });
+    }
+
+    fn crawler_state_changed(&self, state: CrawlerState) {
+        self.send(CrawlerProcessEvent::CrawlerStateChanged {
+            crawler_index: self.index,
+            state,
+        });
+    }
+
+    fn rate_changed(&self, requests_per_second: f64) {
+        self.send(CrawlerProcessEvent::RateChanged {
+            crawler_index: self.index,
+            requests_per_second,
+        });
+    }
+
+    fn circuit_state_changed(&self, host: &str, state: CircuitState) {
+        self.send(CrawlerProcessEvent::CircuitStateChanged {
+            crawler_index: self.index,
+            host: host.to_owned(),
+            state,
+        });
+    }
+
+    fn end(&self) {
+        self.send(CrawlerProcessEvent::End {
+            crawler_index: self.index,
+        });
+    }
+}
@@ -1,59 +1,413 @@
-use std::collections::HashSet;
+use crate::crawler::multi::SharedVisitedSet;
+use crate::crawler::normalize::UrlNormalizer;
+use crate::crawler::scope::UrlPattern;
+use crate::crawler::seed::frontier::Frontier;
+use crate::crawler::seed::frontier_policy::FrontierPolicy;
+use crate::crawler::seed::queued_url::QueuedUrl;
+use crate::crawler::seed::redis_frontier::RedisFrontier;
+use crate::crawler::seed::trap_detector::{SuspectedTrap, TrapDetector};
+use crate::crawler::seed::url_limits::{RejectedUrlCounts, UrlLimits};
+use crate::crawler::seed::visited_urls::VisitedUrls;
+use crate::crawler::visited_filter::VisitedFilterStats;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use url::Url;
 
-#[derive(Clone)]
 pub struct CrawlContext {
-    urls_to_crawl: HashSet<Url>,
-    urls_already_crawled: HashSet<Url>,
+    /// URLs waiting to be crawled, keyed by their normalized form.
+    urls_to_crawl: Frontier,
+    /// Takes the place of `urls_to_crawl` when crawling cooperatively with other processes.
+    shared_frontier: Option<RedisFrontier>,
+    urls_already_crawled: VisitedUrls,
+    shared_visited_set: Option<SharedVisitedSet>,
+    max_depth: Option<usize>,
+    url_normalizer: UrlNormalizer,
+    trap_detector: Option<TrapDetector>,
+    url_limits: UrlLimits,
+    rejected_url_counts: RejectedUrlCounts,
+    /// Number of times each stripped URL that was queued was found linked from a crawled
+    /// page. None when the crawl bounds its memory use, as the counts would grow with every
+    /// URL seen.
+    discovery_counts: Option<HashMap<Url, usize>>,
+    /// Queues each batch of discovered URLs in sorted order rather than as found.
+    sorted_discovery: bool,
+    /// Page URLs listed in the seed's sitemaps, if they were loaded.
+    sitemap_urls: Option<HashSet<Url>>,
+    /// Sitemap URLs found linked from a crawled page, whether or not the crawl's limits let
+    /// them be crawled.
+    linked_sitemap_urls: HashSet<Url>,
 }
 
 impl CrawlContext {
     pub fn new() -> Self {
         Self {
-            urls_to_crawl: HashSet::new(),
-            urls_already_crawled: HashSet::new(),
+            urls_to_crawl: Frontier::new(),
+            shared_frontier: None,
+            urls_already_crawled: VisitedUrls::exact(),
+            shared_visited_set: None,
+            max_depth: None,
+            url_normalizer: UrlNormalizer::default(),
+            trap_detector: None,
+            url_limits: UrlLimits::default(),
+            rejected_url_counts: RejectedUrlCounts::default(),
+            discovery_counts: Some(HashMap::new()),
+            sorted_discovery: false,
+            sitemap_urls: None,
+            linked_sitemap_urls: HashSet::new(),
         }
     }
 
-    pub fn add_url_to_crawl(&mut self, url: &Url) {
+    /// URLs discovered deeper than `max_depth` links from the seed are not crawled.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_url_normalizer(mut self, url_normalizer: UrlNormalizer) -> Self {
+        self.url_normalizer = url_normalizer;
+        self
+    }
+
+    /// Keeps at most `max_in_memory` queued URLs in memory, spilling the rest to a file.
+    /// Discoveries are no longer counted.
+    pub fn with_frontier_spill(mut self, max_in_memory: usize, spill_path: PathBuf) -> Self {
+        self.urls_to_crawl =
+            std::mem::take(&mut self.urls_to_crawl).with_spill(max_in_memory, spill_path);
+        self.discovery_counts = None;
+        self
+    }
+
+    /// Starts spilling queued URLs beyond `max_in_memory` to a file mid-crawl, unless the
+    /// frontier already spills, and stops counting discoveries.
+    pub fn spill_frontier(&mut self, max_in_memory: usize, spill_path: PathBuf) {
+        self.urls_to_crawl.spill_beyond(max_in_memory, spill_path);
+        self.discovery_counts = None;
+    }
+
+    /// Crawls queued URLs in the order `frontier_policy` sets.
+    pub fn with_frontier_policy(mut self, frontier_policy: Box<dyn FrontierPolicy>) -> Self {
+        self.urls_to_crawl = std::mem::take(&mut self.urls_to_crawl).with_policy(frontier_policy);
+        self
+    }
+
+    /// Crawls queued URLs matching any of `priority_patterns` before all others.
+    pub fn with_priority_patterns(mut self, priority_patterns: Vec<UrlPattern>) -> Self {
+        self.urls_to_crawl =
+            std::mem::take(&mut self.urls_to_crawl).with_priority_patterns(priority_patterns);
+        self
+    }
+
+    pub fn with_url_limits(mut self, url_limits: UrlLimits) -> Self {
+        self.url_limits = url_limits;
+        self
+    }
+
+    /// Queues the URLs discovered together in sorted order, so that ties between them are
+    /// broken the same way on every run.
+    pub fn with_sorted_discovery(mut self, sorted_discovery: bool) -> Self {
+        self.sorted_discovery = sorted_discovery;
+        self
+    }
+
+    /// Skips discovered URLs that look like part of a crawler trap, allowing up to
+    /// `family_limit` URLs that differ only in numbers.
+    pub fn with_trap_detection(mut self, family_limit: usize) -> Self {
+        self.trap_detector = Some(TrapDetector::new(family_limit));
+        self
+    }
+
+    /// Remembers crawled URLs in a bloom filter sized for `expected_urls` instead of a set.
+    /// Discoveries are no longer counted.
+    pub fn with_bloom_visited_filter(mut self, expected_urls: usize) -> Self {
+        self.urls_already_crawled = VisitedUrls::bloom(expected_urls);
+        self.discovery_counts = None;
+        self
+    }
+
+    pub fn with_shared_visited_set(mut self, shared_visited_set: Option<SharedVisitedSet>) -> Self {
+        self.shared_visited_set = shared_visited_set;
+        self
+    }
+
+    /// Queues URLs on `shared_frontier` instead of in this process, sharing them with the
+    /// other processes crawling the same seed through it. Checkpoints then hold no queued
+    /// URLs, since the shared frontier outlives the processes.
+    pub fn with_shared_frontier(mut self, shared_frontier: Option<RedisFrontier>) -> Self {
+        self.shared_frontier = shared_frontier;
+        self
+    }
+
+    /// Notes which of the normalized `sitemap_urls` are linked from crawled pages.
+    pub fn with_sitemap_urls(mut self, sitemap_urls: &[Url]) -> Self {
+        self.sitemap_urls = Some(sitemap_urls.iter().cloned().collect());
+        self
+    }
+
+    /// Queues a URL found `depth` links from the seed on the page `discovered_from`.
+    /// If the URL is already queued, the shallowest discovery is kept.
+    pub async fn add_url_to_crawl(
+        &mut self,
+        url: &Url,
+        depth: usize,
+        discovered_from: Option<&Url>,
+    ) -> anyhow::Result<()> {
         let stripped_url = self.strip_url(url);
-        if !self.urls_already_crawled.contains(&stripped_url) {
-            self.urls_to_crawl.insert(stripped_url);
+        self.queue_url(stripped_url, depth, discovered_from).await
+    }
+
+    /// Counts a discovery of a URL being queued now or queued before. Other URLs are left
+    /// out, so that the counts grow no faster than the frontier and the visited set.
+    fn count_discovery(&mut self, stripped_url: &Url, discovered_from: Option<&Url>, queued: bool) {
+        let Some(discovery_counts) = &mut self.discovery_counts else {
+            return;
+        };
+        if !queued && !discovery_counts.contains_key(stripped_url) {
+            return;
+        }
+        let discovery_count = discovery_counts.entry(stripped_url.clone()).or_default();
+        if discovered_from.is_some() {
+            *discovery_count += 1;
+        }
+    }
+
+    async fn queue_url(
+        &mut self,
+        stripped_url: Url,
+        depth: usize,
+        discovered_from: Option<&Url>,
+    ) -> anyhow::Result<()> {
+        let queued_url = self.admit_and_count(stripped_url, depth, discovered_from);
+        self.queue_admitted(queued_url.into_iter().collect()).await
+    }
+
+    /// Admits a discovered URL like `admit`, counting the discovery.
+    fn admit_and_count(
+        &mut self,
+        stripped_url: Url,
+        depth: usize,
+        discovered_from: Option<&Url>,
+    ) -> Option<QueuedUrl> {
+        if discovered_from.is_some() {
+            self.note_sitemap_link(&stripped_url);
+        }
+        let queued_url = self.admit(stripped_url.clone(), depth, discovered_from);
+        self.count_discovery(&stripped_url, discovered_from, queued_url.is_some());
+        queued_url
+    }
+
+    fn note_sitemap_link(&mut self, stripped_url: &Url) {
+        if let Some(sitemap_urls) = &self.sitemap_urls {
+            if sitemap_urls.contains(stripped_url) {
+                self.linked_sitemap_urls.insert(stripped_url.clone());
+            }
+        }
+    }
+
+    /// The URL to queue, unless it is too deep, already crawled or turned away by the URL
+    /// limits or trap detection.
+    fn admit(
+        &mut self,
+        stripped_url: Url,
+        depth: usize,
+        discovered_from: Option<&Url>,
+    ) -> Option<QueuedUrl> {
+        if self.max_depth.is_some_and(|max_depth| depth > max_depth) {
+            return None;
         }
+        if self.urls_already_crawled.contains(&stripped_url) {
+            return None;
+        }
+        // The seed itself is always crawled
+        if depth > 0 {
+            if !self.url_limits.admit(&stripped_url, &mut self.rejected_url_counts) {
+                return None;
+            }
+            if let Some(trap_detector) = &mut self.trap_detector {
+                if !trap_detector.admit(&stripped_url) {
+                    return None;
+                }
+            }
+        }
+        Some(QueuedUrl {
+            url: stripped_url,
+            depth,
+            discovered_from: discovered_from.cloned(),
+        })
+    }
+
+    /// Queues admitted URLs, on the shared frontier in one go.
+    async fn queue_admitted(&mut self, queued_urls: Vec<QueuedUrl>) -> anyhow::Result<()> {
+        match &mut self.shared_frontier {
+            Some(shared_frontier) => shared_frontier.push(&queued_urls).await?,
+            None => {
+                for queued_url in queued_urls {
+                    self.urls_to_crawl.push(queued_url)?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    pub fn add_urls_to_crawl(&mut self, urls: &[Url]) {
+    /// Queues a URL like `add_url_to_crawl`, but keeps its query string regardless of the
+    /// query policy. Used for pagination links, whose query is usually the page number.
+    pub async fn add_url_to_crawl_keeping_query(
+        &mut self,
+        url: &Url,
+        depth: usize,
+        discovered_from: Option<&Url>,
+    ) -> anyhow::Result<()> {
+        let mut url = url.clone();
+        url.set_fragment(None);
+        self.queue_url(url, depth, discovered_from).await
+    }
+
+    pub async fn add_urls_to_crawl(
+        &mut self,
+        urls: &[Url],
+        depth: usize,
+        discovered_from: Option<&Url>,
+    ) -> anyhow::Result<()> {
+        let mut urls = urls.iter().collect::<Vec<_>>();
+        if self.sorted_discovery {
+            urls.sort();
+        }
+        let mut queued_urls = Vec::new();
         for url in urls {
-            self.add_url_to_crawl(url);
+            let stripped_url = self.strip_url(url);
+            queued_urls.extend(self.admit_and_count(stripped_url, depth, discovered_from));
         }
+        self.queue_admitted(queued_urls).await
     }
 
-    pub fn pop_url_to_crawl(&mut self) -> Option<Url> {
-        self.urls_to_crawl.iter().next().cloned().and_then(|url| {self.urls_to_crawl.take(&url)})
+    /// Pops the next URL to crawl, skipping any URL already claimed by another seed crawler.
+    /// With a shared frontier, may return None while other processes crawl the last pages.
+    pub async fn pop_url_to_crawl(&mut self) -> anyhow::Result<Option<QueuedUrl>> {
+        loop {
+            let queued_url = match &mut self.shared_frontier {
+                Some(shared_frontier) => shared_frontier.pop().await?,
+                None => self.urls_to_crawl.pop()?,
+            };
+            let Some(queued_url) = queued_url else {
+                return Ok(None);
+            };
+            match &self.shared_visited_set {
+                Some(shared_visited_set) if !shared_visited_set.try_claim(&queued_url.url) => {
+                    if let Some(shared_frontier) = &mut self.shared_frontier {
+                        shared_frontier.finish(&queued_url).await?;
+                    }
+                    self.urls_already_crawled.insert(queued_url.url);
+                }
+                _ => return Ok(Some(queued_url)),
+            }
+        }
     }
 
+    /// Marks a URL returned by `pop_url_to_crawl` as crawled.
     pub fn mark_url_as_crawled(&mut self, url: &Url) {
-        let stripped_url = self.strip_url(url);
-        self.urls_to_crawl.remove(&stripped_url);
-        self.urls_already_crawled.insert(stripped_url);
+        self.urls_to_crawl.remove(url);
+        self.urls_already_crawled.insert(url.clone());
+    }
+
+    /// Marks a URL returned by `pop_url_to_crawl` as done once the URLs found on its page
+    /// are queued, so that other processes sharing the frontier know whether to wait for
+    /// more.
+    pub async fn finish_url(&mut self, queued_url: &QueuedUrl) -> anyhow::Result<()> {
+        if let Some(shared_frontier) = &mut self.shared_frontier {
+            shared_frontier.finish(queued_url).await?;
+        }
+        Ok(())
+    }
+
+    /// Hands a URL returned by `pop_url_to_crawl` whose crawl was abandoned back to the
+    /// shared frontier, for another process to crawl. Without one it stays with the caller.
+    pub async fn give_back_url(&mut self, queued_url: &QueuedUrl) -> anyhow::Result<()> {
+        if let Some(shared_frontier) = &mut self.shared_frontier {
+            shared_frontier.give_back(queued_url).await?;
+        }
+        Ok(())
+    }
+
+    /// The URLs still to crawl and, if kept exactly, the URLs already crawled.
+    pub fn checkpoint_state(&mut self) -> anyhow::Result<(Vec<QueuedUrl>, Option<Vec<Url>>)> {
+        Ok((self.urls_to_crawl.pending()?, self.urls_already_crawled.urls()))
+    }
+
+    /// Restores the state of an interrupted crawl saved by `checkpoint_state` and
+    /// `discovery_counts`.
+    pub async fn restore(
+        &mut self,
+        pending: Vec<QueuedUrl>,
+        crawled: Vec<Url>,
+        discovery_counts: HashMap<Url, usize>,
+    ) -> anyhow::Result<()> {
+        for (url, discovery_count) in &discovery_counts {
+            if *discovery_count > 0 {
+                self.note_sitemap_link(url);
+            }
+        }
+        if self.discovery_counts.is_some() {
+            self.discovery_counts = Some(discovery_counts);
+        }
+        for url in crawled {
+            if let Some(shared_visited_set) = &self.shared_visited_set {
+                shared_visited_set.try_claim(&url);
+            }
+            self.urls_already_crawled.insert(url);
+        }
+        let mut queued_urls = Vec::new();
+        for queued_url in pending {
+            let discovered_from = queued_url.discovered_from.as_ref();
+            queued_urls.extend(self.admit(queued_url.url, queued_url.depth, discovered_from));
+        }
+        self.queue_admitted(queued_urls).await
     }
 
-    pub fn is_crawling_complete(&self) -> bool {
-        self.urls_to_crawl.is_empty()
+    pub async fn is_crawling_complete(&mut self) -> anyhow::Result<bool> {
+        match &mut self.shared_frontier {
+            Some(shared_frontier) => shared_frontier.is_complete().await,
+            None => Ok(self.urls_to_crawl.is_empty()),
+        }
     }
 
     pub fn progress(&self) -> (usize, usize) {
-        let num_urls_to_crawl = self.urls_to_crawl.len();
+        let num_urls_to_crawl = match &self.shared_frontier {
+            Some(shared_frontier) => shared_frontier.num_queued(),
+            None => self.urls_to_crawl.len(),
+        };
         let num_urls_crawled = self.urls_already_crawled.len();
         (num_urls_to_crawl, num_urls_crawled)
     }
 
-    /// Strips the URL of its fragment and query components.
-    fn strip_url(&self, url: &Url) -> Url {
-        let mut stripped_url = url.clone();
-        stripped_url.set_fragment(None);
-        stripped_url.set_query(None);
-        stripped_url
+    pub fn visited_filter_stats(&self) -> Option<VisitedFilterStats> {
+        self.urls_already_crawled.filter_stats()
+    }
+
+    /// Number of times each queued URL was found linked from a crawled page so far, unless
+    /// discoveries aren't counted.
+    pub fn discovery_counts(&self) -> Option<&HashMap<Url, usize>> {
+        self.discovery_counts.as_ref()
+    }
+
+    /// Sitemap URLs linked from a crawled page so far.
+    pub fn linked_sitemap_urls(&self) -> Vec<Url> {
+        self.linked_sitemap_urls.iter().cloned().collect()
+    }
+
+    /// Number of discovered URLs turned away by the URL limits.
+    pub fn rejected_url_counts(&self) -> RejectedUrlCounts {
+        self.rejected_url_counts
+    }
+
+    /// URL families turned away as suspected crawler traps.
+    pub fn suspected_traps(&self) -> Vec<SuspectedTrap> {
+        self.trap_detector
+            .as_ref()
+            .map_or_else(Vec::new, TrapDetector::suspected_traps)
+    }
+
+    /// Strips the URL of its fragment and applies the crawl's query policy.
+    pub fn strip_url(&self, url: &Url) -> Url {
+        self.url_normalizer.normalize(url)
     }
 }
 
@@ -61,4 +415,24 @@ impl Default for CrawlContext {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn notes_linked_sitemap_urls_beyond_the_maximum_depth() {
+        let url = |path: &str| Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        let mut crawl_context = CrawlContext::new()
+            .with_max_depth(1)
+            .with_sitemap_urls(&[url("/a"), url("/b"), url("/c")]);
+        crawl_context.add_url_to_crawl(&url("/"), 0, None).await.unwrap();
+        crawl_context.add_urls_to_crawl(&[url("/a")], 1, Some(&url("/"))).await.unwrap();
+        crawl_context.add_urls_to_crawl(&[url("/b")], 2, Some(&url("/a"))).await.unwrap();
+
+        let mut linked_sitemap_urls = crawl_context.linked_sitemap_urls();
+        linked_sitemap_urls.sort();
+        assert_eq!(linked_sitemap_urls, vec![url("/a"), url("/b")]);
+        assert_eq!(crawl_context.progress(), (2, 0));
+    }
+}
@@ -0,0 +1,290 @@
+use crate::crawler::scope::UrlPattern;
+use crate::crawler::seed::bloom_filter::BloomFilter;
+use crate::crawler::seed::frontier_policy::{BreadthFirst, FrontierPolicy, FrontierPriority};
+use crate::crawler::seed::queued_url::QueuedUrl;
+use crate::crawler::seed::spill_file::SpillFile;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::io;
+use std::path::PathBuf;
+use url::Url;
+
+/// Expected number of spilled URLs the spill filter is sized for.
+const SPILL_FILTER_EXPECTED_URLS: usize = 1_000_000;
+
+/// False-positive rate of the spill filter; a false positive drops a newly discovered URL.
+const SPILL_FILTER_FALSE_POSITIVE_RATE: f64 = 0.001;
+
+/// A URL held in memory, with what its priority is worked out from.
+struct FrontierEntry {
+    queued_url: QueuedUrl,
+    num_inlinks: usize,
+    /// Order of first discovery, which breaks ties between equal priorities.
+    sequence: u64,
+    priority: FrontierPriority,
+}
+
+/// URLs waiting to be crawled, keyed by their normalized form, and handed out in the order
+/// set by a frontier policy (breadth-first by default). URLs matching a priority pattern
+/// are handed out before all others, in the same order among themselves.
+///
+/// With a memory limit, URLs queued beyond it are appended to a spill file and read back
+/// once the in-memory queue drains. Spilled URLs are remembered in a bloom filter rather
+/// than a set, so rediscovering one costs no memory but no longer updates its depth, and
+/// the policy only orders the URLs in memory.
+pub struct Frontier {
+    in_memory: HashMap<Url, FrontierEntry>,
+    /// Queued URLs by priority. An entry whose priority has since changed is stale and
+    /// skipped, since a heap cannot update entries in place.
+    /// Keyed first by whether the URL misses every priority pattern, so matches come first.
+    by_priority: BinaryHeap<Reverse<(bool, FrontierPriority, u64, Url)>>,
+    policy: Box<dyn FrontierPolicy>,
+    priority_patterns: Vec<UrlPattern>,
+    next_sequence: u64,
+    max_in_memory: Option<usize>,
+    spill_path: Option<PathBuf>,
+    spill_file: Option<SpillFile>,
+    spilled: Option<BloomFilter>,
+}
+
+impl Frontier {
+    pub fn new() -> Self {
+        Self {
+            in_memory: HashMap::new(),
+            by_priority: BinaryHeap::new(),
+            policy: Box::new(BreadthFirst),
+            priority_patterns: Vec::new(),
+            next_sequence: 0,
+            max_in_memory: None,
+            spill_path: None,
+            spill_file: None,
+            spilled: None,
+        }
+    }
+
+    /// Keeps at most `max_in_memory` URLs in memory, spilling the rest to `spill_path`.
+    pub fn with_spill(mut self, max_in_memory: usize, spill_path: PathBuf) -> Self {
+        self.max_in_memory = Some(max_in_memory.max(1));
+        self.spill_path = Some(spill_path);
+        self
+    }
+
+    /// Starts spilling URLs queued beyond `max_in_memory` to `spill_path`, unless the
+    /// frontier already spills. URLs already in memory stay there.
+    pub fn spill_beyond(&mut self, max_in_memory: usize, spill_path: PathBuf) {
+        if self.max_in_memory.is_none() {
+            self.max_in_memory = Some(max_in_memory.max(1));
+            self.spill_path = Some(spill_path);
+        }
+    }
+
+    pub fn with_policy(mut self, policy: Box<dyn FrontierPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_priority_patterns(mut self, priority_patterns: Vec<UrlPattern>) -> Self {
+        self.priority_patterns = priority_patterns;
+        self
+    }
+
+    /// Queues a URL. If it is already queued in memory, the shallowest discovery is kept and
+    /// the new link is counted towards its inlinks.
+    pub fn push(&mut self, queued_url: QueuedUrl) -> io::Result<()> {
+        if let Some(existing) = self.in_memory.get_mut(&queued_url.url) {
+            existing.num_inlinks += 1;
+            if queued_url.depth < existing.queued_url.depth {
+                existing.queued_url = queued_url;
+            }
+            let priority = self.policy.priority(
+                &existing.queued_url,
+                existing.num_inlinks,
+                existing.sequence,
+            );
+            if priority != existing.priority {
+                existing.priority = priority;
+                let url = existing.queued_url.url.clone();
+                let sequence = existing.sequence;
+                self.by_priority
+                    .push(Reverse((!self.is_prioritized(&url), priority, sequence, url)));
+            }
+            return Ok(());
+        }
+        if self
+            .spilled
+            .as_ref()
+            .is_some_and(|spilled| spilled.contains(&queued_url.url))
+        {
+            return Ok(());
+        }
+
+        match (self.max_in_memory, &self.spill_path) {
+            (Some(max_in_memory), Some(spill_path)) if self.in_memory.len() >= max_in_memory => {
+                if self.spill_file.is_none() {
+                    self.spill_file = Some(SpillFile::create(spill_path.clone())?);
+                }
+                if let Some(spill_file) = &mut self.spill_file {
+                    spill_file.push(&queued_url)?;
+                }
+                self.spilled
+                    .get_or_insert_with(|| {
+                        BloomFilter::new(SPILL_FILTER_EXPECTED_URLS, SPILL_FILTER_FALSE_POSITIVE_RATE)
+                    })
+                    .insert(&queued_url.url);
+            }
+            _ => self.insert_in_memory(queued_url),
+        }
+        Ok(())
+    }
+
+    fn insert_in_memory(&mut self, queued_url: QueuedUrl) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        let num_inlinks = usize::from(queued_url.discovered_from.is_some());
+        let priority = self.policy.priority(&queued_url, num_inlinks, sequence);
+        self.by_priority.push(Reverse((
+            !self.is_prioritized(&queued_url.url),
+            priority,
+            sequence,
+            queued_url.url.clone(),
+        )));
+        self.in_memory.insert(
+            queued_url.url.clone(),
+            FrontierEntry {
+                queued_url,
+                num_inlinks,
+                sequence,
+                priority,
+            },
+        );
+    }
+
+    /// Removes and returns the queued URL with the highest priority, refilling memory from
+    /// the spill file when needed.
+    pub fn pop(&mut self) -> io::Result<Option<QueuedUrl>> {
+        if self.in_memory.is_empty() {
+            self.by_priority.clear();
+            let refill = match &mut self.spill_file {
+                // Refill halfway so newly discovered URLs don't immediately spill again
+                Some(spill_file) => {
+                    spill_file.pop_batch(self.max_in_memory.unwrap_or(1).div_ceil(2))?
+                }
+                None => Vec::new(),
+            };
+            for queued_url in refill {
+                if !self.in_memory.contains_key(&queued_url.url) {
+                    self.insert_in_memory(queued_url);
+                }
+            }
+        }
+        while let Some(Reverse((_, priority, _, url))) = self.by_priority.pop() {
+            let is_current = self
+                .in_memory
+                .get(&url)
+                .is_some_and(|entry| entry.priority == priority);
+            if is_current {
+                return Ok(self.in_memory.remove(&url).map(|entry| entry.queued_url));
+            }
+        }
+        Ok(None)
+    }
+
+    fn is_prioritized(&self, url: &Url) -> bool {
+        self.priority_patterns.iter().any(|pattern| pattern.matches(url))
+    }
+
+    /// Lists every queued URL, in memory or spilled, without dequeuing any. URLs in memory
+    /// are listed in discovery order, so restoring them keeps their ties broken the same way.
+    pub fn pending(&mut self) -> io::Result<Vec<QueuedUrl>> {
+        let mut entries = self.in_memory.values().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.sequence);
+        let mut pending = entries
+            .into_iter()
+            .map(|entry| entry.queued_url.clone())
+            .collect::<Vec<_>>();
+        if let Some(spill_file) = &mut self.spill_file {
+            pending.extend(spill_file.pending()?);
+        }
+        Ok(pending)
+    }
+
+    pub fn remove(&mut self, url: &Url) {
+        self.in_memory.remove(url);
+    }
+
+    pub fn len(&self) -> usize {
+        self.in_memory.len() + self.spill_file.as_ref().map_or(0, SpillFile::num_pending)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for Frontier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::seed::CrawlStrategy;
+
+    fn queued_url(path: &str, depth: usize) -> QueuedUrl {
+        let base = Url::parse("https://example.com/").unwrap();
+        QueuedUrl {
+            url: base.join(path).unwrap(),
+            depth,
+            discovered_from: Some(base),
+        }
+    }
+
+    fn crawl_order(strategy: CrawlStrategy, queued_urls: &[(&str, usize)]) -> Vec<String> {
+        crawl_frontier(Frontier::new().with_policy(strategy.frontier_policy()), queued_urls)
+    }
+
+    fn crawl_frontier(mut frontier: Frontier, queued_urls: &[(&str, usize)]) -> Vec<String> {
+        for (path, depth) in queued_urls {
+            frontier.push(queued_url(path, *depth)).unwrap();
+        }
+        let mut order = Vec::new();
+        while let Some(queued_url) = frontier.pop().unwrap() {
+            order.push(queued_url.url.path().to_string());
+        }
+        order
+    }
+
+    #[test]
+    fn hands_out_urls_in_the_order_of_the_strategy() {
+        let queued_urls = [("/a/long/page", 2), ("/b", 1), ("/c", 2), ("/c", 2), ("/dd", 1)];
+        assert_eq!(
+            crawl_order(CrawlStrategy::Bfs, &queued_urls),
+            vec!["/b", "/dd", "/a/long/page", "/c"]
+        );
+        assert_eq!(
+            crawl_order(CrawlStrategy::Dfs, &queued_urls),
+            vec!["/dd", "/c", "/b", "/a/long/page"]
+        );
+        assert_eq!(
+            crawl_order(CrawlStrategy::ShortestUrlFirst, &queued_urls),
+            vec!["/b", "/c", "/dd", "/a/long/page"]
+        );
+        assert_eq!(
+            crawl_order(CrawlStrategy::DepthInlinks, &queued_urls),
+            vec!["/b", "/dd", "/c", "/a/long/page"]
+        );
+    }
+
+    #[test]
+    fn hands_out_urls_matching_a_priority_pattern_first() {
+        let frontier = Frontier::new()
+            .with_priority_patterns(vec!["/docs/*".parse().unwrap(), "/*.pdf$".parse().unwrap()]);
+        let queued_urls = [("/blog/a", 1), ("/docs/b", 3), ("/c.pdf", 2), ("/d", 1)];
+        assert_eq!(
+            crawl_frontier(frontier, &queued_urls),
+            vec!["/c.pdf", "/docs/b", "/blog/a", "/d"]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::crawler::seed::queued_url::QueuedUrl;
+
+/// Sort key of a queued URL: the frontier crawls the URL with the lowest key first, and
+/// URLs with equal keys in the order they were discovered.
+pub type FrontierPriority = (i64, i64);
+
+/// Decides the order in which a seed's frontier hands out its queued URLs.
+pub trait FrontierPolicy: Send + Sync {
+    /// The priority of `queued_url`, linked from `num_inlinks` crawled pages so far and
+    /// discovered as the frontier's `sequence`th URL.
+    fn priority(&self, queued_url: &QueuedUrl, num_inlinks: usize, sequence: u64)
+        -> FrontierPriority;
+}
+
+/// Crawls shallower URLs first, level by level.
+pub struct BreadthFirst;
+
+impl FrontierPolicy for BreadthFirst {
+    fn priority(&self, queued_url: &QueuedUrl, _: usize, _: u64) -> FrontierPriority {
+        (queued_url.depth as i64, 0)
+    }
+}
+
+/// Crawls the most recently discovered URL first, following one branch to the bottom.
+pub struct DepthFirst;
+
+impl FrontierPolicy for DepthFirst {
+    fn priority(&self, _: &QueuedUrl, _: usize, sequence: u64) -> FrontierPriority {
+        (-(sequence as i64), 0)
+    }
+}
+
+/// Crawls shorter URLs first, which tend to be overview and section pages.
+pub struct ShortestUrlFirst;
+
+impl FrontierPolicy for ShortestUrlFirst {
+    fn priority(&self, queued_url: &QueuedUrl, _: usize, _: u64) -> FrontierPriority {
+        (queued_url.url.as_str().len() as i64, 0)
+    }
+}
+
+/// Crawls shallower URLs first and, at each depth, the most linked-to ones first.
+pub struct DepthThenInlinks;
+
+impl FrontierPolicy for DepthThenInlinks {
+    fn priority(&self, queued_url: &QueuedUrl, num_inlinks: usize, _: u64) -> FrontierPriority {
+        (queued_url.depth as i64, -(num_inlinks as i64))
+    }
+}
+
+/// The order URLs are crawled in, chosen with `--strategy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum CrawlStrategy {
+    /// Breadth-first: shallower pages first
+    #[default]
+    Bfs,
+    /// Depth-first: the most recently discovered page first
+    Dfs,
+    /// Shorter URLs first
+    ShortestUrlFirst,
+    /// Shallower pages first, and the pages with most links to them first at each depth
+    DepthInlinks,
+}
+
+impl CrawlStrategy {
+    pub fn frontier_policy(&self) -> Box<dyn FrontierPolicy> {
+        match self {
+            CrawlStrategy::Bfs => Box::new(BreadthFirst),
+            CrawlStrategy::Dfs => Box::new(DepthFirst),
+            CrawlStrategy::ShortestUrlFirst => Box::new(ShortestUrlFirst),
+            CrawlStrategy::DepthInlinks => Box::new(DepthThenInlinks),
+        }
+    }
+}
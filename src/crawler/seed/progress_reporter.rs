@@ -1,9 +1,15 @@
 use crate::console::crawler_state::CrawlerState;
+use crate::crawler::rate_limit::CircuitState;
+use std::time::Duration;
+use url::Url;
 
 pub trait ProgressReporter {
     fn begin(&self);
-    fn progress_update(&self, num_urls_to_crawl: usize, num_urls_crawled: usize);
+    fn progress_update(&self, num_urls_to_crawl: usize, num_urls_crawled: usize, elapsed: Duration);
     fn progress_message(&self, message: &str);
+    fn page_completed(&self, url: &Url, status_code: u16, depth: usize, duration: Duration);
     fn crawler_state_changed(&self, state: CrawlerState);
+    fn rate_changed(&self, requests_per_second: f64);
+    fn circuit_state_changed(&self, host: &str, state: CircuitState);
     fn end(&self);
 }
@@ -0,0 +1,11 @@
+use url::Url;
+
+/// A URL waiting in the crawl frontier.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QueuedUrl {
+    pub url: Url,
+    /// Number of links followed from the seed to reach this URL.
+    pub depth: usize,
+    /// The page this URL was first discovered on, or None for the seed.
+    pub discovered_from: Option<Url>,
+}
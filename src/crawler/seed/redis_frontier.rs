@@ -0,0 +1,276 @@
+use crate::crawler::distributed::{RedisConnection, RedisValue};
+use crate::crawler::seed::queued_url::QueuedUrl;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// How long a process may take over a page before its URL is handed out again, e.g.
+/// because the process died while crawling it.
+const PAGE_LEASE: Duration = Duration::from_secs(600);
+
+/// How long the keys of a crawl outlive its last activity, so that those of crawls whose
+/// processes were all stopped don't stay on the server forever.
+const IDLE_TTL: Duration = Duration::from_secs(7 * 86_400);
+
+/// How long to wait before looking again when the frontier is empty but other processes
+/// are still crawling pages, which may queue more.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Queues each of the URLs not seen before. KEYS: seen, queued. ARGV: the idle TTL in
+/// seconds, then each URL followed by its queued form.
+const PUSH_SCRIPT: &[u8] = br#"
+for i = 2, #ARGV, 2 do
+    if redis.call('SADD', KEYS[1], ARGV[i]) == 1 then
+        redis.call('RPUSH', KEYS[2], ARGV[i + 1])
+    end
+end
+redis.call('EXPIRE', KEYS[1], ARGV[1])
+if redis.call('EXISTS', KEYS[2]) == 1 then
+    redis.call('EXPIRE', KEYS[2], ARGV[1])
+end
+"#;
+
+/// Takes the next queued URL and leases it. KEYS: queued, leased. ARGV: the lease's end,
+/// the idle TTL in seconds.
+const POP_SCRIPT: &[u8] = br#"
+local queued_url = redis.call('LPOP', KEYS[1])
+if queued_url then
+    redis.call('ZADD', KEYS[2], ARGV[1], queued_url)
+    redis.call('EXPIRE', KEYS[2], ARGV[2])
+end
+return queued_url
+"#;
+
+/// Queues a leased URL again, at the front. KEYS: leased, queued. ARGV: the URL.
+const GIVE_BACK_SCRIPT: &[u8] = br#"
+if redis.call('ZREM', KEYS[1], ARGV[1]) == 1 then
+    redis.call('LPUSH', KEYS[2], ARGV[1])
+end
+"#;
+
+/// Queues again the URLs whose lease ran out and counts the queued and leased URLs. Once
+/// both are none the crawl is over, and its seen URLs are forgotten so that the seed can be
+/// crawled afresh. KEYS: queued, leased, seen. ARGV: the current time.
+const CHECK_SCRIPT: &[u8] = br#"
+local expired = redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', ARGV[1])
+for _, queued_url in ipairs(expired) do
+    redis.call('ZREM', KEYS[2], queued_url)
+    redis.call('LPUSH', KEYS[1], queued_url)
+end
+local num_queued = redis.call('LLEN', KEYS[1])
+local num_leased = redis.call('ZCARD', KEYS[2])
+if num_queued == 0 and num_leased == 0 then
+    redis.call('DEL', KEYS[3])
+end
+return {num_queued, num_leased}
+"#;
+
+/// A frontier on a Redis server, shared by every crawler process crawling the same seed
+/// with the same server and key prefix, so that they split its pages between them. Under
+/// the prefix `NAME`:
+///
+/// - `NAME:queued` lists the URLs waiting to be crawled, handed out in discovery order
+/// - `NAME:seen` is the set of URLs queued during the crawl, so no URL is queued twice
+/// - `NAME:leased` holds the URLs being crawled, scored by when their lease runs out
+///
+/// Each step is a script, so it takes one round trip and other processes never see it half
+/// done. The keys go once the crawl is over, and a later crawl of the seed starts afresh;
+/// those of a crawl abandoned midway expire after a week.
+///
+/// Strategies and priority patterns don't apply; limits such as the maximum number of
+/// pages apply to each process separately.
+pub struct RedisFrontier {
+    redis_url: Url,
+    key_prefix: String,
+    connection: Option<RedisConnection>,
+    /// Length of the shared queue when last looked at.
+    num_queued: usize,
+}
+
+impl RedisFrontier {
+    /// Connects to the server lazily, on first use.
+    pub fn new(redis_url: Url, key_prefix: String) -> Self {
+        Self {
+            redis_url,
+            key_prefix,
+            connection: None,
+            num_queued: 0,
+        }
+    }
+
+    fn key(&self, suffix: &str) -> Vec<u8> {
+        format!("{}:{}", self.key_prefix, suffix).into_bytes()
+    }
+
+    async fn command(&mut self, args: &[&[u8]]) -> anyhow::Result<RedisValue> {
+        let mut connection = match self.connection.take() {
+            Some(connection) => connection,
+            None => RedisConnection::connect(&self.redis_url).await?,
+        };
+        // A connection that failed is not reused
+        let value = connection.command(args).await?;
+        self.connection = Some(connection);
+        Ok(value)
+    }
+
+    /// Runs a script with its keys, named by suffix, and arguments.
+    async fn eval(
+        &mut self,
+        script: &[u8],
+        keys: &[&str],
+        args: &[&[u8]],
+    ) -> anyhow::Result<RedisValue> {
+        let keys = keys.iter().map(|suffix| self.key(suffix)).collect::<Vec<_>>();
+        let num_keys = keys.len().to_string();
+        let mut command: Vec<&[u8]> = vec![b"EVAL", script, num_keys.as_bytes()];
+        command.extend(keys.iter().map(Vec::as_slice));
+        command.extend_from_slice(args);
+        self.command(&command).await
+    }
+
+    /// Queues the URLs that no process queued before.
+    pub async fn push(&mut self, queued_urls: &[QueuedUrl]) -> anyhow::Result<()> {
+        if queued_urls.is_empty() {
+            return Ok(());
+        }
+        let idle_ttl = IDLE_TTL.as_secs().to_string();
+        let queued_forms = queued_urls
+            .iter()
+            .map(serde_json::to_vec)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut args: Vec<&[u8]> = vec![idle_ttl.as_bytes()];
+        for (queued_url, queued_form) in queued_urls.iter().zip(&queued_forms) {
+            args.push(queued_url.url.as_str().as_bytes());
+            args.push(queued_form);
+        }
+        self.eval(PUSH_SCRIPT, &["seen", "queued"], &args).await?;
+        Ok(())
+    }
+
+    /// Takes the next URL to crawl, leasing it to this process until `finish` is called for
+    /// it. When the queue is empty, waits a moment first so that callers can poll.
+    pub async fn pop(&mut self) -> anyhow::Result<Option<QueuedUrl>> {
+        let lease_end = (now_millis() + PAGE_LEASE.as_millis()).to_string();
+        let idle_ttl = IDLE_TTL.as_secs().to_string();
+        let args: [&[u8]; 2] = [lease_end.as_bytes(), idle_ttl.as_bytes()];
+        let queued_url = self.eval(POP_SCRIPT, &["queued", "leased"], &args).await?;
+        let Some(data) = queued_url.into_data()? else {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&data)?))
+    }
+
+    /// Ends the lease of a URL returned by `pop` once its page is crawled and the URLs found
+    /// on it are queued.
+    pub async fn finish(&mut self, queued_url: &QueuedUrl) -> anyhow::Result<()> {
+        // URLs serialize the same way every time, so this matches the leased copy
+        let leased_key = self.key("leased");
+        self.command(&[b"ZREM", &leased_key, &serde_json::to_vec(queued_url)?]).await?;
+        Ok(())
+    }
+
+    /// Hands a URL returned by `pop` back to the front of the queue, uncrawled.
+    pub async fn give_back(&mut self, queued_url: &QueuedUrl) -> anyhow::Result<()> {
+        let data = serde_json::to_vec(queued_url)?;
+        self.eval(GIVE_BACK_SCRIPT, &["leased", "queued"], &[&data]).await?;
+        Ok(())
+    }
+
+    /// Whether the queue is empty with no page being crawled by any process. URLs whose
+    /// lease ran out are queued again first.
+    pub async fn is_complete(&mut self) -> anyhow::Result<bool> {
+        let now = now_millis().to_string();
+        let counts = self
+            .eval(CHECK_SCRIPT, &["queued", "leased", "seen"], &[now.as_bytes()])
+            .await?
+            .into_array()?
+            .into_iter()
+            .map(RedisValue::into_integer)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let [num_queued, num_leased] = counts[..] else {
+            return Err(anyhow::anyhow!("Unexpected reply from Redis: {:?}", counts));
+        };
+        self.num_queued = usize::try_from(num_queued).unwrap_or_default();
+        Ok(num_queued == 0 && num_leased == 0)
+    }
+
+    /// Number of URLs waiting in the shared queue, as of the last `is_complete`.
+    pub fn num_queued(&self) -> usize {
+        self.num_queued
+    }
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockRedisServer;
+
+    fn queued_url(path: &str, depth: usize) -> QueuedUrl {
+        QueuedUrl {
+            url: Url::parse(&format!("https://example.com{}", path)).unwrap(),
+            depth,
+            discovered_from: None,
+        }
+    }
+
+    fn frontier(server: &MockRedisServer) -> RedisFrontier {
+        RedisFrontier::new(server.url().clone(), "test".to_string())
+    }
+
+    #[tokio::test]
+    async fn processes_share_the_queue_and_never_queue_a_url_twice() {
+        let server = MockRedisServer::start().await.unwrap();
+        let mut first = frontier(&server);
+        let mut second = frontier(&server);
+        first.push(&[queued_url("/", 0), queued_url("/a", 1)]).await.unwrap();
+        second.push(&[queued_url("/a", 1), queued_url("/b", 1)]).await.unwrap();
+
+        let popped = first.pop().await.unwrap().unwrap();
+        assert_eq!(popped.url.path(), "/");
+        assert_eq!(second.pop().await.unwrap().unwrap().url.path(), "/a");
+        assert!(!first.is_complete().await.unwrap());
+        assert_eq!(first.num_queued(), 1);
+
+        first.finish(&popped).await.unwrap();
+        let popped = first.pop().await.unwrap().unwrap();
+        assert_eq!(popped.url.path(), "/b");
+        first.give_back(&popped).await.unwrap();
+        assert_eq!(second.pop().await.unwrap().unwrap().url.path(), "/b");
+    }
+
+    #[tokio::test]
+    async fn forgets_the_crawl_once_it_is_complete() {
+        let server = MockRedisServer::start().await.unwrap();
+        let mut frontier = frontier(&server);
+        frontier.push(&[queued_url("/", 0)]).await.unwrap();
+        let popped = frontier.pop().await.unwrap().unwrap();
+        assert!(!frontier.is_complete().await.unwrap());
+        assert_eq!(server.keys(), vec!["test:leased", "test:seen"]);
+
+        frontier.finish(&popped).await.unwrap();
+        assert!(frontier.is_complete().await.unwrap());
+        assert!(server.keys().is_empty());
+        // A later crawl of the seed starts afresh
+        frontier.push(&[queued_url("/", 0)]).await.unwrap();
+        assert_eq!(frontier.pop().await.unwrap().unwrap().url.path(), "/");
+    }
+
+    #[tokio::test]
+    async fn requeues_urls_whose_lease_ran_out() {
+        let server = MockRedisServer::start().await.unwrap();
+        let mut frontier = frontier(&server);
+        let data = serde_json::to_vec(&queued_url("/", 0)).unwrap();
+        server.command(&[b"ZADD", b"test:leased", b"0", &data]);
+
+        assert!(!frontier.is_complete().await.unwrap());
+        assert_eq!(frontier.num_queued(), 1);
+        assert_eq!(frontier.pop().await.unwrap().unwrap().url.path(), "/");
+    }
+}
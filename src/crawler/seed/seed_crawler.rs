@@ -1,32 +1,81 @@
+use crate::crawler::checkpoint::{CheckpointFile, SeedCheckpoint, VersionedState};
+use crate::crawler::content::ContentHandlers;
 use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::events::CrawlEvent;
 use crate::crawler::crawl_summary::CrawlSummary;
 use crate::crawler::crawler_config::CrawlerConfig;
 use crate::console::crawler_state::CrawlerState;
+use crate::crawler::http::{CacheHeaders, PrivateNetworkGuard};
 use crate::crawler::page::PageCrawler;
+use crate::crawler::normalize::UrlNormalizer;
 use crate::crawler::page_summary::PageSummary;
+use crate::crawler::rate_limit::{CircuitState, HostCircuitBreaker};
 use crate::crawler::seed::progress_reporter::ProgressReporter;
+use crate::crawler::robots::RobotsTxtCache;
 use crate::crawler::robots::RobotsTxtMatcher;
 use crate::crawler::robots::RobotsTxtSource;
+use crate::crawler::robots::ROBOTS_AGENT;
+use crate::crawler::scope::ScopePolicy;
 use crate::crawler::seed::crawl_context::CrawlContext;
-use std::sync::atomic::AtomicBool;
+use crate::crawler::seed::queued_url::QueuedUrl;
+use crate::crawler::seed::redis_frontier::RedisFrontier;
+use crate::crawler::shared_crawl_state::SharedCrawlState;
+use crate::crawler::feed::FeedLoader;
+use crate::crawler::link_anchor::LinkAnchor;
+use crate::crawler::memory_guard::MemoryGuard;
+use crate::crawler::multi::new_run_id;
+use crate::crawler::sitemap::SitemapLoader;
+use crate::crawler::text::TextStats;
+use crate::crawler::visited_filter::VisitedFilter;
+use crate::crawler::soft_404_detector::Soft404Detector;
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+use std::collections::{HashSet, VecDeque};
+use std::hash::{BuildHasher, RandomState};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
+/// Queued URLs a seed keeps in memory once memory runs low, if no frontier limit was set.
+const DEGRADED_FRONTIER_MEMORY_LIMIT: usize = 10_000;
+
 enum PageCrawlOutput {
-    NoMoreUrlsToCrawl,
     DeniedByRobotsTxt(Url),
     HttpNotFound(Url),
     HttpError(Url, u16),
+    /// The body exceeded the maximum body size; holds the response status code.
+    TooLarge(Url, u16),
+    /// No content handler parses the content type; holds the status code, content type and
+    /// caching headers.
+    UnsupportedContentType(Url, u16, String, CacheHeaders),
+    /// A redirect that was not followed; holds the status code and the redirect's target.
+    Redirect(Url, u16, Url),
+    /// The crawl was cancelled before the page was fetched; holds the page to crawl on resume.
+    Cancelled(QueuedUrl),
+    /// A response middleware kept the page from being parsed; holds the status code and why.
+    Skipped(Url, u16, String),
+    /// Fetching and parsing the page took longer than the page timeout.
+    Timeout(Url),
+    /// The request timed out or could not connect, so there is no status code.
+    NoResponse(Url),
     Success(PageSummary),
+    /// Crawled, but excluded from the results by a noindex directive.
+    NoIndex(PageSummary),
 }
 
 pub struct SeedCrawler<TP>
 where
     TP: ProgressReporter,
 {
-    shutdown_notify: Arc<tokio::sync::Notify>,
+    cancel_token: CancellationToken,
     seed: Url,
     progress_reporter: TP,
+    shared_state: SharedCrawlState,
+    /// IDs of the run and of this seed's crawl, attached to its pages and events.
+    run_id: String,
+    seed_id: String,
 }
 
 impl<TP> SeedCrawler<TP>
@@ -34,112 +83,628 @@ where
     TP: ProgressReporter,
 {
     pub fn new(
-        shutdown_notify: Arc<tokio::sync::Notify>,
+        cancel_token: CancellationToken,
         seed: Url,
         progress_reporter: TP,
+        shared_state: SharedCrawlState,
     ) -> Self {
+        let run_id = new_run_id();
         Self {
-            shutdown_notify,
+            cancel_token,
             //index,
             seed,
             progress_reporter,
+            shared_state,
+            seed_id: format!("{}-1", run_id),
+            run_id,
         }
     }
 
-    pub async fn crawl(&self, config: CrawlerConfig) -> anyhow::Result<CrawlSummary> {
-        let shutdown_requested = Arc::new(AtomicBool::new(false));
-        {
-            let shutdown_notify = Arc::clone(&self.shutdown_notify);
-            let shutdown_requested = Arc::clone(&shutdown_requested);
-            tokio::task::spawn(async move {
-                shutdown_notify.notified().await;
-                shutdown_requested.store(true, std::sync::atomic::Ordering::Relaxed);
-            });
-        }
+    /// Identifies the crawl as the seed `seed_id` of the run `run_id`, instead of as the only
+    /// seed of a run of its own.
+    pub fn with_crawl_ids(mut self, run_id: String, seed_id: String) -> Self {
+        self.run_id = run_id;
+        self.seed_id = seed_id;
+        self
+    }
 
+    pub async fn crawl(&self, config: CrawlerConfig) -> anyhow::Result<CrawlSummary> {
         self.progress_reporter.begin();
-
-        let crawl_delay: Option<tokio::time::Duration> = {
-            if let Some(requests_per_second) = config.requests_per_second() {
-                let crawl_delay_in_ms = (1000.0 / requests_per_second) as u64;
-                Some(tokio::time::Duration::from_millis(crawl_delay_in_ms))
-            } else {
-                None
+        let crawl_start = tokio::time::Instant::now();
+        let deadline = {
+            let seed_deadline = config
+                .max_seed_duration()
+                .map(|max_seed_duration| crawl_start + max_seed_duration);
+            match (seed_deadline, self.shared_state.deadline) {
+                (Some(seed_deadline), Some(deadline)) => Some(seed_deadline.min(deadline)),
+                (seed_deadline, deadline) => seed_deadline.or(deadline),
             }
         };
 
         let seed_url = self.seed.clone();
-        let robots_txt_source = RobotsTxtSource::load_from_url(&seed_url, "rusty-spider").await?;
+        if !config.allow_private_networks() && PrivateNetworkGuard::is_private_url(&seed_url) {
+            return Err(anyhow::anyhow!(
+                "Refusing to crawl the private address {} (use --allow-private-networks to allow this)",
+                seed_url
+            ));
+        }
+        let robots_txt_source = RobotsTxtSource::load_from_url(
+            &self.shared_state.http_client,
+            &seed_url,
+            ROBOTS_AGENT,
+        ).await?;
         let robots_txt_view = robots_txt_source.view();
         let robots_txt_matcher = robots_txt_view.matcher();
+        let mut robots_txt_cache =
+            RobotsTxtCache::new(self.shared_state.http_client.clone(), ROBOTS_AGENT);
+        robots_txt_cache.insert(&seed_url, robots_txt_source.clone());
+        let scope_policy = ScopePolicy::new(seed_url.clone())
+            .with_allow_hosts(config.allow_hosts().to_vec())
+            .with_deny_hosts(config.deny_hosts().to_vec())
+            .with_allow_private_networks(config.allow_private_networks())
+            .with_crawl_script(self.shared_state.crawl_script.clone());
 
-        let mut crawl_context = CrawlContext::new();
-        crawl_context.add_url_to_crawl(&seed_url);
+        let soft_404_detector = self
+            .soft_404_detector(&config, &robots_txt_matcher, &seed_url)
+            .await;
+
+        let url_normalizer = UrlNormalizer::new(config.query_policy().clone())
+            .with_route_fragments(config.spa_routes());
+        let mut crawl_summary = CrawlSummary::default();
+        if config.sitemap_report() {
+            let sitemap_urls =
+                SitemapLoader::sitemap_urls_for_seed(&seed_url, robots_txt_view.sitemaps());
+            match SitemapLoader::new(self.shared_state.http_client.clone())
+                .load(sitemap_urls)
+                .await
+            {
+                Ok(loaded_sitemaps) => {
+                    for e in &loaded_sitemaps.failures {
+                        self.progress_reporter
+                            .progress_message(&format!("Sitemap not loaded: {:#}", e));
+                    }
+                    crawl_summary.set_sitemap_urls(
+                        loaded_sitemaps
+                            .page_urls
+                            .iter()
+                            .map(|page_url| url_normalizer.normalize(page_url))
+                            .collect(),
+                    );
+                }
+                Err(e) => self
+                    .progress_reporter
+                    .progress_message(&format!("Sitemap not loaded: {:#}", e)),
+            }
+        }
+
+        let mut crawl_context =
+            CrawlContext::new()
+                .with_shared_visited_set(self.shared_state.visited_set.clone())
+                .with_max_depth(config.max_depth())
+                .with_frontier_policy(config.strategy().frontier_policy())
+                .with_priority_patterns(config.priority_patterns().to_vec())
+                .with_url_limits(config.url_limits())
+                .with_url_normalizer(url_normalizer)
+                .with_sorted_discovery(config.deterministic());
+        if let Some(trap_family_limit) = config.trap_family_limit() {
+            crawl_context = crawl_context.with_trap_detection(trap_family_limit);
+        }
+        if config.visited_filter() == VisitedFilter::Bloom {
+            crawl_context = crawl_context.with_bloom_visited_filter(config.expected_urls());
+        }
+        if let Some(sitemap_urls) = crawl_summary.sitemap_urls() {
+            crawl_context = crawl_context.with_sitemap_urls(sitemap_urls);
+        }
+        if let Some(frontier_memory_limit) = config.frontier_memory_limit() {
+            let spill_path = Self::frontier_spill_path(&config, &seed_url);
+            crawl_context = crawl_context.with_frontier_spill(frontier_memory_limit, spill_path);
+        }
+        if let Some(shared_frontier_url) = config.shared_frontier_url() {
+            // Processes crawling the same seed share its frontier
+            let key_prefix = format!("{}:frontier:{}", config.queue_name(), seed_url);
+            let shared_frontier = RedisFrontier::new(shared_frontier_url.clone(), key_prefix);
+            crawl_context = crawl_context.with_shared_frontier(Some(shared_frontier));
+        }
+        let mut frontier_spills = config.frontier_memory_limit().is_some();
+        let checkpoint_file = self.checkpoint_file(&config);
+        let checkpoint = match &checkpoint_file {
+            Some(checkpoint_file) => Self::load_checkpoint(checkpoint_file, &seed_url)?,
+            None => None,
+        };
+        match checkpoint {
+            Some(checkpoint) => {
+                self.progress_reporter.progress_message(&format!(
+                    "Resuming from checkpoint: {} pages crawled, {} queued",
+                    checkpoint.pages.len(),
+                    checkpoint.pending.len()
+                ));
+                for page_summary in checkpoint.pages {
+                    crawl_summary.add_page_summary(page_summary);
+                }
+                crawl_context
+                    .restore(checkpoint.pending, checkpoint.crawled, checkpoint.discovery_counts)
+                    .await?;
+            }
+            None => crawl_context.add_url_to_crawl(&seed_url, 0, None).await?,
+        }
+        let mut loaded_feeds = HashSet::new();
+        let mut security_header_origins = HashSet::new();
+        let mut reported_rate = None;
+        let mut pages_since_checkpoint = 0;
+        let mut last_checkpoint = tokio::time::Instant::now();
+        // Pages popped from the frontier and dispatched to the fetcher, crawled in order. A
+        // crawl that stops early gives them back, to be crawled on resume.
+        let dispatch_window = self.shared_state.fetcher.dispatch_window();
+        let mut in_flight = VecDeque::new();
 
         self.progress_reporter
             .crawler_state_changed(CrawlerState::Crawling);
+        self.publish_event(&CrawlEvent::CrawlStarted {
+            run_id: &self.run_id,
+            seed_id: &self.seed_id,
+            seed: &seed_url,
+        });
 
-        let mut crawl_summary = CrawlSummary::default();
-        while !shutdown_requested.load(std::sync::atomic::Ordering::Relaxed)
-            && !crawl_context.is_crawling_complete()
-        {
-            let crawl_progress = crawl_context.progress();
-            self.progress_reporter
-                .progress_update(crawl_progress.0, crawl_progress.1);
+        while !self.cancel_token.is_cancelled() {
+            // Out of pages: the budget is spent on the URLs the strategy and priorities favour
+            let dispatching = in_flight.len() < dispatch_window
+                && crawl_summary.page_summaries().len() + in_flight.len() < config.max_pages()
+                && !crawl_context.is_crawling_complete().await?;
+            if !dispatching && in_flight.is_empty() {
+                break;
+            }
+            if dispatching {
+
+                // Near the memory limit, newly queued URLs go to disk rather than memory
+                if !frontier_spills && self.memory_degraded() {
+                    let spill_path = Self::frontier_spill_path(&config, &seed_url);
+                    crawl_context.spill_frontier(DEGRADED_FRONTIER_MEMORY_LIMIT, spill_path);
+                    self.progress_reporter
+                        .progress_message("Memory is running low: spilling the frontier to disk");
+                    frontier_spills = true;
+                }
 
+                let crawl_control = &self.shared_state.crawl_control;
+                if crawl_control.is_paused(&seed_url) {
+                    self.progress_reporter
+                        .crawler_state_changed(CrawlerState::Paused);
+                    self.progress_reporter.progress_message("Paused");
+                    let deadline_reached = async {
+                        match deadline {
+                            Some(deadline) => tokio::time::sleep_until(deadline).await,
+                            None => std::future::pending().await,
+                        }
+                    };
+                    tokio::select! {
+                        _ = crawl_control.wait_while_paused(&seed_url) => {}
+                        _ = self.cancel_token.cancelled() => break,
+                        // Still paused, but the deadline check below ends the crawl
+                        _ = deadline_reached => {}
+                    }
+                    self.progress_reporter
+                        .crawler_state_changed(CrawlerState::Crawling);
+                }
+
+                // Out of time: the previous request has completed, so stop before starting another
+                if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                    crawl_summary.mark_truncated_by_time();
+                    break;
+                }
+
+                let crawl_progress = crawl_context.progress();
+                self.progress_reporter.progress_update(
+                    crawl_progress.0,
+                    crawl_progress.1,
+                    crawl_start.elapsed(),
+                );
+
+                // The frontier can run dry if its remaining URLs were claimed by other seeds
+                if let Some(queued_url) = crawl_context.pop_url_to_crawl().await? {
+                    // Marked now, so that links to it found meanwhile don't queue it again
+                    crawl_context.mark_url_as_crawled(&queued_url.url);
+                    // In flight from here, so that a cancellation or the deadline gives it back
+                    in_flight.push_back(queued_url.clone());
+
+                    // Each request waits for the rate limit and circuit breaker of its own host
+                    let rate_limit_wait = self
+                        .shared_state
+                        .rate_limiter
+                        .as_ref()
+                        .map_or(Duration::ZERO, |rate_limiter| {
+                            rate_limiter.reserve(&queued_url.url)
+                        });
+                    let circuit_breaker_wait = self
+                        .shared_state
+                        .circuit_breaker
+                        .as_ref()
+                        .map_or(Duration::ZERO, |circuit_breaker| {
+                            circuit_breaker.cooldown_remaining(&queued_url.url)
+                        });
+                    let rate_limit_wait = rate_limit_wait.max(circuit_breaker_wait);
+                    if !rate_limit_wait.is_zero() {
+                        self.progress_reporter
+                            .crawler_state_changed(CrawlerState::Paused);
+                        // Don't sleep past the deadline
+                        let wake_at = tokio::time::Instant::now() + rate_limit_wait;
+                        let wake_at = deadline.map_or(wake_at, |deadline| deadline.min(wake_at));
+                        tokio::select! {
+                            _ = tokio::time::sleep_until(wake_at) => {}
+                            _ = self.cancel_token.cancelled() => break,
+                        }
+                        self.progress_reporter
+                            .crawler_state_changed(CrawlerState::Crawling);
+                    }
+
+                    // The wait may have run into the deadline
+                    if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                        crawl_summary.mark_truncated_by_time();
+                        break;
+                    }
+                    if !circuit_breaker_wait.is_zero() {
+                        self.progress_reporter.circuit_state_changed(
+                            queued_url.url.host_str().unwrap_or_default(),
+                            CircuitState::HalfOpen,
+                        );
+                    }
+
+                    if dispatch_window > 1 && robots_txt_cache.is_allowed(&queued_url.url).await {
+                        self.shared_state.fetcher.dispatch(&queued_url.url).await;
+                    }
+                    if in_flight.len() < dispatch_window {
+                        continue;
+                    }
+                }
+            }
+            let Some(queued_url) = in_flight.pop_front() else {
+                continue;
+            };
+            let depth = queued_url.depth;
+            let discovered_from = queued_url.discovered_from.clone();
+            let popped_url = queued_url.clone();
+            let page_start = tokio::time::Instant::now();
             let output = self
-                .crawl_next_url(&robots_txt_matcher, &mut crawl_context)
+                .crawl_url(
+                    &config,
+                    &scope_policy,
+                    &mut robots_txt_cache,
+                    &soft_404_detector,
+                    &mut crawl_context,
+                    queued_url,
+                )
                 .await?;
-            let page_summary = match output {
-                PageCrawlOutput::Success(page_summary) => Some(page_summary),
-                PageCrawlOutput::HttpNotFound(url) => Some(PageSummary::from_status_code(url, 404)),
+            let page_duration = page_start.elapsed();
+            if let PageCrawlOutput::Cancelled(queued_url) = output {
+                in_flight.push_front(queued_url);
+                break;
+            }
+            let excluded = matches!(output, PageCrawlOutput::NoIndex(_));
+            let mut page_summary = match output {
+                PageCrawlOutput::Success(page_summary) => page_summary,
+                PageCrawlOutput::NoIndex(page_summary) => page_summary,
+                PageCrawlOutput::HttpNotFound(url) => PageSummary::from_status_code(url, depth, 404),
                 PageCrawlOutput::HttpError(url, status_code) => {
-                    Some(PageSummary::from_status_code(url, status_code))
+                    PageSummary::from_status_code(url, depth, status_code)
+                }
+                PageCrawlOutput::TooLarge(url, status_code) => {
+                    let mut page_summary = PageSummary::from_status_code(url, depth, status_code);
+                    page_summary.too_large = true;
+                    page_summary
+                }
+                PageCrawlOutput::UnsupportedContentType(
+                    url,
+                    status_code,
+                    content_type,
+                    cache_headers,
+                ) => {
+                    let mut page_summary = PageSummary::from_status_code(url, depth, status_code);
+                    page_summary.content_type = content_type;
+                    page_summary.cache_headers = Some(cache_headers);
+                    page_summary
+                }
+                PageCrawlOutput::Redirect(url, status_code, location) => {
+                    let mut page_summary = PageSummary::from_status_code(url, depth, status_code);
+                    page_summary.redirect = Some(location);
+                    page_summary
+                }
+                PageCrawlOutput::Skipped(url, status_code, reason) => {
+                    let mut page_summary = PageSummary::from_status_code(url, depth, status_code);
+                    page_summary.skipped = Some(reason);
+                    page_summary
                 }
-                PageCrawlOutput::NoMoreUrlsToCrawl => None,
+                PageCrawlOutput::Cancelled(_) => unreachable!("cancelled pages end the crawl"),
+                PageCrawlOutput::NoResponse(url) => PageSummary::from_status_code(url, depth, 0),
+                PageCrawlOutput::Timeout(url) => {
+                    let mut page_summary = PageSummary::from_status_code(url, depth, 0);
+                    page_summary.timed_out = true;
+                    page_summary
+                }
+                // Never requested, so there is no status to record
                 PageCrawlOutput::DeniedByRobotsTxt(url) => {
-                    Some(PageSummary::from_status_code(url, 403))
+                    let mut page_summary = PageSummary::from_status_code(url, depth, 0);
+                    page_summary.blocked_by_robots_txt = true;
+                    page_summary.skipped = Some("blocked by robots.txt".to_string());
+                    page_summary
                 }
             };
-            if let Some(page_summary) = page_summary {
+            page_summary.discovered_from = discovered_from;
+            page_summary.run_id = Some(self.run_id.clone());
+            page_summary.seed_id = Some(self.seed_id.clone());
+            // Pages blocked by robots.txt were never requested, so say nothing of the host
+            if let Some(circuit_breaker) = &self.shared_state.circuit_breaker {
+                if !page_summary.blocked_by_robots_txt {
+                    let healthy = !HostCircuitBreaker::is_failure_status(page_summary.status_code);
+                    let host = popped_url.url.host_str().unwrap_or_default();
+                    if let Some(state) = circuit_breaker.record_outcome(&popped_url.url, healthy)
+                    {
+                        if state == CircuitState::Open {
+                            crawl_summary.add_circuit_breaker_trip(host);
+                        }
+                        self.progress_reporter.circuit_state_changed(host, state);
+                    }
+                }
+            }
+            // A host's security headers are recorded once, from its first page
+            if page_summary.security_headers.is_some()
+                && !security_header_origins.insert(page_summary.url.origin())
+            {
+                page_summary.security_headers = None;
+            }
+            let nofollow =
+                config.respect_robots_directives() && page_summary.robots_directives.nofollow;
+            if config.follow_feeds() && !nofollow {
+                self.follow_feeds(
+                    &page_summary,
+                    &scope_policy,
+                    &mut loaded_feeds,
+                    &mut crawl_context,
+                )
+                .await?;
+            }
+            crawl_context.finish_url(&popped_url).await?;
+            self.progress_reporter.page_completed(
+                &page_summary.url,
+                page_summary.status_code,
+                depth,
+                page_duration,
+            );
+            if !excluded {
+                self.publish_event(&CrawlEvent::PageCrawled {
+                    run_id: &self.run_id,
+                    seed_id: &self.seed_id,
+                    seed: &seed_url,
+                    page: &page_summary,
+                });
+                if let Some(result_stream) = &self.shared_state.result_stream {
+                    result_stream.write(&page_summary);
+                }
                 crawl_summary.add_page_summary(page_summary);
             }
 
-            if let Some(crawl_delay) = crawl_delay {
-                if !crawl_context.is_crawling_complete() {
-                    if shutdown_requested.load(std::sync::atomic::Ordering::Relaxed) {
-                        break;
+            if config.adaptive_rate() {
+                if let Some(rate_limiter) = &self.shared_state.rate_limiter {
+                    let rate = rate_limiter.current_rate(&popped_url.url);
+                    if reported_rate != Some(rate) {
+                        self.progress_reporter.rate_changed(rate);
+                        reported_rate = Some(rate);
                     }
+                }
+            }
 
-                    self.progress_reporter
-                        .crawler_state_changed(CrawlerState::Paused);
-                    tokio::time::sleep(crawl_delay).await;
-                    self.progress_reporter
-                        .crawler_state_changed(CrawlerState::Crawling);
+            pages_since_checkpoint += 1;
+            if let Some(checkpoint_file) = &checkpoint_file {
+                if config
+                    .checkpoint_interval()
+                    .is_due(pages_since_checkpoint, last_checkpoint.elapsed())
+                {
+                    self.save_checkpoint(
+                        checkpoint_file,
+                        &mut crawl_context,
+                        &crawl_summary,
+                        &in_flight,
+                    )?;
+                    pages_since_checkpoint = 0;
+                    last_checkpoint = tokio::time::Instant::now();
                 }
             }
         }
 
+        // Pages abandoned by a cancellation or the deadline are for another process, or resume
+        for queued_url in &in_flight {
+            crawl_context.give_back_url(queued_url).await?;
+        }
+        // A finished crawl has nothing to resume; an interrupted one resumes from here
+        if let Some(checkpoint_file) = &checkpoint_file {
+            if crawl_context.is_crawling_complete().await? && in_flight.is_empty() {
+                checkpoint_file.remove()?;
+            } else {
+                self.save_checkpoint(
+                    checkpoint_file,
+                    &mut crawl_context,
+                    &crawl_summary,
+                    &in_flight,
+                )?;
+            }
+        }
+
+        crawl_summary.set_visited_filter_stats(crawl_context.visited_filter_stats());
+        crawl_summary.set_suspected_traps(crawl_context.suspected_traps());
+        crawl_summary.set_rejected_url_counts(crawl_context.rejected_url_counts());
+        crawl_summary.set_linked_sitemap_urls(crawl_context.linked_sitemap_urls());
+        if let Some(discovery_counts) = crawl_context.discovery_counts() {
+            crawl_summary.set_discovery_counts(discovery_counts);
+        }
+        crawl_summary.set_seed_id(self.seed_id.clone());
+        self.publish_event(&CrawlEvent::CrawlFinished {
+            run_id: &self.run_id,
+            seed_id: &self.seed_id,
+            seed: &seed_url,
+            num_pages: crawl_summary.page_summaries().len(),
+            truncated_by_time: crawl_summary.truncated_by_time(),
+        });
         self.progress_reporter.end();
 
         Ok(crawl_summary)
     }
 
-    async fn crawl_next_url(
+    fn publish_event(&self, event: &CrawlEvent) {
+        if let Some(event_publisher) = &self.shared_state.event_publisher {
+            event_publisher.publish(event);
+        }
+    }
+
+    /// Queues the items listed in the feeds a page advertises, loading each feed only once.
+    async fn follow_feeds(
+        &self,
+        page_summary: &PageSummary,
+        scope_policy: &ScopePolicy,
+        loaded_feeds: &mut HashSet<Url>,
+        crawl_context: &mut CrawlContext,
+    ) -> anyhow::Result<()> {
+        let feed_loader = FeedLoader::new(self.shared_state.http_client.clone());
+        for feed_url in &page_summary.feeds {
+            if !scope_policy.is_in_scope(feed_url) || !loaded_feeds.insert(feed_url.clone()) {
+                continue;
+            }
+            match feed_loader.load(feed_url).await {
+                Ok(item_urls) => {
+                    // Items are one link away from the page advertising the feed
+                    for item_url in item_urls
+                        .iter()
+                        .filter(|item_url| scope_policy.is_in_scope(item_url))
+                    {
+                        crawl_context
+                            .add_url_to_crawl(item_url, page_summary.depth + 1, Some(feed_url))
+                            .await?;
+                    }
+                }
+                Err(e) => self
+                    .progress_reporter
+                    .progress_message(&format!("Feed not loaded: {:#}", e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// A new spill file for the frontier of the seed `seed_url`, unique to this process.
+    fn frontier_spill_path(config: &CrawlerConfig, seed_url: &Url) -> PathBuf {
+        config.frontier_spill_dir().join(format!(
+            "rusty-spider-frontier-{}-{:016x}.tsv",
+            std::process::id(),
+            RandomState::new().hash_one(seed_url)
+        ))
+    }
+
+    /// Whether the memory guard has asked the crawl to shed memory.
+    fn memory_degraded(&self) -> bool {
+        self.shared_state.memory_guard.as_ref().is_some_and(MemoryGuard::is_degraded)
+    }
+
+    /// The checkpoint file for this seed, named after a hash of the seed URL.
+    fn checkpoint_file(&self, config: &CrawlerConfig) -> Option<CheckpointFile> {
+        let checkpoint_dir = config.checkpoint_dir()?;
+        let seed_hash = format!("{:x}", Sha256::digest(self.seed.as_str().as_bytes()));
+        Some(CheckpointFile::new(
+            checkpoint_dir.join(format!("{}.checkpoint", &seed_hash[..16])),
+        ))
+    }
+
+    fn load_checkpoint(
+        checkpoint_file: &CheckpointFile,
+        seed_url: &Url,
+    ) -> anyhow::Result<Option<SeedCheckpoint>> {
+        let Some(payload) = checkpoint_file.load()? else {
+            return Ok(None);
+        };
+        let checkpoint = SeedCheckpoint::from_payload(&payload).with_context(|| {
+            format!("Invalid checkpoint '{}'", checkpoint_file.path().display())
+        })?;
+        if &checkpoint.seed != seed_url {
+            return Err(anyhow::anyhow!(
+                "Checkpoint '{}' belongs to seed {}",
+                checkpoint_file.path().display(),
+                checkpoint.seed
+            ));
+        }
+        Ok(Some(checkpoint))
+    }
+
+    fn save_checkpoint(
+        &self,
+        checkpoint_file: &CheckpointFile,
+        crawl_context: &mut CrawlContext,
+        crawl_summary: &CrawlSummary,
+        in_flight: &VecDeque<QueuedUrl>,
+    ) -> anyhow::Result<()> {
+        let (mut pending, crawled) = crawl_context.checkpoint_state()?;
+        let pages = crawl_summary.page_summaries().to_vec();
+        // A bloom filter cannot be listed; the crawled pages are the best approximation
+        let mut crawled = crawled
+            .unwrap_or_else(|| pages.iter().map(|page_summary| page_summary.url.clone()).collect());
+        // Pages in flight were marked as crawled, but are still to be crawled
+        for queued_url in in_flight {
+            crawled.retain(|url| url != &queued_url.url);
+            pending.push(queued_url.clone());
+        }
+        let checkpoint = SeedCheckpoint {
+            seed: self.seed.clone(),
+            pending,
+            crawled,
+            pages,
+            discovery_counts: crawl_context.discovery_counts().cloned().unwrap_or_default(),
+        };
+        checkpoint_file.save(&checkpoint.to_payload()?)
+    }
+
+    /// Builds the soft-404 detector for this seed, probing the site with a URL that cannot
+    /// exist to learn what its error page looks like when it is served with a 200.
+    async fn soft_404_detector(
         &self,
+        config: &CrawlerConfig,
         robots_txt_matcher: &RobotsTxtMatcher<'_>,
+        seed_url: &Url,
+    ) -> Soft404Detector {
+        let mut soft_404_detector = Soft404Detector::new(config.soft_404_patterns());
+        let mut probe_url = seed_url.clone();
+        probe_url.set_path(&Soft404Detector::probe_path());
+        probe_url.set_query(None);
+        if !robots_txt_matcher.check_path(probe_url.path()) {
+            return soft_404_detector;
+        }
+        let page_crawler = PageCrawler::new(Arc::clone(&self.shared_state.fetcher))
+            .with_max_body_size(config.max_body_size())
+            .with_response_middleware(config.response_middleware().to_vec());
+        // Any failure, including the expected 404, means there is nothing to learn
+        if let Ok(probe_response) = page_crawler.crawl(&probe_url).await {
+            soft_404_detector.set_probe_text(&probe_response.body_text);
+        }
+        soft_404_detector
+    }
+
+    /// A local seed's directory is the root of its site, e.g. a static site build.
+    fn site_root(&self) -> Option<Url> {
+        if self.seed.scheme() != "file" {
+            return None;
+        }
+        self.seed.join("./").ok()
+    }
+
+    async fn crawl_url(
+        &self,
+        config: &CrawlerConfig,
+        scope_policy: &ScopePolicy,
+        robots_txt_cache: &mut RobotsTxtCache,
+        soft_404_detector: &Soft404Detector,
         crawl_context: &mut CrawlContext,
+        queued_url: QueuedUrl,
     ) -> anyhow::Result<PageCrawlOutput> {
-        // Fetch the next URL to crawl
-        let url_to_crawl = crawl_context.pop_url_to_crawl();
-        if let None = url_to_crawl {
-            return Ok(PageCrawlOutput::NoMoreUrlsToCrawl);
-        }
-        let url_to_crawl = url_to_crawl.unwrap();
-        crawl_context.mark_url_as_crawled(&url_to_crawl);
+        let QueuedUrl {
+            url: url_to_crawl,
+            depth,
+            discovered_from,
+        } = queued_url;
 
         // Ensure this URL is allowed to be crawled by robots.txt
-        if !robots_txt_matcher.check_path(url_to_crawl.path()) {
+        if !robots_txt_cache.is_allowed(&url_to_crawl).await {
             return Ok(PageCrawlOutput::DeniedByRobotsTxt(url_to_crawl));
         }
 
@@ -150,23 +715,204 @@ where
 
         // Fetch the contents of the URL
         let crawl_response = {
-            let page_crawler = PageCrawler::new();
+            let page_crawler = PageCrawler::new(Arc::clone(&self.shared_state.fetcher))
+                .with_site_root(self.site_root())
+                .with_extract_rules(config.extract_rules().to_vec())
+                .with_accessibility_checks(config.accessibility_checks())
+                .with_content_handlers(ContentHandlers::new(config.content_kinds()))
+                .with_link_extractor(Arc::clone(&self.shared_state.link_extractor))
+                .with_streaming_html(config.streaming_html())
+                .with_cancel_token(self.cancel_token.clone())
+                .with_page_timeout(config.page_timeout())
+                .with_max_body_size(config.max_body_size())
+                .with_response_middleware(config.response_middleware().to_vec())
+                .with_crawl_script(self.shared_state.crawl_script.clone())
+                .with_plugins(self.shared_state.plugins.clone())
+                .with_captured_headers(config.captured_headers().to_vec());
             page_crawler.crawl(&url_to_crawl).await
         };
         match crawl_response {
             Ok(crawl_response) => {
-                crawl_context.add_urls_to_crawl(&crawl_response.internal_links);
+                let respect_directives = config.respect_robots_directives();
+                let noindex = respect_directives && crawl_response.robots_directives.noindex;
+                let nofollow = respect_directives && crawl_response.robots_directives.nofollow;
 
-                let page_summary = PageSummary::new(
+                if !nofollow {
+                    let links_in_scope = crawl_response
+                        .internal_links
+                        .iter()
+                        .chain(&crawl_response.outgoing_links)
+                        .filter(|link| scope_policy.is_in_scope(link))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    crawl_context
+                        .add_urls_to_crawl(&links_in_scope, depth + 1, Some(&crawl_response.url))
+                        .await?;
+                    // Pages of a series are siblings, so following them doesn't add depth
+                    let pagination_links =
+                        [&crawl_response.pagination.next, &crawl_response.pagination.prev];
+                    for pagination_url in pagination_links.into_iter().flatten() {
+                        if scope_policy.is_in_scope(pagination_url) {
+                            crawl_context
+                                .add_url_to_crawl_keeping_query(
+                                    pagination_url,
+                                    depth,
+                                    Some(&crawl_response.url),
+                                )
+                                .await?;
+                        }
+                    }
+                    // A meta refresh is a redirect, so its target is as deep as this page
+                    if let Some(meta_refresh) = &crawl_response.meta_refresh {
+                        if config.follow_meta_refresh() && scope_policy.is_in_scope(meta_refresh) {
+                            crawl_context
+                                .add_url_to_crawl(meta_refresh, depth, Some(&crawl_response.url))
+                                .await?;
+                        }
+                    }
+                    if config.script_links() {
+                        let script_links_in_scope = crawl_response
+                            .script_links
+                            .iter()
+                            .filter(|link| scope_policy.is_in_scope(link))
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        crawl_context
+                            .add_urls_to_crawl(
+                                &script_links_in_scope,
+                                depth + 1,
+                                Some(&crawl_response.url),
+                            )
+                            .await?;
+                    }
+                    // Framed pages are embedded rather than linked, but count as a link away
+                    let frame_policy = config.frame_policy();
+                    for frame_url in &crawl_response.frames {
+                        if frame_policy.follows(frame_url, &crawl_response.url, scope_policy) {
+                            crawl_context
+                                .add_url_to_crawl(frame_url, depth + 1, Some(&crawl_response.url))
+                                .await?;
+                        }
+                    }
+                    // AMP versions are crawled so their status and canonical link can be validated
+                    if let Some(amp_url) = &crawl_response.amp_url {
+                        if scope_policy.is_in_scope(amp_url) {
+                            crawl_context
+                                .add_url_to_crawl(amp_url, depth + 1, Some(&crawl_response.url))
+                                .await?;
+                        }
+                    }
+                    // Translations are crawled so their return links can be validated
+                    for alternate in &crawl_response.hreflang_alternates {
+                        if scope_policy.is_in_scope(&alternate.url) {
+                            crawl_context
+                                .add_url_to_crawl(
+                                    &alternate.url,
+                                    depth + 1,
+                                    Some(&crawl_response.url),
+                                )
+                                .await?;
+                        }
+                    }
+                }
+
+                let body_path = match &self.shared_state.body_store {
+                    Some(body_store) if !noindex => Some(body_store.save_body(
+                        &crawl_response.url,
+                        crawl_response.status_code,
+                        &crawl_response.content_type,
+                        &crawl_response.body,
+                    )?),
+                    _ => None,
+                };
+
+                let soft_404 =
+                    soft_404_detector.is_soft_404(&crawl_response.title, &crawl_response.body_text);
+                let text_stats = TextStats::new(
+                    &crawl_response.body_text,
+                    crawl_response.body_bytes,
+                    config.readability(),
+                );
+                let search_matches = config
+                    .search()
+                    .and_then(|search| search.search(&crawl_response.body_text));
+                let mut page_summary = PageSummary::new(
                     crawl_response.url,
+                    depth,
                     crawl_response.status_code,
                     crawl_response.content_type,
                     crawl_response.title,
                     crawl_response.outgoing_links.len(),
                 );
-                Ok(PageCrawlOutput::Success(page_summary))
+                page_summary.outgoing_link_hosts =
+                    PageSummary::count_by_host(&crawl_response.outgoing_links);
+                page_summary.internal_link_anchors = crawl_response
+                    .internal_link_anchors
+                    .into_iter()
+                    .map(|link_anchor| LinkAnchor {
+                        url: crawl_context.strip_url(&link_anchor.url),
+                        anchor_text: link_anchor.anchor_text,
+                    })
+                    .collect();
+                page_summary.body_path = body_path;
+                page_summary.soft_404 = soft_404;
+                page_summary.meta_description = crawl_response.meta_description;
+                page_summary.meta_refresh = crawl_response.meta_refresh;
+                page_summary.num_h1 = crawl_response.num_h1;
+                page_summary.num_pages = crawl_response.num_pages;
+                page_summary.mixed_content = crawl_response.mixed_content;
+                page_summary.robots_directives = crawl_response.robots_directives;
+                page_summary.pagination = crawl_response.pagination;
+                page_summary.hreflang_alternates = crawl_response.hreflang_alternates;
+                page_summary.feeds = crawl_response.feeds;
+                page_summary.canonical =
+                    crawl_response.canonical.map(|canonical| crawl_context.strip_url(&canonical));
+                page_summary.amp_url =
+                    crawl_response.amp_url.map(|amp_url| crawl_context.strip_url(&amp_url));
+                page_summary.extracted = crawl_response.extracted;
+                page_summary.structured_data = crawl_response.structured_data;
+                page_summary.accessibility = crawl_response.accessibility;
+                page_summary.text_stats = Some(text_stats);
+                page_summary.search_matches = search_matches;
+                if config.keep_body_text() && !self.memory_degraded() {
+                    page_summary.body_text = Some(crawl_response.body_text);
+                }
+                page_summary.http_version = crawl_response.http_version;
+                page_summary.content_encoding = crawl_response.content_encoding;
+                page_summary.security_headers = Some(crawl_response.security_headers);
+                page_summary.cache_headers = Some(crawl_response.cache_headers);
+                page_summary.captured_headers = crawl_response.captured_headers;
+                page_summary.transferred_bytes = crawl_response.transferred_bytes;
+                page_summary.body_bytes = crawl_response.body_bytes;
+                page_summary.content_hash = crawl_response.content_hash;
+                page_summary.render_error = crawl_response.render_error;
+                if noindex {
+                    Ok(PageCrawlOutput::NoIndex(page_summary))
+                } else {
+                    Ok(PageCrawlOutput::Success(page_summary))
+                }
             }
             Err(e) => match e {
+                CrawlError::TooLarge(status_code) => {
+                    Ok(PageCrawlOutput::TooLarge(url_to_crawl, status_code))
+                }
+                CrawlError::UnsupportedContentType(status_code, content_type, cache_headers) => {
+                    Ok(PageCrawlOutput::UnsupportedContentType(
+                        url_to_crawl,
+                        status_code,
+                        content_type,
+                        cache_headers,
+                    ))
+                }
+                CrawlError::Redirect(status_code, location) => {
+                    // Like a meta refresh, the target is as deep as the redirecting page
+                    if scope_policy.is_in_scope(&location) {
+                        crawl_context
+                            .add_url_to_crawl(&location, depth, Some(&url_to_crawl))
+                            .await?;
+                    }
+                    Ok(PageCrawlOutput::Redirect(url_to_crawl, status_code, location))
+                }
                 CrawlError::HttpError(status_code) => {
                     if status_code == 404 {
                         Ok(PageCrawlOutput::HttpNotFound(url_to_crawl))
@@ -174,8 +920,386 @@ where
                         Ok(PageCrawlOutput::HttpError(url_to_crawl, status_code))
                     }
                 }
+                CrawlError::Skipped(status_code, reason) => {
+                    Ok(PageCrawlOutput::Skipped(url_to_crawl, status_code, reason))
+                }
+                CrawlError::Timeout => Ok(PageCrawlOutput::Timeout(url_to_crawl)),
+                CrawlError::Cancelled => Ok(PageCrawlOutput::Cancelled(QueuedUrl {
+                    url: url_to_crawl,
+                    depth,
+                    discovered_from,
+                })),
+                CrawlError::ReqwestError(e) if e.is_timeout() || e.is_connect() => {
+                    Ok(PageCrawlOutput::NoResponse(url_to_crawl))
+                }
+                CrawlError::NoResponse(_) => Ok(PageCrawlOutput::NoResponse(url_to_crawl)),
                 _ => Err(anyhow::anyhow!("Crawl error: {}", e)),
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::fail_on_policy::FailOnPolicy;
+    use crate::crawler::http::HttpClientBuilder;
+    use crate::test_support::{MockPage, MockSite, MockSiteServer, RecordingProgressReporter};
+
+    /// Crawls a mock site, which is served on the loopback address.
+    async fn crawl_site(
+        site: MockSite,
+        config: CrawlerConfig,
+    ) -> (MockSiteServer, CrawlSummary, RecordingProgressReporter) {
+        let config = config.with_allow_private_networks(true);
+        let server = MockSiteServer::start(site).await.unwrap();
+        let progress_reporter = RecordingProgressReporter::new();
+        let seed_crawler = SeedCrawler::new(
+            CancellationToken::new(),
+            server.base_url().clone(),
+            progress_reporter.clone(),
+            SharedCrawlState::new(&config).unwrap(),
+        );
+        let crawl_summary = seed_crawler.crawl(config).await.unwrap();
+        (server, crawl_summary, progress_reporter)
+    }
+
+    fn status_of(crawl_summary: &CrawlSummary, url: &Url) -> Option<u16> {
+        crawl_summary
+            .page_summaries()
+            .iter()
+            .find(|page_summary| &page_summary.url == url)
+            .map(|page_summary| page_summary.status_code)
+    }
+
+    #[tokio::test]
+    async fn crawls_a_chain_down_to_the_maximum_depth() {
+        let (server, crawl_summary, _) =
+            crawl_site(MockSite::chain(6), CrawlerConfig::new(100, 3, None)).await;
+        let depths = crawl_summary
+            .page_summaries()
+            .iter()
+            .map(|page_summary| page_summary.depth)
+            .collect::<Vec<_>>();
+        assert_eq!(depths, vec![0, 1, 2, 3]);
+        assert_eq!(status_of(&crawl_summary, &server.url("/page/4")), None);
+    }
+
+    #[tokio::test]
+    async fn deterministic_crawls_queue_links_in_sorted_order() {
+        let site = MockSite::new()
+            .with_html("/", &["/d", "/b", "/c", "/a"])
+            .with_html("/a", &[])
+            .with_html("/b", &[])
+            .with_html("/c", &[])
+            .with_html("/d", &[]);
+        let config = CrawlerConfig::new(100, 4, None).with_deterministic(true);
+        let (server, crawl_summary, _) = crawl_site(site, config).await;
+        let urls = crawl_summary
+            .page_summaries()
+            .iter()
+            .map(|page_summary| page_summary.url.clone())
+            .collect::<Vec<_>>();
+        let expected = ["/", "/a", "/b", "/c", "/d"].map(|path| server.url(path));
+        assert_eq!(urls, expected);
+    }
+
+    #[tokio::test]
+    async fn crawls_each_page_of_a_cycle_once() {
+        let site = MockSite::new()
+            .with_html("/", &["/a"])
+            .with_html("/a", &["/b", "/"])
+            .with_html("/b", &["/a", "/"]);
+        let (server, crawl_summary, progress_reporter) =
+            crawl_site(site, CrawlerConfig::new(100, 10, None)).await;
+        assert_eq!(crawl_summary.page_summaries().len(), 3);
+        assert_eq!(progress_reporter.pages_completed().len(), 3);
+
+        // Every link counts towards its target's in-degree, even to pages already crawled
+        let in_degree_of = |path: &str| {
+            let page_summaries = crawl_summary.page_summaries();
+            let url = server.url(path);
+            page_summaries.iter().find(|page| page.url == url).map(|page| page.in_degree)
+        };
+        assert_eq!(in_degree_of("/"), Some(2));
+        assert_eq!(in_degree_of("/a"), Some(2));
+        assert_eq!(in_degree_of("/b"), Some(1));
+        assert_eq!(crawl_summary.link_discoveries(), Some((5, 3)));
+    }
+
+    #[tokio::test]
+    async fn counts_discoveries_of_queued_urls_only() {
+        let site = MockSite::new()
+            .with_html("/", &["/a"])
+            .with_html("/a", &["/", "/b"])
+            .with_html("/b", &[]);
+        let config = CrawlerConfig::new(100, 1, None);
+        let (_, crawl_summary, _) = crawl_site(site.clone(), config.clone()).await;
+        // "/b" is too deep to be queued
+        assert_eq!(crawl_summary.link_discoveries(), Some((2, 2)));
+
+        let config = config.with_visited_filter(VisitedFilter::Bloom, 100);
+        let (_, crawl_summary, _) = crawl_site(site, config).await;
+        assert_eq!(crawl_summary.link_discoveries(), None);
+    }
+
+    #[tokio::test]
+    async fn reports_broken_links_with_their_referrer() {
+        let site = MockSite::new().with_html("/", &["/missing"]);
+        let (server, crawl_summary, _) = crawl_site(site, CrawlerConfig::new(100, 4, None)).await;
+        let missing = crawl_summary
+            .page_summaries()
+            .iter()
+            .find(|page_summary| page_summary.url == server.url("/missing"))
+            .unwrap();
+        assert_eq!(missing.status_code, 404);
+        assert_eq!(missing.discovered_from.as_ref(), Some(&server.url("/")));
+    }
+
+    #[tokio::test]
+    async fn skips_pages_with_an_unknown_content_encoding() {
+        let garbled = MockPage::html("Garbled", &[]).with_header("content-encoding", "foo");
+        let site = MockSite::new()
+            .with_html("/", &["/garbled", "/fine"])
+            .with_page("/garbled", garbled)
+            .with_html("/fine", &[]);
+        let (server, crawl_summary, _) = crawl_site(site, CrawlerConfig::new(100, 4, None)).await;
+        let garbled = crawl_summary
+            .page_summaries()
+            .iter()
+            .find(|page_summary| page_summary.url == server.url("/garbled"))
+            .unwrap();
+        assert!(garbled.skipped.as_deref().unwrap().contains("'foo'"));
+        assert_eq!(status_of(&crawl_summary, &server.url("/fine")), Some(200));
+    }
+
+    #[tokio::test]
+    async fn follows_redirects() {
+        let site = MockSite::new()
+            .with_html("/", &["/old"])
+            .with_redirect("/old", "/new")
+            .with_html("/new", &[]);
+        let (server, crawl_summary, _) = crawl_site(site, CrawlerConfig::new(100, 4, None)).await;
+        assert_eq!(status_of(&crawl_summary, &server.url("/old")), Some(200));
+    }
+
+    #[tokio::test]
+    async fn reports_off_host_and_unfollowed_redirects() {
+        let site = MockSite::new()
+            .with_html("/", &["/old", "/away"])
+            .with_redirect("/old", "/new")
+            .with_redirect("/away", "https://elsewhere.example/")
+            .with_html("/new", &[]);
+        let redirect_of = |crawl_summary: &CrawlSummary, url: &Url| {
+            let page_summaries = crawl_summary.page_summaries();
+            let page_summary = page_summaries.iter().find(|page_summary| &page_summary.url == url);
+            page_summary.and_then(|page_summary| page_summary.redirect.clone())
+        };
+
+        let config = CrawlerConfig::new(100, 4, None);
+        let (server, crawl_summary, _) = crawl_site(site.clone(), config).await;
+        let away = server.url("/away");
+        assert_eq!(status_of(&crawl_summary, &away), Some(301));
+        let elsewhere = Url::parse("https://elsewhere.example/").unwrap();
+        assert_eq!(redirect_of(&crawl_summary, &away), Some(elsewhere));
+        assert_eq!(redirect_of(&crawl_summary, &server.url("/old")), None);
+
+        let config = CrawlerConfig::new(100, 4, None).with_follow_redirects(false);
+        let (server, crawl_summary, _) = crawl_site(site, config).await;
+        assert_eq!(status_of(&crawl_summary, &server.url("/old")), Some(301));
+        assert_eq!(redirect_of(&crawl_summary, &server.url("/old")), Some(server.url("/new")));
+        assert_eq!(status_of(&crawl_summary, &server.url("/new")), Some(200));
+    }
+
+    #[tokio::test]
+    async fn pauses_a_failing_host_until_it_recovers() {
+        let site = MockSite::new()
+            .with_html("/", &["/a", "/b", "/c", "/d", "/e"])
+            .with_page("/a", MockPage::status(500))
+            .with_page("/b", MockPage::status(503))
+            .with_page("/c", MockPage::status(500))
+            .with_page("/d", MockPage::status(502))
+            .with_html("/e", &[]);
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_deterministic(true)
+            .with_circuit_breaker(Some(2), Duration::from_millis(50));
+        let (server, crawl_summary, progress_reporter) = crawl_site(site, config).await;
+        assert_eq!(status_of(&crawl_summary, &server.url("/e")), Some(200));
+        assert_eq!(
+            progress_reporter.circuit_states(),
+            vec![
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Closed,
+            ]
+        );
+        let host = server.base_url().host_str().unwrap().to_string();
+        assert_eq!(crawl_summary.circuit_breaker_trips().get(&host), Some(&3));
+    }
+
+    #[tokio::test]
+    async fn rate_limits_each_allowed_host_separately() {
+        let other_host = MockSiteServer::start(MockSite::new().with_html("/other", &[]))
+            .await
+            .unwrap();
+        let mut other_url = other_host.url("/other");
+        other_url.set_host(Some("localhost")).unwrap();
+        let site = MockSite::new().with_html("/", &[other_url.as_str()]);
+        // One request a second: the seed host's request would hold up the other host's
+        let config = CrawlerConfig::new(100, 4, Some(1.0))
+            .with_burst(1)
+            .with_allow_hosts(vec!["localhost".parse().unwrap()]);
+        let crawl_start = tokio::time::Instant::now();
+        let (_server, crawl_summary, _) = crawl_site(site, config).await;
+        assert_eq!(status_of(&crawl_summary, &other_url), Some(200));
+        assert!(crawl_start.elapsed() < Duration::from_millis(700));
+    }
+
+    #[tokio::test]
+    async fn obeys_robots_txt() {
+        let site = MockSite::new()
+            .with_robots_txt("User-agent: *\nDisallow: /private/\n")
+            .with_html("/", &["/private/page", "/public"])
+            .with_html("/private/page", &[])
+            .with_html("/public", &[]);
+        let (server, crawl_summary, _) = crawl_site(site, CrawlerConfig::new(100, 4, None)).await;
+        assert_eq!(status_of(&crawl_summary, &server.url("/private/page")), Some(0));
+        assert_eq!(status_of(&crawl_summary, &server.url("/public")), Some(200));
+
+        // The blocked page was never requested, so it is no 4xx failure
+        let fail_on_policy =
+            FailOnPolicy::new(vec!["4xx".parse().unwrap(), "5xx".parse().unwrap()]);
+        assert!(fail_on_policy.failures(crawl_summary.page_summaries()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_when_the_seed_time_budget_runs_out() {
+        let site = MockSite::new()
+            .with_slow_page("/", &["/a", "/b"], Duration::from_millis(300))
+            .with_html("/a", &[])
+            .with_html("/b", &[]);
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_max_seed_duration(Some(Duration::from_millis(100)));
+        let (_server, crawl_summary, _) = crawl_site(site, config).await;
+        assert!(crawl_summary.truncated_by_time());
+        assert_eq!(crawl_summary.page_summaries().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn records_pages_over_the_page_timeout_and_moves_on() {
+        let site = MockSite::new()
+            .with_html("/", &["/slow", "/fast"])
+            .with_slow_page("/slow", &[], Duration::from_millis(1000))
+            .with_html("/fast", &[]);
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_page_timeout(Some(Duration::from_millis(200)));
+        let (server, crawl_summary, _) = crawl_site(site, config).await;
+        let slow = crawl_summary
+            .page_summaries()
+            .iter()
+            .find(|page_summary| page_summary.url == server.url("/slow"))
+            .unwrap();
+        assert!(slow.timed_out);
+        assert_eq!(slow.status_code, 0);
+        assert_eq!(status_of(&crawl_summary, &server.url("/fast")), Some(200));
+    }
+
+    #[tokio::test]
+    async fn resumes_an_interrupted_crawl_from_its_checkpoint() {
+        let checkpoint_dir =
+            std::env::temp_dir().join(format!("rusty-spider-test-{}", std::process::id()));
+        std::fs::create_dir_all(&checkpoint_dir).unwrap();
+        let site = MockSite::new()
+            .with_slow_page("/", &["/a", "/b"], Duration::from_millis(300))
+            .with_html("/a", &[])
+            .with_html("/b", &[]);
+        let server = MockSiteServer::start(site).await.unwrap();
+        let crawl = |config: CrawlerConfig| {
+            let seed_crawler = SeedCrawler::new(
+                CancellationToken::new(),
+                server.base_url().clone(),
+                RecordingProgressReporter::new(),
+                SharedCrawlState::new(&config).unwrap(),
+            );
+            async move { seed_crawler.crawl(config).await.unwrap() }
+        };
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_allow_private_networks(true)
+            .with_checkpoint_dir(Some(checkpoint_dir.clone()));
+
+        let interrupted = crawl(
+            config
+                .clone()
+                .with_max_seed_duration(Some(Duration::from_millis(100))),
+        )
+        .await;
+        assert_eq!(interrupted.page_summaries().len(), 1);
+        let resumed = crawl(config).await;
+        assert_eq!(resumed.page_summaries().len(), 3);
+        assert_eq!(std::fs::read_dir(&checkpoint_dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&checkpoint_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancelling_abandons_the_page_in_flight_until_resumed() {
+        let checkpoint_dir =
+            std::env::temp_dir().join(format!("rusty-spider-cancel-test-{}", std::process::id()));
+        std::fs::create_dir_all(&checkpoint_dir).unwrap();
+        let site = MockSite::new()
+            .with_html("/", &["/slow"])
+            .with_slow_page("/slow", &[], Duration::from_millis(1000));
+        let server = MockSiteServer::start(site).await.unwrap();
+        let config = CrawlerConfig::new(100, 4, None)
+            .with_allow_private_networks(true)
+            .with_checkpoint_dir(Some(checkpoint_dir.clone()));
+        let crawl = |cancel_token: CancellationToken| {
+            let seed_crawler = SeedCrawler::new(
+                cancel_token,
+                server.base_url().clone(),
+                RecordingProgressReporter::new(),
+                SharedCrawlState::new(&config).unwrap(),
+            );
+            let config = config.clone();
+            async move { seed_crawler.crawl(config).await.unwrap() }
+        };
+
+        let cancel_token = CancellationToken::new();
+        let crawl_start = tokio::time::Instant::now();
+        let cancelled = tokio::task::spawn(crawl(cancel_token.clone()));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        cancel_token.cancel();
+        let cancelled = cancelled.await.unwrap();
+        assert!(crawl_start.elapsed() < Duration::from_millis(800));
+        assert_eq!(cancelled.page_summaries().len(), 1);
+
+        let resumed = crawl(CancellationToken::new()).await;
+        assert_eq!(status_of(&resumed, &server.url("/slow")), Some(200));
+        std::fs::remove_dir_all(&checkpoint_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn refuses_private_addresses_unless_allowed() {
+        let server = MockSiteServer::start(MockSite::chain(1)).await.unwrap();
+        let config = CrawlerConfig::new(100, 4, None);
+        let seed_crawler = SeedCrawler::new(
+            CancellationToken::new(),
+            server.base_url().clone(),
+            RecordingProgressReporter::new(),
+            SharedCrawlState::new(&config).unwrap(),
+        );
+        let error = seed_crawler.crawl(config).await.unwrap_err();
+        assert!(error.to_string().contains("private address"), "{}", error);
+
+        // Host names are checked once resolved
+        let mut localhost_url = server.base_url().clone();
+        localhost_url.set_host(Some("localhost")).unwrap();
+        let config = CrawlerConfig::new(100, 4, None);
+        let http_client = HttpClientBuilder::new(&config).build().unwrap();
+        let error = http_client.get(localhost_url).send().await.unwrap_err();
+        assert!(format!("{:?}", error).contains("private address"), "{:?}", error);
+    }
+}
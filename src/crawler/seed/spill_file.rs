@@ -0,0 +1,104 @@
+use crate::crawler::seed::queued_url::QueuedUrl;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use url::Url;
+
+/// Append-only file of queued URLs that did not fit in memory, read back in order.
+///
+/// Each line holds depth, referrer ("-" for none) and URL, separated by tabs. Serialized
+/// URLs never contain tabs or newlines.
+pub struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    reader: BufReader<File>,
+    num_pending: usize,
+}
+
+impl SpillFile {
+    pub fn create(path: PathBuf) -> io::Result<Self> {
+        let writer = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)?;
+        let reader = File::open(&path)?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(writer),
+            reader: BufReader::new(reader),
+            num_pending: 0,
+        })
+    }
+
+    pub fn push(&mut self, queued_url: &QueuedUrl) -> io::Result<()> {
+        let discovered_from = queued_url
+            .discovered_from
+            .as_ref()
+            .map_or("-", |url| url.as_str());
+        writeln!(
+            self.writer,
+            "{}\t{}\t{}",
+            queued_url.depth, discovered_from, queued_url.url
+        )?;
+        self.num_pending += 1;
+        Ok(())
+    }
+
+    /// Reads back up to `max_urls` of the oldest spilled URLs.
+    pub fn pop_batch(&mut self, max_urls: usize) -> io::Result<Vec<QueuedUrl>> {
+        self.writer.flush()?;
+        let mut batch = Vec::new();
+        let mut line = String::new();
+        while batch.len() < max_urls && self.num_pending > 0 {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            self.num_pending -= 1;
+            if let Some(queued_url) = Self::parse_line(line.trim_end()) {
+                batch.push(queued_url);
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Reads all spilled URLs not yet popped, leaving them in the file.
+    pub fn pending(&mut self) -> io::Result<Vec<QueuedUrl>> {
+        self.writer.flush()?;
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(self.reader.stream_position()?))?;
+        let mut pending = Vec::new();
+        for line in reader.lines().take(self.num_pending) {
+            if let Some(queued_url) = Self::parse_line(line?.trim_end()) {
+                pending.push(queued_url);
+            }
+        }
+        Ok(pending)
+    }
+
+    pub fn num_pending(&self) -> usize {
+        self.num_pending
+    }
+
+    fn parse_line(line: &str) -> Option<QueuedUrl> {
+        let mut fields = line.splitn(3, '\t');
+        let depth = fields.next()?.parse().ok()?;
+        let discovered_from = match fields.next()? {
+            "-" => None,
+            discovered_from => Some(Url::parse(discovered_from).ok()?),
+        };
+        let url = Url::parse(fields.next()?).ok()?;
+        Some(QueuedUrl {
+            url,
+            depth,
+            discovered_from,
+        })
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
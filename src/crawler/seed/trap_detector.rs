@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, RandomState};
+use url::Url;
+
+/// Distinct URLs a family may have before further members are skipped as a suspected trap.
+pub const DEFAULT_TRAP_FAMILY_LIMIT: usize = 500;
+
+/// Paths with more segments than this are skipped as a suspected trap.
+const TRAP_PATH_DEPTH: usize = 15;
+
+/// A path segment occurring this many times suggests relative links resolving in a loop.
+const TRAP_SEGMENT_REPEATS: usize = 3;
+
+/// Why a URL looked like part of a crawler trap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub enum TrapReason {
+    /// Too many URLs differing only in numbers or dates, like an endless calendar.
+    GrowingFamily,
+    /// A path nested implausibly deep.
+    DeepPath,
+    /// The same path segment over and over, like "/a/b/a/b/a/b".
+    RepeatingSegments,
+}
+
+/// A family of URLs the crawl stopped following, and how many of its URLs were skipped.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SuspectedTrap {
+    /// The URLs' shape, with numbers replaced by "{n}", e.g. "example.com/cal/{n}-{n}".
+    pub family: String,
+    pub reason: TrapReason,
+    pub example_url: Url,
+    pub num_skipped: usize,
+}
+
+/// Clusters discovered URLs into families that differ only in numeric or date segments,
+/// and turns away URLs that look like they belong to an infinite URL space.
+pub struct TrapDetector {
+    family_limit: usize,
+    /// Hashes of the distinct URLs admitted for each family, up to the family limit.
+    families: HashMap<String, HashSet<u64>>,
+    suspected_traps: BTreeMap<(String, TrapReason), SuspectedTrap>,
+    hasher: RandomState,
+}
+
+impl TrapDetector {
+    pub fn new(family_limit: usize) -> Self {
+        Self {
+            family_limit,
+            families: HashMap::new(),
+            suspected_traps: BTreeMap::new(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Whether a discovered URL may be queued. URLs that look like part of a trap are
+    /// recorded and turned away.
+    pub fn admit(&mut self, url: &Url) -> bool {
+        let family = Self::family(url);
+        let segments = url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let reason = if segments.len() > TRAP_PATH_DEPTH {
+            Some(TrapReason::DeepPath)
+        } else if Self::has_repeating_segments(&segments) {
+            Some(TrapReason::RepeatingSegments)
+        } else {
+            let url_hash = self.hasher.hash_one(url.as_str());
+            let members = self.families.entry(family.clone()).or_default();
+            if members.contains(&url_hash) || members.len() < self.family_limit {
+                members.insert(url_hash);
+                None
+            } else {
+                Some(TrapReason::GrowingFamily)
+            }
+        };
+        let Some(reason) = reason else {
+            return true;
+        };
+        self.suspected_traps
+            .entry((family.clone(), reason))
+            .or_insert_with(|| SuspectedTrap {
+                family,
+                reason,
+                example_url: url.clone(),
+                num_skipped: 0,
+            })
+            .num_skipped += 1;
+        false
+    }
+
+    pub fn suspected_traps(&self) -> Vec<SuspectedTrap> {
+        self.suspected_traps.values().cloned().collect()
+    }
+
+    /// The URL with every run of digits in its path and query replaced by "{n}", so that
+    /// e.g. "/calendar/2024-01-05" and "/calendar/2031-12-30" fall in the same family.
+    fn family(url: &Url) -> String {
+        let mut family = String::new();
+        family.push_str(url.host_str().unwrap_or_default());
+        let path_and_query = &url[url::Position::BeforePath..url::Position::AfterQuery];
+        let mut in_digits = false;
+        for c in path_and_query.chars() {
+            if c.is_ascii_digit() {
+                if !in_digits {
+                    family.push_str("{n}");
+                }
+                in_digits = true;
+            } else {
+                family.push(c);
+                in_digits = false;
+            }
+        }
+        family
+    }
+
+    fn has_repeating_segments(segments: &[&str]) -> bool {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        segments.iter().any(|segment| {
+            let count = counts.entry(segment).or_default();
+            *count += 1;
+            *count >= TRAP_SEGMENT_REPEATS
+        })
+    }
+}
+
+impl fmt::Display for TrapReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrapReason::GrowingFamily => write!(f, "too many URLs differing only in numbers"),
+            TrapReason::DeepPath => write!(f, "path over {} segments deep", TRAP_PATH_DEPTH),
+            TrapReason::RepeatingSegments => write!(f, "repeating path segments"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(path: &str) -> Url {
+        Url::parse("https://example.com/").unwrap().join(path).unwrap()
+    }
+
+    #[test]
+    fn turns_away_growing_families_deep_paths_and_repeating_segments() {
+        let mut trap_detector = TrapDetector::new(3);
+        for day in 1..=5 {
+            trap_detector.admit(&url(&format!("/calendar/2024-01-{:02}", day)));
+        }
+        // Already admitted URLs may be rediscovered, and other families are unaffected
+        assert!(trap_detector.admit(&url("/calendar/2024-01-01")));
+        assert!(trap_detector.admit(&url("/products/1")));
+        assert!(!trap_detector.admit(&url(&format!("/{}", ["x"; 16].join("/")))));
+        assert!(!trap_detector.admit(&url("/a/b/a/b/a/b")));
+        assert!(trap_detector.admit(&url("/a/b/a/b")));
+
+        let traps = trap_detector.suspected_traps();
+        assert_eq!(
+            traps
+                .iter()
+                .map(|trap| (trap.family.as_str(), trap.reason, trap.num_skipped))
+                .collect::<Vec<_>>(),
+            vec![
+                ("example.com/a/b/a/b/a/b", TrapReason::RepeatingSegments, 1),
+                ("example.com/calendar/{n}-{n}-{n}", TrapReason::GrowingFamily, 2),
+                ("example.com/x/x/x/x/x/x/x/x/x/x/x/x/x/x/x/x", TrapReason::DeepPath, 1),
+            ]
+        );
+    }
+}
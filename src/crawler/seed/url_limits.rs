@@ -0,0 +1,116 @@
+use std::fmt;
+
+/// Default longest URL queued, the length most browsers and search engines handle.
+pub const DEFAULT_MAX_URL_LENGTH: usize = 2048;
+
+/// Default largest number of path segments in a queued URL.
+pub const DEFAULT_MAX_PATH_SEGMENTS: usize = 32;
+
+/// Default longest query string in a queued URL.
+pub const DEFAULT_MAX_QUERY_LENGTH: usize = 1024;
+
+/// Bounds on the URLs queued from discovered links, so that pathological URLs can't blow
+/// up the frontier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UrlLimits {
+    pub max_url_length: usize,
+    pub max_path_segments: usize,
+    pub max_query_length: usize,
+}
+
+/// Number of discovered URLs turned away by each of the URL limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RejectedUrlCounts {
+    pub too_long: usize,
+    pub too_many_segments: usize,
+    pub query_too_long: usize,
+}
+
+impl UrlLimits {
+    /// Whether `url` is within the limits, counting it in `rejected_url_counts` if not.
+    pub fn admit(&self, url: &url::Url, rejected_url_counts: &mut RejectedUrlCounts) -> bool {
+        if url.as_str().len() > self.max_url_length {
+            rejected_url_counts.too_long += 1;
+            return false;
+        }
+        let num_path_segments = url
+            .path_segments()
+            .map_or(0, |segments| segments.filter(|segment| !segment.is_empty()).count());
+        if num_path_segments > self.max_path_segments {
+            rejected_url_counts.too_many_segments += 1;
+            return false;
+        }
+        if url.query().is_some_and(|query| query.len() > self.max_query_length) {
+            rejected_url_counts.query_too_long += 1;
+            return false;
+        }
+        true
+    }
+}
+
+impl Default for UrlLimits {
+    fn default() -> Self {
+        Self {
+            max_url_length: DEFAULT_MAX_URL_LENGTH,
+            max_path_segments: DEFAULT_MAX_PATH_SEGMENTS,
+            max_query_length: DEFAULT_MAX_QUERY_LENGTH,
+        }
+    }
+}
+
+impl RejectedUrlCounts {
+    pub fn total(&self) -> usize {
+        self.too_long + self.too_many_segments + self.query_too_long
+    }
+
+    pub fn add(&mut self, other: &RejectedUrlCounts) {
+        self.too_long += other.too_long;
+        self.too_many_segments += other.too_many_segments;
+        self.query_too_long += other.query_too_long;
+    }
+}
+
+impl fmt::Display for RejectedUrlCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} too long, {} with too many path segments, {} with too long a query",
+            self.too_long, self.too_many_segments, self.query_too_long
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    #[test]
+    fn counts_urls_beyond_each_limit() {
+        let url_limits = UrlLimits {
+            max_url_length: 40,
+            max_path_segments: 3,
+            max_query_length: 8,
+        };
+        let mut rejected_url_counts = RejectedUrlCounts::default();
+        let urls = [
+            "https://example.com/a/b/c?page=1",
+            "https://example.com/a/very/long/path/indeed/",
+            "https://example.com/a/b/c/d",
+            "https://example.com/?page=1&sort=asc",
+        ];
+        let admitted = urls
+            .iter()
+            .map(|url| url_limits.admit(&Url::parse(url).unwrap(), &mut rejected_url_counts))
+            .collect::<Vec<_>>();
+        assert_eq!(admitted, vec![true, false, false, false]);
+        assert_eq!(
+            rejected_url_counts,
+            RejectedUrlCounts {
+                too_long: 1,
+                too_many_segments: 1,
+                query_too_long: 1,
+            }
+        );
+    }
+}
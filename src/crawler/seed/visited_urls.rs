@@ -0,0 +1,128 @@
+use crate::crawler::seed::bloom_filter::BloomFilter;
+use crate::crawler::visited_filter::VisitedFilterStats;
+use std::collections::HashSet;
+use url::Url;
+
+/// False-positive rate the bloom visited filter is sized for at its expected URL count.
+const BLOOM_FALSE_POSITIVE_RATE: f64 = 0.001;
+
+/// URLs a seed crawler has already crawled, kept exactly or in a bloom filter.
+pub enum VisitedUrls {
+    Exact(HashSet<Url>),
+    Bloom {
+        filter: BloomFilter,
+        estimated_misses: f64,
+    },
+}
+
+impl VisitedUrls {
+    pub fn exact() -> Self {
+        VisitedUrls::Exact(HashSet::new())
+    }
+
+    pub fn bloom(expected_urls: usize) -> Self {
+        VisitedUrls::Bloom {
+            filter: BloomFilter::new(expected_urls, BLOOM_FALSE_POSITIVE_RATE),
+            estimated_misses: 0.0,
+        }
+    }
+
+    pub fn insert(&mut self, url: Url) {
+        match self {
+            VisitedUrls::Exact(urls) => {
+                urls.insert(url);
+            }
+            VisitedUrls::Bloom { filter, .. } => filter.insert(&url),
+        }
+    }
+
+    pub fn contains(&mut self, url: &Url) -> bool {
+        match self {
+            VisitedUrls::Exact(urls) => urls.contains(url),
+            VisitedUrls::Bloom {
+                filter,
+                estimated_misses,
+            } => {
+                let contains = filter.contains(url);
+                // Every lookup of an unvisited URL risks a false positive; negatives are
+                // the best available count of those lookups
+                if !contains {
+                    let false_positive_rate = filter.estimated_false_positive_rate();
+                    *estimated_misses += false_positive_rate / (1.0 - false_positive_rate);
+                }
+                contains
+            }
+        }
+    }
+
+    /// The crawled URLs, if they are kept exactly.
+    pub fn urls(&self) -> Option<Vec<Url>> {
+        match self {
+            VisitedUrls::Exact(urls) => Some(urls.iter().cloned().collect()),
+            VisitedUrls::Bloom { .. } => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            VisitedUrls::Exact(urls) => urls.len(),
+            VisitedUrls::Bloom { filter, .. } => filter.len(),
+        }
+    }
+
+    /// Accuracy statistics, for the bloom filter only.
+    pub fn filter_stats(&self) -> Option<VisitedFilterStats> {
+        match self {
+            VisitedUrls::Exact(_) => None,
+            VisitedUrls::Bloom {
+                filter,
+                estimated_misses,
+            } => Some(VisitedFilterStats {
+                size_in_bytes: filter.size_in_bytes(),
+                num_urls: filter.len(),
+                false_positive_rate: filter.estimated_false_positive_rate(),
+                estimated_misses: *estimated_misses,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(path: &str) -> Url {
+        Url::parse("https://example.com/").unwrap().join(path).unwrap()
+    }
+
+    #[test]
+    fn keeps_exact_urls_without_filter_stats() {
+        let mut visited_urls = VisitedUrls::exact();
+        visited_urls.insert(url("/a"));
+        assert!(visited_urls.contains(&url("/a")));
+        assert!(!visited_urls.contains(&url("/b")));
+        assert_eq!(visited_urls.urls(), Some(vec![url("/a")]));
+        assert!(visited_urls.filter_stats().is_none());
+    }
+
+    #[test]
+    fn estimates_misses_of_the_bloom_filter() {
+        let mut visited_urls = VisitedUrls::bloom(100);
+        for index in 0..100 {
+            visited_urls.insert(url(&format!("/page/{}", index)));
+        }
+        assert!((0..100).all(|index| visited_urls.contains(&url(&format!("/page/{}", index)))));
+        for index in 100..1100 {
+            visited_urls.contains(&url(&format!("/page/{}", index)));
+        }
+        assert_eq!(visited_urls.urls(), None);
+        let filter_stats = visited_urls.filter_stats().unwrap();
+        assert_eq!(filter_stats.num_urls, 100);
+        // About 1000 lookups at a 0.1% false-positive rate
+        assert!(
+            (0.5..2.0).contains(&filter_stats.estimated_misses),
+            "{}",
+            filter_stats.estimated_misses
+        );
+    }
+}
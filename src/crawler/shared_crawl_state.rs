@@ -0,0 +1,155 @@
+use crate::crawler::crawler_config::CrawlerConfig;
+use crate::crawler::distributed::{RedisFrontierService, RemoteFetcher};
+use crate::crawler::events::{EventPublisher, ResultStream};
+use crate::crawler::fetch::{FileFetcher, Fetcher, JsRenderFetcher, RenderMode, ReqwestFetcher};
+use crate::crawler::http::HttpClientBuilder;
+use crate::crawler::memory_guard::MemoryGuard;
+use crate::crawler::multi::SharedVisitedSet;
+use crate::crawler::page::LinkExtractor;
+use crate::crawler::plugin::WasmPlugin;
+use crate::crawler::script::CrawlScript;
+use crate::crawler::seed::CrawlControl;
+use crate::crawler::rate_limit::{
+    BandwidthLimiter, HostCircuitBreaker, HostRateLimiter, ThrottleFeedbackFetcher,
+};
+use crate::crawler::store::BodyStore;
+use std::sync::Arc;
+
+/// Maximum rate of adaptive throttling when no `--rate` is given, in requests per second.
+const DEFAULT_ADAPTIVE_MAX_RATE: f64 = 10.0;
+
+/// State owned by the MultiCrawler and shared by all of its seed crawlers.
+#[derive(Clone)]
+pub struct SharedCrawlState {
+    pub http_client: reqwest::Client,
+    /// Fetches pages; robots.txt and sitemaps always go through the HTTP client.
+    pub fetcher: Arc<dyn Fetcher>,
+    pub body_store: Option<Arc<BodyStore>>,
+    pub visited_set: Option<SharedVisitedSet>,
+    /// Per-host request rate limit, from `--rate`, `--burst` and `--adaptive-rate`.
+    pub rate_limiter: Option<Arc<HostRateLimiter>>,
+    /// Pauses hosts that keep failing, from `--circuit-breaker`.
+    pub circuit_breaker: Option<Arc<HostCircuitBreaker>>,
+    /// Has the crawlers shed memory near the limit, from `--memory-limit`.
+    pub memory_guard: Option<MemoryGuard>,
+    /// When the whole run must stop, from `--max-duration`.
+    pub deadline: Option<tokio::time::Instant>,
+    /// Streams crawl events to NATS as they happen, from `--nats-url`.
+    pub event_publisher: Option<Arc<EventPublisher>>,
+    /// Streams page results as JSON lines as they happen, from `--output jsonl`.
+    pub result_stream: Option<Arc<ResultStream>>,
+    /// Pauses and resumes the seed crawlers.
+    pub crawl_control: CrawlControl,
+    /// Finds the links of HTML pages, with its selectors compiled once for the whole run.
+    pub link_extractor: Arc<LinkExtractor>,
+    /// Custom crawl logic, from `--script`.
+    pub crawl_script: Option<Arc<CrawlScript>>,
+    /// WebAssembly page processors, compiled once for the whole run, from `--plugin`.
+    pub plugins: Vec<Arc<WasmPlugin>>,
+}
+
+impl SharedCrawlState {
+    /// Builds the HTTP clients and fetcher for a run; its deadline starts counting now. Must be
+    /// called within a runtime if events are published.
+    pub fn new(crawler_config: &CrawlerConfig) -> anyhow::Result<Self> {
+        let http_client = HttpClientBuilder::new(crawler_config).build()?;
+        let page_client =
+            HttpClientBuilder::new(crawler_config).with_scoped_redirects(true).build()?;
+        let requests_per_second = match crawler_config.requests_per_second() {
+            None if crawler_config.adaptive_rate() => Some(DEFAULT_ADAPTIVE_MAX_RATE),
+            requests_per_second => requests_per_second,
+        };
+        let rate_limiter = requests_per_second.map(|requests_per_second| {
+            Arc::new(
+                HostRateLimiter::new(requests_per_second, crawler_config.burst())
+                    .with_adaptive(crawler_config.adaptive_rate()),
+            )
+        });
+        let bandwidth_limiter = (crawler_config.max_bandwidth().is_some()
+            || crawler_config.max_host_bandwidth().is_some())
+        .then(|| {
+            Arc::new(BandwidthLimiter::new(
+                crawler_config.max_bandwidth(),
+                crawler_config.max_host_bandwidth(),
+            ))
+        });
+        let mut local_fetcher: Box<dyn Fetcher> = Box::new(FileFetcher::new(Box::new(
+            ReqwestFetcher::new(page_client)
+                .with_max_body_size(crawler_config.max_body_size())
+                .with_bandwidth_limiter(bandwidth_limiter)
+                .with_request_middleware(crawler_config.request_middleware().to_vec()),
+        )));
+        if let Some(rate_limiter) = &rate_limiter {
+            if crawler_config.adaptive_rate() {
+                local_fetcher =
+                    Box::new(ThrottleFeedbackFetcher::new(local_fetcher, Arc::clone(rate_limiter)));
+            }
+        }
+        let work_queue_url = crawler_config.work_queue_url();
+        let fetcher: Arc<dyn Fetcher> = match (work_queue_url, crawler_config.render_mode()) {
+            // Workers fetch with their own options, rendering included
+            (Some(work_queue_url), _) => Arc::new(RemoteFetcher::start(
+                Arc::new(RedisFrontierService::new(
+                    work_queue_url.clone(),
+                    crawler_config.queue_name(),
+                )),
+                crawler_config.dispatch_window(),
+            )),
+            (None, RenderMode::None) => Arc::from(local_fetcher),
+            (None, RenderMode::Js) => Arc::new(
+                JsRenderFetcher::new(
+                    local_fetcher,
+                    crawler_config.webdriver_url().clone(),
+                    crawler_config.render_timeout(),
+                    crawler_config.render_tabs(),
+                )?
+                .with_rate_limiter(rate_limiter.clone())
+                .with_allow_private_networks(crawler_config.allow_private_networks()),
+            ),
+        };
+        Ok(Self {
+            http_client,
+            fetcher,
+            body_store: match crawler_config.save_bodies_dir() {
+                Some(save_bodies_dir) => Some(Arc::new(BodyStore::create(save_bodies_dir)?)),
+                None => None,
+            },
+            visited_set: crawler_config
+                .global_dedupe()
+                .then(SharedVisitedSet::new),
+            rate_limiter,
+            circuit_breaker: crawler_config.circuit_breaker_threshold().map(|threshold| {
+                Arc::new(HostCircuitBreaker::new(
+                    threshold,
+                    crawler_config.circuit_breaker_cooldown(),
+                ))
+            }),
+            memory_guard: crawler_config.memory_limit().map(MemoryGuard::start),
+            deadline: crawler_config
+                .max_duration()
+                .map(|max_duration| tokio::time::Instant::now() + max_duration),
+            event_publisher: crawler_config.nats_url().map(|nats_url| {
+                Arc::new(EventPublisher::spawn(nats_url.clone(), crawler_config.nats_subject()))
+            }),
+            result_stream: match crawler_config.jsonl_output() {
+                Some(jsonl_output) => Some(Arc::new(ResultStream::spawn(jsonl_output)?)),
+                None => None,
+            },
+            crawl_control: CrawlControl::new(),
+            link_extractor: Arc::new(
+                LinkExtractor::new()
+                    .with_link_selector(crawler_config.link_selector())?
+                    .with_route_fragments(crawler_config.spa_routes()),
+            ),
+            crawl_script: match crawler_config.script_file() {
+                Some(script_file) => Some(Arc::new(CrawlScript::load(script_file)?)),
+                None => None,
+            },
+            plugins: crawler_config
+                .plugin_files()
+                .iter()
+                .map(|plugin_file| WasmPlugin::load(plugin_file).map(Arc::new))
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
@@ -0,0 +1,3 @@
+mod sitemap_loader;
+
+pub use sitemap_loader::SitemapLoader;
@@ -0,0 +1,192 @@
+use crate::crawler::http::ContentDecoder;
+use anyhow::Context;
+use std::collections::{HashSet, VecDeque};
+use url::Url;
+use xml::reader::{EventReader, XmlEvent};
+
+/// Upper bound on the number of sitemap files fetched, including nested sitemap indexes.
+const MAX_SITEMAPS: usize = 100;
+
+/// Fetches sitemaps and collects the page URLs they list, following sitemap indexes.
+pub struct SitemapLoader {
+    http_client: reqwest::Client,
+}
+
+/// The page URLs found in a set of sitemaps, and the sitemaps that could not be loaded.
+#[derive(Debug, Default)]
+pub struct LoadedSitemaps {
+    pub page_urls: Vec<Url>,
+    pub failures: Vec<anyhow::Error>,
+}
+
+/// The URLs found in one sitemap file.
+#[derive(Default)]
+struct SitemapEntries {
+    page_urls: Vec<Url>,
+    /// Nested sitemaps, when the file is a sitemap index.
+    sitemap_urls: Vec<Url>,
+}
+
+impl SitemapLoader {
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self { http_client }
+    }
+
+    /// Sitemaps to load for `seed_url`: those named in robots.txt, or /sitemap.xml if none are.
+    pub fn sitemap_urls_for_seed(seed_url: &Url, robots_txt_sitemaps: Vec<Url>) -> Vec<Url> {
+        if !robots_txt_sitemaps.is_empty() {
+            return robots_txt_sitemaps;
+        }
+        let mut sitemap_url = seed_url.clone();
+        sitemap_url.set_path("/sitemap.xml");
+        sitemap_url.set_query(None);
+        sitemap_url.set_fragment(None);
+        vec![sitemap_url]
+    }
+
+    /// Loads `sitemap_urls` and the sitemaps they nest. A sitemap that fails to load is
+    /// recorded and skipped; the load only fails if none of them could be loaded.
+    pub async fn load(&self, sitemap_urls: Vec<Url>) -> anyhow::Result<LoadedSitemaps> {
+        let mut pending = VecDeque::from(sitemap_urls);
+        let mut fetched = HashSet::new();
+        let mut loaded = LoadedSitemaps::default();
+        while let Some(sitemap_url) = pending.pop_front() {
+            if fetched.len() >= MAX_SITEMAPS {
+                break;
+            }
+            if !fetched.insert(sitemap_url.clone()) {
+                continue;
+            }
+            match self.load_sitemap(&sitemap_url).await {
+                Ok(entries) => {
+                    loaded.page_urls.extend(entries.page_urls);
+                    pending.extend(entries.sitemap_urls);
+                }
+                Err(e) => loaded.failures.push(e),
+            }
+        }
+        if loaded.failures.len() == fetched.len() {
+            if let Some(e) = loaded.failures.pop() {
+                return Err(e);
+            }
+        }
+        Ok(loaded)
+    }
+
+    async fn load_sitemap(&self, sitemap_url: &Url) -> anyhow::Result<SitemapEntries> {
+        let response = self
+            .http_client
+            .get(sitemap_url.clone())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Failed to fetch sitemap {}", sitemap_url))?;
+        let content_encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let body = response.bytes().await?;
+        let mut body = ContentDecoder::decode_content(&body, content_encoding.as_deref(), None)?;
+        // sitemap.xml.gz files are usually served as plain application/gzip
+        if body.starts_with(&[0x1f, 0x8b]) {
+            body = ContentDecoder::decode_content(&body, Some("gzip"), None)?;
+        }
+        Self::parse_sitemap(&body)
+            .with_context(|| format!("Failed to parse sitemap {}", sitemap_url))
+    }
+
+    fn parse_sitemap(body: &[u8]) -> anyhow::Result<SitemapEntries> {
+        let mut entries = SitemapEntries::default();
+        // Local names of the open elements, e.g. ["urlset", "url", "loc"]
+        let mut open_elements: Vec<String> = Vec::new();
+        let mut loc_text = String::new();
+        for event in EventReader::new(body) {
+            match event? {
+                XmlEvent::StartElement { name, .. } => {
+                    if name.local_name == "loc" {
+                        loc_text.clear();
+                    }
+                    open_elements.push(name.local_name);
+                }
+                XmlEvent::Characters(text) | XmlEvent::CData(text)
+                    if open_elements.last().is_some_and(|element| element == "loc") =>
+                {
+                    loc_text.push_str(&text);
+                }
+                XmlEvent::EndElement { name } => {
+                    open_elements.pop();
+                    if name.local_name != "loc" {
+                        continue;
+                    }
+                    let Ok(url) = Url::parse(loc_text.trim()) else {
+                        continue;
+                    };
+                    match open_elements.last().map(String::as_str) {
+                        Some("url") => entries.page_urls.push(url),
+                        Some("sitemap") => entries.sitemap_urls.push(url),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockPage, MockSite, MockSiteServer};
+
+    fn xml_page(body: String) -> MockPage {
+        let mut page = MockPage::status(200).with_header("content-type", "application/xml");
+        page.body = body;
+        page
+    }
+
+    #[tokio::test]
+    async fn skips_nested_sitemaps_that_fail_to_load() {
+        let pages = MockSiteServer::start(MockSite::new()).await.unwrap();
+        let urlset = format!(
+            "<urlset><url><loc>{}</loc></url><url><loc>{}</loc></url></urlset>",
+            pages.url("/a"),
+            pages.url("/b"),
+        );
+        let pages_sitemap =
+            MockSiteServer::start(MockSite::new().with_page("/pages.xml", xml_page(urlset)))
+                .await
+                .unwrap();
+        let index = format!(
+            "<sitemapindex><sitemap><loc>{}</loc></sitemap>\
+             <sitemap><loc>{}</loc></sitemap></sitemapindex>",
+            pages_sitemap.url("/missing.xml"),
+            pages_sitemap.url("/pages.xml"),
+        );
+        let index_server =
+            MockSiteServer::start(MockSite::new().with_page("/sitemap.xml", xml_page(index)))
+                .await
+                .unwrap();
+
+        let loader = SitemapLoader::new(reqwest::Client::new());
+        let loaded = loader
+            .load(vec![index_server.url("/sitemap.xml")])
+            .await
+            .unwrap();
+        assert_eq!(loaded.page_urls, vec![pages.url("/a"), pages.url("/b")]);
+        assert_eq!(loaded.failures.len(), 1);
+        assert!(format!("{:#}", loaded.failures[0]).contains("/missing.xml"));
+    }
+
+    #[tokio::test]
+    async fn fails_when_no_sitemap_loads() {
+        let server = MockSiteServer::start(MockSite::new()).await.unwrap();
+        let loader = SitemapLoader::new(reqwest::Client::new());
+        let error = loader
+            .load(vec![server.url("/sitemap.xml")])
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("404"), "{:#}", error);
+    }
+}
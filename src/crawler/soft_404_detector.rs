@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use std::hash::{BuildHasher, RandomState};
+
+/// Phrases that mark a page as an error page when they appear as whole words in its title
+/// or short body. A bare "404" is left out, as it turns up in route numbers and prices.
+pub const DEFAULT_SOFT_404_PATTERNS: &[&str] = &[
+    "not found",
+    "page does not exist",
+    "page doesn't exist",
+    "no longer available",
+    "error 404",
+    "404 error",
+];
+
+/// Bodies longer than this many words are not matched against the patterns; a real
+/// page can mention "not found" in passing, an error page rarely says much else.
+const MAX_PATTERN_MATCH_WORDS: usize = 150;
+
+/// Word overlap with the probe page above which a page is considered the same error page.
+const PROBE_SIMILARITY_THRESHOLD: f64 = 0.9;
+
+/// Flags 200 responses that are really "not found" pages.
+#[derive(Debug, Clone, Default)]
+pub struct Soft404Detector {
+    patterns: Vec<String>,
+    /// Words of the page served for a URL that cannot exist, if the site answered it with 200.
+    probe_words: Option<HashSet<String>>,
+}
+
+impl Soft404Detector {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|pattern| pattern.to_lowercase())
+                .collect(),
+            probe_words: None,
+        }
+    }
+
+    /// Path of a URL that should not exist on any site, used to learn what its error page
+    /// looks like.
+    pub fn probe_path() -> String {
+        let nonce = RandomState::new().hash_one("rusty-spider-soft-404-probe");
+        format!("/rusty-spider-probe-{:016x}", nonce)
+    }
+
+    /// Remembers the text of the page the site served for `probe_path` with a 200 status.
+    pub fn set_probe_text(&mut self, probe_text: &str) {
+        self.probe_words = Some(Self::words(probe_text));
+    }
+
+    pub fn is_soft_404(&self, title: &str, body_text: &str) -> bool {
+        let title = title.to_lowercase();
+        if self.matches_pattern(&title) {
+            return true;
+        }
+
+        let words = Self::words(body_text);
+        if words.len() <= MAX_PATTERN_MATCH_WORDS {
+            let body_text = body_text.to_lowercase();
+            if self.matches_pattern(&body_text) {
+                return true;
+            }
+        }
+
+        match &self.probe_words {
+            Some(probe_words) if !probe_words.is_empty() && !words.is_empty() => {
+                let shared = words.intersection(probe_words).count();
+                let total = words.union(probe_words).count();
+                shared as f64 / total as f64 >= PROBE_SIMILARITY_THRESHOLD
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether one of the patterns occurs in the lowercased `text` with no letter or digit
+    /// right before or after it, so "404 error" does not match "4404 errors".
+    fn matches_pattern(&self, text: &str) -> bool {
+        self.patterns
+            .iter()
+            .filter(|pattern| !pattern.is_empty())
+            .any(|pattern| {
+                text.match_indices(pattern.as_str()).any(|(start, _)| {
+                    let before = text[..start].chars().next_back();
+                    let after = text[start + pattern.len()..].chars().next();
+                    !before.is_some_and(char::is_alphanumeric)
+                        && !after.is_some_and(char::is_alphanumeric)
+                })
+            })
+    }
+
+    fn words(text: &str) -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> Soft404Detector {
+        let patterns: Vec<String> = DEFAULT_SOFT_404_PATTERNS
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect();
+        Soft404Detector::new(&patterns)
+    }
+
+    #[test]
+    fn flags_error_pages_by_title_and_short_body() {
+        let detector = detector();
+        assert!(detector.is_soft_404("Error 404 - Example", "Welcome"));
+        assert!(detector.is_soft_404("Page Not Found", ""));
+        assert!(detector.is_soft_404("Example", "Sorry, this page doesn't exist."));
+    }
+
+    #[test]
+    fn matches_patterns_only_as_whole_words() {
+        let detector = detector();
+        assert!(!detector.is_soft_404("Route 404 timetable", "Departures every hour"));
+        assert!(!detector.is_soft_404("Top 4040 tips", "Tip one"));
+        assert!(!detector.is_soft_404("Terror 404 errors explained", ""));
+        assert!(!detector.is_soft_404("Example", "Nothing was notfound here"));
+    }
+
+    #[test]
+    fn flags_pages_that_look_like_the_probe_page() {
+        let mut detector = detector();
+        detector.set_probe_text("Oops, we could not find that. Go back home.");
+        assert!(detector.is_soft_404("Example", "Oops, we could not find that. Go back home."));
+        assert!(!detector.is_soft_404("Example", "Our lamps are handmade in small batches."));
+    }
+}
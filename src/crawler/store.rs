@@ -0,0 +1,7 @@
+#![allow(unused_imports)]
+
+mod body_store;
+mod reprocessor;
+
+pub use body_store::{BodyStore, StoredBody};
+pub use reprocessor::Reprocessor;
@@ -0,0 +1,172 @@
+use anyhow::{Context, anyhow};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use url::Url;
+
+/// Name of the index file that maps stored bodies back to the URLs they were fetched from.
+const INDEX_FILE_NAME: &str = "index.tsv";
+
+/// A single page body recorded in a body store.
+#[derive(Debug, Clone)]
+pub struct StoredBody {
+    pub url: Url,
+    pub status_code: u16,
+    pub content_type: String,
+    /// Path of the body file, relative to the store root.
+    pub path: PathBuf,
+}
+
+/// A directory of previously fetched page bodies.
+///
+/// Bodies are content-addressed: each one is written to `<aa>/<sha256>.html`, where `aa` is the
+/// first two hex digits of the SHA-256 of the body, so identical pages are only stored once.
+/// The store root also contains an `index.tsv` file with one tab-separated
+/// `url, status code, content type, relative path` record per stored body.
+pub struct BodyStore {
+    root: PathBuf,
+    index_writer: Mutex<Option<fs::File>>,
+}
+
+impl BodyStore {
+    pub fn open(root: &Path) -> anyhow::Result<Self> {
+        if !root.is_dir() {
+            return Err(anyhow!("Body store '{}' is not a directory", root.display()));
+        }
+        Ok(Self {
+            root: root.to_owned(),
+            index_writer: Mutex::new(None),
+        })
+    }
+
+    /// Opens a body store for writing, creating the directory if needed.
+    pub fn create(root: &Path) -> anyhow::Result<Self> {
+        fs::create_dir_all(root)
+            .with_context(|| format!("Unable to create body store '{}'", root.display()))?;
+        let index_path = root.join(INDEX_FILE_NAME);
+        let index_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index_path)
+            .with_context(|| format!("Unable to open body store index '{}'", index_path.display()))?;
+        Ok(Self {
+            root: root.to_owned(),
+            index_writer: Mutex::new(Some(index_file)),
+        })
+    }
+
+    /// Writes a page body to the store and returns the path it was written to.
+    pub fn save_body(
+        &self,
+        url: &Url,
+        status_code: u16,
+        content_type: &str,
+        body: &str,
+    ) -> anyhow::Result<PathBuf> {
+        let digest = format!("{:x}", Sha256::digest(body.as_bytes()));
+        let relative_path = PathBuf::from(&digest[..2]).join(format!("{}.html", digest));
+        let body_path = self.root.join(&relative_path);
+        if !body_path.exists() {
+            fs::create_dir_all(body_path.parent().unwrap())?;
+            fs::write(&body_path, body)
+                .with_context(|| format!("Unable to write body '{}'", body_path.display()))?;
+        }
+
+        let record = format!(
+            "{}\t{}\t{}\t{}\n",
+            url,
+            status_code,
+            content_type.replace(['\t', '\n', '\r'], " "),
+            relative_path.display()
+        );
+        let mut index_writer = self.index_writer.lock().unwrap();
+        let index_file = index_writer
+            .as_mut()
+            .ok_or_else(|| anyhow!("Body store '{}' is read-only", self.root.display()))?;
+        index_file.write_all(record.as_bytes())?;
+
+        Ok(body_path)
+    }
+
+    pub fn entries(&self) -> anyhow::Result<Vec<StoredBody>> {
+        let index_path = self.root.join(INDEX_FILE_NAME);
+        let index_file = fs::File::open(&index_path)
+            .with_context(|| format!("Unable to open body store index '{}'", index_path.display()))?;
+
+        let mut entries = Vec::new();
+        for (line_index, line) in BufReader::new(index_file).lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let entry = Self::parse_index_line(&line).with_context(|| {
+                format!("{}:{}: malformed index record", index_path.display(), line_index + 1)
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    pub fn read_body(&self, entry: &StoredBody) -> anyhow::Result<String> {
+        let body_path = self.root.join(&entry.path);
+        fs::read_to_string(&body_path)
+            .with_context(|| format!("Unable to read stored body '{}'", body_path.display()))
+    }
+
+    fn parse_index_line(line: &str) -> anyhow::Result<StoredBody> {
+        let fields = line.split('\t').collect::<Vec<&str>>();
+        if fields.len() != 4 {
+            return Err(anyhow!("expected 4 fields, found {}", fields.len()));
+        }
+        Ok(StoredBody {
+            url: Url::parse(fields[0])?,
+            status_code: fields[1].parse()?,
+            content_type: fields[2].to_owned(),
+            path: PathBuf::from(fields[3]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_dir(name: &str) -> PathBuf {
+        let dir_name = format!("rusty-spider-body-store-{}-{}", name, std::process::id());
+        std::env::temp_dir().join(dir_name)
+    }
+
+    #[test]
+    fn stores_identical_bodies_once_and_indexes_every_page() {
+        let store_dir = store_dir("dedupe");
+        let body_store = BodyStore::create(&store_dir).unwrap();
+        let page_a = Url::parse("https://example.com/a").unwrap();
+        let page_b = Url::parse("https://example.com/b").unwrap();
+        let path_a = body_store.save_body(&page_a, 200, "text/html", "<p>Same</p>").unwrap();
+        let path_b = body_store.save_body(&page_b, 404, "text/html", "<p>Same</p>").unwrap();
+        assert_eq!(path_a, path_b);
+
+        let body_store = BodyStore::open(&store_dir).unwrap();
+        let entries = body_store.entries().unwrap();
+        let urls = entries.iter().map(|entry| &entry.url).collect::<Vec<_>>();
+        assert_eq!(urls, [&page_a, &page_b]);
+        assert_eq!(entries[1].status_code, 404);
+        assert_eq!(body_store.read_body(&entries[1]).unwrap(), "<p>Same</p>");
+        let error = body_store.save_body(&page_a, 200, "text/html", "").unwrap_err();
+        assert!(error.to_string().contains("read-only"), "{}", error);
+        fs::remove_dir_all(&store_dir).unwrap();
+    }
+
+    #[test]
+    fn names_the_line_of_a_malformed_index_record() {
+        let store_dir = store_dir("malformed");
+        fs::create_dir_all(&store_dir).unwrap();
+        fs::write(store_dir.join(INDEX_FILE_NAME), "https://example.com/\tnot a status\n")
+            .unwrap();
+        let error = BodyStore::open(&store_dir).unwrap().entries().unwrap_err();
+        fs::remove_dir_all(&store_dir).unwrap();
+        assert!(error.to_string().ends_with("index.tsv:1: malformed index record"), "{}", error);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::crawler::crawl_error::CrawlError;
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::crawler::content::ContentHandlers;
+use crate::crawler::crawler_config::CrawlerConfig;
+use crate::crawler::fetch::{FetchResult, Fetcher};
+use crate::crawler::page::{FetchedPage, LinkExtractor, PageCrawler};
+use crate::crawler::page_summary::PageSummary;
+use crate::crawler::plugin::WasmPlugin;
+use crate::crawler::script::CrawlScript;
+use crate::crawler::search::TextSearch;
+use crate::crawler::store::body_store::BodyStore;
+use anyhow::anyhow;
+use futures::future::BoxFuture;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use std::sync::Arc;
+use url::Url;
+
+/// Re-runs page extraction over the bodies in a body store without touching the network.
+pub struct Reprocessor {
+    body_store: BodyStore,
+    page_crawler: PageCrawler,
+    search: Option<TextSearch>,
+}
+
+/// Stands in for the network, which reprocessing never uses.
+struct OfflineFetcher;
+
+impl Fetcher for OfflineFetcher {
+    fn fetch<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, Result<FetchResult, CrawlError>> {
+        Box::pin(async move { Err(anyhow!("Not fetching {} while reprocessing", url).into()) })
+    }
+}
+
+impl Reprocessor {
+    /// Parses the stored bodies as a crawl with `crawler_config` would, with its extract
+    /// rules, text search, crawl script and plugins.
+    pub fn new(body_store: BodyStore, crawler_config: &CrawlerConfig) -> anyhow::Result<Self> {
+        let link_extractor = LinkExtractor::new()
+            .with_link_selector(crawler_config.link_selector())?
+            .with_route_fragments(crawler_config.spa_routes());
+        let crawl_script = match crawler_config.script_file() {
+            Some(script_file) => Some(Arc::new(CrawlScript::load(script_file)?)),
+            None => None,
+        };
+        let plugins = crawler_config
+            .plugin_files()
+            .iter()
+            .map(|plugin_file| WasmPlugin::load(plugin_file).map(Arc::new))
+            .collect::<anyhow::Result<_>>()?;
+        let page_crawler = PageCrawler::new(Arc::new(OfflineFetcher))
+            .with_extract_rules(crawler_config.extract_rules().to_vec())
+            .with_accessibility_checks(crawler_config.accessibility_checks())
+            .with_content_handlers(ContentHandlers::new(crawler_config.content_kinds()))
+            .with_link_extractor(Arc::new(link_extractor))
+            .with_streaming_html(crawler_config.streaming_html())
+            .with_crawl_script(crawl_script)
+            .with_plugins(plugins);
+        Ok(Self {
+            body_store,
+            page_crawler,
+            search: crawler_config.search().cloned(),
+        })
+    }
+
+    pub fn run(&self) -> anyhow::Result<CrawlSummary> {
+        let mut crawl_summary = CrawlSummary::default();
+        for entry in self.body_store.entries()? {
+            let body = self.body_store.read_body(&entry)?.into_bytes();
+            let mut headers = HeaderMap::new();
+            if let Ok(content_type) = HeaderValue::from_str(&entry.content_type) {
+                headers.insert(CONTENT_TYPE, content_type);
+            }
+            let fetched_page = FetchedPage {
+                url: entry.url.clone(),
+                status_code: entry.status_code,
+                http_version: None,
+                headers,
+                content_encoding: None,
+                transferred_bytes: body.len(),
+                body,
+                render_error: None,
+            };
+            let crawl_response = match self.page_crawler.parse(&fetched_page) {
+                Ok(crawl_response) => crawl_response,
+                Err(CrawlError::Skipped(status_code, reason)) => {
+                    let mut page_summary =
+                        PageSummary::from_status_code(entry.url, 0, status_code);
+                    page_summary.skipped = Some(reason);
+                    crawl_summary.add_page_summary(page_summary);
+                    continue;
+                }
+                Err(CrawlError::UnsupportedContentType(status_code, content_type, _)) => {
+                    let mut page_summary =
+                        PageSummary::from_status_code(entry.url, 0, status_code);
+                    page_summary.content_type = content_type;
+                    crawl_summary.add_page_summary(page_summary);
+                    continue;
+                }
+                Err(e) => return Err(anyhow!("Unable to reprocess {}: {}", entry.url, e)),
+            };
+            let search_matches = self
+                .search
+                .as_ref()
+                .and_then(|search| search.search(&crawl_response.body_text));
+            let mut page_summary = PageSummary::new(
+                crawl_response.url,
+                0,
+                crawl_response.status_code,
+                crawl_response.content_type,
+                crawl_response.title,
+                crawl_response.outgoing_links.len(),
+            );
+            page_summary.outgoing_link_hosts =
+                PageSummary::count_by_host(&crawl_response.outgoing_links);
+            page_summary.meta_refresh = crawl_response.meta_refresh;
+            page_summary.robots_directives = crawl_response.robots_directives;
+            page_summary.extracted = crawl_response.extracted;
+            page_summary.accessibility = crawl_response.accessibility;
+            page_summary.search_matches = search_matches;
+            crawl_summary.add_page_summary(page_summary);
+        }
+        Ok(crawl_summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reprocesses_stored_bodies_with_the_crawl_options() {
+        let store_dir = std::env::temp_dir()
+            .join(format!("rusty-spider-reprocess-test-{}", std::process::id()));
+        let body_store = BodyStore::create(&store_dir).unwrap();
+        body_store
+            .save_body(
+                &Url::parse("https://example.com/product").unwrap(),
+                200,
+                "text/html",
+                "<html><head><title>Lamp</title></head>\
+                 <body><span class=\"price\">12 EUR</span> Out of stock</body></html>",
+            )
+            .unwrap();
+        let config = CrawlerConfig::new(10, 2, None)
+            .with_extract_rules(vec!["price:.price".parse().unwrap()])
+            .with_search(Some("(?i)out of stock".parse().unwrap()));
+
+        let reprocessor = Reprocessor::new(BodyStore::open(&store_dir).unwrap(), &config).unwrap();
+        let crawl_summary = reprocessor.run().unwrap();
+        std::fs::remove_dir_all(&store_dir).unwrap();
+
+        let page_summary = &crawl_summary.page_summaries()[0];
+        assert_eq!(page_summary.title, "Lamp");
+        assert_eq!(page_summary.extracted["price"], "12 EUR");
+        assert_eq!(page_summary.search_matches.as_ref().unwrap().num_matches, 1);
+    }
+}
@@ -0,0 +1,5 @@
+mod readability;
+mod text_stats;
+
+pub use readability::flesch_reading_ease;
+pub use text_stats::{THIN_CONTENT_WORDS, TextStats};
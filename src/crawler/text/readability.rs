@@ -0,0 +1,71 @@
+/// The Flesch reading ease of English text: roughly 90-100 is easy enough for an 11 year old,
+/// 60-70 is plain English and below 30 is best understood by university graduates.
+/// Clamped to 0-100, as text without sentence punctuation sends the raw score far below zero.
+/// None if the text has no words.
+pub fn flesch_reading_ease(text: &str) -> Option<f64> {
+    let words = text
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        return None;
+    }
+    // Text without terminal punctuation, like a list of links, still counts as one sentence
+    let num_sentences = text
+        .split(['.', '!', '?'])
+        .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+        .count()
+        .max(1);
+    let num_syllables = words.iter().map(|word| count_syllables(word)).sum::<usize>();
+    let num_words = words.len() as f64;
+    let reading_ease = 206.835
+        - 1.015 * (num_words / num_sentences as f64)
+        - 84.6 * (num_syllables as f64 / num_words);
+    Some(reading_ease.clamp(0.0, 100.0))
+}
+
+/// Estimates syllables by counting groups of vowels, ignoring a silent final "e".
+fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+    let mut num_syllables = 0;
+    let mut previous_was_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_was_vowel {
+            num_syllables += 1;
+        }
+        previous_was_vowel = vowel;
+    }
+    if word.ends_with('e') && !word.ends_with("le") && num_syllables > 1 {
+        num_syllables -= 1;
+    }
+    num_syllables.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_text_reads_more_easily_than_dense_text() {
+        let simple = flesch_reading_ease("The cat sat on the mat. It was a good cat.").unwrap();
+        let dense = flesch_reading_ease(
+            "Institutional considerations notwithstanding, organizational interoperability \
+             necessitates comprehensive standardization initiatives.",
+        )
+        .unwrap();
+        assert!(simple > 90.0, "{}", simple);
+        assert_eq!(dense, 0.0);
+        assert_eq!(flesch_reading_ease(" ... "), None);
+    }
+
+    #[test]
+    fn counts_syllables() {
+        assert_eq!(count_syllables("cat"), 1);
+        assert_eq!(count_syllables("make"), 1);
+        assert_eq!(count_syllables("table"), 2);
+        assert_eq!(count_syllables("readability"), 5);
+    }
+}
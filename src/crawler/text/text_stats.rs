@@ -0,0 +1,33 @@
+use crate::crawler::text::flesch_reading_ease;
+
+/// Pages with fewer words than this count as thin content.
+pub const THIN_CONTENT_WORDS: usize = 200;
+
+/// Measures of the visible text of a page, for content audits.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TextStats {
+    pub word_count: usize,
+    /// Share of the HTML that is visible text, from 0 to 1.
+    pub text_to_html_ratio: f64,
+    /// Flesch reading ease, if readability scoring was requested.
+    pub reading_ease: Option<f64>,
+}
+
+impl TextStats {
+    /// Stats for a page whose visible text, with whitespace collapsed, is `text`.
+    pub fn new(text: &str, html_bytes: usize, readability: bool) -> Self {
+        Self {
+            word_count: text.split_whitespace().count(),
+            text_to_html_ratio: if html_bytes > 0 {
+                (text.len() as f64 / html_bytes as f64).min(1.0)
+            } else {
+                0.0
+            },
+            reading_ease: if readability { flesch_reading_ease(text) } else { None },
+        }
+    }
+
+    pub fn is_thin(&self) -> bool {
+        self.word_count < THIN_CONTENT_WORDS
+    }
+}
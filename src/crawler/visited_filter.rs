@@ -0,0 +1,20 @@
+/// How each seed crawler remembers the URLs it has already crawled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum VisitedFilter {
+    /// An exact set of URLs
+    #[default]
+    Exact,
+    /// A bloom filter: far smaller, but may skip a few never-crawled URLs as false positives
+    Bloom,
+}
+
+/// How well a bloom visited filter held up over a crawl.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize)]
+pub struct VisitedFilterStats {
+    pub size_in_bytes: usize,
+    pub num_urls: usize,
+    /// False-positive rate at the end of the crawl.
+    pub false_positive_rate: f64,
+    /// Expected number of uncrawled URLs wrongly skipped as already visited.
+    pub estimated_misses: f64,
+}
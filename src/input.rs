@@ -0,0 +1,7 @@
+pub mod bandwidth;
+pub mod count;
+pub mod css_selector;
+pub mod domain_list;
+pub mod rate;
+pub mod request_header;
+pub mod seed_list;
@@ -0,0 +1,32 @@
+use bytesize::ByteSize;
+
+/// Parses a transfer rate in bytes per second, e.g. "2MBps", "512KiB/s" or "1MB".
+pub fn parse_bandwidth(s: &str) -> Result<u64, String> {
+    let trimmed = s.trim();
+    let lowercase = trimmed.to_ascii_lowercase();
+    let size = if lowercase.ends_with("/s") || lowercase.ends_with("ps") {
+        &trimmed[..trimmed.len() - 2]
+    } else {
+        trimmed
+    };
+    size.trim()
+        .parse::<ByteSize>()
+        .ok()
+        .map(|size| size.as_u64())
+        .filter(|bytes_per_second| *bytes_per_second > 0)
+        .ok_or_else(|| format!("Invalid bandwidth '{}' (expected e.g. 2MBps or 512KiB/s)", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rates_with_or_without_a_per_second_suffix() {
+        assert_eq!(parse_bandwidth("2MBps"), Ok(2_000_000));
+        assert_eq!(parse_bandwidth("512KiB/s"), Ok(512 * 1024));
+        assert_eq!(parse_bandwidth("100 KB"), Ok(100_000));
+        assert!(parse_bandwidth("0MBps").is_err());
+        assert!(parse_bandwidth("fast").is_err());
+    }
+}
@@ -0,0 +1,6 @@
+use scraper::Selector;
+
+/// Parses a CSS selector, e.g. "#cookie-banner, .consent-dialog".
+pub fn parse_css_selector(s: &str) -> Result<Selector, String> {
+    Selector::parse(s).map_err(|e| format!("Invalid selector '{}': {}", s, e))
+}
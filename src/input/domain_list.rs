@@ -0,0 +1,52 @@
+use crate::input::seed_list::STDIN_MARKER;
+use anyhow::Context;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Bare domains such as "example.com" whose entry points are probed for use as seeds.
+#[derive(Debug, Clone, Default)]
+pub struct DomainList {
+    domains: Vec<String>,
+}
+
+impl DomainList {
+    pub fn new() -> Self {
+        Self {
+            domains: Vec::new(),
+        }
+    }
+
+    pub fn domains(&self) -> &[String] {
+        &self.domains
+    }
+
+    /// Loads domains from a file; "-" reads domains from stdin.
+    pub fn load_from_path(&mut self, path: &Path) -> anyhow::Result<()> {
+        if path.as_os_str() == STDIN_MARKER {
+            let stdin = std::io::stdin();
+            return self.load_from_reader(stdin.lock(), "<stdin>");
+        }
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open domain file '{}'", path.display()))?;
+        self.load_from_reader(BufReader::new(file), &path.display().to_string())
+    }
+
+    /// Reads one domain per line. Blank lines and lines starting with "#" are ignored.
+    pub fn load_from_reader<R: BufRead>(
+        &mut self,
+        reader: R,
+        source_name: &str,
+    ) -> anyhow::Result<()> {
+        for line in reader.lines() {
+            let line =
+                line.with_context(|| format!("Unable to read domains from {}", source_name))?;
+            let domain = line.trim().to_ascii_lowercase();
+            if domain.is_empty() || domain.starts_with('#') || self.domains.contains(&domain) {
+                continue;
+            }
+            self.domains.push(domain);
+        }
+        Ok(())
+    }
+}
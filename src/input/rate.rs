@@ -0,0 +1,27 @@
+/// Parses a rate limit in requests per second, which must be a positive number.
+pub fn parse_rate(s: &str) -> Result<f64, String> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|rate| is_valid_rate(*rate))
+        .ok_or_else(|| format!("Invalid rate '{}' (expected a positive number, e.g. 2 or 0.5)", s))
+}
+
+/// Whether `rate` can be used as a rate limit: finite and above zero.
+pub fn is_valid_rate(rate: f64) -> bool {
+    rate.is_finite() && rate > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_positive_finite_rates() {
+        assert_eq!(parse_rate("2"), Ok(2.0));
+        assert_eq!(parse_rate(" 0.5 "), Ok(0.5));
+        for rate in ["0", "-1", "NaN", "inf", "fast"] {
+            assert!(parse_rate(rate).is_err(), "{}", rate);
+        }
+    }
+}
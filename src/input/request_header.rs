@@ -0,0 +1,15 @@
+use reqwest::header::{HeaderName, HeaderValue};
+
+/// Parses a header given as "Name: value", e.g. "Authorization: Bearer abc123".
+pub fn parse_request_header(s: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let invalid = || format!("Invalid header '{}' (expected e.g. \"X-Api-Key: abc123\")", s);
+    let (name, value) = s.split_once(':').ok_or_else(invalid)?;
+    let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| invalid())?;
+    let value = HeaderValue::from_str(value.trim()).map_err(|_| invalid())?;
+    Ok((name, value))
+}
+
+/// Parses a header name, e.g. "Cache-Control".
+pub fn parse_header_name(s: &str) -> Result<HeaderName, String> {
+    HeaderName::from_bytes(s.trim().as_bytes()).map_err(|_| format!("Invalid header name '{}'", s))
+}
@@ -0,0 +1,115 @@
+use anyhow::Context;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use url::Url;
+
+/// Seed list argument that refers to stdin rather than a URL or file.
+pub const STDIN_MARKER: &str = "-";
+
+#[derive(Debug, Clone, Default)]
+pub struct SeedList {
+    seeds: Vec<Url>,
+    /// The seeds listed so far, to drop duplicates from long lists quickly.
+    listed: HashSet<Url>,
+}
+
+impl SeedList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seeds(&self) -> &[Url] {
+        &self.seeds
+    }
+
+    /// Adds a single seed given on the command line; "-" reads seeds from stdin.
+    pub fn add_seed_arg(&mut self, seed: &str) -> anyhow::Result<()> {
+        if seed == STDIN_MARKER {
+            return self.load_from_stdin();
+        }
+        let seed_url = Url::parse(seed).with_context(|| format!("Invalid seed URL '{}'", seed))?;
+        self.add_seed(seed_url);
+        Ok(())
+    }
+
+    /// Adds a local directory, such as a static site build, as a `file://` seed.
+    pub fn add_seed_dir(&mut self, dir: &Path) -> anyhow::Result<()> {
+        let dir = dir
+            .canonicalize()
+            .with_context(|| format!("Unable to open seed directory '{}'", dir.display()))?;
+        let seed_url = Url::from_directory_path(&dir)
+            .map_err(|_| anyhow::anyhow!("Invalid seed directory '{}'", dir.display()))?;
+        self.add_seed(seed_url);
+        Ok(())
+    }
+
+    /// Loads seeds from a file; "-" reads seeds from stdin.
+    pub fn load_from_path(&mut self, path: &Path) -> anyhow::Result<()> {
+        if path.as_os_str() == STDIN_MARKER {
+            return self.load_from_stdin();
+        }
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open seed file '{}'", path.display()))?;
+        self.load_from_reader(BufReader::new(file), &path.display().to_string())
+    }
+
+    pub fn load_from_stdin(&mut self) -> anyhow::Result<()> {
+        let stdin = std::io::stdin();
+        self.load_from_reader(stdin.lock(), "<stdin>")
+    }
+
+    /// Reads one seed URL per line. Blank lines and lines starting with "#" are ignored.
+    pub fn load_from_reader<R: BufRead>(&mut self, reader: R, source_name: &str) -> anyhow::Result<()> {
+        for (line_index, line) in reader.lines().enumerate() {
+            let line = line.with_context(|| format!("Unable to read seeds from {}", source_name))?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let seed_url = Url::parse(line).with_context(|| {
+                format!("{}:{}: invalid seed URL '{}'", source_name, line_index + 1, line)
+            })?;
+            self.add_seed(seed_url);
+        }
+        Ok(())
+    }
+
+    /// Adds a seed unless it is already listed.
+    pub fn add_seed(&mut self, seed_url: Url) {
+        if self.listed.insert(seed_url.clone()) {
+            self.seeds.push(seed_url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_seeds_skipping_comments_blank_lines_and_duplicates() {
+        let seeds = "# Sites to check\nhttps://example.com/\n\n  https://example.org/  \n\
+                     https://example.com/\n";
+        let mut seed_list = SeedList::new();
+        seed_list.load_from_reader(seeds.as_bytes(), "seeds.txt").unwrap();
+        seed_list.add_seed_arg("https://example.org/").unwrap();
+        assert_eq!(
+            seed_list.seeds(),
+            [
+                Url::parse("https://example.com/").unwrap(),
+                Url::parse("https://example.org/").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn names_the_line_of_an_invalid_seed() {
+        let mut seed_list = SeedList::new();
+        let error = seed_list
+            .load_from_reader("https://example.com/\nnot a url\n".as_bytes(), "seeds.txt")
+            .unwrap_err();
+        assert!(error.to_string().starts_with("seeds.txt:2:"), "{}", error);
+    }
+}
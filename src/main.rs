@@ -1,23 +1,147 @@
-use clap::Parser;
-use console::console_progress_reporter::ConsoleProcessReporter;
+use analysis::cache_report::CacheReport;
+use analysis::security_header_report::SecurityHeaderReport;
+use analysis::seo_report::{DEFAULT_MAX_TITLE_LENGTH, SeoReport};
+use bytesize::ByteSize;
+use checks::check_outcome::CheckOutcome;
+use checks::expected_urls::ExpectedUrls;
+use checks::fail_on_policy::{FailOnPolicy, StatusPattern};
+use checks::status_assertion::{AssertionReport, StatusAssertion};
+use clap::builder::FalseyValueParser;
+use clap::{Arg, ArgAction, CommandFactory, FromArgMatches, Parser, Subcommand};
+use console::console_progress_reporter::{ConsoleProcessReporter, ConsoleReporterHandle};
+use console::crawler_progress_event::CrawlerProcessEvent;
+use console::progress_mode::ProgressMode;
+use console::verbosity::Verbosity;
+use crawler::checkpoint::{CheckpointFile, CheckpointInterval};
+use crawler::crawl_statistics::CrawlStatistics;
 use crawler::crawl_summary::CrawlSummary;
 use crawler::crawler_config::CrawlerConfig;
-use crawler::multi::MultiCrawler;
+use crawler::distributed::{CrawlWorker, DEFAULT_DISPATCH_WINDOW, DEFAULT_QUEUE_NAME, redacted};
+use crawler::entry_point::EntryPointProber;
+use crawler::events::{DEFAULT_NATS_SUBJECT, STDOUT_PATH};
+use crawler::extract::ExtractRule;
+use crawler::fetch::{RenderMode, StaticHeaders};
+use crawler::hreflang::HreflangReport;
+use crawler::http::{
+    CrawlProfile, DEFAULT_MAX_REDIRECTS, HttpClientBuilder, HttpVersionPreference, ProfileName,
+    ResolveOverride,
+};
+use crawler::multi::{CrawlRun, MultiCrawler};
+use crawler::normalize::QueryPolicy;
+use crawler::page::{DEFAULT_LINK_SELECTOR, SkipIfContains, StripElements};
+use crawler::robots::{ROBOTS_AGENT, RobotsTxtSource};
+use crawler::content::ContentKind;
+use crawler::scope::{FramePolicy, HostPattern, UrlPattern};
+use crawler::search::TextSearch;
+use crawler::seed::{
+    CrawlStrategy, DEFAULT_MAX_PATH_SEGMENTS, DEFAULT_MAX_QUERY_LENGTH, DEFAULT_MAX_URL_LENGTH,
+    DEFAULT_TRAP_FAMILY_LIMIT, UrlLimits,
+};
+use crawler::store::{BodyStore, Reprocessor};
+use crawler::visited_filter::VisitedFilter;
+use input::bandwidth::parse_bandwidth;
+use input::count::parse_count;
+use input::css_selector::parse_css_selector;
+use input::domain_list::DomainList;
+use input::rate::parse_rate;
+use input::request_header::{parse_header_name, parse_request_header};
+use input::seed_list::SeedList;
+use monitor::change_alerter::ChangeAlerter;
+use monitor::monitor_baseline::MonitorBaseline;
+use output::elasticsearch_exporter::{DEFAULT_ES_INDEX, ElasticsearchExporter};
+use output::postgres_exporter::PostgresExporter;
+use output::s3_uploader::{S3Location, S3Uploader};
+use reqwest::header::{HeaderName, HeaderValue};
+use scraper::Selector;
+use server::{ApiServer, CrawlStatus, StatusServer};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
+mod analysis;
+mod checks;
 mod crawler;
 mod console;
+mod input;
+mod monitor;
+mod output;
+mod server;
+#[cfg(test)]
+mod test_support;
+
+/// Exit code for a fatal error.
+const EXIT_FATAL_ERROR: i32 = 1;
+
+/// Exit code for a crawl that completed but found failing pages.
+const EXIT_CHECKS_FAILED: i32 = 2;
+
+/// Prefix of the environment variables that can set any option, e.g. RUSTY_SPIDER_MAX_PAGES for
+/// --max-pages, or RUSTY_SPIDER_MONITOR_STATE for `monitor --state`.
+const ENV_PREFIX: &str = "RUSTY_SPIDER";
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    after_help = "Every option can also be set with a RUSTY_SPIDER_* environment variable, shown \
+                  next to it in --help. Command line options take precedence, and options that \
+                  can be repeated take a single value from the environment."
+)]
 struct CommandLineArgs {
-    /// Seed URLs to start crawling from
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Only print fatal errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print crawl statistics and a summary of failing pages after the results
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Status codes that make the exit code 2, e.g. "4xx,5xx", "404", "soft404" or "none"
+    #[arg(long, global = true, value_delimiter = ',', default_value = "4xx,5xx")]
+    fail_on: Vec<StatusPattern>,
+
+    /// Statuses pages must have, e.g. "/docs/ => 200" or "/admin/ => 401,403"; a page breaking
+    /// an assertion makes the exit code 2 ("*" and "$" work as in robots.txt rules)
+    #[arg(long = "assert", global = true, value_name = "PATTERN => STATUS")]
+    assertions: Vec<StatusAssertion>,
+
+    /// File of URLs the crawl should find, one per line; expected URLs that were not crawled
+    /// and crawled URLs that were not expected are reported after the crawl
+    #[arg(long, global = true, value_name = "FILE")]
+    expected_urls: Option<PathBuf>,
+
+    /// Make the exit code 2 if the crawl finds missing or unexpected URLs
+    #[arg(long, global = true, requires = "expected_urls")]
+    fail_on_url_mismatch: bool,
+
+    /// Seed URLs to start crawling from, http(s):// or file:// ("-" reads seeds from stdin)
     #[arg(long, value_name = "URL")]
     seed: Vec<String>,
 
-    /// Maximum number of pages to crawl
+    /// File containing seed URLs, one per line ("#" starts a comment, "-" reads stdin)
+    #[arg(long, value_name = "FILE")]
+    seed_file: Vec<PathBuf>,
+
+    /// Local directory to crawl as a file:// site, e.g. a static site build
+    #[arg(long, value_name = "DIR")]
+    seed_dir: Vec<PathBuf>,
+
+    /// File of bare domains to crawl, one per line, e.g. "example.com" ("-" reads stdin).
+    /// Each is tried over HTTPS and HTTP, with and without "www.", and the page the first
+    /// working variant redirects to becomes its seed
+    #[arg(long, value_name = "FILE")]
+    domains: Vec<PathBuf>,
+
+    /// Maximum number of pages to crawl per seed
     #[arg(long, default_value_t = 1000)]
     max_pages: usize,
 
@@ -25,74 +149,1069 @@ struct CommandLineArgs {
     #[arg(long, default_value_t = 4)]
     max_depth: usize,
 
+    /// Order to crawl each seed's discovered pages in, e.g. to reach overview pages first
+    /// when --max-pages or a time budget is tight
+    #[arg(long, value_enum, default_value_t = CrawlStrategy::Bfs)]
+    strategy: CrawlStrategy,
+
+    /// Crawl pages matching this URL pattern (e.g. "/docs/*") before all others, so a tight
+    /// --max-pages budget goes to them first (can be repeated)
+    #[arg(long, value_name = "PATTERN")]
+    prioritize: Vec<UrlPattern>,
+
+    /// URLs differing only in numbers or dates (e.g. calendar pages) crawled per family
+    /// before the rest are skipped as a suspected crawler trap
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_TRAP_FAMILY_LIMIT)]
+    trap_family_limit: usize,
+
+    /// Follow every URL, even those that look like crawler traps
+    #[arg(long, conflicts_with = "trap_family_limit")]
+    no_trap_detection: bool,
+
+    /// Longest discovered URL to queue
+    #[arg(long, value_name = "CHARS", default_value_t = DEFAULT_MAX_URL_LENGTH)]
+    max_url_length: usize,
+
+    /// Most path segments a discovered URL may have to be queued
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_MAX_PATH_SEGMENTS)]
+    max_path_segments: usize,
+
+    /// Longest query string a discovered URL may have to be queued
+    #[arg(long, value_name = "CHARS", default_value_t = DEFAULT_MAX_QUERY_LENGTH)]
+    max_query_length: usize,
+
     /// Rate limit for crawling (requests per second)
-    #[arg(long)]
+    #[arg(long, value_parser = parse_rate)]
     rate: Option<f64>,
+
+    /// Requests to a host that may go out back to back after a lull, within the rate limit
+    #[arg(long, default_value_t = 1, requires = "rate")]
+    burst: u32,
+
+    /// Slow down when a server answers 429/503 or responds more slowly, and speed back up
+    /// to --rate (or 10 requests per second) as it recovers
+    #[arg(long)]
+    adaptive_rate: bool,
+
+    /// Cap on the bytes per second received over the whole crawl, e.g. "2MBps" or "512KiB/s";
+    /// response bodies are read no faster than this
+    #[arg(long, value_name = "RATE", value_parser = parse_bandwidth)]
+    max_bandwidth: Option<u64>,
+
+    /// Cap on the bytes per second received from each host, e.g. "500KBps"
+    #[arg(long, value_name = "RATE", value_parser = parse_bandwidth)]
+    max_host_bandwidth: Option<u64>,
+
+    /// Pause crawling a host after this many requests to it in a row fail with a 5xx
+    /// response, a timeout or a failed connection, resuming after --circuit-breaker-cooldown
+    #[arg(long, value_name = "FAILURES")]
+    circuit_breaker: Option<u32>,
+
+    /// How long a host is left alone once the circuit breaker opens, e.g. "30s" or "5m"
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = humantime::parse_duration,
+        default_value = "1m",
+        requires = "circuit_breaker"
+    )]
+    circuit_breaker_cooldown: Duration,
+
+    /// Wall-clock budget for the whole crawl, e.g. "10m" or "1h 30m"
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    max_duration: Option<Duration>,
+
+    /// Sample pages per second, error rate, frontier size and memory use at this interval,
+    /// e.g. "10s"; the samples are kept with the run in the JSON results and printed with -v
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    timeline_interval: Option<Duration>,
+
+    /// Wall-clock budget for each seed's crawl, e.g. "2m"
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    max_seed_duration: Option<Duration>,
+
+    /// Also follow links to these hosts, e.g. "cdn.example.com", "*.example.com" or a regex
+    /// such as "re:^docs[0-9]+\.example\.com$"
+    #[arg(long, value_name = "HOST")]
+    allow_host: Vec<HostPattern>,
+
+    /// Never follow links to these hosts, even the seed's own; same patterns as --allow-host
+    #[arg(long, value_name = "HOST")]
+    deny_host: Vec<HostPattern>,
+
+    /// Allow requests to private, link-local and loopback addresses such as 10.x, 169.254.x
+    /// and localhost, which are refused by default to protect internal services
+    #[arg(long)]
+    allow_private_networks: bool,
+
+    /// Query strings of discovered URLs: "strip", "keep", "keep:page,id" or "strip:utm_*,fbclid"
+    #[arg(long, value_name = "POLICY", default_value = "strip")]
+    query_policy: QueryPolicy,
+
+    /// Keep query strings and deduplicate on the full URL, for sites that route on the query
+    /// string (e.g. "?p=123"). Same as --query-policy keep
+    #[arg(long, conflicts_with = "query_policy")]
+    keep_query: bool,
+
+    /// Phrases in a page's title or short body that flag a 200 response as a soft 404
+    #[arg(long, value_name = "PHRASE", value_delimiter = ',')]
+    soft_404_pattern: Vec<String>,
+
+    /// Load each seed's sitemaps and report orphan pages and pages missing from the sitemap
+    #[arg(long)]
+    sitemap_report: bool,
+
+    /// Report missing, duplicate and overlong titles, missing meta descriptions, multiple h1
+    /// headings, pages only linked as "click here" and the like, http:// assets and links on
+    /// https:// pages, broken AMP versions and AMP versions without a canonical link back, and
+    /// internally linked pages blocked by robots.txt
+    #[arg(long)]
+    seo_report: bool,
+
+    /// Titles longer than this are reported by --seo-report
+    #[arg(long, value_name = "CHARS", default_value_t = DEFAULT_MAX_TITLE_LENGTH, requires = "seo_report")]
+    max_title_length: usize,
+
+    /// Report how the pages served may be cached, by Cache-Control and Expires policy, and
+    /// flag HTML pages cached for over a day and crawled static assets without a max-age
+    #[arg(long)]
+    cache_report: bool,
+
+    /// Fetch the RSS and Atom feeds pages advertise and crawl the items they list
+    #[arg(long)]
+    follow_feeds: bool,
+
+    /// Crawl the sources of <frame> and <iframe> elements
+    #[arg(long, value_enum, default_value_t = FramePolicy::None)]
+    frames: FramePolicy,
+
+    /// Also follow URLs in onclick handlers (location.href='...') and data-href/data-url
+    /// attributes, for navigation hidden behind JavaScript
+    #[arg(long)]
+    script_links: bool,
+
+    /// Abandon pages whose body is larger than this, e.g. "5MB" or "512KiB"
+    #[arg(long, value_name = "SIZE")]
+    max_body_size: Option<ByteSize>,
+
+    /// Give up on pages that take longer than this to fetch and parse, e.g. "30s"; they are
+    /// reported as timed out and the crawl moves on
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    page_timeout: Option<Duration>,
+
+    /// Queued URLs each seed keeps in memory; beyond this they spill to disk
+    #[arg(long, value_name = "URLS")]
+    frontier_memory_limit: Option<usize>,
+
+    /// Directory for frontier spill files (defaults to the system temp directory)
+    #[arg(long, value_name = "DIR")]
+    frontier_spill_dir: Option<PathBuf>,
+
+    /// Resident memory the crawl should stay under, e.g. "2GiB"; close to it, frontiers spill
+    /// to disk and page text is no longer kept, with a warning (Linux only)
+    #[arg(long, value_name = "SIZE")]
+    memory_limit: Option<ByteSize>,
+
+    /// How each seed remembers crawled URLs; "bloom" trades a few missed URLs for much less memory
+    #[arg(long, value_enum, default_value_t = VisitedFilter::Exact)]
+    visited_filter: VisitedFilter,
+
+    /// URLs per seed the bloom visited filter is sized for, e.g. "10M"
+    #[arg(long, value_name = "COUNT", value_parser = parse_count, default_value = "1M")]
+    visited_filter_capacity: usize,
+
+    /// Render pages in a headless browser before extracting links, for JavaScript-heavy sites
+    #[arg(long, value_enum, default_value_t = RenderMode::None)]
+    render: RenderMode,
+
+    /// Crawl the "#/" and "#!/" routes of single-page apps as pages of their own, rather than
+    /// ignoring fragment links
+    #[arg(long, requires = "render")]
+    spa_routes: bool,
+
+    /// WebDriver server driving the headless browser for "--render js", e.g. a running chromedriver
+    #[arg(long, value_name = "URL", default_value = "http://localhost:9515/")]
+    webdriver_url: Url,
+
+    /// Longest a page may take to render before its HTML is used as served
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration, default_value = "30s")]
+    render_timeout: Duration,
+
+    /// Number of browser tabs rendering pages at once
+    #[arg(long, value_name = "TABS", default_value_t = 4)]
+    render_tabs: usize,
+
+    /// Periodically save each seed's crawl state to this directory, and resume from it when
+    /// rerun after a crash or interruption
+    #[arg(long, value_name = "DIR")]
+    checkpoint_dir: Option<PathBuf>,
+
+    /// How often to checkpoint: a page count such as "100" or a duration such as "30s"
+    #[arg(long, value_name = "INTERVAL", default_value = "100")]
+    checkpoint_interval: CheckpointInterval,
+
+    /// Regular expression to search for in the visible text of every page, e.g. "(?i)old name";
+    /// matching pages list the number of matches and the first few in context
+    #[arg(long, value_name = "REGEX")]
+    search: Option<TextSearch>,
+
+    /// Score the readability of each page's text (Flesch reading ease, for English text)
+    #[arg(long)]
+    readability: bool,
+
+    /// Check each page for images without alt text, empty links and buttons, skipped heading
+    /// levels and a missing lang attribute
+    #[arg(long)]
+    accessibility: bool,
+
+    /// Kinds of content to parse; documents of other content types are recorded but skipped
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "html")]
+    content_types: Vec<ContentKind>,
+
+    /// Extract a value from every page, e.g. "price:.product-price" for the text of the first
+    /// match or "image:img.hero@src" for an attribute
+    #[arg(long, value_name = "NAME:SELECTOR[@ATTR]")]
+    extract: Vec<ExtractRule>,
+
+    /// Remove the elements matching this CSS selector from HTML pages before parsing them,
+    /// e.g. "#cookie-banner"; may be repeated
+    #[arg(long, value_name = "SELECTOR", value_parser = parse_css_selector)]
+    strip_selector: Vec<Selector>,
+
+    /// Skip parsing pages whose body contains this text, e.g. a CAPTCHA or login wall
+    /// marker; may be repeated
+    #[arg(long, value_name = "TEXT")]
+    skip_if_contains: Vec<String>,
+
+    /// Rhai script with crawl callbacks: should_follow(url) returns whether to follow a URL in
+    /// scope, and on_page(url, html) a map of extra fields to extract from each page
+    #[arg(long, value_name = "FILE")]
+    script: Option<PathBuf>,
+
+    /// WebAssembly module (.wasm or .wat) implementing the PageProcessor interface, whose
+    /// output is added to each page's extracted data; runs sandboxed, may be repeated
+    #[arg(long, value_name = "FILE")]
+    plugin: Vec<PathBuf>,
+
+    /// Directory to save fetched HTML bodies to, for later reprocessing
+    #[arg(long, value_name = "DIR")]
+    save_bodies: Option<PathBuf>,
+
+    /// Elasticsearch or OpenSearch endpoint to index the crawled pages and their text into,
+    /// e.g. "http://localhost:9200"
+    #[arg(long, value_name = "URL")]
+    es_url: Option<Url>,
+
+    /// Index to export pages to with --es-url
+    #[arg(long, value_name = "INDEX", default_value = DEFAULT_ES_INDEX)]
+    es_index: String,
+
+    /// Upload the results as results.json, and the bodies saved with --save-bodies, to S3 or
+    /// an S3-compatible store set with AWS_ENDPOINT_URL, e.g. "s3://bucket/crawls/"
+    #[arg(long, value_name = "S3_URL")]
+    upload_to: Option<S3Location>,
+
+    /// Record the crawl run, its pages and their links in a Postgres database, creating or
+    /// upgrading the tables as needed, e.g. "postgres://user@host/crawls"
+    #[arg(long, value_name = "URL")]
+    postgres_url: Option<String>,
+
+    /// NATS server to stream each page's results and the start and end of each seed's crawl
+    /// to as JSON while crawling, e.g. "nats://localhost:4222"
+    #[arg(long, value_name = "URL")]
+    nats_url: Option<Url>,
+
+    /// Subject prefix for --nats-url: pages are published to "<SUBJECT>.pages", the start and
+    /// end of crawls to "<SUBJECT>.lifecycle"
+    #[arg(long, value_name = "SUBJECT", default_value = DEFAULT_NATS_SUBJECT)]
+    nats_subject: String,
+
+    /// Coordinate a distributed crawl through this Redis server: the frontier stays in this
+    /// process and the pages are fetched by `worker` processes, e.g. "redis://localhost:6379"
+    #[arg(long, value_name = "URL")]
+    distribute: Option<Url>,
+
+    /// Name of the work queue on the --distribute server, and prefix of the keys on the
+    /// --frontier server, to run several crawls on one server
+    #[arg(long, value_name = "NAME", default_value = DEFAULT_QUEUE_NAME)]
+    queue_name: String,
+
+    /// Number of pages each seed keeps handed to the --distribute workers at once, so that
+    /// several workers fetch one site together
+    #[arg(long, value_name = "PAGES", default_value_t = DEFAULT_DISPATCH_WINDOW, requires = "distribute")]
+    dispatch_window: usize,
+
+    /// Keep the frontier and visited set on this Redis server, so that several crawler
+    /// processes started with the same seeds and server split the pages between them rather
+    /// than crawling each one twice, e.g. "redis://localhost:6379". A seed's keys are removed
+    /// once its crawl is over, so later runs, and monitor checks, crawl it afresh
+    #[arg(long, value_name = "URL")]
+    frontier: Option<Url>,
+
+    /// Stream each page's results as a line of JSON while crawling, to a file or to stdout
+    /// with "-", e.g. "--output jsonl -". Progress stays on stderr, and the summary that is
+    /// otherwise printed to stdout is left out when streaming to it
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "PATH"])]
+    output: Vec<String>,
+
+    /// Skip URLs that any other seed has already crawled
+    #[arg(long)]
+    global_dedupe: bool,
+
+    /// CSS selector of the elements whose href is followed as a link, e.g. "a[href], area[href]"
+    #[arg(long, value_name = "SELECTOR", default_value = DEFAULT_LINK_SELECTOR)]
+    link_selector: String,
+
+    /// Extract links with a streaming HTML tokenizer instead of building each page's DOM:
+    /// faster on very large pages, but only follows <a href> links and skips the checks that
+    /// need a DOM, such as assets, mixed content and structured data
+    #[arg(long, conflicts_with_all = ["link_selector", "extract", "accessibility"])]
+    streaming_html: bool,
+
+    /// Crawl seeds one at a time and queue discovered links in sorted order, so repeated runs
+    /// over an unchanged site crawl and report the same pages in the same order
+    #[arg(long)]
+    deterministic: bool,
+
+    /// Crawl pages marked noindex/nofollow as if unmarked (directives are still reported)
+    #[arg(long)]
+    ignore_robots_directives: bool,
+
+    /// Treat pages that redirect with <meta http-equiv="refresh"> as ordinary pages
+    #[arg(long)]
+    ignore_meta_refresh: bool,
+
+    /// Report HTTP redirects instead of following them; targets in scope are crawled as pages
+    /// of their own
+    #[arg(long)]
+    no_follow_redirects: bool,
+
+    /// Most HTTP redirects to follow for one request. Redirects that leave the crawl's scope
+    /// are never followed, but reported as off-host redirects
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_REDIRECTS)]
+    max_redirects: usize,
+
+    /// HTTP protocol version to use, to diagnose protocol-specific server issues
+    #[arg(long, global = true, value_enum, default_value_t = HttpVersionPreference::Auto)]
+    http_version: HttpVersionPreference,
+
+    /// Pin a host name to an IP address instead of resolving it, e.g. "example.com:10.0.0.5"
+    #[arg(long, global = true, value_name = "HOST:IP")]
+    resolve: Vec<ResolveOverride>,
+
+    /// Resolve host names with this DNS-over-HTTPS JSON endpoint instead of the system resolver
+    #[arg(long, global = true, value_name = "URL")]
+    doh_url: Option<Url>,
+
+    /// Crawl as a desktop or mobile browser would (User-Agent, Accept-Language, viewport hints)
+    #[arg(long, global = true, value_enum)]
+    profile: Option<ProfileName>,
+
+    /// User-Agent header, overriding the profile's
+    #[arg(long, global = true)]
+    user_agent: Option<String>,
+
+    /// Accept-Language header, overriding the profile's
+    #[arg(long, global = true)]
+    accept_language: Option<String>,
+
+    /// Viewport width client hint in pixels, overriding the profile's
+    #[arg(long, global = true)]
+    viewport_width: Option<u32>,
+
+    /// Email address or URL site owners can reach you at, e.g. "crawler@example.com":
+    /// appended to the User-Agent, sent as the From header if an email address, and included
+    /// in the run metadata and monitor webhook payloads
+    #[arg(long, global = true, value_name = "EMAIL_OR_URL")]
+    contact: Option<String>,
+
+    /// Header to send with every page request, e.g. "Authorization: Bearer abc123"; may be
+    /// repeated (robots.txt and sitemap requests are sent without it)
+    #[arg(long, value_name = "NAME: VALUE", value_parser = parse_request_header)]
+    request_header: Vec<(HeaderName, HeaderValue)>,
+
+    /// Response headers to record for each page, e.g. "cache-control,server,x-request-id".
+    /// They are printed after the page and included in the JSON output and exports
+    #[arg(long, value_name = "NAMES", value_delimiter = ',', value_parser = parse_header_name)]
+    capture_headers: Vec<HeaderName>,
+
+    /// How to display crawl progress [default: none with --quiet, tty on a terminal, plain
+    /// otherwise]
+    #[arg(long, value_enum)]
+    progress: Option<ProgressMode>,
+
+    /// Serve GET /health and GET /status on this address while crawling, for orchestrators to
+    /// check liveness and the sizes of the crawl frontiers
+    #[arg(long, value_name = "ADDR")]
+    status_addr: Option<SocketAddr>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Re-run page extraction over previously saved bodies without recrawling
+    Reprocess {
+        /// Directory of saved page bodies
+        #[arg(long, value_name = "DIR")]
+        store: PathBuf,
+    },
+    /// Check whether robots.txt allows crawling a URL, and which rule decided it.
+    /// Exits with code 2 if the URL is disallowed.
+    Robots {
+        /// URL to check
+        #[arg(long, value_name = "URL")]
+        url: Url,
+
+        /// User agent to evaluate the rules for
+        #[arg(long, default_value = ROBOTS_AGENT)]
+        agent: String,
+    },
+    /// Crawl the seeds again and again, reporting pages whose status code, title or content
+    /// changed since they were last crawled. Exits with code 2 if the last run found changes.
+    Monitor {
+        /// Time between the start of one crawl and the next, e.g. "5m"
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = humantime::parse_duration,
+            default_value = "5m"
+        )]
+        interval: Duration,
+
+        /// File keeping the last known state of each page between crawls and restarts
+        #[arg(long, value_name = "FILE")]
+        state: PathBuf,
+
+        /// Also post the changes of each crawl as JSON to this URL
+        #[arg(long, value_name = "URL")]
+        webhook: Option<Url>,
+
+        /// Stop after this many crawls, e.g. 1 to check once from cron
+        #[arg(long, value_name = "COUNT")]
+        runs: Option<usize>,
+    },
+    /// Fetch pages for a coordinator started with --distribute, taking them from the queue
+    /// --queue-name on a Redis server. Fetch options such as --request-header, --max-body-size
+    /// and --render apply to the pages fetched. Each worker fetches one page at a time; start
+    /// several to fetch more at once, up to --dispatch-window pages per seed
+    Worker {
+        /// Redis server the coordinator distributes pages through, e.g. "redis://localhost:6379"
+        #[arg(long, value_name = "URL")]
+        queue: Url,
+    },
+    /// Run an HTTP API for starting, monitoring, pausing and cancelling crawls remotely
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: SocketAddr,
+    },
+}
+
+impl CommandLineArgs {
+    /// Parses the command line, falling back to the RUSTY_SPIDER_* environment variables for
+    /// options it does not give.
+    fn try_parse_with_env() -> Result<Self, clap::Error> {
+        let command = Self::command()
+            .mut_args(|arg| with_env(arg, ENV_PREFIX))
+            .mut_subcommands(|subcommand| {
+                let prefix = format!("{}_{}", ENV_PREFIX, env_name(subcommand.get_name()));
+                subcommand.mut_args(|arg| with_env(arg, &prefix))
+            });
+        Self::from_arg_matches(&command.try_get_matches()?)
+    }
+}
+
+/// Lets the environment variable `{prefix}_{OPTION}` set `arg`. Counted flags such as -v are
+/// left alone, and boolean flags accept "1"/"0" and the like besides "true"/"false".
+fn with_env(arg: Arg, prefix: &str) -> Arg {
+    let option = arg.get_long().unwrap_or(arg.get_id().as_str());
+    let name = format!("{}_{}", prefix, env_name(option));
+    match arg.get_action() {
+        ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version => arg,
+        ArgAction::Count => arg,
+        ArgAction::SetTrue => arg.value_parser(FalseyValueParser::new()).env(name),
+        _ => arg.env(name),
+    }
+}
+
+fn env_name(name: &str) -> String {
+    name.replace('-', "_").to_uppercase()
 }
 
-async fn main_impl(args: &CommandLineArgs) -> anyhow::Result<()> {
-    let crawler_config = CrawlerConfig::new(args.max_pages, args.max_depth, args.rate);
+async fn main_impl(args: &CommandLineArgs) -> anyhow::Result<CheckOutcome> {
+    match &args.command {
+        Some(Command::Reprocess { store }) => reprocess_impl(args, store),
+        Some(Command::Robots { url, agent }) => robots_impl(args, url, agent).await,
+        Some(Command::Worker { queue }) => worker_impl(args, queue).await,
+        Some(Command::Serve { listen }) => {
+            ApiServer::new(*listen).run().await?;
+            Ok(CheckOutcome::Passed)
+        }
+        Some(Command::Monitor {
+            interval,
+            state,
+            webhook,
+            runs,
+        }) => monitor_impl(args, *interval, state, webhook.as_ref(), *runs).await,
+        None => crawl_impl(args).await,
+    }
+}
+
+fn reprocess_impl(args: &CommandLineArgs, store: &Path) -> anyhow::Result<CheckOutcome> {
+    let expected_urls = load_expected_urls(args)?;
+    let body_store = BodyStore::open(store)?;
+    let crawl_summary = Reprocessor::new(body_store, &crawler_config(args))?.run()?;
+    Ok(report_results(args, &[crawl_summary], expected_urls.as_ref()))
+}
+
+/// Loads the `--expected-urls` file up front, so a bad file fails before a long crawl.
+fn load_expected_urls(args: &CommandLineArgs) -> anyhow::Result<Option<ExpectedUrls>> {
+    args.expected_urls
+        .as_deref()
+        .map(ExpectedUrls::load_from_path)
+        .transpose()
+}
 
-    // Set up a shutdown signal handler
-    let shutdown_notify = Arc::new(tokio::sync::Notify::new());
+async fn robots_impl(args: &CommandLineArgs, url: &Url, agent: &str) -> anyhow::Result<CheckOutcome> {
+    let crawler_config = crawler_config(args);
+    let http_client = HttpClientBuilder::new(&crawler_config).build()?;
+    let robots_txt_source = RobotsTxtSource::load_from_url(&http_client, url, agent).await?;
+    let robots_txt_view = robots_txt_source.view();
+    let robots_txt_match = robots_txt_view.matcher().explain_path(url.path());
+
+    println!("Agent:   {}", agent);
+    println!("Section: User-agent: {}", robots_txt_view.section_user_agents().join(", "));
+    println!("Path:    {}", url.path());
+    println!(
+        "Result:  {}",
+        if robots_txt_match.allowed { "allowed" } else { "disallowed" }
+    );
+    println!(
+        "Rule:    {}",
+        robots_txt_match
+            .matched_rule
+            .as_deref()
+            .unwrap_or("(no matching rule, allowed by default)")
+    );
+
+    Ok(CheckOutcome::from_passed(robots_txt_match.allowed))
+}
+
+fn crawler_config(args: &CommandLineArgs) -> CrawlerConfig {
+    let profile = args
+        .profile
+        .map(CrawlProfile::from_name)
+        .unwrap_or_default()
+        .with_user_agent(args.user_agent.clone())
+        .with_accept_language(args.accept_language.clone())
+        .with_viewport_width(args.viewport_width)
+        .with_contact(args.contact.clone());
+    let mut crawler_config = CrawlerConfig::new(args.max_pages, args.max_depth, args.rate)
+        .with_strategy(args.strategy)
+        .with_priority_patterns(args.prioritize.clone())
+        .with_trap_family_limit((!args.no_trap_detection).then_some(args.trap_family_limit))
+        .with_url_limits(UrlLimits {
+            max_url_length: args.max_url_length,
+            max_path_segments: args.max_path_segments,
+            max_query_length: args.max_query_length,
+        })
+        .with_burst(args.burst)
+        .with_bandwidth_limits(args.max_bandwidth, args.max_host_bandwidth)
+        .with_adaptive_rate(args.adaptive_rate)
+        .with_circuit_breaker(args.circuit_breaker, args.circuit_breaker_cooldown)
+        .with_save_bodies_dir(args.save_bodies.clone())
+        .with_keep_body_text(args.es_url.is_some())
+        .with_global_dedupe(args.global_dedupe)
+        .with_deterministic(args.deterministic)
+        .with_link_selector(&args.link_selector)
+        .with_streaming_html(args.streaming_html)
+        .with_respect_robots_directives(!args.ignore_robots_directives)
+        .with_follow_meta_refresh(!args.ignore_meta_refresh)
+        .with_follow_redirects(!args.no_follow_redirects)
+        .with_max_redirects(args.max_redirects)
+        .with_http_version(args.http_version)
+        .with_resolve_overrides(args.resolve.clone())
+        .with_doh_url(args.doh_url.clone())
+        .with_max_duration(args.max_duration)
+        .with_timeline_interval(args.timeline_interval)
+        .with_max_seed_duration(args.max_seed_duration)
+        .with_query_policy(if args.keep_query {
+            QueryPolicy::KeepAll
+        } else {
+            args.query_policy.clone()
+        })
+        .with_allow_hosts(args.allow_host.clone())
+        .with_deny_hosts(args.deny_host.clone())
+        .with_allow_private_networks(args.allow_private_networks)
+        .with_sitemap_report(args.sitemap_report)
+        .with_profile(profile)
+        .with_max_body_size(args.max_body_size.map(|max_body_size| max_body_size.as_u64()))
+        .with_page_timeout(args.page_timeout)
+        .with_frontier_memory_limit(args.frontier_memory_limit)
+        .with_frontier_spill_dir(args.frontier_spill_dir.clone())
+        .with_memory_limit(args.memory_limit.map(|memory_limit| memory_limit.as_u64()))
+        .with_visited_filter(args.visited_filter, args.visited_filter_capacity)
+        .with_render_mode(args.render)
+        .with_spa_routes(args.spa_routes)
+        .with_webdriver_url(args.webdriver_url.clone())
+        .with_nats(args.nats_url.clone(), &args.nats_subject)
+        .with_work_queue(args.distribute.clone(), &args.queue_name)
+        .with_dispatch_window(args.dispatch_window)
+        .with_shared_frontier(args.frontier.clone())
+        .with_render_timeout(args.render_timeout)
+        .with_render_tabs(args.render_tabs)
+        .with_checkpoint_dir(args.checkpoint_dir.clone())
+        .with_checkpoint_interval(args.checkpoint_interval)
+        .with_follow_feeds(args.follow_feeds)
+        .with_frame_policy(args.frames)
+        .with_script_links(args.script_links)
+        .with_search(args.search.clone())
+        .with_readability(args.readability)
+        .with_accessibility_checks(args.accessibility)
+        .with_content_kinds(args.content_types.clone())
+        .with_extract_rules(args.extract.clone())
+        .with_captured_headers(args.capture_headers.clone())
+        .with_script_file(args.script.clone())
+        .with_plugin_files(args.plugin.clone());
+    if !args.request_header.is_empty() {
+        crawler_config = crawler_config
+            .with_request_middleware(Arc::new(StaticHeaders::new(args.request_header.clone())));
+    }
+    if !args.strip_selector.is_empty() {
+        crawler_config = crawler_config
+            .with_response_middleware(Arc::new(StripElements::new(args.strip_selector.clone())));
+    }
+    if !args.skip_if_contains.is_empty() {
+        crawler_config = crawler_config.with_response_middleware(Arc::new(SkipIfContains::new(
+            args.skip_if_contains.clone(),
+        )));
+    }
+    if !args.soft_404_pattern.is_empty() {
+        crawler_config = crawler_config.with_soft_404_patterns(args.soft_404_pattern.clone());
+    }
+    crawler_config
+}
+
+async fn worker_impl(args: &CommandLineArgs, queue: &Url) -> anyhow::Result<CheckOutcome> {
+    let crawl_worker = CrawlWorker::for_redis(queue.clone(), &crawler_config(args))?;
+    eprintln!(
+        "Worker {} fetching pages from the queue {} on {}",
+        crawl_worker.id(),
+        args.queue_name,
+        redacted(queue)
+    );
+    let num_pages = crawl_worker.run(shutdown_token()?).await?;
+    eprintln!("Worker {} stopped after fetching {} pages", crawl_worker.id(), num_pages);
+    Ok(CheckOutcome::Passed)
+}
+
+/// The file, or "-" for stdout, that `--output jsonl` streams page results to.
+fn jsonl_output(args: &CommandLineArgs) -> anyhow::Result<Option<PathBuf>> {
+    match args.output.as_slice() {
+        [] => Ok(None),
+        [format, path] if format == "jsonl" => Ok(Some(PathBuf::from(path))),
+        [format, _] => {
+            Err(anyhow::anyhow!("Unsupported output format '{}' (expected jsonl)", format))
+        }
+        _ => Err(anyhow::anyhow!("--output takes one format and path, e.g. \"--output jsonl -\"")),
+    }
+}
+
+/// Collects the seeds of `--seed`, `--seed-file` and `--seed-dir`. Must run before the
+/// terminal is touched, since the seeds may come from stdin.
+fn load_seed_list(args: &CommandLineArgs) -> anyhow::Result<SeedList> {
+    let mut seed_list = SeedList::new();
+    for seed_str in &args.seed {
+        seed_list.add_seed_arg(seed_str)?;
+    }
+    for seed_file in &args.seed_file {
+        seed_list.load_from_path(seed_file)?;
+    }
+    for seed_dir in &args.seed_dir {
+        seed_list.add_seed_dir(seed_dir)?;
+    }
+    Ok(seed_list)
+}
+
+/// Collects the domains of `--domains`, which, like the seeds, may come from stdin.
+fn load_domain_list(args: &CommandLineArgs) -> anyhow::Result<DomainList> {
+    let mut domain_list = DomainList::new();
+    for domains_file in &args.domains {
+        domain_list.load_from_path(domains_file)?;
+    }
+    Ok(domain_list)
+}
+
+/// Adds the entry point of each domain as a seed, warning about domains without one.
+async fn add_domain_seeds(
+    seed_list: &mut SeedList,
+    domain_list: &DomainList,
+    crawler_config: &CrawlerConfig,
+) -> anyhow::Result<()> {
+    if domain_list.domains().is_empty() {
+        return Ok(());
+    }
+    let mut prober = EntryPointProber::new(HttpClientBuilder::new(crawler_config).build()?);
+    if let Some(page_timeout) = crawler_config.page_timeout() {
+        prober = prober.with_timeout(page_timeout);
+    }
+    for entry_point in prober.probe_all(domain_list.domains()).await {
+        match entry_point {
+            Ok(entry_point) => seed_list.add_seed(entry_point),
+            Err(e) => eprintln!("Warning: {:#}", e),
+        }
+    }
+    Ok(())
+}
+
+/// A token cancelled when the user presses Ctrl-C. Can only be set up once per process.
+fn shutdown_token() -> anyhow::Result<CancellationToken> {
+    let cancel_token = CancellationToken::new();
     {
-        let shutdown_notify = Arc::clone(&shutdown_notify);
+        let cancel_token = cancel_token.clone();
         ctrlc::set_handler(move || {
-            shutdown_notify.notify_waiters();
+            cancel_token.cancel();
         })?;
     }
+    Ok(cancel_token)
+}
 
-    // Run the crawlers for all seeds
-    let crawl_summaries = {
-        let console_reporter = ConsoleProcessReporter::new();
-        let _console_reporter_task = {
-            let shutdown_notify = Arc::clone(&shutdown_notify);
-            let mut console_reporter = console_reporter.clone();
-            tokio::task::spawn(async move {
-                console_reporter.run(shutdown_notify).await.unwrap();
-            })
-        };
-
-        let mut multi_crawler = MultiCrawler::new(
-            shutdown_notify.clone(),
-            crawler_config.clone(),
-            console_reporter.clone(),
-        );
-        for seed_str in &args.seed {
-            let seed_url = Url::parse(seed_str)?;
-            multi_crawler.add_seed(seed_url);
-        }
-        let multi_crawler_handle = tokio::task::spawn(async move {
-            let results = multi_crawler.run().await?;
-            Ok::<Vec<CrawlSummary>, anyhow::Error>(results)
-        });
+/// Starts the status endpoint if one was asked for.
+async fn start_status_server(args: &CommandLineArgs) -> anyhow::Result<Option<CrawlStatus>> {
+    let Some(status_addr) = args.status_addr else {
+        return Ok(None);
+    };
+    Ok(Some(StatusServer::start(status_addr).await?))
+}
 
-        multi_crawler_handle.await??
+/// Starts the console progress display, whose events are also reported to `crawl_status`.
+fn start_console_reporter(
+    args: &CommandLineArgs,
+    cancel_token: &CancellationToken,
+    crawl_status: Option<&CrawlStatus>,
+) -> (ConsoleReporterHandle, tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>) {
+    let progress_mode = match args.progress {
+        Some(progress_mode) => progress_mode,
+        None if args.quiet => ProgressMode::None,
+        None => ProgressMode::for_stderr(),
     };
+    let (console_reporter, event_tx) = ConsoleProcessReporter::new(progress_mode);
+    let console_reporter = console_reporter.spawn(cancel_token);
+    let event_tx = match crawl_status {
+        Some(crawl_status) => crawl_status.forward_to(event_tx),
+        None => event_tx,
+    };
+    (console_reporter, event_tx)
+}
+
+/// Runs the crawlers for all seeds, sending their progress on `event_tx`.
+async fn run_crawl(
+    seed_list: &SeedList,
+    crawler_config: CrawlerConfig,
+    cancel_token: CancellationToken,
+    event_tx: &tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
+) -> anyhow::Result<CrawlRun> {
+    let mut multi_crawler = MultiCrawler::new(cancel_token, crawler_config, event_tx.clone());
+    for seed_url in seed_list.seeds() {
+        multi_crawler.add_seed(seed_url.clone());
+    }
+    let multi_crawler_handle = tokio::task::spawn(async move {
+        let results = multi_crawler.run().await?;
+        Ok::<CrawlRun, anyhow::Error>(results)
+    });
+    multi_crawler_handle.await?
+}
+
+async fn crawl_impl(args: &CommandLineArgs) -> anyhow::Result<CheckOutcome> {
+    let mut seed_list = load_seed_list(args)?;
+    let domain_list = load_domain_list(args)?;
+    let expected_urls = load_expected_urls(args)?;
+    let crawler_config = crawler_config(args).with_jsonl_output(jsonl_output(args)?);
+    add_domain_seeds(&mut seed_list, &domain_list, &crawler_config).await?;
+    // Missing credentials fail before the crawl rather than after it
+    let s3_uploader = args.upload_to.clone().map(S3Uploader::from_env).transpose()?;
+
+    let crawl_status = start_status_server(args).await?;
+    let cancel_token = shutdown_token()?;
+    let (console_reporter, event_tx) =
+        start_console_reporter(args, &cancel_token, crawl_status.as_ref());
+    let crawl_run = run_crawl(&seed_list, crawler_config.clone(), cancel_token, &event_tx).await;
+    // The terminal is restored before the results are printed
+    console_reporter.stop().await?;
+    let crawl_run = crawl_run?;
 
     // Summarize the results
+    let check_outcome = report_results(args, &crawl_run.crawl_summaries, expected_urls.as_ref());
+    if Verbosity::from_flags(args.quiet, args.verbose) >= Verbosity::Verbose {
+        eprint!("{}", crawl_run.run);
+    }
+
+    if let Some(es_url) = &args.es_url {
+        let num_exported = ElasticsearchExporter::new(es_url.clone(), &args.es_index)
+            .export(&crawl_run)
+            .await?;
+        eprintln!("Exported {} pages to Elasticsearch index {}", num_exported, args.es_index);
+    }
+    if let Some(postgres_url) = &args.postgres_url {
+        PostgresExporter::new(postgres_url).export(&crawl_run).await?;
+    }
+    if let Some(s3_uploader) = &s3_uploader {
+        let results = serde_json::to_vec_pretty(&crawl_run)?;
+        s3_uploader.put_object("results.json", results, "application/json").await?;
+        if let Some(save_bodies_dir) = crawler_config.save_bodies_dir() {
+            s3_uploader.upload_dir(save_bodies_dir, "bodies/").await?;
+        }
+    }
+    Ok(check_outcome)
+}
+
+async fn monitor_impl(
+    args: &CommandLineArgs,
+    interval: Duration,
+    state: &Path,
+    webhook: Option<&Url>,
+    runs: Option<usize>,
+) -> anyhow::Result<CheckOutcome> {
+    let mut seed_list = load_seed_list(args)?;
+    let domain_list = load_domain_list(args)?;
+    let crawler_config = crawler_config(args);
+    // Entry points are found once; later runs crawl the same seeds
+    add_domain_seeds(&mut seed_list, &domain_list, &crawler_config).await?;
+    let state_file = CheckpointFile::new(state.to_path_buf());
+    let mut baseline = MonitorBaseline::load(&state_file)?;
+    let change_alerter =
+        ChangeAlerter::new(webhook.cloned()).with_contact(args.contact.clone());
+    let verbosity = Verbosity::from_flags(args.quiet, args.verbose);
+
+    let crawl_status = start_status_server(args).await?;
+    let cancel_token = shutdown_token()?;
+    // One progress display lasts for all runs, so the monitor's messages go through it too
+    let (console_reporter, event_tx) =
+        start_console_reporter(args, &cancel_token, crawl_status.as_ref());
+    let monitor_run = async {
+        let mut num_runs = 0;
+        let mut num_changes = 0;
+        loop {
+            let next_run = tokio::time::Instant::now() + interval;
+            let crawl_run =
+                run_crawl(&seed_list, crawler_config.clone(), cancel_token.clone(), &event_tx)
+                    .await?;
+            // An interrupted crawl is incomplete, so it is neither compared nor kept
+            if cancel_token.is_cancelled() {
+                break;
+            }
+            let current = MonitorBaseline::from_crawl_summaries(&crawl_run.crawl_summaries);
+            match &mut baseline {
+                Some(baseline) => {
+                    let changes = baseline.changes(&current);
+                    num_changes = changes.len();
+                    if let Err(e) = change_alerter.alert(&crawl_run.run.id, &changes).await {
+                        let message = format!("Unable to send alert: {:#}", e);
+                        let _ = event_tx.send(CrawlerProcessEvent::Notice { message });
+                    }
+                    if verbosity >= Verbosity::Normal {
+                        let message = format!(
+                            "{} pages checked, {} changed",
+                            current.num_pages(),
+                            num_changes
+                        );
+                        let _ = event_tx.send(CrawlerProcessEvent::Notice { message });
+                    }
+                    baseline.update(current);
+                }
+                None => {
+                    if verbosity >= Verbosity::Normal {
+                        let message =
+                            format!("Recorded the baseline of {} pages", current.num_pages());
+                        let _ = event_tx.send(CrawlerProcessEvent::Notice { message });
+                    }
+                    baseline = Some(current);
+                }
+            }
+            if let Some(baseline) = &baseline {
+                baseline.save(&state_file)?;
+            }
+
+            num_runs += 1;
+            if runs.is_some_and(|runs| num_runs >= runs) {
+                break;
+            }
+            tokio::select! {
+                _ = cancel_token.cancelled() => break,
+                _ = tokio::time::sleep_until(next_run) => {}
+            }
+        }
+        Ok::<_, anyhow::Error>(CheckOutcome::from_passed(num_changes == 0))
+    }
+    .await;
+    console_reporter.stop().await?;
+    monitor_run
+}
+
+/// Prints the results at the requested verbosity and evaluates them against `--fail-on`,
+/// `--fail-on-url-mismatch` and `--assert`.
+fn report_results(
+    args: &CommandLineArgs,
+    crawl_summaries: &[CrawlSummary],
+    expected_urls: Option<&ExpectedUrls>,
+) -> CheckOutcome {
+    let verbosity = Verbosity::from_flags(args.quiet, args.verbose);
+    let streams_to_stdout = args.output.get(1).is_some_and(|path| path == STDOUT_PATH);
+    if verbosity >= Verbosity::Normal && !streams_to_stdout {
+        print_crawl_summaries(crawl_summaries);
+    }
+
+    let fail_on_policy = FailOnPolicy::new(args.fail_on.clone());
+    let page_summaries = crawl_summaries
+        .iter()
+        .flat_map(|crawl_summary| crawl_summary.page_summaries().iter().cloned())
+        .collect::<Vec<_>>();
+    let failures = fail_on_policy.failures(&page_summaries);
+    let num_truncated = crawl_summaries
+        .iter()
+        .filter(|crawl_summary| crawl_summary.truncated_by_time())
+        .count();
+    if num_truncated > 0 && verbosity >= Verbosity::Normal {
+        eprintln!(
+            "{} of {} seed crawls stopped early after reaching their time budget",
+            num_truncated,
+            crawl_summaries.len()
+        );
+    }
+    if args.seo_report && verbosity >= Verbosity::Normal {
+        eprint!(
+            "{}",
+            SeoReport::from_crawl_summaries(crawl_summaries, args.max_title_length)
+        );
+    }
+    if args.cache_report && verbosity >= Verbosity::Normal {
+        eprint!("{}", CacheReport::from_crawl_summaries(crawl_summaries));
+    }
+    if verbosity >= Verbosity::Verbose {
+        eprint!("{}", CrawlStatistics::from_crawl_summaries(crawl_summaries));
+        let hreflang_report = HreflangReport::from_crawl_summaries(crawl_summaries);
+        if hreflang_report.has_annotations() {
+            eprint!("{}", hreflang_report);
+        }
+        let security_header_report = SecurityHeaderReport::from_crawl_summaries(crawl_summaries);
+        if !security_header_report.is_empty() {
+            eprint!("{}", security_header_report);
+        }
+        eprintln!(
+            "{} of {} pages matched --fail-on {}",
+            failures.len(),
+            page_summaries.len(),
+            fail_on_policy
+        );
+        for failure in &failures {
+            match &failure.discovered_from {
+                Some(discovered_from) => eprintln!(
+                    "   {} {} (linked from {})",
+                    failure.status_code, failure.url, discovered_from
+                ),
+                None => eprintln!("   {} {}", failure.status_code, failure.url),
+            }
+        }
+    }
+
+    let mut urls_as_expected = true;
+    if let Some(expected_urls) = expected_urls {
+        let comparison = expected_urls.compare(crawl_summaries);
+        if verbosity >= Verbosity::Normal {
+            eprint!("{}", comparison);
+        }
+        urls_as_expected = comparison.matches() || !args.fail_on_url_mismatch;
+    }
+
+    let mut assertions_passed = true;
+    if !args.assertions.is_empty() {
+        let assertion_report = AssertionReport::new(&args.assertions, crawl_summaries);
+        if verbosity >= Verbosity::Normal {
+            eprint!("{}", assertion_report);
+        }
+        assertions_passed = assertion_report.passed();
+    }
+
+    CheckOutcome::from_passed(failures.is_empty() && urls_as_expected && assertions_passed)
+}
+
+fn print_crawl_summaries(crawl_summaries: &[CrawlSummary]) {
     for crawl_summary in crawl_summaries {
         for page_summary in crawl_summary.page_summaries() {
-            println!(
-                "{}, {}, {}, {}, {}",
+            let mut line = format!(
+                "{}, {}, {}, {}, {}, {}, {}, {}",
                 page_summary.url,
                 page_summary.status_code,
                 page_summary.content_type,
                 page_summary.title,
-                page_summary.num_outgoing_links
+                page_summary.num_outgoing_links,
+                page_summary.depth,
+                page_summary
+                    .discovered_from
+                    .as_ref()
+                    .map(|url| url.as_str())
+                    .unwrap_or(""),
+                page_summary.robots_directives
             );
+            if let Some(meta_refresh) = &page_summary.meta_refresh {
+                line.push_str(&format!(", refresh -> {}", meta_refresh));
+            }
+            if let Some(redirect) = &page_summary.redirect {
+                line.push_str(&format!(", redirect -> {}", redirect));
+                if page_summary.off_host_redirect().is_some() {
+                    line.push_str(" (off-host)");
+                }
+            }
+            if page_summary.timed_out {
+                line.push_str(", timed out");
+            }
+            if page_summary.blocked_by_robots_txt {
+                line.push_str(", blocked by robots.txt");
+            }
+            if let Some(render_error) = &page_summary.render_error {
+                line.push_str(&format!(", not rendered ({})", render_error));
+            }
+            if let Some(num_pages) = page_summary.num_pages {
+                line.push_str(&format!(", {} pages", num_pages));
+            }
+            if let Some(body_path) = &page_summary.body_path {
+                line.push_str(&format!(", {}", body_path.display()));
+            }
+            for (name, value) in &page_summary.extracted {
+                line.push_str(&format!(", {}={}", name, value));
+            }
+            for (name, value) in &page_summary.captured_headers {
+                line.push_str(&format!(", {}: {}", name, value));
+            }
+            if let Some(accessibility) =
+                page_summary.accessibility.filter(|accessibility| accessibility.total() > 0)
+            {
+                line.push_str(&format!(", accessibility ({})", accessibility));
+            }
+            if let Some(search_matches) = &page_summary.search_matches {
+                line.push_str(&format!(", {} matches", search_matches.num_matches));
+                for snippet in &search_matches.snippets {
+                    line.push_str(&format!("\n   {}", snippet));
+                }
+            }
+            println!("{}", line);
         }
     }
-
-    Ok(())
 }
 
 #[tokio::main]
 async fn main() {
-    let args = CommandLineArgs::parse();
+    // Usage errors would otherwise exit with clap's code 2, which is reserved for failed checks
+    let args = CommandLineArgs::try_parse_with_env().unwrap_or_else(|e| {
+        let _ = e.print();
+        process::exit(if e.use_stderr() { EXIT_FATAL_ERROR } else { 0 });
+    });
 
-    if let Err(e) = main_impl(&args).await {
-        eprintln!("Error: {}", e);
-        process::exit(1);
+    match main_impl(&args).await {
+        Ok(CheckOutcome::Passed) => {}
+        Ok(CheckOutcome::Failed) => process::exit(EXIT_CHECKS_FAILED),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(EXIT_FATAL_ERROR);
+        }
     }
 }
@@ -0,0 +1,3 @@
+pub mod change_alerter;
+pub mod monitor_baseline;
+pub mod page_change;
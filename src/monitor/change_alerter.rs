@@ -0,0 +1,62 @@
+use crate::monitor::page_change::PageChange;
+use anyhow::Context;
+use url::Url;
+
+/// The JSON body posted to the webhook.
+#[derive(serde::Serialize)]
+struct WebhookPayload<'a> {
+    /// ID of the crawl run that found the changes.
+    run_id: &'a str,
+    /// Who runs the crawl, from `--contact`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contact: Option<&'a str>,
+    changes: &'a [PageChange],
+}
+
+/// Announces page changes on stdout, one per line, and optionally posts them to a webhook.
+pub struct ChangeAlerter {
+    http_client: reqwest::Client,
+    webhook_url: Option<Url>,
+    contact: Option<String>,
+}
+
+impl ChangeAlerter {
+    pub fn new(webhook_url: Option<Url>) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            webhook_url,
+            contact: None,
+        }
+    }
+
+    /// Names whoever runs the crawl in the webhook payloads.
+    pub fn with_contact(mut self, contact: Option<String>) -> Self {
+        self.contact = contact;
+        self
+    }
+
+    /// Prints the `changes` found by the run `run_id` and posts them to the webhook in one
+    /// request. Does nothing if there are no changes.
+    pub async fn alert(&self, run_id: &str, changes: &[PageChange]) -> anyhow::Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        for change in changes {
+            println!("{}", change);
+        }
+        if let Some(webhook_url) = &self.webhook_url {
+            self.http_client
+                .post(webhook_url.clone())
+                .json(&WebhookPayload {
+                    run_id,
+                    contact: self.contact.as_deref(),
+                    changes,
+                })
+                .send()
+                .await
+                .with_context(|| format!("Failed to reach webhook {}", webhook_url))?
+                .error_for_status()?;
+        }
+        Ok(())
+    }
+}
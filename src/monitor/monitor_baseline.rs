@@ -0,0 +1,163 @@
+use crate::crawler::checkpoint::{CheckpointFile, Migration, VersionedState};
+use crate::crawler::crawl_summary::CrawlSummary;
+use crate::monitor::page_change::{ChangeKind, PageChange};
+use anyhow::Context;
+use std::collections::BTreeMap;
+use url::Url;
+
+/// The last known state of each monitored page, kept in a state file between runs.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MonitorBaseline {
+    pages: BTreeMap<Url, PageState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct PageState {
+    status_code: u16,
+    title: String,
+    content_hash: Option<String>,
+}
+
+impl VersionedState for MonitorBaseline {
+    const MIGRATIONS: &'static [Migration] = &[];
+}
+
+impl MonitorBaseline {
+    pub fn from_crawl_summaries(crawl_summaries: &[CrawlSummary]) -> Self {
+        let pages = crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .map(|page_summary| {
+                let page_state = PageState {
+                    status_code: page_summary.status_code,
+                    title: page_summary.title.clone(),
+                    content_hash: page_summary.content_hash.clone(),
+                };
+                (page_summary.url.clone(), page_state)
+            })
+            .collect();
+        Self { pages }
+    }
+
+    /// Loads the baseline saved in `state_file`, or None before the first run.
+    pub fn load(state_file: &CheckpointFile) -> anyhow::Result<Option<Self>> {
+        let Some(payload) = state_file.load()? else {
+            return Ok(None);
+        };
+        let baseline = Self::from_payload(&payload).with_context(|| {
+            format!("Invalid monitor state '{}'", state_file.path().display())
+        })?;
+        Ok(Some(baseline))
+    }
+
+    pub fn save(&self, state_file: &CheckpointFile) -> anyhow::Result<()> {
+        state_file.save(&self.to_payload()?)
+    }
+
+    pub fn num_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// How the pages crawled in `current` changed since this baseline. Pages missing from
+    /// either side are not compared, since a crawl cut short by its limits may not reach
+    /// them. A new status is reported in place of the title and content changes it brings.
+    pub fn changes(&self, current: &MonitorBaseline) -> Vec<PageChange> {
+        let mut changes = Vec::new();
+        for (url, current_state) in &current.pages {
+            let Some(previous_state) = self.pages.get(url) else {
+                continue;
+            };
+            let kind = if previous_state.status_code != current_state.status_code {
+                ChangeKind::Status {
+                    previous: previous_state.status_code,
+                    current: current_state.status_code,
+                }
+            } else if previous_state.title != current_state.title {
+                ChangeKind::Title {
+                    previous: previous_state.title.clone(),
+                    current: current_state.title.clone(),
+                }
+            } else if previous_state.content_hash.is_some()
+                && current_state.content_hash.is_some()
+                && previous_state.content_hash != current_state.content_hash
+            {
+                ChangeKind::Content
+            } else {
+                continue;
+            };
+            changes.push(PageChange {
+                url: url.clone(),
+                kind,
+            });
+        }
+        changes
+    }
+
+    /// Takes the state of the pages crawled in `current`, keeping pages it did not reach.
+    pub fn update(&mut self, current: MonitorBaseline) {
+        self.pages.extend(current.pages);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(pages: &[(&str, u16, &str, &str)]) -> MonitorBaseline {
+        let pages = pages
+            .iter()
+            .map(|(url, status_code, title, content_hash)| {
+                let page_state = PageState {
+                    status_code: *status_code,
+                    title: title.to_string(),
+                    content_hash: Some(content_hash.to_string()),
+                };
+                (Url::parse(url).unwrap(), page_state)
+            })
+            .collect();
+        MonitorBaseline { pages }
+    }
+
+    #[test]
+    fn reports_the_most_significant_change_of_each_page() {
+        let previous = baseline(&[
+            ("https://example.com/", 200, "Home", "a"),
+            ("https://example.com/down", 200, "Down", "b"),
+            ("https://example.com/renamed", 200, "Old", "c"),
+            ("https://example.com/edited", 200, "Edited", "d"),
+            ("https://example.com/unreached", 200, "Unreached", "e"),
+        ]);
+        let current = baseline(&[
+            ("https://example.com/", 200, "Home", "a"),
+            ("https://example.com/down", 503, "", "x"),
+            ("https://example.com/renamed", 200, "New", "y"),
+            ("https://example.com/edited", 200, "Edited", "z"),
+            ("https://example.com/added", 200, "Added", "f"),
+        ]);
+        let url = |path: &str| Url::parse("https://example.com/").unwrap().join(path).unwrap();
+        assert_eq!(
+            previous.changes(&current),
+            vec![
+                PageChange {
+                    url: url("/down"),
+                    kind: ChangeKind::Status { previous: 200, current: 503 },
+                },
+                PageChange {
+                    url: url("/edited"),
+                    kind: ChangeKind::Content,
+                },
+                PageChange {
+                    url: url("/renamed"),
+                    kind: ChangeKind::Title {
+                        previous: "Old".to_string(),
+                        current: "New".to_string(),
+                    },
+                },
+            ]
+        );
+
+        let mut updated = previous;
+        updated.update(current);
+        assert_eq!(updated.num_pages(), 6);
+    }
+}
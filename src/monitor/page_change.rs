@@ -0,0 +1,34 @@
+use std::fmt;
+use url::Url;
+
+/// A difference between a page's state in the last monitoring run and in the baseline.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PageChange {
+    pub url: Url,
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The page answered with another status code; 0 means no response.
+    Status { previous: u16, current: u16 },
+    Title { previous: String, current: String },
+    /// The body's content hash changed.
+    Content,
+}
+
+impl fmt::Display for PageChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChangeKind::Status { previous, current } => {
+                write!(f, "{}: status changed from {} to {}", self.url, previous, current)
+            }
+            ChangeKind::Title { previous, current } => {
+                write!(f, "{}: title changed from {:?} to {:?}", self.url, previous, current)
+            }
+            ChangeKind::Content => write!(f, "{}: content changed", self.url),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod aws_signature;
+pub mod elasticsearch_exporter;
+pub mod postgres_exporter;
+pub mod s3_uploader;
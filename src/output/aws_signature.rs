@@ -0,0 +1,180 @@
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Credentials for signing requests to AWS or an S3-compatible service.
+#[derive(Debug, Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Session token of temporary credentials.
+    pub session_token: Option<String>,
+}
+
+/// Signs requests with AWS Signature Version 4.
+pub struct AwsSignature<'a> {
+    credentials: &'a AwsCredentials,
+    region: &'a str,
+    service: &'a str,
+}
+
+impl<'a> AwsSignature<'a> {
+    pub fn new(credentials: &'a AwsCredentials, region: &'a str, service: &'a str) -> Self {
+        Self {
+            credentials,
+            region,
+            service,
+        }
+    }
+
+    /// The headers to add to a request to `path` on `host` with a body hashing to
+    /// `payload_hash`, including the Authorization header.
+    pub fn headers(
+        &self,
+        method: &str,
+        host: &str,
+        path: &str,
+        payload_hash: &str,
+        time: SystemTime,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = Self::amz_date(time);
+        let date = &amz_date[..8];
+        let mut headers = vec![
+            ("host", host.to_string()),
+            ("x-amz-content-sha256", payload_hash.to_string()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(session_token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", session_token.clone()));
+        }
+        let canonical_headers = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect::<String>();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method, path, canonical_headers, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+        let signature = hex(&hmac_sha256(&self.signing_key(date), string_to_sign.as_bytes()));
+        headers.push((
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.credentials.access_key_id, scope, signed_headers, signature
+            ),
+        ));
+        // reqwest sets the Host header itself
+        headers.retain(|(name, _)| *name != "host");
+        headers
+    }
+
+    /// The key derived from the secret for signing requests on `date` (YYYYMMDD).
+    fn signing_key(&self, date: &str) -> Vec<u8> {
+        let secret = format!("AWS4{}", self.credentials.secret_access_key);
+        let date_key = hmac_sha256(secret.as_bytes(), date.as_bytes());
+        let region_key = hmac_sha256(&date_key, self.region.as_bytes());
+        let service_key = hmac_sha256(&region_key, self.service.as_bytes());
+        hmac_sha256(&service_key, b"aws4_request")
+    }
+
+    /// `time` in UTC as YYYYMMDD'T'HHMMSS'Z'.
+    fn amz_date(time: SystemTime) -> String {
+        let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+        // Converts days since the epoch to a civil date (Howard Hinnant's algorithm)
+        let z = days as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+            year,
+            month,
+            day,
+            seconds_of_day / 3_600,
+            seconds_of_day / 60 % 60,
+            seconds_of_day % 60
+        )
+    }
+}
+
+/// Hex-encoded SHA-256 of `payload`, as sent in the x-amz-content-sha256 header.
+pub fn payload_hash(payload: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(payload))
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986, as signing requires.
+pub fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+    let mut block_key = if key.len() > BLOCK_SIZE {
+        Sha256::digest(key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    block_key.resize(BLOCK_SIZE, 0);
+    let inner_key = block_key.iter().map(|byte| byte ^ 0x36).collect::<Vec<_>>();
+    let outer_key = block_key.iter().map(|byte| byte ^ 0x5c).collect::<Vec<_>>();
+    let inner_hash = Sha256::new().chain_update(&inner_key).chain_update(message).finalize();
+    Sha256::new()
+        .chain_update(&outer_key)
+        .chain_update(inner_hash)
+        .finalize()
+        .to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn derives_keys_and_dates_like_the_aws_examples() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        assert_eq!(
+            hex(&AwsSignature::new(&credentials, "us-east-1", "iam").signing_key("20120215")),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+        assert_eq!(AwsSignature::amz_date(UNIX_EPOCH), "19700101T000000Z");
+        assert_eq!(
+            AwsSignature::amz_date(UNIX_EPOCH + Duration::from_secs(1_709_210_096)),
+            "20240229T123456Z"
+        );
+    }
+}
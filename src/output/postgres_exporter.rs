@@ -0,0 +1,240 @@
+use crate::crawler::link_anchor::LinkAnchor;
+use crate::crawler::multi::{CrawlRun, RunInfo};
+use crate::crawler::page_summary::PageSummary;
+use anyhow::Context;
+use sqlx::{Connection, PgConnection, Postgres, QueryBuilder};
+use url::Url;
+
+/// Schema changes applied in order, each at most once per database.
+const MIGRATIONS: [&str; 3] = ["CREATE TABLE crawl_runs (
+    id BIGSERIAL PRIMARY KEY,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    seeds TEXT[] NOT NULL,
+    num_pages INTEGER NOT NULL
+);
+CREATE TABLE pages (
+    run_id BIGINT NOT NULL REFERENCES crawl_runs (id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    status_code INTEGER NOT NULL,
+    content_type TEXT NOT NULL,
+    title TEXT NOT NULL,
+    depth INTEGER NOT NULL,
+    discovered_from TEXT,
+    meta_description TEXT,
+    canonical TEXT,
+    PRIMARY KEY (run_id, url)
+);
+CREATE TABLE links (
+    run_id BIGINT NOT NULL REFERENCES crawl_runs (id) ON DELETE CASCADE,
+    from_url TEXT NOT NULL,
+    to_url TEXT NOT NULL,
+    anchor_text TEXT NOT NULL
+);
+CREATE INDEX links_to_url ON links (run_id, to_url);",
+    "ALTER TABLE crawl_runs
+    ADD COLUMN started_at TIMESTAMPTZ,
+    ADD COLUMN run JSONB;",
+    "ALTER TABLE pages ADD COLUMN headers JSONB;",
+];
+
+/// Records which migrations a database has had.
+const CREATE_MIGRATIONS_TABLE: &str = "CREATE TABLE IF NOT EXISTS rusty_spider_migrations (
+    version INTEGER PRIMARY KEY,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+)";
+
+/// Rows inserted by each INSERT statement.
+const INSERT_BATCH_SIZE: usize = 500;
+
+/// Records crawl runs, with their pages and internal links, in a Postgres database.
+///
+/// Each export is a single transaction that first brings the schema up to date.
+pub struct PostgresExporter {
+    database_url: String,
+}
+
+impl PostgresExporter {
+    /// Exports to the database at a connection URL, e.g. "postgres://user@host/crawls".
+    pub fn new(database_url: &str) -> Self {
+        Self {
+            database_url: database_url.to_string(),
+        }
+    }
+
+    pub async fn export(&self, crawl_run: &CrawlRun) -> anyhow::Result<()> {
+        let mut connection = PgConnection::connect(&self.database_url)
+            .await
+            .context("Unable to connect to Postgres for the export")?;
+        let mut transaction = connection.begin().await?;
+        Self::migrate(&mut transaction).await?;
+
+        let page_summaries = crawl_run
+            .crawl_summaries
+            .iter()
+            .flat_map(|crawl_summary| crawl_summary.page_summaries())
+            .collect::<Vec<_>>();
+        let run_id = Self::run_query(&crawl_run.run, page_summaries.len())?
+            .build_query_scalar::<i64>()
+            .fetch_one(&mut *transaction)
+            .await?;
+        for batch in page_summaries.chunks(INSERT_BATCH_SIZE) {
+            Self::pages_query(run_id, batch)?.build().execute(&mut *transaction).await?;
+        }
+        let links = page_summaries
+            .iter()
+            .flat_map(|page_summary| {
+                let from_url = &page_summary.url;
+                let link_anchors = page_summary.internal_link_anchors.iter();
+                link_anchors.map(move |link_anchor| (from_url, link_anchor))
+            })
+            .collect::<Vec<_>>();
+        for batch in links.chunks(INSERT_BATCH_SIZE) {
+            Self::links_query(run_id, batch).build().execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Applies the migrations the database hasn't had yet.
+    async fn migrate(connection: &mut PgConnection) -> anyhow::Result<()> {
+        sqlx::raw_sql(CREATE_MIGRATIONS_TABLE).execute(&mut *connection).await?;
+        // Exports running at the same time wait here rather than migrating twice
+        sqlx::raw_sql("LOCK TABLE rusty_spider_migrations").execute(&mut *connection).await?;
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            let version = index as i32 + 1;
+            let applied = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM rusty_spider_migrations WHERE version = $1)",
+            )
+            .bind(version)
+            .fetch_one(&mut *connection)
+            .await?;
+            if !applied {
+                sqlx::raw_sql(migration).execute(&mut *connection).await?;
+                sqlx::query("INSERT INTO rusty_spider_migrations (version) VALUES ($1)")
+                    .bind(version)
+                    .execute(&mut *connection)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts the run, returning its ID.
+    fn run_query(
+        run: &RunInfo,
+        num_pages: usize,
+    ) -> anyhow::Result<QueryBuilder<'static, Postgres>> {
+        let seeds = run.seeds.iter().map(|seed| text(seed.as_str())).collect::<Vec<_>>();
+        let mut query = QueryBuilder::new(
+            "INSERT INTO crawl_runs (seeds, num_pages, started_at, finished_at, run) VALUES (",
+        );
+        let mut values = query.separated(", ");
+        values.push_bind(seeds);
+        values.push_bind(i32::try_from(num_pages)?);
+        values.push_bind(text(&run.started_at)).push_unseparated("::TIMESTAMPTZ");
+        // A run that hasn't finished is recorded as finishing now
+        values
+            .push("COALESCE(")
+            .push_bind_unseparated(run.finished_at.as_deref().map(text))
+            .push_unseparated("::TIMESTAMPTZ, now())");
+        values.push_bind(serde_json::to_string(run)?).push_unseparated("::JSONB");
+        query.push(") RETURNING id");
+        Ok(query)
+    }
+
+    fn pages_query(
+        run_id: i64,
+        page_summaries: &[&PageSummary],
+    ) -> anyhow::Result<QueryBuilder<'static, Postgres>> {
+        // Pages without captured headers have none recorded
+        let headers = page_summaries
+            .iter()
+            .map(|page_summary| {
+                let captured_headers = &page_summary.captured_headers;
+                (!captured_headers.is_empty())
+                    .then(|| serde_json::to_string(captured_headers))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut query = QueryBuilder::new(
+            "INSERT INTO pages (run_id, url, status_code, content_type, title, depth, \
+             discovered_from, meta_description, canonical, headers) ",
+        );
+        query.push_values(page_summaries.iter().zip(headers), |mut row, (page_summary, headers)| {
+            let discovered_from = page_summary.discovered_from.as_ref();
+            let canonical = page_summary.canonical.as_ref();
+            row.push_bind(run_id)
+                .push_bind(text(page_summary.url.as_str()))
+                .push_bind(i32::from(page_summary.status_code))
+                .push_bind(text(&page_summary.content_type))
+                .push_bind(text(&page_summary.title))
+                .push_bind(page_summary.depth as i32)
+                .push_bind(discovered_from.map(|url| text(url.as_str())))
+                .push_bind(page_summary.meta_description.as_deref().map(text))
+                .push_bind(canonical.map(|url| text(url.as_str())))
+                .push_bind(headers)
+                .push_unseparated("::JSONB");
+        });
+        // A URL crawled by more than one seed is recorded once
+        query.push(" ON CONFLICT DO NOTHING");
+        Ok(query)
+    }
+
+    fn links_query(run_id: i64, links: &[(&Url, &LinkAnchor)]) -> QueryBuilder<'static, Postgres> {
+        let mut query =
+            QueryBuilder::new("INSERT INTO links (run_id, from_url, to_url, anchor_text) ");
+        query.push_values(links, |mut row, (from_url, link_anchor)| {
+            row.push_bind(run_id)
+                .push_bind(text(from_url.as_str()))
+                .push_bind(text(link_anchor.url.as_str()))
+                .push_bind(text(&link_anchor.anchor_text));
+        });
+        query
+    }
+}
+
+/// Text as stored in Postgres, which can't hold NUL characters, so they are dropped.
+fn text(s: &str) -> String {
+    s.replace('\0', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::crawler_config::CrawlerConfig;
+
+    #[test]
+    fn records_a_run_without_a_finish_time_as_finishing_now() {
+        let mut run = RunInfo::new(&CrawlerConfig::new(10, 2, None));
+        run.seeds.push(Url::parse("https://example.com/").unwrap());
+        run.started_at = "2024-05-01T12:00:00Z".to_string();
+        let query = PostgresExporter::run_query(&run, 1).unwrap();
+        assert_eq!(
+            query.sql(),
+            "INSERT INTO crawl_runs (seeds, num_pages, started_at, finished_at, run) \
+             VALUES ($1, $2, $3::TIMESTAMPTZ, COALESCE($4::TIMESTAMPTZ, now()), $5::JSONB) \
+             RETURNING id"
+        );
+    }
+
+    #[test]
+    fn binds_each_page_and_drops_nul_characters() {
+        let mut page_summary = PageSummary::new(
+            Url::parse("https://example.com/").unwrap(),
+            0,
+            200,
+            "text/html".to_string(),
+            "It's\0 home".to_string(),
+            0,
+        );
+        page_summary
+            .captured_headers
+            .insert("cache-control".to_string(), "max-age=60".to_string());
+        let query = PostgresExporter::pages_query(1, &[&page_summary, &page_summary]).unwrap();
+        assert!(query.sql().ends_with(
+            "VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10::JSONB), \
+             ($11, $12, $13, $14, $15, $16, $17, $18, $19, $20::JSONB) ON CONFLICT DO NOTHING"
+        ));
+        assert_eq!(text(&page_summary.title), "It's home");
+    }
+}
@@ -0,0 +1,206 @@
+use crate::output::aws_signature::{AwsCredentials, AwsSignature, payload_hash, uri_encode};
+use anyhow::{Context, anyhow};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::SystemTime;
+use url::Url;
+
+/// Region used when neither AWS_REGION nor AWS_DEFAULT_REGION is set.
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// A bucket and key prefix, written as "s3://bucket/prefix/".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    /// Prefix of every uploaded key, empty or ending in "/".
+    pub prefix: String,
+}
+
+impl FromStr for S3Location {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let location = s
+            .strip_prefix("s3://")
+            .ok_or_else(|| anyhow!("'{}' is not an s3://bucket/prefix/ location", s))?;
+        let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+        if bucket.is_empty() {
+            return Err(anyhow!("'{}' has no bucket name", s));
+        }
+        let mut prefix = prefix.trim_matches('/').to_string();
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        Ok(Self {
+            bucket: bucket.to_string(),
+            prefix,
+        })
+    }
+}
+
+/// Uploads crawl artifacts to S3 or an S3-compatible object store.
+///
+/// Credentials and the region come from the standard AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY,
+/// AWS_SESSION_TOKEN and AWS_REGION variables. Setting AWS_ENDPOINT_URL sends requests to an
+/// S3-compatible service instead, addressing buckets by path.
+pub struct S3Uploader {
+    http_client: reqwest::Client,
+    location: S3Location,
+    credentials: AwsCredentials,
+    region: String,
+    endpoint: Option<Url>,
+}
+
+impl S3Uploader {
+    pub fn from_env(location: S3Location) -> anyhow::Result<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let credentials = AwsCredentials {
+            access_key_id: env("AWS_ACCESS_KEY_ID")
+                .ok_or_else(|| anyhow!("AWS_ACCESS_KEY_ID is needed to upload to S3"))?,
+            secret_access_key: env("AWS_SECRET_ACCESS_KEY")
+                .ok_or_else(|| anyhow!("AWS_SECRET_ACCESS_KEY is needed to upload to S3"))?,
+            session_token: env("AWS_SESSION_TOKEN"),
+        };
+        let region = env("AWS_REGION")
+            .or_else(|| env("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|| DEFAULT_REGION.to_string());
+        let endpoint = env("AWS_ENDPOINT_URL")
+            .map(|endpoint| Url::parse(&endpoint))
+            .transpose()
+            .context("AWS_ENDPOINT_URL is not a valid URL")?;
+        Ok(Self {
+            http_client: reqwest::Client::new(),
+            location,
+            credentials,
+            region,
+            endpoint,
+        })
+    }
+
+    /// Uploads `body` to `key` under the location's prefix.
+    pub async fn put_object(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+    ) -> anyhow::Result<()> {
+        let url = self.object_url(&format!("{}{}", self.location.prefix, key))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(anyhow!("S3 URL {} has no host", url)),
+        };
+        let signature = AwsSignature::new(&self.credentials, &self.region, "s3");
+        let headers =
+            signature.headers("PUT", &host, url.path(), &payload_hash(&body), SystemTime::now());
+        let mut request = self
+            .http_client
+            .put(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, content_type);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload {}", url))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Upload of {} failed with {}: {}", url, status, message.trim()));
+        }
+        Ok(())
+    }
+
+    /// Uploads every file under `dir` to `key_prefix` followed by its path relative to `dir`,
+    /// returning the number of files uploaded.
+    pub async fn upload_dir(&self, dir: &Path, key_prefix: &str) -> anyhow::Result<usize> {
+        let mut num_uploaded = 0;
+        let mut dirs = vec![dir.to_path_buf()];
+        while let Some(current_dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&current_dir)
+                .with_context(|| format!("Unable to read '{}'", current_dir.display()))?
+            {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let relative_path = path.strip_prefix(dir)?;
+                let key = relative_path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let content_type = match path.extension().and_then(|extension| extension.to_str())
+                {
+                    Some("html") => "text/html",
+                    Some("tsv") => "text/tab-separated-values",
+                    _ => "application/octet-stream",
+                };
+                let body = std::fs::read(&path)
+                    .with_context(|| format!("Unable to read '{}'", path.display()))?;
+                self.put_object(&format!("{}{}", key_prefix, key), body, content_type)
+                    .await?;
+                num_uploaded += 1;
+            }
+        }
+        Ok(num_uploaded)
+    }
+
+    /// Virtual-hosted style on AWS, path style on other endpoints. The key is encoded the way
+    /// it is signed, which escapes more characters than URLs require.
+    fn object_url(&self, key: &str) -> anyhow::Result<Url> {
+        let key_path = key.split('/').map(uri_encode).collect::<Vec<_>>().join("/");
+        let url = match &self.endpoint {
+            Some(endpoint) => format!(
+                "{}/{}/{}",
+                endpoint.as_str().trim_end_matches('/'),
+                uri_encode(&self.location.bucket),
+                key_path
+            ),
+            None => format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.location.bucket, self.region, key_path
+            ),
+        };
+        Ok(Url::parse(&url)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_locations_and_addresses_objects() {
+        let location = "s3://crawls/site/2024/".parse::<S3Location>().unwrap();
+        assert_eq!(location.bucket, "crawls");
+        assert_eq!(location.prefix, "site/2024/");
+        assert_eq!("s3://crawls".parse::<S3Location>().unwrap().prefix, "");
+        assert!("https://crawls/".parse::<S3Location>().is_err());
+
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+        };
+        let mut uploader = S3Uploader {
+            http_client: reqwest::Client::new(),
+            location,
+            credentials,
+            region: "eu-west-1".to_string(),
+            endpoint: None,
+        };
+        assert_eq!(
+            uploader.object_url("site/2024/bodies/ab/c (1).html").unwrap().as_str(),
+            "https://crawls.s3.eu-west-1.amazonaws.com/site/2024/bodies/ab/c%20%281%29.html"
+        );
+        uploader.endpoint = Some(Url::parse("http://localhost:9000").unwrap());
+        assert_eq!(
+            uploader.object_url("results.json").unwrap().as_str(),
+            "http://localhost:9000/crawls/results.json"
+        );
+    }
+}
@@ -0,0 +1,8 @@
+mod api_server;
+mod crawl_job;
+mod crawl_registry;
+mod crawl_request;
+mod status_server;
+
+pub use api_server::ApiServer;
+pub use status_server::{CrawlStatus, StatusServer};
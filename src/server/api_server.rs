@@ -0,0 +1,170 @@
+use crate::server::crawl_registry::CrawlRegistry;
+use crate::server::crawl_request::CrawlRequest;
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use tokio::sync::broadcast;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use url::Url;
+
+/// REST API for starting, watching, pausing and cancelling crawls remotely.
+///
+/// - `POST /crawls` starts a crawl from a JSON `CrawlRequest` and returns its id
+/// - `GET /crawls` lists all crawls
+/// - `GET /crawls/{id}` returns a crawl's status and progress
+/// - `GET /crawls/{id}/results` returns the run details and page summaries once the crawl
+///   has finished
+/// - `GET /crawls/{id}/events` upgrades to a WebSocket streaming the crawl's progress
+///   events as JSON text messages, closed when the crawl ends
+/// - `POST /crawls/{id}/pause` and `POST /crawls/{id}/resume` pause and resume a crawl, or
+///   only one of its seeds given as `?seed=URL`
+/// - `DELETE /crawls/{id}` cancels a running crawl
+pub struct ApiServer {
+    listen_addr: SocketAddr,
+    registry: CrawlRegistry,
+}
+
+/// An error response with a JSON body of the form `{"error": "..."}`.
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// The query of the pause and resume requests.
+#[derive(serde::Deserialize)]
+struct SeedQuery {
+    seed: Option<Url>,
+}
+
+impl ApiServer {
+    pub fn new(listen_addr: SocketAddr) -> Self {
+        Self {
+            listen_addr,
+            registry: CrawlRegistry::new(),
+        }
+    }
+
+    pub async fn run(self) -> anyhow::Result<()> {
+        let router = Router::new()
+            .route("/crawls", get(Self::list_crawls).post(Self::start_crawl))
+            .route("/crawls/{id}", get(Self::get_crawl).delete(Self::cancel_crawl))
+            .route("/crawls/{id}/results", get(Self::get_results))
+            .route("/crawls/{id}/events", get(Self::stream_events))
+            .route("/crawls/{id}/pause", post(Self::pause_crawl))
+            .route("/crawls/{id}/resume", post(Self::resume_crawl))
+            .with_state(self.registry);
+        let listener = tokio::net::TcpListener::bind(self.listen_addr).await?;
+        eprintln!("Listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, router).await?;
+        Ok(())
+    }
+
+    async fn start_crawl(
+        State(registry): State<CrawlRegistry>,
+        Json(crawl_request): Json<CrawlRequest>,
+    ) -> Result<Response, ApiError> {
+        let id = registry
+            .start(crawl_request)
+            .map_err(|e| ApiError(StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+        Ok((StatusCode::CREATED, Json(serde_json::json!({ "id": id }))).into_response())
+    }
+
+    async fn list_crawls(State(registry): State<CrawlRegistry>) -> Response {
+        Json(registry.list()).into_response()
+    }
+
+    async fn get_crawl(
+        State(registry): State<CrawlRegistry>,
+        Path(id): Path<u64>,
+    ) -> Result<Response, ApiError> {
+        let view = registry.view(id).ok_or_else(|| Self::not_found(id))?;
+        Ok(Json(view).into_response())
+    }
+
+    async fn get_results(
+        State(registry): State<CrawlRegistry>,
+        Path(id): Path<u64>,
+    ) -> Result<Response, ApiError> {
+        match registry.results(id) {
+            None => Err(Self::not_found(id)),
+            Some(None) => Err(ApiError(
+                StatusCode::CONFLICT,
+                format!("Crawl {} has not finished", id),
+            )),
+            Some(Some(results)) => Ok(Json(results).into_response()),
+        }
+    }
+
+    async fn stream_events(
+        State(registry): State<CrawlRegistry>,
+        Path(id): Path<u64>,
+        ws: WebSocketUpgrade,
+    ) -> Result<Response, ApiError> {
+        let event_rx = registry.subscribe(id).ok_or_else(|| Self::not_found(id))?;
+        Ok(ws.on_upgrade(move |socket| Self::send_events(socket, event_rx)))
+    }
+
+    async fn send_events(
+        mut socket: WebSocket,
+        event_rx: Option<broadcast::Receiver<CrawlerProcessEvent>>,
+    ) {
+        if let Some(mut event_rx) = event_rx {
+            loop {
+                let event = match event_rx.recv().await {
+                    Ok(event) => event,
+                    // A slow client misses events rather than holding up the crawl.
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let Ok(json) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    return;
+                }
+            }
+        }
+        let _ = socket.send(Message::Close(None)).await;
+    }
+
+    async fn pause_crawl(
+        State(registry): State<CrawlRegistry>,
+        Path(id): Path<u64>,
+        Query(query): Query<SeedQuery>,
+    ) -> Result<Response, ApiError> {
+        let view = registry
+            .pause(id, query.seed.as_ref())
+            .ok_or_else(|| Self::not_found(id))?;
+        Ok(Json(view).into_response())
+    }
+
+    async fn resume_crawl(
+        State(registry): State<CrawlRegistry>,
+        Path(id): Path<u64>,
+        Query(query): Query<SeedQuery>,
+    ) -> Result<Response, ApiError> {
+        let view = registry
+            .resume(id, query.seed.as_ref())
+            .ok_or_else(|| Self::not_found(id))?;
+        Ok(Json(view).into_response())
+    }
+
+    async fn cancel_crawl(
+        State(registry): State<CrawlRegistry>,
+        Path(id): Path<u64>,
+    ) -> Result<Response, ApiError> {
+        let view = registry.cancel(id).ok_or_else(|| Self::not_found(id))?;
+        Ok((StatusCode::ACCEPTED, Json(view)).into_response())
+    }
+
+    fn not_found(id: u64) -> ApiError {
+        ApiError(StatusCode::NOT_FOUND, format!("No crawl with id {}", id))
+    }
+}
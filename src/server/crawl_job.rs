@@ -0,0 +1,139 @@
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use crate::crawler::multi::CrawlRun;
+use crate::crawler::seed::CrawlControl;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlJobStatus {
+    Running,
+    /// Paused through the API, keeping its progress until it is resumed.
+    Paused,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Progress of one seed's crawler, as last reported.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SeedProgress {
+    pub seed: Url,
+    /// ID of the seed's crawl, known once it has begun.
+    pub seed_id: Option<String>,
+    pub num_urls_to_crawl: usize,
+    pub num_urls_crawled: usize,
+    pub finished: bool,
+    /// Whether this seed alone has been paused.
+    pub paused: bool,
+}
+
+/// A crawl started through the API.
+pub struct CrawlJob {
+    pub id: u64,
+    pub status: CrawlJobStatus,
+    pub error: Option<String>,
+    pub seeds: Vec<SeedProgress>,
+    pub num_pages_completed: usize,
+    pub results: Option<CrawlRun>,
+    pub cancel_token: CancellationToken,
+    /// ID of the crawl run, the same as in its results and published events.
+    pub run_id: String,
+    /// Pauses and resumes the running crawl.
+    pub crawl_control: CrawlControl,
+    /// Rebroadcasts the crawl's events to WebSocket subscribers; None once the crawl has ended.
+    pub event_broadcast: Option<tokio::sync::broadcast::Sender<CrawlerProcessEvent>>,
+}
+
+/// The status document returned by `GET /crawls/{id}`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CrawlJobView {
+    pub id: u64,
+    /// ID of the crawl run, the same as in its results and published events.
+    pub run_id: String,
+    pub status: CrawlJobStatus,
+    pub error: Option<String>,
+    pub num_pages_completed: usize,
+    pub seeds: Vec<SeedProgress>,
+}
+
+impl CrawlJob {
+    pub fn new(
+        id: u64,
+        seeds: &[Url],
+        cancel_token: CancellationToken,
+        run_id: String,
+        crawl_control: CrawlControl,
+        event_broadcast: tokio::sync::broadcast::Sender<CrawlerProcessEvent>,
+    ) -> Self {
+        Self {
+            id,
+            status: CrawlJobStatus::Running,
+            error: None,
+            seeds: seeds
+                .iter()
+                .map(|seed| SeedProgress {
+                    seed: seed.clone(),
+                    seed_id: None,
+                    num_urls_to_crawl: 0,
+                    num_urls_crawled: 0,
+                    finished: false,
+                    paused: false,
+                })
+                .collect(),
+            num_pages_completed: 0,
+            results: None,
+            cancel_token,
+            run_id,
+            crawl_control,
+            event_broadcast: Some(event_broadcast),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: CrawlerProcessEvent) {
+        if let Some(event_broadcast) = &self.event_broadcast {
+            // No subscribers is not an error.
+            let _ = event_broadcast.send(event.clone());
+        }
+        match event {
+            CrawlerProcessEvent::Begin {
+                crawler_index,
+                seed_id,
+                ..
+            } => {
+                if let Some(seed_progress) = self.seeds.get_mut(crawler_index) {
+                    seed_progress.seed_id = Some(seed_id);
+                }
+            }
+            CrawlerProcessEvent::ProgressUpdate {
+                crawler_index,
+                num_urls_to_crawl,
+                num_urls_crawled,
+                ..
+            } => {
+                if let Some(seed_progress) = self.seeds.get_mut(crawler_index) {
+                    seed_progress.num_urls_to_crawl = num_urls_to_crawl;
+                    seed_progress.num_urls_crawled = num_urls_crawled;
+                }
+            }
+            CrawlerProcessEvent::PageCompleted { .. } => self.num_pages_completed += 1,
+            CrawlerProcessEvent::End { crawler_index } => {
+                if let Some(seed_progress) = self.seeds.get_mut(crawler_index) {
+                    seed_progress.finished = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn view(&self) -> CrawlJobView {
+        CrawlJobView {
+            id: self.id,
+            run_id: self.run_id.clone(),
+            status: self.status,
+            error: self.error.clone(),
+            num_pages_completed: self.num_pages_completed,
+            seeds: self.seeds.clone(),
+        }
+    }
+}
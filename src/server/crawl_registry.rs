@@ -0,0 +1,195 @@
+use crate::crawler::multi::{CrawlRun, MultiCrawler};
+use crate::server::crawl_job::{CrawlJob, CrawlJobStatus, CrawlJobView};
+use crate::server::crawl_request::CrawlRequest;
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// Events a slow WebSocket client may fall behind by before it starts missing some.
+const EVENT_BROADCAST_CAPACITY: usize = 1024;
+
+/// The crawls started through the API, running or finished.
+#[derive(Clone, Default)]
+pub struct CrawlRegistry {
+    jobs: Arc<Mutex<HashMap<u64, CrawlJob>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl CrawlRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a crawl in the background and returns its id.
+    pub fn start(&self, crawl_request: CrawlRequest) -> anyhow::Result<u64> {
+        let crawler_config = crawl_request.crawler_config()?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancel_token = CancellationToken::new();
+        let (event_broadcast, _) = tokio::sync::broadcast::channel(EVENT_BROADCAST_CAPACITY);
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut multi_crawler =
+            MultiCrawler::new(cancel_token.clone(), crawler_config, event_tx);
+        for seed in &crawl_request.seeds {
+            multi_crawler.add_seed(seed.clone());
+        }
+        self.jobs.lock().unwrap().insert(
+            id,
+            CrawlJob::new(
+                id,
+                &crawl_request.seeds,
+                cancel_token,
+                multi_crawler.run_id().to_string(),
+                multi_crawler.crawl_control(),
+                event_broadcast,
+            ),
+        );
+
+        {
+            let registry = self.clone();
+            tokio::task::spawn(async move {
+                while let Some(event) = event_rx.recv().await {
+                    registry.with_job(id, |job| job.handle_event(event));
+                }
+                // Closes the subscribers' streams.
+                registry.with_job(id, |job| job.event_broadcast = None);
+            });
+        }
+        {
+            let registry = self.clone();
+            // The crawler moves into its task, so its events end once it has run.
+            tokio::task::spawn(async move {
+                let results = multi_crawler.run().await;
+                registry.with_job(id, |job| Self::finish(job, results));
+            });
+        }
+        Ok(id)
+    }
+
+    pub fn list(&self) -> Vec<CrawlJobView> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut views = jobs.values().map(CrawlJob::view).collect::<Vec<_>>();
+        views.sort_by_key(|view| view.id);
+        views
+    }
+
+    /// Subscribes to a crawl's events: Some(None) if the crawl has already ended.
+    pub fn subscribe(
+        &self,
+        id: u64,
+    ) -> Option<Option<tokio::sync::broadcast::Receiver<CrawlerProcessEvent>>> {
+        self.with_job(id, |job| job.event_broadcast.as_ref().map(|tx| tx.subscribe()))
+    }
+
+    pub fn view(&self, id: u64) -> Option<CrawlJobView> {
+        self.with_job(id, |job| job.view())
+    }
+
+    /// The results of a finished crawl: Some(None) while it is still running.
+    pub fn results(&self, id: u64) -> Option<Option<CrawlRun>> {
+        self.with_job(id, |job| job.results.clone())
+    }
+
+    /// Pauses a running crawl, or only its crawler of `seed`, once the pages being crawled
+    /// are done.
+    pub fn pause(&self, id: u64, seed: Option<&Url>) -> Option<CrawlJobView> {
+        self.with_job(id, |job| {
+            match seed {
+                Some(seed) => {
+                    job.crawl_control.pause_seed(seed);
+                    Self::mark_seed_paused(job, seed, true);
+                }
+                None if job.status == CrawlJobStatus::Running => {
+                    job.crawl_control.pause();
+                    job.status = CrawlJobStatus::Paused;
+                }
+                None => {}
+            }
+            job.view()
+        })
+    }
+
+    /// Resumes a paused crawl and all of its seeds, or only its crawler of `seed`.
+    pub fn resume(&self, id: u64, seed: Option<&Url>) -> Option<CrawlJobView> {
+        self.with_job(id, |job| {
+            match seed {
+                Some(seed) => {
+                    job.crawl_control.resume_seed(seed);
+                    Self::mark_seed_paused(job, seed, false);
+                }
+                None => {
+                    job.crawl_control.resume();
+                    for seed_progress in &mut job.seeds {
+                        seed_progress.paused = false;
+                    }
+                    if job.status == CrawlJobStatus::Paused {
+                        job.status = CrawlJobStatus::Running;
+                    }
+                }
+            }
+            job.view()
+        })
+    }
+
+    /// Asks a running crawl to stop; it keeps the pages crawled so far.
+    pub fn cancel(&self, id: u64) -> Option<CrawlJobView> {
+        self.with_job(id, |job| {
+            if matches!(job.status, CrawlJobStatus::Running | CrawlJobStatus::Paused) {
+                job.status = CrawlJobStatus::Cancelled;
+                job.cancel_token.cancel();
+            }
+            job.view()
+        })
+    }
+
+    fn mark_seed_paused(job: &mut CrawlJob, seed: &Url, paused: bool) {
+        for seed_progress in job.seeds.iter_mut().filter(|progress| &progress.seed == seed) {
+            seed_progress.paused = paused;
+        }
+    }
+
+    fn finish(job: &mut CrawlJob, results: anyhow::Result<CrawlRun>) {
+        match results {
+            Ok(results) => {
+                if matches!(job.status, CrawlJobStatus::Running | CrawlJobStatus::Paused) {
+                    job.status = CrawlJobStatus::Completed;
+                }
+                job.results = Some(results);
+            }
+            Err(e) => {
+                job.status = CrawlJobStatus::Failed;
+                job.error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    fn with_job<T>(&self, id: u64, f: impl FnOnce(&mut CrawlJob) -> T) -> Option<T> {
+        self.jobs.lock().unwrap().get_mut(&id).map(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn closes_event_subscriptions_once_the_crawl_has_ended() {
+        let registry = CrawlRegistry::new();
+        let crawl_request: CrawlRequest =
+            serde_json::from_str(r#"{"seeds": ["http://127.0.0.1:9/"], "max_pages": 1}"#)
+                .unwrap();
+        let id = registry.start(crawl_request).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !matches!(registry.subscribe(id), Some(None)) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the event stream should close when the crawl ends");
+        assert_ne!(registry.view(id).unwrap().status, CrawlJobStatus::Running);
+    }
+}
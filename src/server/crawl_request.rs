@@ -0,0 +1,73 @@
+use crate::crawler::crawler_config::CrawlerConfig;
+use crate::crawler::normalize::QueryPolicy;
+use crate::input::rate::is_valid_rate;
+use anyhow::anyhow;
+use url::Url;
+
+/// The JSON body of `POST /crawls`. Omitted options take the command line defaults.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CrawlRequest {
+    pub seeds: Vec<Url>,
+    #[serde(default = "CrawlRequest::default_max_pages")]
+    pub max_pages: usize,
+    #[serde(default = "CrawlRequest::default_max_depth")]
+    pub max_depth: usize,
+    /// Requests per second.
+    pub rate: Option<f64>,
+    /// Requests to a host that may go out back to back, within the rate limit.
+    #[serde(default = "CrawlRequest::default_burst")]
+    pub burst: u32,
+    #[serde(default)]
+    pub global_dedupe: bool,
+    #[serde(default)]
+    pub ignore_robots_directives: bool,
+    /// Same syntax as `--query-policy`, e.g. "strip:utm_*".
+    pub query_policy: Option<String>,
+    /// Same as `--keep-query`: keep query strings instead of applying a query policy.
+    #[serde(default)]
+    pub keep_query: bool,
+    /// Same syntax as `--max-duration`, e.g. "10m".
+    pub max_duration: Option<String>,
+}
+
+impl CrawlRequest {
+    fn default_max_pages() -> usize {
+        1000
+    }
+
+    fn default_max_depth() -> usize {
+        4
+    }
+
+    fn default_burst() -> u32 {
+        1
+    }
+
+    pub fn crawler_config(&self) -> anyhow::Result<CrawlerConfig> {
+        if self.seeds.is_empty() {
+            return Err(anyhow!("At least one seed is required"));
+        }
+        if self.rate.is_some_and(|rate| !is_valid_rate(rate)) {
+            return Err(anyhow!("rate must be a positive number"));
+        }
+        let query_policy = match (&self.query_policy, self.keep_query) {
+            (Some(_), true) => {
+                return Err(anyhow!("query_policy and keep_query can't be used together"));
+            }
+            (Some(query_policy), false) => query_policy.parse::<QueryPolicy>()?,
+            (None, true) => QueryPolicy::KeepAll,
+            (None, false) => QueryPolicy::default(),
+        };
+        let max_duration = match &self.max_duration {
+            Some(max_duration) => Some(humantime::parse_duration(max_duration)?),
+            None => None,
+        };
+        Ok(CrawlerConfig::new(self.max_pages, self.max_depth, self.rate)
+            .with_burst(self.burst)
+            .with_global_dedupe(self.global_dedupe)
+            .with_respect_robots_directives(!self.ignore_robots_directives)
+            .with_query_policy(query_policy)
+            .with_max_duration(max_duration))
+    }
+}
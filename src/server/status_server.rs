@@ -0,0 +1,155 @@
+use crate::console::crawler_progress_event::CrawlerProcessEvent;
+use anyhow::Context;
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// A lightweight HTTP endpoint for orchestrators to watch a crawl run from the command line.
+///
+/// - `GET /health` answers `ok` for as long as the process is alive
+/// - `GET /status` returns the current run's id and, for each seed, the size of its frontier
+///   and the number of pages crawled
+pub struct StatusServer;
+
+/// The status document returned by `GET /status`.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+struct RunStatus {
+    /// ID of the current run, known once it has started.
+    run_id: Option<String>,
+    /// Runs started by this process, which is more than one when monitoring.
+    num_runs: usize,
+    num_pages_completed: usize,
+    /// URLs waiting to be crawled, over all seeds.
+    num_urls_to_crawl: usize,
+    seeds: Vec<SeedStatus>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct SeedStatus {
+    #[serde(skip)]
+    crawler_index: usize,
+    seed: Url,
+    seed_id: String,
+    num_urls_to_crawl: usize,
+    num_urls_crawled: usize,
+    finished: bool,
+}
+
+/// The status of the crawl, kept up to date from its progress events.
+#[derive(Clone, Default)]
+pub struct CrawlStatus {
+    status: Arc<Mutex<RunStatus>>,
+}
+
+impl StatusServer {
+    /// Starts serving on `listen_addr` in the background and returns the status it reports.
+    pub async fn start(listen_addr: SocketAddr) -> anyhow::Result<CrawlStatus> {
+        let crawl_status = CrawlStatus::default();
+        let router = Router::new()
+            .route("/health", get(Self::health))
+            .route("/status", get(Self::status))
+            .with_state(crawl_status.clone());
+        let listener = tokio::net::TcpListener::bind(listen_addr)
+            .await
+            .with_context(|| format!("Unable to serve the status endpoint on {}", listen_addr))?;
+        tokio::task::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                eprintln!("Status endpoint stopped: {}", e);
+            }
+        });
+        Ok(crawl_status)
+    }
+
+    async fn health() -> &'static str {
+        "ok"
+    }
+
+    async fn status(State(crawl_status): State<CrawlStatus>) -> Response {
+        let status = crawl_status.status.lock().unwrap().clone();
+        Json(status).into_response()
+    }
+}
+
+impl CrawlStatus {
+    /// Returns a sender whose events update this status before being passed on to `event_tx`.
+    pub fn forward_to(
+        &self,
+        event_tx: tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent>,
+    ) -> tokio::sync::mpsc::UnboundedSender<CrawlerProcessEvent> {
+        let (forward_tx, mut forward_rx) = tokio::sync::mpsc::unbounded_channel();
+        let crawl_status = self.clone();
+        tokio::task::spawn(async move {
+            while let Some(event) = forward_rx.recv().await {
+                crawl_status.status.lock().unwrap().handle_event(&event);
+                if event_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        forward_tx
+    }
+}
+
+impl RunStatus {
+    fn handle_event(&mut self, event: &CrawlerProcessEvent) {
+        match event {
+            CrawlerProcessEvent::RunStarted { run_id } => {
+                *self = RunStatus {
+                    run_id: Some(run_id.clone()),
+                    num_runs: self.num_runs + 1,
+                    ..RunStatus::default()
+                };
+            }
+            CrawlerProcessEvent::Begin {
+                crawler_index,
+                url,
+                seed_id,
+            } => {
+                self.seeds.push(SeedStatus {
+                    crawler_index: *crawler_index,
+                    seed: url.clone(),
+                    seed_id: seed_id.clone(),
+                    num_urls_to_crawl: 0,
+                    num_urls_crawled: 0,
+                    finished: false,
+                });
+            }
+            CrawlerProcessEvent::ProgressUpdate {
+                crawler_index,
+                num_urls_to_crawl,
+                num_urls_crawled,
+                ..
+            } => {
+                if let Some(seed_status) = self.seed_mut(*crawler_index) {
+                    seed_status.num_urls_to_crawl = *num_urls_to_crawl;
+                    seed_status.num_urls_crawled = *num_urls_crawled;
+                }
+                self.count_urls_to_crawl();
+            }
+            CrawlerProcessEvent::PageCompleted { .. } => self.num_pages_completed += 1,
+            CrawlerProcessEvent::End { crawler_index } => {
+                if let Some(seed_status) = self.seed_mut(*crawler_index) {
+                    seed_status.num_urls_to_crawl = 0;
+                    seed_status.finished = true;
+                }
+                self.count_urls_to_crawl();
+            }
+            _ => {}
+        }
+    }
+
+    fn seed_mut(&mut self, crawler_index: usize) -> Option<&mut SeedStatus> {
+        self.seeds
+            .iter_mut()
+            .find(|seed_status| seed_status.crawler_index == crawler_index)
+    }
+
+    fn count_urls_to_crawl(&mut self) {
+        self.num_urls_to_crawl =
+            self.seeds.iter().map(|seed_status| seed_status.num_urls_to_crawl).sum();
+    }
+}
@@ -0,0 +1,11 @@
+//! Support for testing crawls against a local mock site.
+
+mod mock_redis_server;
+mod mock_site;
+mod mock_site_server;
+mod recording_progress_reporter;
+
+pub use mock_redis_server::{MockRedisServer, MockReply};
+pub use mock_site::{MockPage, MockSite};
+pub use mock_site_server::MockSiteServer;
+pub use recording_progress_reporter::RecordingProgressReporter;
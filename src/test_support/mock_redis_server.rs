@@ -0,0 +1,630 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+use url::Url;
+
+/// How often blocking commands look again for something to pop.
+const BLOCKING_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A value stored under a key.
+#[derive(Debug, Clone)]
+enum Entry {
+    String(Vec<u8>),
+    List(VecDeque<Vec<u8>>),
+    Set(BTreeSet<Vec<u8>>),
+    /// Members with their scores, kept in insertion order.
+    SortedSet(Vec<(f64, Vec<u8>)>),
+}
+
+/// A reply to a command, as sent over the wire.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockReply {
+    Nil,
+    Ok,
+    Integer(i64),
+    Data(Vec<u8>),
+    Array(Vec<MockReply>),
+    Error(String),
+}
+
+/// The keys of a MockRedisServer, with their expiry times.
+#[derive(Debug, Default)]
+struct MockRedisStore {
+    entries: HashMap<Vec<u8>, (Entry, Option<Instant>)>,
+    /// The password clients must AUTH with before sending other commands, if any.
+    password: Option<Vec<u8>>,
+}
+
+/// Serves an in-memory stand-in for a Redis server on an ephemeral localhost port until
+/// dropped, speaking RESP2. Implements the commands the crawler sends, with their Redis
+/// semantics; each command runs on its own, as on a real server.
+pub struct MockRedisServer {
+    url: Url,
+    store: Arc<Mutex<MockRedisStore>>,
+    shutdown_tx: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl MockRedisServer {
+    pub async fn start() -> anyhow::Result<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = Url::parse(&format!("redis://{}/", listener.local_addr()?))?;
+        let store = Arc::new(Mutex::new(MockRedisStore::default()));
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let served_store = Arc::clone(&store);
+        tokio::task::spawn(async move {
+            loop {
+                let stream = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(_) => break,
+                    },
+                    _ = &mut shutdown_rx => break,
+                };
+                tokio::task::spawn(Self::serve(stream, Arc::clone(&served_store)));
+            }
+        });
+        Ok(Self {
+            url,
+            store,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    /// Requires clients to AUTH with `password` before sending other commands, as a server
+    /// with `requirepass` does.
+    pub fn with_password(self, password: &str) -> Self {
+        self.store.lock().unwrap().password = Some(password.as_bytes().to_vec());
+        self
+    }
+
+    /// The URL to connect to, e.g. "redis://127.0.0.1:49152/".
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Runs a command directly against the store, e.g. to set up or inspect a test.
+    pub fn command(&self, args: &[&[u8]]) -> MockReply {
+        let args = args.iter().map(|arg| arg.to_vec()).collect::<Vec<_>>();
+        self.store.lock().unwrap().execute(&args)
+    }
+
+    /// The keys currently stored, sorted.
+    pub fn keys(&self) -> Vec<String> {
+        let mut store = self.store.lock().unwrap();
+        store.remove_expired();
+        let mut keys = store
+            .entries
+            .keys()
+            .map(|key| String::from_utf8_lossy(key).into_owned())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    async fn serve(stream: TcpStream, store: Arc<Mutex<MockRedisStore>>) {
+        let mut stream = BufReader::new(stream);
+        let mut authenticated = false;
+        while let Ok(Some(args)) = read_command(&mut stream).await {
+            let name = args.first().map(|name| name.to_ascii_uppercase()).unwrap_or_default();
+            let reply = if name == b"AUTH" {
+                let reply = store.lock().unwrap().authenticate(&args[1..]);
+                authenticated |= reply == MockReply::Ok;
+                reply
+            } else if !authenticated && store.lock().unwrap().password.is_some() {
+                MockReply::Error("NOAUTH Authentication required.".into())
+            } else {
+                execute_blocking(&store, &args).await
+            };
+            let mut encoded = Vec::new();
+            reply.encode(&mut encoded);
+            if stream.get_mut().write_all(&encoded).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for MockRedisServer {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+    }
+}
+
+/// Reads a command sent as an array of bulk strings, or None once the client disconnects.
+async fn read_command(
+    stream: &mut BufReader<TcpStream>,
+) -> anyhow::Result<Option<Vec<Vec<u8>>>> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    let num_args = line.trim_end().trim_start_matches('*').parse::<usize>()?;
+    let mut args = Vec::with_capacity(num_args);
+    for _ in 0..num_args {
+        line.clear();
+        stream.read_line(&mut line).await?;
+        let len = line.trim_end().trim_start_matches('$').parse::<usize>()?;
+        let mut arg = vec![0; len + 2];
+        stream.read_exact(&mut arg).await?;
+        arg.truncate(len);
+        args.push(arg);
+    }
+    Ok(Some(args))
+}
+
+/// Runs a command, waiting outside the lock while a blocking command has nothing to pop.
+async fn execute_blocking(store: &Mutex<MockRedisStore>, args: &[Vec<u8>]) -> MockReply {
+    let name = args.first().map(|name| name.to_ascii_uppercase()).unwrap_or_default();
+    let (non_blocking, timeout) = match name.as_slice() {
+        b"BLPOP" => {
+            let mut non_blocking = args[..args.len() - 1].to_vec();
+            non_blocking[0] = b"LPOP".to_vec();
+            (non_blocking, args.last())
+        }
+        b"BLMOVE" => {
+            let mut non_blocking = args[..args.len() - 1].to_vec();
+            non_blocking[0] = b"LMOVE".to_vec();
+            (non_blocking, args.last())
+        }
+        _ => return store.lock().unwrap().execute(args),
+    };
+    let timeout = timeout
+        .and_then(|timeout| std::str::from_utf8(timeout).ok()?.parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let deadline = (timeout > 0.0).then(|| Instant::now() + Duration::from_secs_f64(timeout));
+    loop {
+        {
+            let mut store = store.lock().unwrap();
+            if name.as_slice() == b"BLPOP" {
+                // Pops from the first of the keys that has anything
+                for key in &non_blocking[1..] {
+                    let reply = store.execute(&[b"LPOP".to_vec(), key.clone()]);
+                    if reply != MockReply::Nil {
+                        return MockReply::Array(vec![MockReply::Data(key.clone()), reply]);
+                    }
+                }
+            } else {
+                let reply = store.execute(&non_blocking);
+                if reply != MockReply::Nil {
+                    return reply;
+                }
+            }
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return MockReply::Nil;
+        }
+        tokio::time::sleep(BLOCKING_POLL_INTERVAL).await;
+    }
+}
+
+impl MockReply {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            MockReply::Nil => out.extend_from_slice(b"$-1\r\n"),
+            MockReply::Ok => out.extend_from_slice(b"+OK\r\n"),
+            MockReply::Integer(integer) => {
+                out.extend_from_slice(format!(":{}\r\n", integer).as_bytes())
+            }
+            MockReply::Data(data) => {
+                out.extend_from_slice(format!("${}\r\n", data.len()).as_bytes());
+                out.extend_from_slice(data);
+                out.extend_from_slice(b"\r\n");
+            }
+            MockReply::Array(values) => {
+                out.extend_from_slice(format!("*{}\r\n", values.len()).as_bytes());
+                for value in values {
+                    value.encode(out);
+                }
+            }
+            MockReply::Error(message) => {
+                out.extend_from_slice(format!("-ERR {}\r\n", message).as_bytes())
+            }
+        }
+    }
+}
+
+fn wrong_type() -> MockReply {
+    MockReply::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+}
+
+fn parse<T: std::str::FromStr>(arg: &[u8]) -> Option<T> {
+    std::str::from_utf8(arg).ok()?.parse().ok()
+}
+
+impl MockRedisStore {
+    fn remove_expired(&mut self) {
+        let now = Instant::now();
+        self.entries
+            .retain(|_, (_, expires_at)| expires_at.is_none_or(|expires_at| expires_at > now));
+    }
+
+    fn get(&mut self, key: &[u8]) -> Option<&mut Entry> {
+        self.remove_expired();
+        self.entries.get_mut(key).map(|(entry, _)| entry)
+    }
+
+    /// The entry at `key`, created with `new` if missing.
+    fn get_or_insert(&mut self, key: &[u8], new: fn() -> Entry) -> &mut Entry {
+        self.remove_expired();
+        &mut self.entries.entry(key.to_vec()).or_insert_with(|| (new(), None)).0
+    }
+
+    /// Drops the key if its list, set or sorted set is now empty, as Redis does.
+    fn drop_if_empty(&mut self, key: &[u8]) {
+        let empty = match self.entries.get(key) {
+            Some((Entry::List(list), _)) => list.is_empty(),
+            Some((Entry::Set(set), _)) => set.is_empty(),
+            Some((Entry::SortedSet(sorted_set), _)) => sorted_set.is_empty(),
+            _ => false,
+        };
+        if empty {
+            self.entries.remove(key);
+        }
+    }
+
+    /// Checks the password of an AUTH command, given with or without a user name.
+    fn authenticate(&self, args: &[Vec<u8>]) -> MockReply {
+        let ([password] | [_, password]) = args else {
+            return MockReply::Error("wrong number of arguments for AUTH".into());
+        };
+        match &self.password {
+            Some(expected) if expected != password => MockReply::Error(
+                "WRONGPASS invalid username-password pair or user is disabled.".into(),
+            ),
+            _ => MockReply::Ok,
+        }
+    }
+
+    /// Runs a command, never blocking: blocking commands must go through `execute_blocking`.
+    fn execute(&mut self, args: &[Vec<u8>]) -> MockReply {
+        let Some(name) = args.first() else {
+            return MockReply::Error("empty command".into());
+        };
+        let name = String::from_utf8_lossy(name).to_ascii_uppercase();
+        let args = &args[1..];
+        let reply = match (name.as_str(), args) {
+            ("PING", _) => Some(MockReply::Data(b"PONG".to_vec())),
+            ("AUTH" | "SELECT", _) => Some(MockReply::Ok),
+            ("GET", [key]) => Some(match self.get(key) {
+                None => MockReply::Nil,
+                Some(Entry::String(value)) => MockReply::Data(value.clone()),
+                Some(_) => wrong_type(),
+            }),
+            ("SET", [key, value, options @ ..]) => {
+                let expires_at = match options {
+                    [] => None,
+                    [px, millis] if px.eq_ignore_ascii_case(b"PX") => {
+                        parse(millis).map(|millis| Instant::now() + Duration::from_millis(millis))
+                    }
+                    _ => return MockReply::Error("unsupported SET options".into()),
+                };
+                self.entries.insert(key.clone(), (Entry::String(value.clone()), expires_at));
+                Some(MockReply::Ok)
+            }
+            ("EXISTS", keys) => {
+                self.remove_expired();
+                let num_existing =
+                    keys.iter().filter(|key| self.entries.contains_key(*key)).count();
+                Some(MockReply::Integer(num_existing as i64))
+            }
+            ("DEL", keys) => {
+                self.remove_expired();
+                let num_deleted =
+                    keys.iter().filter(|key| self.entries.remove(*key).is_some()).count();
+                Some(MockReply::Integer(num_deleted as i64))
+            }
+            ("EXPIRE" | "PEXPIRE", [key, ttl]) => {
+                let Some(ttl) = parse::<u64>(ttl) else {
+                    return MockReply::Error("invalid expire time".into());
+                };
+                let ttl = match name.as_str() {
+                    "EXPIRE" => Duration::from_secs(ttl),
+                    _ => Duration::from_millis(ttl),
+                };
+                self.remove_expired();
+                Some(match self.entries.get_mut(key) {
+                    Some((_, expires_at)) => {
+                        *expires_at = Some(Instant::now() + ttl);
+                        MockReply::Integer(1)
+                    }
+                    None => MockReply::Integer(0),
+                })
+            }
+            ("LPUSH" | "RPUSH", [key, values @ ..]) if !values.is_empty() => {
+                let Entry::List(list) = self.get_or_insert(key, || Entry::List(VecDeque::new()))
+                else {
+                    return wrong_type();
+                };
+                for value in values {
+                    match name.as_str() {
+                        "LPUSH" => list.push_front(value.clone()),
+                        _ => list.push_back(value.clone()),
+                    }
+                }
+                Some(MockReply::Integer(list.len() as i64))
+            }
+            ("LPOP" | "RPOP", [key]) => {
+                let reply = match self.get(key) {
+                    None => MockReply::Nil,
+                    Some(Entry::List(list)) => {
+                        let value = match name.as_str() {
+                            "LPOP" => list.pop_front(),
+                            _ => list.pop_back(),
+                        };
+                        value.map_or(MockReply::Nil, MockReply::Data)
+                    }
+                    Some(_) => wrong_type(),
+                };
+                self.drop_if_empty(key);
+                Some(reply)
+            }
+            ("LLEN", [key]) => Some(match self.get(key) {
+                None => MockReply::Integer(0),
+                Some(Entry::List(list)) => MockReply::Integer(list.len() as i64),
+                Some(_) => wrong_type(),
+            }),
+            ("LRANGE", [key, start, stop]) => {
+                let (Some(start), Some(stop)) = (parse::<i64>(start), parse::<i64>(stop)) else {
+                    return MockReply::Error("invalid range".into());
+                };
+                Some(match self.get(key) {
+                    None => MockReply::Array(Vec::new()),
+                    Some(Entry::List(list)) => {
+                        let len = list.len() as i64;
+                        let index = |i: i64| if i < 0 { (len + i).max(0) } else { i };
+                        let (start, stop) = (index(start), index(stop).min(len - 1));
+                        let values = (start..=stop)
+                            .map(|i| MockReply::Data(list[i as usize].clone()))
+                            .collect();
+                        MockReply::Array(values)
+                    }
+                    Some(_) => wrong_type(),
+                })
+            }
+            ("LREM", [key, count, value]) => {
+                let Some(count) = parse::<i64>(count) else {
+                    return MockReply::Error("invalid count".into());
+                };
+                let reply = match self.get(key) {
+                    None => MockReply::Integer(0),
+                    Some(Entry::List(list)) => {
+                        // Only removal from the head, by a positive count, is supported
+                        let mut num_removed = 0;
+                        while count <= 0 || num_removed < count {
+                            let Some(i) = list.iter().position(|element| element == value) else {
+                                break;
+                            };
+                            list.remove(i);
+                            num_removed += 1;
+                        }
+                        MockReply::Integer(num_removed)
+                    }
+                    Some(_) => wrong_type(),
+                };
+                self.drop_if_empty(key);
+                Some(reply)
+            }
+            ("LMOVE", [source, destination, from, to]) => {
+                let value = match self.get(source) {
+                    None => return MockReply::Nil,
+                    Some(Entry::List(list)) => {
+                        let value = match from.to_ascii_uppercase().as_slice() {
+                            b"LEFT" => list.pop_front(),
+                            _ => list.pop_back(),
+                        };
+                        value.expect("empty lists are dropped")
+                    }
+                    Some(_) => return wrong_type(),
+                };
+                self.drop_if_empty(source);
+                let Entry::List(list) =
+                    self.get_or_insert(destination, || Entry::List(VecDeque::new()))
+                else {
+                    return wrong_type();
+                };
+                match to.to_ascii_uppercase().as_slice() {
+                    b"LEFT" => list.push_front(value.clone()),
+                    _ => list.push_back(value.clone()),
+                }
+                Some(MockReply::Data(value))
+            }
+            ("SADD", [key, members @ ..]) if !members.is_empty() => {
+                let Entry::Set(set) = self.get_or_insert(key, || Entry::Set(BTreeSet::new())) else {
+                    return wrong_type();
+                };
+                let num_added = members.iter().filter(|member| set.insert(member.to_vec())).count();
+                Some(MockReply::Integer(num_added as i64))
+            }
+            ("SREM", [key, members @ ..]) => {
+                let reply = match self.get(key) {
+                    None => MockReply::Integer(0),
+                    Some(Entry::Set(set)) => {
+                        let num_removed =
+                            members.iter().filter(|member| set.remove(member.as_slice())).count();
+                        MockReply::Integer(num_removed as i64)
+                    }
+                    Some(_) => wrong_type(),
+                };
+                self.drop_if_empty(key);
+                Some(reply)
+            }
+            ("SMEMBERS", [key]) => Some(match self.get(key) {
+                None => MockReply::Array(Vec::new()),
+                Some(Entry::Set(set)) => {
+                    MockReply::Array(set.iter().cloned().map(MockReply::Data).collect())
+                }
+                Some(_) => wrong_type(),
+            }),
+            ("SISMEMBER", [key, member]) => Some(match self.get(key) {
+                None => MockReply::Integer(0),
+                Some(Entry::Set(set)) => MockReply::Integer(set.contains(member) as i64),
+                Some(_) => wrong_type(),
+            }),
+            ("SCARD", [key]) => Some(match self.get(key) {
+                None => MockReply::Integer(0),
+                Some(Entry::Set(set)) => MockReply::Integer(set.len() as i64),
+                Some(_) => wrong_type(),
+            }),
+            ("ZADD", [key, scores_and_members @ ..])
+                if !scores_and_members.is_empty() && scores_and_members.len() % 2 == 0 =>
+            {
+                let Entry::SortedSet(sorted_set) =
+                    self.get_or_insert(key, || Entry::SortedSet(Vec::new()))
+                else {
+                    return wrong_type();
+                };
+                let mut num_added = 0;
+                for pair in scores_and_members.chunks(2) {
+                    let Some(score) = parse::<f64>(&pair[0]) else {
+                        return MockReply::Error("invalid score".into());
+                    };
+                    match sorted_set.iter_mut().find(|(_, member)| *member == pair[1]) {
+                        Some(existing) => existing.0 = score,
+                        None => {
+                            sorted_set.push((score, pair[1].clone()));
+                            num_added += 1;
+                        }
+                    }
+                }
+                Some(MockReply::Integer(num_added))
+            }
+            ("ZREM", [key, members @ ..]) => {
+                let reply = match self.get(key) {
+                    None => MockReply::Integer(0),
+                    Some(Entry::SortedSet(sorted_set)) => {
+                        let len = sorted_set.len();
+                        sorted_set.retain(|(_, member)| !members.contains(member));
+                        MockReply::Integer((len - sorted_set.len()) as i64)
+                    }
+                    Some(_) => wrong_type(),
+                };
+                self.drop_if_empty(key);
+                Some(reply)
+            }
+            ("ZRANGEBYSCORE" | "ZCOUNT", [key, min, max]) => {
+                let (Some(min), Some(max)) = (parse::<f64>(min), parse::<f64>(max)) else {
+                    return MockReply::Error("invalid score range".into());
+                };
+                let mut in_range = match self.get(key) {
+                    None => Vec::new(),
+                    Some(Entry::SortedSet(sorted_set)) => sorted_set
+                        .iter()
+                        .filter(|(score, _)| (min..=max).contains(score))
+                        .cloned()
+                        .collect(),
+                    Some(_) => return wrong_type(),
+                };
+                Some(match name.as_str() {
+                    "ZCOUNT" => MockReply::Integer(in_range.len() as i64),
+                    _ => {
+                        in_range.sort_by(|a, b| a.0.total_cmp(&b.0));
+                        let members = in_range.into_iter().map(|(_, member)| member);
+                        MockReply::Array(members.map(MockReply::Data).collect())
+                    }
+                })
+            }
+            ("ZCARD", [key]) => Some(match self.get(key) {
+                None => MockReply::Integer(0),
+                Some(Entry::SortedSet(sorted_set)) => MockReply::Integer(sorted_set.len() as i64),
+                Some(_) => wrong_type(),
+            }),
+            #[cfg(test)]
+            ("EVAL", [script, num_keys, keys_and_args @ ..]) => {
+                Some(self.eval(script, num_keys, keys_and_args))
+            }
+            _ => None,
+        };
+        reply.unwrap_or_else(|| MockReply::Error(format!("unsupported command {}", name)))
+    }
+
+    /// Runs a Lua script as EVAL does, all at once, with `redis.call` running commands on
+    /// the store.
+    #[cfg(test)]
+    fn eval(&mut self, script: &[u8], num_keys: &[u8], keys_and_args: &[Vec<u8>]) -> MockReply {
+        let Some(num_keys) = parse::<usize>(num_keys).filter(|n| *n <= keys_and_args.len())
+        else {
+            return MockReply::Error("invalid number of keys".into());
+        };
+        let (keys, args) = keys_and_args.split_at(num_keys);
+        let lua = mlua::Lua::new();
+        let result = lua.scope(|scope| {
+            let globals = lua.globals();
+            globals.set("KEYS", lua.create_sequence_from(to_lua_strings(&lua, keys)?)?)?;
+            globals.set("ARGV", lua.create_sequence_from(to_lua_strings(&lua, args)?)?)?;
+            let call = scope.create_function_mut(
+                |lua, args: mlua::Variadic<mlua::String>| -> mlua::Result<mlua::Value> {
+                    let args = args.iter().map(|arg| arg.as_bytes().to_vec()).collect::<Vec<_>>();
+                    match self.execute(&args) {
+                        MockReply::Error(message) => Err(mlua::Error::RuntimeError(message)),
+                        reply => reply.into_lua(lua),
+                    }
+                },
+            )?;
+            let redis = lua.create_table()?;
+            redis.set("call", call)?;
+            globals.set("redis", redis)?;
+            let value: mlua::Value = lua.load(script).eval()?;
+            Ok(MockReply::from_lua(value))
+        });
+        result.unwrap_or_else(|e| MockReply::Error(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+fn to_lua_strings<'lua>(
+    lua: &'lua mlua::Lua,
+    values: &[Vec<u8>],
+) -> mlua::Result<Vec<mlua::String<'lua>>> {
+    values.iter().map(|value| lua.create_string(value)).collect()
+}
+
+#[cfg(test)]
+impl MockReply {
+    /// The reply as `redis.call` returns it to a script.
+    fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value<'_>> {
+        Ok(match self {
+            MockReply::Nil => mlua::Value::Boolean(false),
+            MockReply::Ok => {
+                let status = lua.create_table()?;
+                status.set("ok", "OK")?;
+                mlua::Value::Table(status)
+            }
+            MockReply::Integer(integer) => mlua::Value::Integer(integer),
+            MockReply::Data(data) => mlua::Value::String(lua.create_string(data)?),
+            MockReply::Array(values) => {
+                let values = values
+                    .into_iter()
+                    .map(|value| value.into_lua(lua))
+                    .collect::<mlua::Result<Vec<_>>>()?;
+                mlua::Value::Table(lua.create_sequence_from(values)?)
+            }
+            MockReply::Error(message) => return Err(mlua::Error::RuntimeError(message)),
+        })
+    }
+
+    /// The reply to EVAL for the value a script returned.
+    fn from_lua(value: mlua::Value) -> Self {
+        match value {
+            mlua::Value::Boolean(true) => MockReply::Integer(1),
+            mlua::Value::Integer(integer) => MockReply::Integer(integer),
+            mlua::Value::Number(number) => MockReply::Integer(number as i64),
+            mlua::Value::String(string) => MockReply::Data(string.as_bytes().to_vec()),
+            mlua::Value::Table(table) => {
+                if let Ok(message) = table.get::<_, String>("err") {
+                    return MockReply::Error(message);
+                }
+                if table.contains_key("ok").unwrap_or(false) {
+                    return MockReply::Ok;
+                }
+                let values = table.sequence_values::<mlua::Value>().map_while(Result::ok);
+                MockReply::Array(values.map(MockReply::from_lua).collect())
+            }
+            _ => MockReply::Nil,
+        }
+    }
+}
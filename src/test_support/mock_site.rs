@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A canned response served by the mock site for one path.
+#[derive(Debug, Clone)]
+pub struct MockPage {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// How long the server waits before responding.
+    pub delay: Duration,
+}
+
+impl MockPage {
+    /// An HTML page titled `title` linking to each of `links`.
+    pub fn html(title: &str, links: &[&str]) -> Self {
+        let anchors = links
+            .iter()
+            .map(|link| format!("<a href=\"{}\">{}</a>", link, link))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            status_code: 200,
+            headers: vec![("content-type".to_string(), "text/html".to_string())],
+            body: format!(
+                "<html><head><title>{}</title></head><body>\n{}\n</body></html>",
+                title, anchors
+            ),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn status(status_code: u16) -> Self {
+        Self {
+            status_code,
+            headers: Vec::new(),
+            body: String::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// The topology of a fake site: what each path serves. Paths not in the site are 404s.
+///
+/// Paths include the query string, if any, e.g. "/archive?page=2".
+#[derive(Debug, Clone, Default)]
+pub struct MockSite {
+    pages: HashMap<String, MockPage>,
+}
+
+impl MockSite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A chain of `num_pages` pages, "/" linking to "/page/1", which links to "/page/2"
+    /// and so on. The last page is `num_pages - 1` links deep.
+    pub fn chain(num_pages: usize) -> Self {
+        let path = |index: usize| match index {
+            0 => "/".to_string(),
+            _ => format!("/page/{}", index),
+        };
+        let mut site = Self::new();
+        for index in 0..num_pages {
+            let next = path(index + 1);
+            let links: &[&str] = if index + 1 < num_pages { &[&next] } else { &[] };
+            site = site.with_page(&path(index), MockPage::html(&format!("Page {}", index), links));
+        }
+        site
+    }
+
+    pub fn with_page(mut self, path: &str, page: MockPage) -> Self {
+        self.pages.insert(path.to_string(), page);
+        self
+    }
+
+    /// An HTML page at `path` linking to `links`.
+    pub fn with_html(self, path: &str, links: &[&str]) -> Self {
+        self.with_page(path, MockPage::html(path, links))
+    }
+
+    /// A `301 Moved Permanently` from `path` to `location`.
+    pub fn with_redirect(self, path: &str, location: &str) -> Self {
+        self.with_page(path, MockPage::status(301).with_header("location", location))
+    }
+
+    /// An HTML page at `path` that takes `delay` to respond.
+    pub fn with_slow_page(self, path: &str, links: &[&str], delay: Duration) -> Self {
+        self.with_page(path, MockPage::html(path, links).with_delay(delay))
+    }
+
+    pub fn with_robots_txt(self, content: &str) -> Self {
+        let mut page = MockPage::status(200).with_header("content-type", "text/plain");
+        page.body = content.to_string();
+        self.with_page("/robots.txt", page)
+    }
+
+    pub fn page(&self, path: &str) -> Option<&MockPage> {
+        self.pages.get(path)
+    }
+}
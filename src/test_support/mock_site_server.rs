@@ -0,0 +1,74 @@
+use crate::test_support::MockSite;
+use axum::Router;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{Request, Response, StatusCode};
+use std::sync::Arc;
+use url::Url;
+
+/// Serves a MockSite on an ephemeral localhost port until dropped.
+pub struct MockSiteServer {
+    base_url: Url,
+    shutdown_tx: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl MockSiteServer {
+    pub async fn start(site: MockSite) -> anyhow::Result<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = Url::parse(&format!("http://{}/", listener.local_addr()?))?;
+        let router = Router::new()
+            .fallback(Self::serve)
+            .with_state(Arc::new(site));
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+        tokio::task::spawn(async move {
+            let _ = axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+        });
+        Ok(Self {
+            base_url,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    /// The root URL of the site, e.g. "http://127.0.0.1:49152/".
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// The absolute URL of `path` on the site.
+    pub fn url(&self, path: &str) -> Url {
+        self.base_url.join(path).unwrap()
+    }
+
+    async fn serve(State(site): State<Arc<MockSite>>, request: Request<Body>) -> Response<Body> {
+        let path = request
+            .uri()
+            .path_and_query()
+            .map(|path_and_query| path_and_query.as_str())
+            .unwrap_or("/");
+        let Some(page) = site.page(path) else {
+            let mut response = Response::new(Body::from("Not Found"));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            return response;
+        };
+        if !page.delay.is_zero() {
+            tokio::time::sleep(page.delay).await;
+        }
+        let mut response = Response::builder().status(page.status_code);
+        for (name, value) in &page.headers {
+            response = response.header(name, value);
+        }
+        response.body(Body::from(page.body.clone())).unwrap()
+    }
+}
+
+impl Drop for MockSiteServer {
+    fn drop(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+    }
+}
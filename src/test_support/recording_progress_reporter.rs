@@ -0,0 +1,49 @@
+use crate::console::crawler_state::CrawlerState;
+use crate::crawler::rate_limit::CircuitState;
+use crate::crawler::seed::ProgressReporter;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// Records the pages a seed crawler completes, in order, instead of displaying progress.
+#[derive(Clone, Default)]
+pub struct RecordingProgressReporter {
+    pages_completed: Arc<Mutex<Vec<(Url, u16)>>>,
+    circuit_states: Arc<Mutex<Vec<CircuitState>>>,
+}
+
+impl RecordingProgressReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pages_completed(&self) -> Vec<(Url, u16)> {
+        self.pages_completed.lock().unwrap().clone()
+    }
+
+    pub fn circuit_states(&self) -> Vec<CircuitState> {
+        self.circuit_states.lock().unwrap().clone()
+    }
+}
+
+impl ProgressReporter for RecordingProgressReporter {
+    fn begin(&self) {}
+
+    fn progress_update(&self, _num_urls_to_crawl: usize, _num_urls_crawled: usize, _elapsed: Duration) {}
+
+    fn progress_message(&self, _message: &str) {}
+
+    fn page_completed(&self, url: &Url, status_code: u16, _depth: usize, _duration: Duration) {
+        self.pages_completed.lock().unwrap().push((url.clone(), status_code));
+    }
+
+    fn crawler_state_changed(&self, _state: CrawlerState) {}
+
+    fn rate_changed(&self, _requests_per_second: f64) {}
+
+    fn circuit_state_changed(&self, _host: &str, state: CircuitState) {
+        self.circuit_states.lock().unwrap().push(state);
+    }
+
+    fn end(&self) {}
+}